@@ -0,0 +1,42 @@
+import { describe, it, expect } from "vitest";
+import { planCardGrid } from "../placement";
+
+const card = { w: 100, h: 140 };
+
+describe("planCardGrid", () => {
+  it("is the plain 104 x 144 grid for same-size cards", () => {
+    const plan = planCardGrid([card, card, card, card], 4, 4, 8);
+    expect(plan.pos).toEqual([
+      { x: 0, y: 0 },
+      { x: 104, y: 0 },
+      { x: 0, y: 144 },
+      { x: 104, y: 144 },
+    ]);
+    expect([plan.w, plan.h]).toEqual([204, 284]);
+  });
+
+  it("widens columns and rows around tapped and enlarged cards", () => {
+    const tapped = { w: 140, h: 100 };
+    const big = { w: 200, h: 280 };
+    const plan = planCardGrid([big, card, tapped, card], 4, 4, 8);
+    expect(plan.pos).toEqual([
+      { x: 0, y: 0 },
+      { x: 208, y: 0 },
+      { x: 0, y: 288 },
+      { x: 208, y: 288 },
+    ]);
+    // No footprint overlaps another
+    const sizes = [big, card, tapped, card];
+    for (let i = 0; i < 4; i++)
+      for (let j = i + 1; j < 4; j++) {
+        const a = { ...plan.pos[i], ...sizes[i] };
+        const b = { ...plan.pos[j], ...sizes[j] };
+        const apart =
+          a.x + a.w <= b.x ||
+          b.x + b.w <= a.x ||
+          a.y + a.h <= b.y ||
+          b.y + b.h <= a.y;
+        expect(apart).toBe(true);
+      }
+  });
+});
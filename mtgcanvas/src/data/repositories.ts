@@ -1,5 +1,13 @@
-import type { CardInstance, GroupRow } from "../types/repositories";
+import type {
+  CardInstance,
+  GroupRow,
+  GroupTransform,
+} from "../types/repositories";
 import type { Rect } from "../types/geometry";
+import type { GroupLayoutMode } from "../scene/stackLayout";
+import type { GroupSortSpec } from "../scene/groupSort";
+import type { GroupDeck } from "../services/deckValidation";
+import type { SmartGroupRule } from "../services/smartGroups";
 
 const mem = { instances: [] as CardInstance[], groups: [] as GroupRow[] };
 // Fast id lookup for instances to avoid O(N) scans in hot update paths
@@ -35,6 +43,7 @@ export const InstancesRepo = {
     scale?: number;
     tags?: string | null;
     group_id?: number | null;
+    foil?: boolean;
   }) {
     const inst: CardInstance = {
       id: row.id,
@@ -46,6 +55,7 @@ export const InstancesRepo = {
       rotation: row.rotation ?? 0,
       scale: row.scale ?? 1,
       tags: row.tags ?? null,
+      foil: !!row.foil,
     };
     // Avoid id collisions on subsequent creates
     if (row.id >= memInstanceId) memInstanceId = row.id + 1;
@@ -56,6 +66,9 @@ export const InstancesRepo = {
   list() {
     return mem.instances;
   },
+  get(id: number) {
+    return instById.get(id);
+  },
   deleteMany(ids: number[]) {
     if (!ids.length) return;
     mem.instances = mem.instances.filter((i) => {
@@ -81,6 +94,10 @@ export const InstancesRepo = {
       y?: number;
       z?: number;
       group_id?: number | null;
+      foil?: boolean;
+      rotation?: number;
+      scale?: number;
+      tags?: string | null;
     }[],
   ) {
     if (!batch.length) return;
@@ -91,6 +108,10 @@ export const InstancesRepo = {
         if (r.y !== undefined) inst.y = r.y;
         if (r.z !== undefined) inst.z = r.z;
         if (r.group_id !== undefined) inst.group_id = r.group_id ?? null;
+        if (r.foil !== undefined) inst.foil = r.foil;
+        if (r.rotation !== undefined) inst.rotation = r.rotation;
+        if (r.scale !== undefined) inst.scale = r.scale;
+        if (r.tags !== undefined) inst.tags = r.tags || null;
       }
     });
   },
@@ -140,6 +161,14 @@ export const InstancesRepo = {
   },
 };
 
+function readTransform(g: GroupRow): Partial<GroupTransform> {
+  try {
+    return g.transform_json ? JSON.parse(g.transform_json) : {};
+  } catch {
+    return {};
+  }
+}
+
 export const GroupsRepo = {
   create(
     name: string | null,
@@ -153,12 +182,31 @@ export const GroupsRepo = {
       id: memGroupId++,
       parent_id,
       name,
-      // collapsed removed
       transform_json: JSON.stringify({ x, y, w, h }),
     };
     mem.groups.push(row);
     return row.id;
   },
+  // Memory-only: recreate a group row with an explicit id (used by undo/redo to restore deleted groups)
+  createWithId(row: {
+    id: number;
+    name: string | null;
+    parent_id?: number | null;
+    x: number;
+    y: number;
+    w: number;
+    h: number;
+  }) {
+    mem.groups = mem.groups.filter((g) => g.id !== row.id);
+    mem.groups.push({
+      id: row.id,
+      parent_id: row.parent_id ?? null,
+      name: row.name,
+      transform_json: JSON.stringify({ x: row.x, y: row.y, w: row.w, h: row.h }),
+    });
+    if (row.id >= memGroupId) memGroupId = row.id + 1;
+    return row.id;
+  },
   list() {
     return mem.groups as any;
   },
@@ -166,16 +214,74 @@ export const GroupsRepo = {
     if (!ids.length) return;
     mem.groups = mem.groups.filter((g) => !ids.includes(g.id));
   },
+  // Rect updates keep the collapse fields already stored in transform_json
   updateTransform(id: number, t: Rect) {
-    const json = JSON.stringify(t);
     const g = mem.groups.find((g) => g.id === id);
-    if (g) (g as any).transform_json = json;
+    if (g) g.transform_json = JSON.stringify({ ...readTransform(g), ...t });
+  },
+  setCollapsed(
+    id: number,
+    collapsed: boolean,
+    expanded: { w: number; h: number } | null,
+  ) {
+    const g = mem.groups.find((g) => g.id === id);
+    if (!g) return;
+    const t: Partial<GroupTransform> = {
+      ...readTransform(g),
+      collapsed,
+      expanded,
+    };
+    if (!collapsed) {
+      delete t.collapsed;
+      delete t.expanded;
+    }
+    g.transform_json = JSON.stringify(t);
+  },
+  setLayout(id: number, layout: GroupLayoutMode) {
+    const g = mem.groups.find((g) => g.id === id);
+    if (!g) return;
+    const t: Partial<GroupTransform> = { ...readTransform(g), layout };
+    if (layout === "grid") delete t.layout;
+    g.transform_json = JSON.stringify(t);
+  },
+  setSort(id: number, sort: GroupSortSpec) {
+    const g = mem.groups.find((g) => g.id === id);
+    if (!g) return;
+    const t: Partial<GroupTransform> = { ...readTransform(g), sort };
+    if (!sort.length) delete t.sort;
+    g.transform_json = JSON.stringify(t);
+  },
+  setStacking(id: number, stack: boolean) {
+    const g = mem.groups.find((g) => g.id === id);
+    if (!g) return;
+    const t: Partial<GroupTransform> = { ...readTransform(g), stack };
+    if (!stack) delete t.stack;
+    g.transform_json = JSON.stringify(t);
+  },
+  setDeck(id: number, deck: GroupDeck | null) {
+    const g = mem.groups.find((g) => g.id === id);
+    if (!g) return;
+    const t: Partial<GroupTransform> = { ...readTransform(g) };
+    if (deck) t.deck = deck;
+    else delete t.deck;
+    g.transform_json = JSON.stringify(t);
+  },
+  setSmart(id: number, smart: SmartGroupRule | null) {
+    const g = mem.groups.find((g) => g.id === id);
+    if (!g) return;
+    const t: Partial<GroupTransform> = { ...readTransform(g) };
+    if (smart) t.smart = smart;
+    else delete t.smart;
+    g.transform_json = JSON.stringify(t);
   },
-  // setCollapsed removed
   rename(id: number, name: string) {
     const g = mem.groups.find((g) => g.id === id);
     if (g) g.name = name;
   },
+  setParent(id: number, parent_id: number | null) {
+    const g = mem.groups.find((g) => g.id === id);
+    if (g) g.parent_id = parent_id;
+  },
   // Ensure the next generated group id is at least `min`.
   ensureNextId(min: number) {
     if (typeof min === "number" && isFinite(min)) {
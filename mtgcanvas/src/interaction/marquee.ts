@@ -1,6 +1,7 @@
 import * as PIXI from "pixi.js";
 import { Colors } from "../ui/theme";
 import type { CardSprite } from "../scene/cardNode";
+import { cardBounds } from "../scene/cardTransform";
 import { SelectionStore } from "../state/selectionStore";
 
 interface MarqueeState {
@@ -9,7 +10,11 @@ interface MarqueeState {
   additive: boolean;
   active: boolean;
 }
-type QueryResult = { cards?: CardSprite[]; groupIds?: number[] };
+type QueryResult = {
+  cards?: CardSprite[];
+  groupIds?: number[];
+  noteIds?: number[];
+};
 type QueryFn = (rect: {
   x: number;
   y: number;
@@ -77,13 +82,15 @@ export class MarqueeSystem {
       res = this.query({ x: data.x, y: data.y, w: data.w, h: data.h });
     } else {
       const sprites = this.getSprites();
-      const cards = sprites.filter(
-        (s) =>
-          s.x + 100 >= data.x &&
-          s.x <= data.x + data.w &&
-          s.y + 140 >= data.y &&
-          s.y <= data.y + data.h,
-      );
+      const cards = sprites.filter((s) => {
+        const b = cardBounds(s);
+        return (
+          b.maxX >= data.x &&
+          b.minX <= data.x + data.w &&
+          b.maxY >= data.y &&
+          b.minY <= data.y + data.h
+        );
+      });
       res = { cards, groupIds: [] };
     }
     const selCards = new Set<CardSprite>(
@@ -92,6 +99,7 @@ export class MarqueeSystem {
     const selGroups = new Set<number>(
       additive ? SelectionStore.getGroups() : [],
     );
+    const selNotes = new Set<number>(additive ? SelectionStore.getNotes() : []);
     // If not additive, clear marquee-tint flags on all previously selected cards
     if (!additive) {
       SelectionStore.getCards().forEach((c) => {
@@ -103,7 +111,12 @@ export class MarqueeSystem {
       (c as any).__tintByMarquee = true;
     });
     (res?.groupIds || []).forEach((id) => selGroups.add(id));
-    SelectionStore.replace({ cards: selCards, groupIds: selGroups });
+    (res?.noteIds || []).forEach((id) => selNotes.add(id));
+    SelectionStore.replace({
+      cards: selCards,
+      groupIds: selGroups,
+      noteIds: selNotes,
+    });
   }
   isActive() {
     return !!this.state;
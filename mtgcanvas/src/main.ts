@@ -3,16 +3,21 @@ import type { Card } from "./types/card";
 import {
   planImportPositions,
   computeBestGrid,
+  planCardGrid,
   planRectangles,
 } from "./placement";
 import type { PlacementContext } from "./placement";
+import type { Rect } from "./types/geometry";
 import { SelectionStore } from "./state/selectionStore";
+import { History } from "./state/history";
 import { Camera } from "./scene/camera";
 import {
   updateCardSpriteAppearance,
+  applyCardTransform,
   type CardSprite,
   ensureTexture,
   updateFlipFab,
+  updateStackBadge,
   updateFloatAndTilt,
   updateDraggedTopLeft,
   clearTextureCaches,
@@ -43,18 +48,87 @@ import {
   removeCardFromGroup,
   clearGroupMembers,
   updateGroupTextQuality,
-  updateGroupMetrics,
+  updateGroupMetrics as updateGroupTotals,
   updateGroupZoomPresentation,
   ensureMembersZOrder,
   placeCardInGroup,
+  childGroups,
+  collapsedGroupSize,
+  descendantGroups,
+  isWithinGroup,
+  type DeckBadge,
 } from "./scene/groupNode";
+import {
+  createNoteVisual,
+  drawNote,
+  noteRecord,
+  type NoteVisual,
+} from "./scene/noteNode";
+import {
+  NOTE_COLORS,
+  NOTE_DEFAULT_H,
+  NOTE_DEFAULT_W,
+  NOTE_MIN_H,
+  NOTE_MIN_W,
+  parseNoteRecords,
+  type NoteColor,
+  type NoteRecord,
+} from "./scene/notes";
+import {
+  createConnectorVisual,
+  drawConnector,
+  type ConnectorVisual,
+} from "./scene/connectorNode";
+import {
+  CONNECTOR_LABEL_MAX,
+  parseConnectorRecords,
+  sameEnd,
+  type ConnectorEnd,
+  type ConnectorRecord,
+} from "./scene/connectors";
 import { SpatialIndex, type SpatialItem } from "./scene/SpatialIndex";
+import {
+  buildDuplicateStacks,
+  duplicateKey,
+  markDuplicateStacksDirty,
+  stackIntact,
+  takeDuplicateStacksDirty,
+} from "./scene/duplicateStacks";
+import {
+  cardBounds,
+  cardSize,
+  isTapped,
+  KEY_CARD_SCALE,
+  normalizeRotation,
+  normalizeScale,
+  TAPPED_ROTATION,
+} from "./scene/cardTransform";
+import {
+  GROUP_LAYOUT_MODES,
+  isGroupLayoutMode,
+  primaryType,
+  type GroupLayoutMode,
+} from "./scene/stackLayout";
+import {
+  GROUP_SORT_KEYS,
+  describeGroupSort,
+  parseGroupSortSpec,
+  promoteSortKey,
+  type GroupSortSpec,
+} from "./scene/groupSort";
 import { MarqueeSystem } from "./interaction/marquee";
 import { initHelp } from "./ui/helpPanel";
 import {
   queuePosition,
   persistGroupTransform,
   persistGroupRename,
+  persistGroupParent,
+  persistGroupCollapsed,
+  persistGroupLayout,
+  persistGroupSort,
+  persistGroupStacking,
+  persistGroupDeck,
+  persistGroupSmart,
 } from "./services/persistenceService";
 import { InstancesRepo, GroupsRepo } from "./data/repositories";
 import {
@@ -65,9 +139,53 @@ import {
   getUiScale,
 } from "./ui/theme";
 import { Colors } from "./ui/theme";
-import { installSearchPalette } from "./ui/searchPalette";
-import { installImportExport } from "./ui/importExport";
-import { searchScryfall, fetchScryfallByNames } from "./services/scryfall";
+import {
+  installSearchPalette,
+  type SpotlightHit,
+} from "./ui/searchPalette";
+import {
+  installImportExport,
+  stackDuplicatesPref,
+} from "./ui/importExport";
+import {
+  catalogSource,
+  scryfallSource,
+  withFallback,
+  resolveDeckEntries,
+} from "./services/cardSource";
+import {
+  importDeckEntries,
+  type DeckImportTarget,
+} from "./services/deckImport";
+import type { ComboLine, DeckEntry } from "./services/decklist";
+import {
+  computeDeckStats,
+  describeIdentity,
+  type StatBucket,
+} from "./services/deckStats";
+import {
+  DECK_FORMATS,
+  isSideboardName,
+  parseGroupDeck,
+  validateDeck,
+  type DeckViolation,
+  type GroupDeck,
+} from "./services/deckValidation";
+import {
+  SMART_QUERY_MAX,
+  describeSmartScope,
+  parseSmartGroupRule,
+  planSmartGroup,
+  smartQueryError,
+  type SmartGroupInfo,
+  type SmartGroupRule,
+  type SmartGroupScope,
+} from "./services/smartGroups";
+import { parseScryfallQuery } from "./search/scryfallQuery";
+import {
+  createCanvasContextLookup,
+  parseInstanceTags,
+} from "./search/canvasContext";
 import {
   addImportedCards,
   getAllImportedCards,
@@ -89,7 +207,11 @@ import {
   SPACING_Y,
 } from "./config/dimensions";
 import { snap } from "./utils/snap";
+import { computeAlignment, type GuideBox } from "./utils/alignGuides";
+import { createGuideLayer } from "./scene/guideLayer";
+import { createSpotlightLayer } from "./scene/spotlightLayer";
 import { installNetworkActivitySpinner } from "./ui/networkSpinner";
+import { installMinimap, type MinimapAPI } from "./ui/minimap";
 import {
   ensureInitialProject,
   getCurrentProjectMeta,
@@ -101,11 +223,20 @@ import {
   createProject,
   deleteProject,
   duplicateProject,
+  exportProject,
+  importProject,
+  getProjectBookmarks,
+  setProjectBookmarks,
+  MAX_BOOKMARKS,
+  type CameraBookmark,
 } from "./services/projects";
 
 // Global hard cap on number of card sprites in the scene
 const MAX_CARD_SPRITES = 40000;
 
+// Card lookups/search: offline catalog when loaded, live Scryfall API for the rest
+const cardSource = withFallback(catalogSource, scryfallSource);
+
 // Build placement context for the planner module
 // buildPlacementContext is defined later inside the app bootstrap where world/sprites/groups are available
 
@@ -444,8 +575,14 @@ const splashEl = document.getElementById("splash");
     newBtn.id = "project-new-btn";
     newBtn.textContent = "New";
     newBtn.title = "Create a new project";
+    const importBtn = document.createElement("button");
+    importBtn.className = "ui-btn";
+    importBtn.id = "project-import-btn";
+    importBtn.textContent = "Import";
+    importBtn.title = "Restore a project backup (JSON)";
     rowName.appendChild(rowSpacer);
     rowName.appendChild(newBtn);
+    rowName.appendChild(importBtn);
     menu.appendChild(rowName);
     // Projects list section (clickable items will populate here)
     const projectsLabelRow = document.createElement("div");
@@ -458,6 +595,26 @@ const splashEl = document.getElementById("splash");
     list.className = "project-list";
     list.id = "project-list";
     menu.appendChild(list);
+    // Camera bookmarks of the current project (number keys recall, Shift+number saves)
+    const bookmarksLabelRow = document.createElement("div");
+    bookmarksLabelRow.className = "row";
+    const bookmarksLabel = document.createElement("label");
+    bookmarksLabel.textContent = "Bookmarks";
+    const bookmarksSpacer = document.createElement("div");
+    bookmarksSpacer.className = "spacer";
+    const saveViewBtn = document.createElement("button");
+    saveViewBtn.className = "ui-btn";
+    saveViewBtn.id = "bookmark-save-btn";
+    saveViewBtn.textContent = "Save view";
+    saveViewBtn.title = "Bookmark the current view (Shift+1…9)";
+    bookmarksLabelRow.appendChild(bookmarksLabel);
+    bookmarksLabelRow.appendChild(bookmarksSpacer);
+    bookmarksLabelRow.appendChild(saveViewBtn);
+    menu.appendChild(bookmarksLabelRow);
+    const bookmarkList = document.createElement("div");
+    bookmarkList.className = "project-list";
+    bookmarkList.id = "bookmark-list";
+    menu.appendChild(bookmarkList);
     el.appendChild(menu);
 
     // Track hover state to allow ESC to cancel only when menu is interacted with
@@ -491,6 +648,7 @@ const splashEl = document.getElementById("splash");
   );
   // Optional hook for UI list refresh (assigned inside initProjectUI)
   let __refreshProjectList: (() => void) | null = null;
+  let __refreshBookmarkList: (() => void) | null = null;
   // Initialize Project UI: name field and list population
   (function initProjectUI() {
     const banner = document.getElementById("title-banner");
@@ -503,6 +661,15 @@ const splashEl = document.getElementById("splash");
     const newBtnEl = banner?.querySelector(
       "#project-new-btn",
     ) as HTMLButtonElement | null;
+    const importBtnEl = banner?.querySelector(
+      "#project-import-btn",
+    ) as HTMLButtonElement | null;
+    const bookmarkListEl = banner?.querySelector(
+      "#bookmark-list",
+    ) as HTMLDivElement | null;
+    const saveViewBtnEl = banner?.querySelector(
+      "#bookmark-save-btn",
+    ) as HTMLButtonElement | null;
     // Seed current name
     try {
       if (nameInput) nameInput.value = getCurrentProjectMeta().name || "";
@@ -527,6 +694,99 @@ const splashEl = document.getElementById("splash");
           closeProjectMenu();
         } catch {}
       });
+    // Restore a backup from the Export button as a new project, bookmarks included
+    importBtnEl &&
+      (importBtnEl.onclick = () => {
+        const input = document.createElement("input");
+        input.type = "file";
+        input.accept = ".json,application/json";
+        input.onchange = async () => {
+          const file = input.files?.[0];
+          if (!file) return;
+          const meta = importProject(await file.text());
+          if (!meta) {
+            alert("That file is not a project backup.");
+            return;
+          }
+          await switchProject(meta.id);
+          try {
+            closeProjectMenu();
+          } catch {}
+        };
+        input.click();
+      });
+    saveViewBtnEl &&
+      (saveViewBtnEl.onclick = () => {
+        const used = new Set(
+          getProjectBookmarks(currentProjectId).map((b) => b.slot),
+        );
+        let slot = 1;
+        while (used.has(slot)) slot++;
+        if (slot > MAX_BOOKMARKS) {
+          alert(`All ${MAX_BOOKMARKS} bookmark slots are in use.`);
+          return;
+        }
+        const name = prompt("Bookmark name", `View ${slot}`);
+        if (name == null) return;
+        saveBookmark(slot, name);
+      });
+    function renderBookmarks() {
+      if (!bookmarkListEl) return;
+      bookmarkListEl.innerHTML = "";
+      const bookmarks = getProjectBookmarks(currentProjectId);
+      if (saveViewBtnEl)
+        saveViewBtnEl.disabled = bookmarks.length >= MAX_BOOKMARKS;
+      if (!bookmarks.length) {
+        const hint = document.createElement("div");
+        hint.className = "hint";
+        hint.textContent = "No bookmarks yet. Shift+1…9 saves the view.";
+        bookmarkListEl.appendChild(hint);
+        return;
+      }
+      for (const b of bookmarks) {
+        const item = document.createElement("div");
+        item.className = "project-item";
+        const left = document.createElement("div");
+        left.className = "left";
+        const title = document.createElement("div");
+        title.className = "title";
+        title.textContent = b.name;
+        const meta = document.createElement("div");
+        meta.className = "meta";
+        meta.textContent = `Key ${b.slot}`;
+        left.appendChild(title);
+        left.appendChild(meta);
+        const actions = document.createElement("div");
+        actions.className = "actions";
+        const renameBtn = document.createElement("button");
+        renameBtn.className = "ui-pill";
+        renameBtn.textContent = "Rename";
+        renameBtn.title = "Rename bookmark";
+        renameBtn.onclick = (e) => {
+          e.stopPropagation();
+          const name = prompt("Bookmark name", b.name);
+          if (name == null) return;
+          updateBookmarks((list) =>
+            list.map((x) => (x.slot === b.slot ? { ...x, name } : x)),
+          );
+        };
+        const delBtn = document.createElement("button");
+        delBtn.className = "ui-pill danger";
+        delBtn.textContent = "Delete";
+        delBtn.title = "Delete bookmark";
+        delBtn.onclick = (e) => {
+          e.stopPropagation();
+          updateBookmarks((list) => list.filter((x) => x.slot !== b.slot));
+        };
+        actions.appendChild(renameBtn);
+        actions.appendChild(delBtn);
+        item.appendChild(left);
+        item.appendChild(actions);
+        item.onclick = () => recallBookmark(b.slot);
+        bookmarkListEl.appendChild(item);
+      }
+    }
+    __refreshBookmarkList = renderBookmarks;
     function renderList() {
       if (!listEl) return;
       listEl.innerHTML = "";
@@ -567,6 +827,34 @@ const splashEl = document.getElementById("splash");
             renderList();
           } catch {}
         };
+        const exportBtn = document.createElement("button");
+        exportBtn.className = "ui-pill";
+        exportBtn.textContent = "Export";
+        exportBtn.title = "Download project backup (JSON)";
+        exportBtn.onclick = (e) => {
+          e.stopPropagation();
+          // Flush pending edits so the backup matches the canvas
+          if (m.id === currentProjectId) {
+            try {
+              persistence.flushGroups();
+              persistence.flushPositions();
+            } catch {}
+          }
+          const json = exportProject(m.id);
+          if (!json) return;
+          const blob = new Blob([json], { type: "application/json" });
+          const url = URL.createObjectURL(blob);
+          const a = document.createElement("a");
+          a.href = url;
+          const safe = (m.name || "project").replace(/[\\/:*?"<>|]+/g, "_");
+          a.download = `${safe}.mtgcanvas.json`;
+          document.body.appendChild(a);
+          a.click();
+          setTimeout(() => {
+            URL.revokeObjectURL(url);
+            a.remove();
+          }, 0);
+        };
         const delBtn = document.createElement("button");
         delBtn.className = "ui-pill danger";
         delBtn.textContent = "Delete";
@@ -588,6 +876,7 @@ const splashEl = document.getElementById("splash");
           }
         };
         actions.appendChild(dupBtn);
+        actions.appendChild(exportBtn);
         actions.appendChild(delBtn);
         item.appendChild(left);
         item.appendChild(actions);
@@ -621,6 +910,8 @@ const splashEl = document.getElementById("splash");
       persistence = createLocalPersistence({
         getSprites: () => sprites,
         getGroups: () => groups,
+        getNotes: () => [...notes.values()].map(noteRecord),
+        getConnectors: () => liveConnectorRecords(),
         spatial,
         getCanvasBounds,
         cardW: CARD_W_GLOBAL,
@@ -629,6 +920,7 @@ const splashEl = document.getElementById("splash");
         groupsKey: LS_GROUPS_KEY,
       });
       // Clear scene & memory state
+      History.clear();
       SUPPRESS_SAVES = true;
       try {
         SelectionStore.clear();
@@ -642,6 +934,7 @@ const splashEl = document.getElementById("splash");
           } catch {}
         }
         sprites.length = 0;
+        markSpotlightDirty();
         // Destroy groups
         groups.forEach((gv) => {
           try {
@@ -649,6 +942,8 @@ const splashEl = document.getElementById("splash");
           } catch {}
         });
         groups.clear();
+        [...notes.keys()].forEach((id) => removeNoteVisual(id));
+        [...connectors.keys()].forEach((id) => removeConnectorVisual(id));
         // Ensure world container is emptied
         try {
           world.removeChildren().forEach((c) => {
@@ -714,12 +1009,14 @@ const splashEl = document.getElementById("splash");
         if (nameInput) nameInput.value = getCurrentProjectMeta().name || "";
       } catch {}
       renderList();
+      renderBookmarks();
       // Ensure global helpers now reference the current sprite list only
       try {
         (window as any).__mtgGetSprites = () => sprites;
       } catch {}
     }
     renderList();
+    renderBookmarks();
   })();
 
   // Controls helper overlay (on-canvas) — shown whenever there are zero cards & zero groups
@@ -886,6 +1183,8 @@ const splashEl = document.getElementById("splash");
   });
   // Keep camera min zoom accommodating full bounds on viewport resize (debounced above)
   const spatial = new SpatialIndex();
+  // Installed with the other overlays below; edits invalidate its cached scene layer
+  let minimap: MinimapAPI | null = null;
   // Global z-order helper: monotonic counter shared across modules via window
   function nextZ(): number {
     const w: any = window as any;
@@ -931,7 +1230,22 @@ const splashEl = document.getElementById("splash");
   // Lightweight visual raise without persistence; used on drag start only
   function bringGroupsToFrontVisual(groupIds: number[]) {
     if (!groupIds || !groupIds.length) return;
-    const ids = Array.from(new Set(groupIds));
+    // Nested groups travel with their parents and must stay above them
+    const idSet = new Set(groupIds);
+    groupIds.forEach((id) => {
+      const gv = groups.get(id);
+      if (gv) descendantGroups(gv, groups).forEach((c) => idSet.add(c.id));
+    });
+    const ids = Array.from(idSet);
+    const depth = (id: number) => {
+      let d = 0;
+      let cur = groups.get(id);
+      while (cur && cur.parentId != null && d < groups.size) {
+        cur = groups.get(cur.parentId);
+        d++;
+      }
+      return d;
+    };
     // Find current max z across groups and sprites (cheap scan; no sorting/persistence)
     let base = 0;
     sprites.forEach((s) => {
@@ -943,10 +1257,12 @@ const splashEl = document.getElementById("splash");
       if (z > base) base = z;
     });
     base += 1;
-    // Preserve relative order by current z then id
+    // Preserve relative order by nesting depth, current z, then id
     const ordered = ids
-      .map((id) => ({ id, z: groups.get(id)?.gfx?.zIndex || 0 }))
-      .sort((a, b) => (a.z === b.z ? a.id - b.id : a.z - b.z))
+      .map((id) => ({ id, d: depth(id), z: groups.get(id)?.gfx?.zIndex || 0 }))
+      .sort((a, b) =>
+        a.d !== b.d ? a.d - b.d : a.z === b.z ? a.id - b.id : a.z - b.z,
+      )
       .map((e) => e.id);
     for (const id of ordered) {
       const gg = groups.get(id);
@@ -967,9 +1283,29 @@ const splashEl = document.getElementById("splash");
   }
   // Groups container + visuals (initialized early so camera fit can consider them)
   const groups = new Map<number, GroupVisual>();
+  // Sticky notes by id (saved with the groups payload)
+  const notes = new Map<number, NoteVisual>();
+  // Arrows between cards/groups by id (saved with the groups payload)
+  const connectors = new Map<number, ConnectorVisual>();
+  // Pending connector redraws (see markConnectorsDirty)
+  const dirtyConnectorCards = new Set<number>();
+  const dirtyConnectorGroups = new Set<number>();
+  let allConnectorsDirty = true;
+  let connectorsByEnd: Map<string, ConnectorVisual[]> | null = null;
+  // Smart-guide lines shown while dragging cards or groups
+  const guideLayer = createGuideLayer(world);
+  // Search spotlight: matched cards outlined, the rest dimmed (see setSpotlight)
+  const spotlightLayer = createSpotlightLayer(world);
+  const spotlightMatches = new Set<CardSprite>();
+  const spotlightCounted = new Set<CardSprite>(); // counted in group headers
+  let spotlightHit: SpotlightHit | null = null;
+  // Pending re-classification (see markSpotlightDirty)
+  const spotlightPending = new Set<CardSprite>();
+  let spotlightAllDirty = false;
   // Transient z-order rules during drag: use very high z values below HUD/banner
   // Live-refresh groups on theme changes (colors, text fills, overlay presentation)
   registerThemeListener(() => {
+    markConnectorsDirty();
     groups.forEach((gv) => {
       // Ensure overlay/header visibility & overlay text color/position reflect new theme
       updateGroupZoomPresentation(gv, world.scale.x);
@@ -991,9 +1327,12 @@ const splashEl = document.getElementById("splash");
       gapY: GAP_Y_GLOBAL,
       spacingX: SPACING_X,
       spacingY: SPACING_Y,
+      notes: [...notes.values()].map(noteRect),
     } as PlacementContext;
   }
   let zCounter = 1;
+  // Open undo change for the card drag in progress (committed by handleDroppedSprites)
+  let pendingCardDrag: SceneChange | null = null;
   // Fast path: create many sprites with minimal side effects and batch spatial index updates
   function createSpritesBulk(
     items: Array<{
@@ -1004,6 +1343,9 @@ const splashEl = document.getElementById("splash");
       group_id?: number | null;
       card?: Card | null;
       scryfall_id?: string | null;
+      foil?: boolean;
+      rotation?: number;
+      scale?: number;
     }>,
   ): CardSprite[] {
     const __tm = createPhaseTimer("createSpritesBulk");
@@ -1019,8 +1361,24 @@ const splashEl = document.getElementById("splash");
         world,
         stage: app.stage,
         getAll: () => sprites,
-        onDrop: (moved) => handleDroppedSprites(moved),
-        onDragMove: (moved) =>
+        onDrop: (moved) => {
+          guideLayer.clear();
+          handleDroppedSprites(moved);
+        },
+        onDragStart: (dragged) => {
+          // Repeated nudges of the same set collapse into one undo step
+          const key = dragged
+            .map((d) => d.__id)
+            .sort((a, b) => a - b)
+            .join(",");
+          pendingCardDrag = beginSceneChange("Move cards", dragged, {
+            mergeKey: `move:${key}`,
+          });
+          beginNoteFollow(dragged);
+        },
+        onDragMove: (moved) => {
+          updateNoteFollow();
+          markConnectorsDirty(moved);
           moved.forEach((ms) => {
             const x = (ms as any).__tiltActive
               ? ((ms as any).__tlx ?? ms.x)
@@ -1030,19 +1388,49 @@ const splashEl = document.getElementById("splash");
               : ms.y;
             spatial.update({
               sprite: ms,
-              minX: x,
-              minY: y,
-              maxX: x + CARD_W_GLOBAL,
-              maxY: y + CARD_H_GLOBAL,
+              ...cardBounds(ms, x, y),
             });
-          }),
+          });
+        },
         spatial,
         cardW: CARD_W_GLOBAL,
         cardH: CARD_H_GLOBAL,
         isPanning: () => panning,
         startMarquee: (global, additive) => marquee.start(global, additive),
+        onSplitStack: (s) => splitFromStack(s),
+        alignDrag: (starts, dX, dY, free) => {
+          // The dragged cards' combined box at the proposed delta
+          let box: GuideBox | null = null;
+          for (const st of starts) {
+            if (st.sprite.__hidden) continue;
+            const b = cardBounds(st.sprite, st.x0 + dX, st.y0 + dY);
+            box = box
+              ? {
+                  minX: Math.min(box.minX, b.minX),
+                  minY: Math.min(box.minY, b.minY),
+                  maxX: Math.max(box.maxX, b.maxX),
+                  maxY: Math.max(box.maxY, b.maxY),
+                }
+              : b;
+          }
+          const dragged = new Set(starts.map((st) => st.sprite));
+          const a = box
+            ? alignMove(box, (s) => dragged.has(s), () => false, free)
+            : null;
+          if (!a) return { dX, dY, keepX: free, keepY: free };
+          return {
+            dX: dX + a.dx,
+            dY: dY + a.dy,
+            keepX: a.snapX,
+            keepY: a.snapY,
+          };
+        },
       });
       sprites.push(...created);
+      // New copies may land on existing ones
+      markDuplicateStacksDirty();
+      markSpotlightDirty(created);
+      minimap?.invalidate();
       return created;
     } finally {
       SUPPRESS_SAVES = prevSuppress;
@@ -1071,6 +1459,8 @@ const splashEl = document.getElementById("splash");
   let persistence = createLocalPersistence({
     getSprites: () => sprites,
     getGroups: () => groups,
+    getNotes: () => [...notes.values()].map(noteRecord),
+    getConnectors: () => liveConnectorRecords(),
     spatial,
     getCanvasBounds,
     cardW: CARD_W_GLOBAL,
@@ -1081,6 +1471,8 @@ const splashEl = document.getElementById("splash");
   (window as any).__mtgFlushPositions = () => persistence.flushPositions();
   function applyStoredPositionsMemory() {
     persistence.applyStoredPositions();
+    // Saved rotation/scale may differ from what the sprites were created with
+    for (const s of sprites) applyCardTransform(s);
   }
   // Rehydrate previously imported Scryfall cards (raw JSON) and attach sprites
   async function rehydrateImportedCards() {
@@ -1104,6 +1496,10 @@ const splashEl = document.getElementById("splash");
         y: number;
         z?: number;
         group_id: number | null;
+        foil?: boolean;
+        rotation?: number;
+        scale?: number;
+        tags?: string | null;
       }[]
     > = new Map();
     if (saved && Array.isArray(saved.instances)) {
@@ -1117,6 +1513,10 @@ const splashEl = document.getElementById("splash");
           y: r.y ?? 0,
           z: typeof r.z === "number" ? r.z : undefined,
           group_id: r.group_id ?? null,
+          foil: !!r.foil,
+          rotation: normalizeRotation(r.rotation),
+          scale: normalizeScale(r.scale),
+          tags: typeof r.tags === "string" ? r.tags : null,
         });
         savedByScry.set(sid, arr);
       }
@@ -1148,6 +1548,9 @@ const splashEl = document.getElementById("splash");
         group_id?: number | null;
         card?: any;
         scryfall_id?: string | null;
+        foil?: boolean;
+        rotation?: number;
+        scale?: number;
       }[] = [];
       let cap = cap0;
       for (const [sid, pool] of savedByScry.entries()) {
@@ -1168,6 +1571,10 @@ const splashEl = document.getElementById("splash");
             y,
             z,
             group_id: gid,
+            foil: entry.foil,
+            rotation: entry.rotation,
+            scale: entry.scale,
+            tags: entry.tags,
           });
           bulkItems.push({
             id,
@@ -1177,6 +1584,9 @@ const splashEl = document.getElementById("splash");
             group_id: gid ?? undefined,
             card,
             scryfall_id: sid,
+            foil: entry.foil,
+            rotation: entry.rotation,
+            scale: entry.scale,
           });
           cap--;
         }
@@ -1207,17 +1617,14 @@ const splashEl = document.getElementById("splash");
     w: number;
     h: number;
   } | null {
-    if (!sprites.length && !(groups && groups.size)) return null;
+    if (!sprites.length && !(groups && groups.size) && !notes.size) return null;
     let minX = Number.POSITIVE_INFINITY;
     let minY = Number.POSITIVE_INFINITY;
     let maxX = Number.NEGATIVE_INFINITY;
     let maxY = Number.NEGATIVE_INFINITY;
     // Include card sprites
     for (const s of sprites) {
-      const x1 = s.x;
-      const y1 = s.y;
-      const x2 = s.x + CARD_W_GLOBAL;
-      const y2 = s.y + CARD_H_GLOBAL;
+      const { minX: x1, minY: y1, maxX: x2, maxY: y2 } = cardBounds(s);
       if (x1 < minX) minX = x1;
       if (y1 < minY) minY = y1;
       if (x2 > maxX) maxX = x2;
@@ -1234,6 +1641,12 @@ const splashEl = document.getElementById("splash");
       if (x2 > maxX) maxX = x2;
       if (y2 > maxY) maxY = y2;
     });
+    notes.forEach((nv) => {
+      minX = Math.min(minX, nv.gfx.x);
+      minY = Math.min(minY, nv.gfx.y);
+      maxX = Math.max(maxX, nv.gfx.x + nv.w);
+      maxY = Math.max(maxY, nv.gfx.y + nv.h);
+    });
     if (
       !Number.isFinite(minX) ||
       !Number.isFinite(minY) ||
@@ -1264,6 +1677,50 @@ const splashEl = document.getElementById("splash");
     );
   }
 
+  // World rectangle currently on screen
+  function currentViewRect(): Rect {
+    const s = world.scale.x || 1;
+    return {
+      x: -world.position.x / s,
+      y: -world.position.y / s,
+      w: window.innerWidth / s,
+      h: window.innerHeight / s,
+    };
+  }
+  function updateBookmarks(fn: (list: CameraBookmark[]) => CameraBookmark[]) {
+    const next = fn(getProjectBookmarks(currentProjectId));
+    setProjectBookmarks(currentProjectId, next);
+    __refreshBookmarkList?.();
+  }
+  // Save the current view into a slot (1..9), keeping the slot's name when overwriting
+  function saveBookmark(slot: number, name?: string) {
+    updateBookmarks((list) => {
+      const prev = list.find((b) => b.slot === slot);
+      const next: CameraBookmark = {
+        slot,
+        name: name?.trim() || prev?.name || `View ${slot}`,
+        ...currentViewRect(),
+      };
+      return [...list.filter((b) => b.slot !== slot), next];
+    });
+  }
+  // Glide to a bookmark, fitting its saved rectangle like focusViewOnContent does
+  function recallBookmark(slot: number, duration = 220): boolean {
+    const b = getProjectBookmarks(currentProjectId).find(
+      (x) => x.slot === slot,
+    );
+    if (!b) return false;
+    camera.animateTo(
+      {
+        bounds: { x: b.x, y: b.y, w: b.w, h: b.h },
+        viewport: { w: window.innerWidth, h: window.innerHeight },
+        margin: 0,
+      },
+      duration,
+    );
+    return true;
+  }
+
   // Rehydrate any previously imported cards (from IndexedDB/LS), then apply positions/groups
   await (async () => {
     const t = createPhaseTimer("startup:rehydrateImported");
@@ -1282,38 +1739,31 @@ const splashEl = document.getElementById("splash");
   // and relayout affected groups a single time to avoid O(n^2) redraw/metrics churn.
   function handleDroppedSprites(moved: CardSprite[]) {
     if (!moved || !moved.length) return;
+    // Cards may also join or leave groups below; the commit redraws every connector
+    markConnectorsDirty(moved);
     // 1) Update spatial bounds for all moved sprites
     for (const ms of moved) {
       spatial.update({
         sprite: ms,
-        minX: ms.x,
-        minY: ms.y,
-        maxX: ms.x + CARD_W_GLOBAL,
-        maxY: ms.y + CARD_H_GLOBAL,
+        ...cardBounds(ms),
       });
     }
-    // Helper: find group under sprite center
+    // Helper: find group under sprite center (innermost when nested)
     const hitGroup = (s: CardSprite): GroupVisual | null => {
-      const cx = s.x + CARD_W_GLOBAL / 2;
-      const cy = s.y + CARD_H_GLOBAL / 2;
-      for (const gv of groups.values()) {
-        if (
-          cx >= gv.gfx.x &&
-          cx <= gv.gfx.x + gv.w &&
-          cy >= gv.gfx.y &&
-          cy <= gv.gfx.y + gv.h
-        )
-          return gv;
-      }
-      return null;
+      const { w, h } = cardSize(s);
+      return topGroupAt(s.x + w / 2, s.y + h / 2);
     };
     // 2) Partition by target group and track old groups for removals
     const toAdd = new Map<number, CardSprite[]>();
     const toRemove = new Map<number, CardSprite[]>();
     const noGroup: CardSprite[] = [];
+    // Stacked groups snap their cards back into columns whenever members move or leave
+    const restack = new Set<GroupVisual>();
     for (const s of moved) {
       const target = hitGroup(s);
       const oldId = (s as any).__groupId as number | undefined;
+      const oldGroup = oldId ? groups.get(oldId) : undefined;
+      if (oldGroup && oldGroup.layoutMode !== "grid") restack.add(oldGroup);
       if (target) {
         if (!oldId || oldId !== target.id) {
           if (oldId && groups.has(oldId)) {
@@ -1345,7 +1795,7 @@ const splashEl = document.getElementById("splash");
         removeCardFromGroup(gv, s);
         (s as any).__groupId = undefined;
       }
-      updateGroupMetrics(gv);
+      updateGroupMetrics(gv, groups);
       drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
     });
     // 4) Apply additions to target groups
@@ -1360,19 +1810,35 @@ const splashEl = document.getElementById("splash");
         membershipUpdates.push({ id: s.__id, group_id: gv.id });
       }
       timer.mark("membership");
-      // Strategy: if many being added, do a single grid layout; else, place near drop point
+      // Strategy: if many being added, do a single grid layout; else, place near drop point.
+      // Groups holding nested groups always re-flow so cards never land on a child block,
+      // and stacked, sorted or duplicate-stacking groups re-flow so each card lands in its place.
       const MANY_THRESHOLD = 8;
-      if (spritesToAdd.length >= MANY_THRESHOLD) {
+      const ordered =
+        gv.layoutMode !== "grid" ||
+        gv.sortSpec.length > 0 ||
+        gv.stackDuplicates;
+      if (
+        spritesToAdd.length >= MANY_THRESHOLD ||
+        childGroups(gv, groups).length ||
+        ordered
+      ) {
+        if (ordered) {
+          extendSceneChange(pendingCardDrag, [...gv.items]);
+          restack.delete(gv);
+        }
         const items: SpatialItem[] = [];
-        layoutGroup(gv, sprites, (sp) => {
-          items.push({
-            sprite: sp,
-            minX: sp.x,
-            minY: sp.y,
-            maxX: sp.x + CARD_W_GLOBAL,
-            maxY: sp.y + CARD_H_GLOBAL,
-          });
-        });
+        layoutGroup(
+          gv,
+          sprites,
+          (sp) => {
+            items.push({
+              sprite: sp,
+              ...cardBounds(sp),
+            });
+          },
+          groups,
+        );
         timer.mark("layout");
         if (items.length) spatial.bulkUpdate(items);
         timer.mark("spatial");
@@ -1386,10 +1852,7 @@ const splashEl = document.getElementById("splash");
             (sp) =>
               spatial.update({
                 sprite: sp,
-                minX: sp.x,
-                minY: sp.y,
-                maxX: sp.x + CARD_W_GLOBAL,
-                maxY: sp.y + CARD_H_GLOBAL,
+                ...cardBounds(sp),
               }),
             s.x,
             s.y,
@@ -1406,11 +1869,21 @@ const splashEl = document.getElementById("splash");
         }
       }
       ensureMembersZOrder(gv);
-      updateGroupMetrics(gv);
+      updateGroupMetrics(gv, groups);
       drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
       timer.mark("z+metrics+draw");
+      if (gv.parentId != null) {
+        extendSceneChange(pendingCardDrag, groupTreeCards(outermostGroup(gv)));
+        relayoutAncestors(gv);
+      }
       timer.end({ added: spritesToAdd.length });
     });
+    // 4b) Re-flow stacked groups that lost cards or had cards dragged within them
+    restack.forEach((gv) => {
+      if (!groups.has(gv.id) || gv.collapsed) return;
+      extendSceneChange(pendingCardDrag, groupTreeCards(outermostGroup(gv)));
+      relayoutGroup(gv);
+    });
     // 5) Persist membership changes in one batch
     if (membershipUpdates.length) {
       InstancesRepo.updateMany(membershipUpdates);
@@ -1427,74 +1900,776 @@ const splashEl = document.getElementById("splash");
     for (const ms of moved) queuePosition(ms);
     scheduleLocalSave();
     if (toAdd.size || toRemove.size) scheduleGroupSave();
+    endNoteFollow();
+    refreshSmartGroups(pendingCardDrag);
+    commitSceneChange(pendingCardDrag);
+    pendingCardDrag = null;
+    // Copies dropped onto each other pile up right away
+    refreshDuplicateStacks();
   }
-  // Unified group deletion: reset member cards and remove the group
-  function deleteGroupById(id: number) {
-    const gv = groups.get(id);
-    if (!gv) return;
-    const updates: { id: number; group_id: null }[] = [];
-    gv.items.forEach((sp) => {
-      sp.__groupId = undefined;
-      sp.eventMode = "static";
-      sp.cursor = "pointer";
-      sp.visible = true;
-      updateCardSpriteAppearance(sp, SelectionStore.state.cards.has(sp));
-      updates.push({ id: sp.__id, group_id: null });
-    });
-    if (updates.length) InstancesRepo.updateMany(updates);
-    // Drop strong references to member sprites from the group
-    clearGroupMembers(gv);
-    gv.gfx.destroy();
-    groups.delete(id);
-    updateEmptyStateOverlay();
-    // If scene is now empty, drop session/image/texture caches to release memory
-    if (sprites.length === 0 && groups.size === 0) {
-      try {
-        clearTextureCaches();
-        clearSessionCaches();
-      } catch {}
+  // ---- Nested groups ----
+  // Topmost group containing a world point (nested groups sit above their parents)
+  function topGroupAt(
+    x: number,
+    y: number,
+    skip?: (gv: GroupVisual) => boolean,
+  ): GroupVisual | null {
+    let best: GroupVisual | null = null;
+    for (const gv of groups.values()) {
+      // Collapsed piles (and groups hidden inside them) don't take drops
+      if (gv.collapsed || !gv.gfx.visible) continue;
+      if (skip && skip(gv)) continue;
+      if (
+        x >= gv.gfx.x &&
+        x <= gv.gfx.x + gv.w &&
+        y >= gv.gfx.y &&
+        y <= gv.gfx.y + gv.h &&
+        (!best || (gv.gfx.zIndex || 0) > (best.gfx.zIndex || 0))
+      )
+        best = gv;
     }
+    return best;
   }
-
-  // Bulk deletion helper to avoid O(n^2) work and per-item cache churn
-  async function deleteSelectedCardsFast(list: CardSprite[]) {
-    if (!list || !list.length) return;
-    // Dedupe and create a lookup set
-    const toDelete = Array.from(new Set(list));
-    const delSet = new Set<CardSprite>(toDelete);
-
-    // 1) Partition deletions by group id for efficient membership removal
-    const byGroup = new Map<number, CardSprite[]>();
-    for (const s of toDelete) {
-      const gid = (s as any).__groupId as number | undefined;
-      if (gid && groups.has(gid)) {
-        const arr = byGroup.get(gid) || [];
-        arr.push(s);
-        byGroup.set(gid, arr);
-      }
+  function outermostGroup(gv: GroupVisual): GroupVisual {
+    let cur = gv;
+    const seen = new Set<number>([gv.id]);
+    while (cur.parentId != null) {
+      const p = groups.get(cur.parentId);
+      if (!p || seen.has(p.id)) break;
+      seen.add(p.id);
+      cur = p;
+    }
+    return cur;
+  }
+  // Cards in a group and all of its nested groups
+  function groupTreeCards(gv: GroupVisual): CardSprite[] {
+    return [gv, ...descendantGroups(gv, groups)].flatMap((g) => [...g.items]);
+  }
+  // A deck group's cards: the main deck (commanders included) and the cards of a
+  // nested group named "Sideboard"
+  function deckParts(gv: GroupVisual) {
+    const side = childGroups(gv, groups).find((c) => isSideboardName(c.name));
+    const sideboard = side ? groupTreeCards(side) : [];
+    const inSide = new Set(sideboard);
+    const main = groupTreeCards(gv).filter((s) => !inSide.has(s));
+    const ids = new Set(gv.deck?.commanders ?? []);
+    return { main, sideboard, commanders: main.filter((s) => ids.has(s.__id)) };
+  }
+  function deckViolations(gv: GroupVisual): DeckViolation<CardSprite>[] {
+    if (!gv.deck) return [];
+    return validateDeck(gv.deck.format, deckParts(gv), (s) => s.__card);
+  }
+  function deckBadge(gv: GroupVisual): DeckBadge | null {
+    if (!gv.deck) return null;
+    const label = DECK_FORMATS[gv.deck.format]?.label ?? gv.deck.format;
+    const issues = deckViolations(gv).length;
+    return issues
+      ? { text: `⚠ ${label} · ${issues}`, ok: false }
+      : { text: `✓ ${label}`, ok: true };
+  }
+  // Price/count totals (groupNode) plus deck validation for gv and every enclosing
+  // group, since a parent deck counts its nested sideboard. Callers redraw gv.
+  function updateGroupMetrics(
+    gv: GroupVisual,
+    groups: Map<number, GroupVisual>,
+  ) {
+    updateGroupTotals(gv, groups);
+    const seen = new Set<number>();
+    let cur: GroupVisual | undefined = gv;
+    while (cur && !seen.has(cur.id)) {
+      seen.add(cur.id);
+      const prev = cur.deckStatus;
+      cur.deckStatus = deckBadge(cur);
+      if (cur !== gv && prev?.text !== cur.deckStatus?.text)
+        drawGroup(cur, SelectionStore.state.groupIds.has(cur.id));
+      cur = cur.parentId != null ? groups.get(cur.parentId) : undefined;
+    }
+  }
+  // Re-flow every enclosing group after a nested group changed size or moved. Parents
+  // fit their content, so they shrink again when a child gets smaller or leaves.
+  function relayoutAncestors(gv: GroupVisual) {
+    const items: SpatialItem[] = [];
+    const seen = new Set<number>([gv.id]);
+    let parent = gv.parentId != null ? groups.get(gv.parentId) : undefined;
+    while (parent && !seen.has(parent.id)) {
+      seen.add(parent.id);
+      layoutGroup(
+        parent,
+        sprites,
+        (s) => {
+          items.push({
+            sprite: s,
+            ...cardBounds(s),
+          });
+        },
+        groups,
+        { fit: true },
+      );
+      parent =
+        parent.parentId != null ? groups.get(parent.parentId) : undefined;
     }
+    if (items.length) spatial.bulkUpdate(items);
+  }
+  // Returns false (and changes nothing) when parent is gv itself or nested inside it
+  function setGroupParent(gv: GroupVisual, parent: GroupVisual | null) {
+    if (parent && isWithinGroup(parent, gv.id, groups)) return false;
+    const prev = gv.parentId != null ? groups.get(gv.parentId) : undefined;
+    gv.parentId = parent ? parent.id : null;
+    persistGroupParent(gv.id, gv.parentId);
+    if (prev && prev !== parent) {
+      updateGroupMetrics(prev, groups);
+      drawGroup(prev, SelectionStore.state.groupIds.has(prev.id));
+    }
+    updateGroupMetrics(gv, groups);
+    return true;
+  }
+  // True when the group or any enclosing group is collapsed
+  function hiddenByCollapse(gv: GroupVisual): boolean {
+    const seen = new Set<number>();
+    let cur: GroupVisual | undefined = gv;
+    while (cur && !seen.has(cur.id)) {
+      if (cur.collapsed) return true;
+      seen.add(cur.id);
+      cur = cur.parentId != null ? groups.get(cur.parentId) : undefined;
+    }
+    return false;
+  }
+  // Hide or reveal the cards and nested groups behind collapsed piles in this tree.
+  // Hidden cards leave the spatial index, so hit tests, culling and texture streaming skip them.
+  function syncCollapsedMembers(gv: GroupVisual) {
+    const items: SpatialItem[] = [];
+    const visit = (g: GroupVisual, hide: boolean) => {
+      for (const sp of g.items) {
+        // Copies under a stack stay hidden when the group expands
+        const hidden = hide || !!sp.__stackOf;
+        if (!!sp.__hidden === hidden) continue;
+        sp.__hidden = hidden;
+        items.push({
+          sprite: sp,
+          ...cardBounds(sp),
+        });
+      }
+      updateGroupZoomPresentation(g, world.scale.x);
+      for (const c of childGroups(g, groups)) {
+        c.gfx.visible = !hide;
+        visit(c, hide || c.collapsed);
+      }
+    };
+    visit(gv, hiddenByCollapse(gv));
+    if (items.length) spatial.bulkUpdate(items);
+  }
+  function applyGroupCollapsed(gv: GroupVisual, collapsed: boolean) {
+    if (collapsed) {
+      gv.expandedSize = { w: gv.w, h: gv.h };
+      const size = collapsedGroupSize();
+      gv.w = size.w;
+      gv.h = size.h;
+    } else if (gv.expandedSize) {
+      gv.w = gv.expandedSize.w;
+      gv.h = gv.expandedSize.h;
+      gv.expandedSize = null;
+    }
+    gv.collapsed = collapsed;
+    persistGroupTransform(gv.id, {
+      x: gv.gfx.x,
+      y: gv.gfx.y,
+      w: gv.w,
+      h: gv.h,
+    });
+    persistGroupCollapsed(gv.id, collapsed, gv.expandedSize);
+    syncCollapsedMembers(gv);
+    // Hidden cards can't be acted on, so they leave the selection
+    const sel = SelectionStore.state;
+    if ([...sel.cards].some((s) => s.__hidden))
+      SelectionStore.replace({
+        cards: new Set([...sel.cards].filter((s) => !s.__hidden)),
+        groupIds: new Set(sel.groupIds),
+        noteIds: new Set(sel.noteIds),
+      });
+    drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
+    relayoutAncestors(gv);
+    scheduleGroupSave();
+    updateGroupInfoPanel();
+  }
+  // Collapse a group into a compact pile, or expand it back to its previous size
+  function setGroupCollapsed(gv: GroupVisual, collapsed: boolean) {
+    if (gv.collapsed === collapsed) return;
+    recordSceneChange(
+      collapsed ? "Collapse group" : "Expand group",
+      groupTreeCards(outermostGroup(gv)),
+      () => applyGroupCollapsed(gv, collapsed),
+    );
+  }
+  // Lay out a group's members again (plus enclosing groups) and refresh the spatial index
+  function relayoutGroup(gv: GroupVisual) {
+    const items: SpatialItem[] = [];
+    layoutGroup(
+      gv,
+      sprites,
+      (s) => {
+        items.push({
+          sprite: s,
+          ...cardBounds(s),
+        });
+      },
+      groups,
+    );
+    relayoutAncestors(gv);
+    if (items.length) spatial.bulkUpdate(items);
+    persistGroupTransform(gv.id, {
+      x: gv.gfx.x,
+      y: gv.gfx.y,
+      w: gv.w,
+      h: gv.h,
+    });
+  }
+  // Switch between the packed grid and column stacks; members re-flow immediately
+  function setGroupLayoutMode(gv: GroupVisual, mode: GroupLayoutMode) {
+    if (gv.layoutMode === mode) return;
+    recordSceneChange(
+      "Change group layout",
+      groupTreeCards(outermostGroup(gv)),
+      () => {
+        gv.layoutMode = mode;
+        persistGroupLayout(gv.id, mode);
+        relayoutGroup(gv);
+      },
+    );
+    scheduleLocalSave();
+    scheduleGroupSave();
+    updateGroupInfoPanel();
+  }
+  // Mark a group as a deck for a format (null = plain group); the header badge and
+  // info panel show what breaks the format's rules.
+  function setGroupDeck(
+    gv: GroupVisual,
+    deck: GroupDeck | null,
+    label = deck ? "Set deck format" : "Clear deck format",
+  ) {
+    const ch = beginSceneChange(label);
+    gv.deck = deck;
+    persistGroupDeck(gv.id, deck);
+    commitSceneChange(ch);
+    updateGroupMetrics(gv, groups);
+    drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
+    scheduleGroupSave();
+    updateGroupInfoPanel();
+  }
+  // Closest group holding the card (directly or nested) that is a deck
+  function deckGroupOf(card: CardSprite): GroupVisual | undefined {
+    let gv = card.__groupId ? groups.get(card.__groupId) : undefined;
+    const seen = new Set<number>();
+    while (gv && !seen.has(gv.id)) {
+      if (gv.deck) return gv;
+      seen.add(gv.id);
+      gv = gv.parentId != null ? groups.get(gv.parentId) : undefined;
+    }
+    return undefined;
+  }
+  function toggleCommander(gv: GroupVisual, card: CardSprite) {
+    if (!gv.deck) return;
+    const ids = gv.deck.commanders;
+    const on = !ids.includes(card.__id);
+    setGroupDeck(
+      gv,
+      {
+        ...gv.deck,
+        commanders: on
+          ? [...ids, card.__id]
+          : ids.filter((id) => id !== card.__id),
+      },
+      on ? "Set commander" : "Remove commander",
+    );
+  }
+  // Set the member sort (empty = manual). Sorted groups re-sort on every layout.
+  function setGroupSort(gv: GroupVisual, spec: GroupSortSpec) {
+    recordSceneChange("Sort group", groupTreeCards(outermostGroup(gv)), () => {
+      gv.sortSpec = spec;
+      persistGroupSort(gv.id, spec);
+      relayoutGroup(gv);
+    });
+    scheduleLocalSave();
+    scheduleGroupSave();
+    updateGroupInfoPanel();
+  }
+  // Pile copies of a card into one counted slot, or give every copy its own slot again
+  function setGroupStacking(gv: GroupVisual, on: boolean) {
+    if (gv.stackDuplicates === on) return;
+    recordSceneChange(
+      on ? "Stack duplicates" : "Unstack duplicates",
+      groupTreeCards(outermostGroup(gv)),
+      () => {
+        gv.stackDuplicates = on;
+        persistGroupStacking(gv.id, on);
+        relayoutGroup(gv);
+      },
+    );
+    scheduleLocalSave();
+    scheduleGroupSave();
+    updateGroupInfoPanel();
+  }
 
-    // 2) Remove from groups in bulk (filter order once)
-    const touchedGroups = new Set<number>();
-    byGroup.forEach((members, gid) => {
-      const gv = groups.get(gid);
-      if (!gv) return;
+  // ---- Smart groups ----
+  // Groups whose members follow a saved query (services/smartGroups.ts). Re-evaluated
+  // after imports, drops and deletes; pulled cards join like a drop, released ones go
+  // back to the scope group or to free space on the canvas.
+  function refreshSmartGroups(ch: SceneChange | null) {
+    if (History.isApplying) return;
+    const smart = [...groups.values()]
+      .filter((gv) => gv.smart)
+      .sort((a, b) => a.id - b.id);
+    if (!smart.length) return;
+    const byId = new Map(sprites.map((s) => [s.__id, s]));
+    // Membership as the planner sees it, kept current as cards move
+    const placed = sprites.map((s) => ({
+      id: s.__id,
+      groupId: s.__groupId ?? null,
+    }));
+    const placedById = new Map(placed.map((p) => [p.id, p]));
+    const info = new Map<number, SmartGroupInfo>();
+    groups.forEach((gv) =>
+      info.set(gv.id, { parentId: gv.parentId, smart: !!gv.smart }),
+    );
+    const updates = new Map<number, number | null>();
+    const gained = new Set<GroupVisual>();
+    const lost = new Set<GroupVisual>();
+    const pulled: CardSprite[] = [];
+    const freed: CardSprite[] = [];
+    const move = (s: CardSprite, to: GroupVisual | null) => {
+      const from = s.__groupId ? groups.get(s.__groupId) : undefined;
+      if (from) {
+        removeCardFromGroup(from, s);
+        lost.add(from);
+      }
+      s.__groupId = to ? to.id : undefined;
+      if (to) {
+        addCardToGroupOrdered(to, s, to.order.length);
+        gained.add(to);
+      }
+      updates.set(s.__id, to ? to.id : null);
+      const p = placedById.get(s.__id);
+      if (p) p.groupId = to ? to.id : null;
+    };
+    // A rule that tests membership (group:, in:) would trade cards back and forth
+    const preds = new Map(
+      smart.map((gv) => {
+        const q = gv.smart!.query;
+        return [gv.id, smartQueryError(q) ? null : parseScryfallQuery(q)];
+      }),
+    );
+    // Nothing a smart query may test changes as cards move between groups, so one canvas
+    // lookup serves every group and both passes
+    const ctxOf = canvasContextLookup();
+    // A second pass lets earlier groups take cards released by later ones
+    for (let pass = 0; pass < 2; pass++) {
       let changed = false;
-      for (const s of members) {
-        if (gv.items.delete(s)) changed = true;
-      }
-      if (gv.order && gv.order.length) {
-        const memSet = new Set(members);
-        const newOrder = gv.order.filter((sp) => !memSet.has(sp));
-        if (newOrder.length !== gv.order.length) {
-          gv.order.length = 0;
-          gv.order.push(...newOrder);
-          changed = true;
+      for (const gv of smart) {
+        const pred = preds.get(gv.id);
+        // A query that no longer parses (or can't drive it) keeps the members it has
+        if (!pred) continue;
+        const plan = planSmartGroup(
+          gv.id,
+          gv.smart!,
+          placed,
+          info,
+          (id) => {
+            const s = byId.get(id);
+            return !!s?.__card && pred(s.__card, ctxOf(s));
+          },
+        );
+        if (!plan.pull.length && !plan.release.length) continue;
+        changed = true;
+        const touched = new Set<GroupVisual>([gv]);
+        const moving = [
+          ...plan.pull.map((id) => byId.get(id)!),
+          ...plan.release.map((r) => byId.get(r.id)!),
+        ];
+        for (const s of moving) {
+          const from = s.__groupId ? groups.get(s.__groupId) : undefined;
+          if (from) touched.add(from);
+        }
+        for (const r of plan.release) {
+          const to = r.to != null ? groups.get(r.to) : undefined;
+          if (to) touched.add(to);
+        }
+        const roots = new Set([...touched].map(outermostGroup));
+        extendSceneChange(ch, [
+          ...moving,
+          ...[...roots].flatMap((r) => groupTreeCards(r)),
+        ]);
+        for (const id of plan.pull) {
+          const s = byId.get(id)!;
+          pulled.push(s);
+          move(s, gv);
+        }
+        for (const r of plan.release) {
+          const s = byId.get(r.id)!;
+          const to = r.to != null ? groups.get(r.to) : undefined;
+          move(s, to ?? null);
+          if (!to) freed.push(s);
         }
       }
-      if (changed) touchedGroups.add(gid);
+      if (!changed) break;
+    }
+    if (!updates.size) return;
+    // Released cards that left every group move to free space on the canvas
+    const loose = freed.filter((s) => !s.__groupId);
+    if (loose.length) {
+      const { positions } = planImportPositions(
+        loose.length,
+        buildPlacementContext(),
+      );
+      const items: SpatialItem[] = [];
+      loose.forEach((s, i) => {
+        s.x = positions[i].x;
+        s.y = positions[i].y;
+        s.__hidden = false;
+        s.eventMode = "static";
+        s.cursor = "pointer";
+        s.visible = true;
+        updateCardSpriteAppearance(s, SelectionStore.state.cards.has(s));
+        items.push({
+          sprite: s,
+          ...cardBounds(s),
+        });
+        queuePosition(s);
+      });
+      spatial.bulkUpdate(items);
+    }
+    const roots = new Set<GroupVisual>();
+    const relaid = new Set<GroupVisual>(gained);
+    lost.forEach((gv) => {
+      if (gv.layoutMode !== "grid") relaid.add(gv);
     });
-
+    relaid.forEach((gv) => {
+      if (groups.has(gv.id)) relayoutGroup(gv);
+    });
+    new Set([...gained, ...lost]).forEach((gv) => {
+      if (!groups.has(gv.id)) return;
+      ensureMembersZOrder(gv);
+      updateGroupMetrics(gv, groups);
+      drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
+      roots.add(outermostGroup(gv));
+    });
+    roots.forEach((gv) => syncCollapsedMembers(gv));
+    // Overlay: if zoom overlay active, hide new members immediately
+    if (world.scale.x <= 0.85) {
+      for (const s of pulled) {
+        if (!s.__groupId) continue;
+        s.eventMode = "none" as any;
+        s.cursor = "default";
+        s.visible = false;
+      }
+    }
+    for (const s of pulled) queuePosition(s);
+    InstancesRepo.updateMany(
+      [...updates].map(([id, group_id]) => ({ id, group_id })),
+    );
+    markDuplicateStacksDirty();
+    scheduleLocalSave();
+    scheduleGroupSave();
+  }
+  // Turn a group into a smart group (or back into a plain one, keeping its cards)
+  function setGroupSmart(gv: GroupVisual, rule: SmartGroupRule | null) {
+    if (JSON.stringify(gv.smart) === JSON.stringify(rule)) return;
+    const ch = beginSceneChange(
+      rule ? "Set smart group query" : "Clear smart group query",
+      groupTreeCards(outermostGroup(gv)),
+    );
+    gv.smart = rule;
+    persistGroupSmart(gv.id, rule);
+    refreshSmartGroups(ch);
+    drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
+    commitSceneChange(ch);
+    scheduleGroupSave();
+    updateGroupInfoPanel();
+  }
+  // New top-level smart group, filled right away and placed beside the existing layout
+  function createSmartGroup(rule: SmartGroupRule) {
+    const ch = beginSceneChange("Create smart group");
+    const gv = createGroupWithSpritesAndName([], rule.query, { silent: true });
+    gv.smart = rule;
+    persistGroupSmart(gv.id, rule);
+    refreshSmartGroups(ch);
+    placeGroupSmart(gv);
+    persistGroupTransform(gv.id, {
+      x: gv.gfx.x,
+      y: gv.gfx.y,
+      w: gv.w,
+      h: gv.h,
+    });
+    drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
+    commitSceneChange(ch);
+    const b = { x: gv.gfx.x, y: gv.gfx.y, w: gv.w, h: gv.h };
+    camera.fitBounds(b, { w: window.innerWidth, h: window.innerHeight });
+    return gv;
+  }
+
+  // ---- Duplicate stacks ----
+  // Re-plan which copies pile up (identical cards on the same spot), then hide the copies
+  // under each pile and reveal the ones that left. Runs after drops and, via the dirty
+  // flag, on the next frame after anything else co-locates or separates copies.
+  function refreshDuplicateStacks() {
+    // Groups opt in one by one; loose cards follow the canvas-wide setting
+    const looseStack = stackDuplicatesPref();
+    const changed = buildDuplicateStacks(
+      sprites,
+      (s) => {
+        const gv = s.__groupId ? groups.get(s.__groupId) : undefined;
+        return !!gv && hiddenByCollapse(gv);
+      },
+      (s) => {
+        const gv = s.__groupId ? groups.get(s.__groupId) : undefined;
+        return gv ? gv.stackDuplicates : looseStack;
+      },
+    );
+    const touched = new Set<GroupVisual>();
+    const items: SpatialItem[] = [];
+    for (const s of changed) {
+      const gv = s.__groupId ? groups.get(s.__groupId) : undefined;
+      if (gv) touched.add(gv);
+      else {
+        s.visible = !s.__hidden;
+        s.eventMode = s.__hidden ? "none" : "static";
+        s.cursor = s.__hidden ? "default" : "pointer";
+      }
+      items.push({
+        sprite: s,
+        ...cardBounds(s),
+      });
+    }
+    touched.forEach((gv) => updateGroupZoomPresentation(gv, world.scale.x));
+    if (items.length) spatial.bulkUpdate(items);
+    // Copies that joined or left a pile change where their arrows point
+    markConnectorsDirty(changed);
+    // Publish the re-planned stacks as a fresh selection (the store expands it)
+    const sel = SelectionStore.state;
+    if (expandStackSelection(new Set(sel.cards))) {
+      SelectionStore.replace({
+        cards: new Set(sel.cards),
+        groupIds: new Set(sel.groupIds),
+        noteIds: new Set(sel.noteIds),
+      });
+    }
+  }
+  // Copies under a stack are selected with the visible copy, so drag, delete and export
+  // cover the whole stack. Returns true when the set changed.
+  function expandStackSelection(cards: Set<CardSprite>): boolean {
+    let changed = false;
+    for (const s of [...cards]) {
+      if (s.__stackOf && !cards.has(s.__stackOf)) {
+        cards.delete(s);
+        changed = true;
+      }
+      for (const f of s.__stack ?? []) {
+        if (cards.has(f)) continue;
+        cards.add(f);
+        changed = true;
+      }
+    }
+    return changed;
+  }
+  // Applied by the store as each selection is made, before any listener runs
+  SelectionStore.setCardExpander(expandStackSelection);
+  // Arrows touching the selection are drawn highlighted
+  SelectionStore.on(() => markConnectorsDirty());
+  // Ctrl/Cmd+drag on a stack pulls off the top copy; the next copy takes its place
+  function splitFromStack(s: CardSprite) {
+    const rest = s.__stack;
+    if (!rest?.length) return;
+    s.__stack = undefined;
+    const [next, ...others] = rest
+      .slice()
+      .sort((a, b) => b.zIndex - a.zIndex || a.__id - b.__id);
+    next.__stackOf = undefined;
+    next.__stack = others.length ? others : undefined;
+    for (const f of others) f.__stackOf = next;
+    next.__hidden = false;
+    next.visible = true;
+    next.eventMode = "static";
+    next.cursor = "pointer";
+    spatial.update({
+      sprite: next,
+      ...cardBounds(next),
+    });
+    SelectionStore.replace({
+      cards: new Set([s]),
+      groupIds: new Set(),
+      noteIds: new Set(),
+    });
+  }
+  // Fan a loose stack out to the right so every copy can be handled again
+  function splitStack(rep: CardSprite) {
+    const copies = [rep, ...(rep.__stack ?? [])];
+    if (copies.length < 2) return;
+    const step = snap(CARD_W_GLOBAL / 4);
+    recordSceneChange("Split stack", copies, () => {
+      copies.forEach((s, i) => {
+        s.x = rep.x + i * step;
+        s.zIndex = rep.zIndex + i;
+        (s as any).__baseZ = s.zIndex;
+        queuePosition(s);
+      });
+      refreshDuplicateStacks();
+    });
+    scheduleLocalSave();
+  }
+  // Rotation (tap) and scale are per instance; the footprint keeps its top-left, so
+  // bounds, spatial entries and the repo are refreshed in one undoable step
+  function setCardTransforms(
+    list: CardSprite[],
+    label: string,
+    next: { rotation?: number; scale?: number },
+  ) {
+    if (!list.length) return;
+    // Groups re-flow around the new footprints, so their whole trees join the undo step
+    const touched = new Set<GroupVisual>();
+    for (const s of list) {
+      const gv = s.__groupId ? groups.get(s.__groupId) : undefined;
+      if (gv) touched.add(gv);
+    }
+    const scope = new Set(list);
+    touched.forEach((gv) =>
+      groupTreeCards(outermostGroup(gv)).forEach((s) => scope.add(s)),
+    );
+    recordSceneChange(label, [...scope], () => {
+      const items: SpatialItem[] = [];
+      for (const s of list) {
+        if (next.rotation != null) s.__rotation = next.rotation || undefined;
+        if (next.scale != null)
+          s.__scale = next.scale !== 1 ? next.scale : undefined;
+        applyCardTransform(s);
+        items.push({ sprite: s, ...cardBounds(s) });
+      }
+      spatial.bulkUpdate(items);
+      InstancesRepo.updateMany(
+        list.map((s) => ({
+          id: s.__id,
+          rotation: s.__rotation ?? 0,
+          scale: s.__scale ?? 1,
+        })),
+      );
+      touched.forEach((gv) => {
+        relayoutGroup(gv);
+        for (const s of gv.items) queuePosition(s);
+      });
+    });
+    if (touched.size) scheduleGroupSave();
+    scheduleLocalSave();
+  }
+  // Tap the selection, or untap it when every card is already tapped
+  function toggleTapped(list: CardSprite[]) {
+    const tap = list.some((s) => !isTapped(s));
+    setCardTransforms(list, tap ? "Tap" : "Untap", {
+      rotation: tap ? TAPPED_ROTATION : 0,
+    });
+  }
+  function toggleKeyCardScale(list: CardSprite[]) {
+    const enlarge = list.some((s) => (s.__scale ?? 1) === 1);
+    setCardTransforms(list, enlarge ? "Enlarge" : "Normal size", {
+      scale: enlarge ? KEY_CARD_SCALE : 1,
+    });
+  }
+  // Instance tags (what tag: searches): a comma separated list, asked for once and
+  // written to every card in the list
+  function editCardTags(list: CardSprite[]) {
+    if (!list.length) return;
+    const current = InstancesRepo.get(list[0].__id)?.tags ?? "";
+    const raw = prompt("Tags (comma separated)", current);
+    if (raw == null) return;
+    const tags = parseInstanceTags(raw).join(", ") || null;
+    const ch = beginSceneChange("Edit tags", list);
+    InstancesRepo.updateMany(list.map((s) => ({ id: s.__id, tags })));
+    markSpotlightDirty(list);
+    // tag: queries may pull or release these cards
+    refreshSmartGroups(ch);
+    commitSceneChange(ch);
+    scheduleLocalSave();
+  }
+
+  // Unified group deletion: reset member cards and remove the group
+  function deleteGroupById(id: number) {
+    const gv = groups.get(id);
+    if (!gv) return;
+    // Nested groups go with their parent (deepest first)
+    const nested = descendantGroups(gv, groups).reverse();
+    for (const c of nested) deleteGroupById(c.id);
+    const updates: { id: number; group_id: null }[] = [];
+    // Cards hidden behind a collapsed pile rejoin the spatial index
+    const revealed: SpatialItem[] = [];
+    gv.items.forEach((sp) => {
+      sp.__groupId = undefined;
+      sp.eventMode = "static";
+      sp.cursor = "pointer";
+      sp.visible = true;
+      updateCardSpriteAppearance(sp, SelectionStore.state.cards.has(sp));
+      updates.push({ id: sp.__id, group_id: null });
+      if (sp.__hidden)
+        revealed.push({
+          sprite: sp,
+          ...cardBounds(sp),
+        });
+    });
+    if (updates.length) InstancesRepo.updateMany(updates);
+    // Drop strong references to member sprites from the group
+    clearGroupMembers(gv);
+    if (revealed.length) spatial.bulkUpdate(revealed);
+    gv.gfx.destroy();
+    groups.delete(id);
+    const parent = gv.parentId != null ? groups.get(gv.parentId) : undefined;
+    if (parent) {
+      updateGroupMetrics(parent, groups);
+      drawGroup(parent, SelectionStore.state.groupIds.has(parent.id));
+    }
+    updateEmptyStateOverlay();
+    // If scene is now empty, drop session/image/texture caches to release memory
+    if (sprites.length === 0 && groups.size === 0) {
+      try {
+        clearTextureCaches();
+        clearSessionCaches();
+      } catch {}
+    }
+  }
+
+  // Bulk deletion helper to avoid O(n^2) work and per-item cache churn
+  async function deleteSelectedCardsFast(list: CardSprite[]) {
+    if (!list || !list.length) return;
+    // Dedupe and create a lookup set
+    const toDelete = Array.from(new Set(list));
+    const delSet = new Set<CardSprite>(toDelete);
+
+    // 1) Partition deletions by group id for efficient membership removal
+    const byGroup = new Map<number, CardSprite[]>();
+    for (const s of toDelete) {
+      const gid = (s as any).__groupId as number | undefined;
+      if (gid && groups.has(gid)) {
+        const arr = byGroup.get(gid) || [];
+        arr.push(s);
+        byGroup.set(gid, arr);
+      }
+    }
+
+    // 2) Remove from groups in bulk (filter order once)
+    const touchedGroups = new Set<number>();
+    byGroup.forEach((members, gid) => {
+      const gv = groups.get(gid);
+      if (!gv) return;
+      let changed = false;
+      for (const s of members) {
+        if (gv.items.delete(s)) changed = true;
+      }
+      if (gv.order && gv.order.length) {
+        const memSet = new Set(members);
+        const newOrder = gv.order.filter((sp) => !memSet.has(sp));
+        if (newOrder.length !== gv.order.length) {
+          gv.order.length = 0;
+          gv.order.push(...newOrder);
+          changed = true;
+        }
+      }
+      if (changed) touchedGroups.add(gid);
+    });
+
     // 3) Collect image URLs once (dedup) for cache/texture purge after detach
     const urlSet = new Set<string>();
     for (const s of toDelete) {
@@ -1537,6 +2712,7 @@ const splashEl = document.getElementById("splash");
       }
       sprites.length = write;
     }
+    markSpotlightDirty(toDelete);
 
     // Restore sorting and do one sort pass if needed
     world.sortableChildren = prevSortable;
@@ -1549,23 +2725,12 @@ const splashEl = document.getElementById("splash");
     touchedGroups.forEach((gid) => {
       const gv = groups.get(gid);
       if (gv) {
-        updateGroupMetrics(gv);
+        updateGroupMetrics(gv, groups);
         drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
       }
     });
 
-    // 8) Purge textures/caches once (deduped)
-    if (urlSet.size) {
-      const urls = Array.from(urlSet);
-      try {
-        purgeTextureUrls(urls);
-      } catch {}
-      try {
-        await purgeCacheForUrls(urls, { includePersistent: false } as any);
-      } catch {}
-    }
-
-    // 9) Repo + persistence (batched)
+    // 8) Repo + persistence (batched; kept synchronous so undo/redo can rely on it)
     try {
       InstancesRepo.deleteMany(toDelete.map((c) => c.__id));
     } catch {}
@@ -1583,11 +2748,544 @@ const splashEl = document.getElementById("splash");
       } catch {}
     }
     SelectionStore.clear();
+
+    // 9) Purge textures/caches once (deduped)
+    if (urlSet.size) {
+      const urls = Array.from(urlSet);
+      try {
+        purgeTextureUrls(urls);
+      } catch {}
+      try {
+        await purgeCacheForUrls(urls, { includePersistent: false } as any);
+      } catch {}
+    }
+  }
+  // ---- Undo / redo (scene snapshots) ----
+  // An edit records the cards it touches plus every group frame and note before and after; undo/redo
+  // re-applies one side and routes through the repos + local saves like a normal edit.
+  type CardSnap = {
+    id: number;
+    x: number;
+    y: number;
+    z: number;
+    group_id: number | null;
+    card: Card | null;
+    scryfall_id: string | null;
+    foil: boolean;
+    rotation: number;
+    scale: number;
+    tags: string | null;
+  };
+  type GroupSnap = {
+    id: number;
+    x: number;
+    y: number;
+    w: number;
+    h: number;
+    z: number;
+    name: string;
+    parent_id: number | null;
+    collapsed: boolean;
+    expanded: { w: number; h: number } | null;
+    layout: GroupLayoutMode;
+    sort: GroupSortSpec;
+    stack: boolean;
+    deck: GroupDeck | null;
+    smart: SmartGroupRule | null;
+    members: number[];
+  };
+  // null entries mean "absent" (deleted / not yet created)
+  type SceneSnap = {
+    cards: Map<number, CardSnap | null>;
+    groups: Map<number, GroupSnap | null>;
+    notes: Map<number, NoteRecord | null>;
+    connectors: Map<number, ConnectorRecord | null>;
+  };
+  type SceneChange = {
+    label: string;
+    mergeKey?: string;
+    cardIds: Set<number> | null; // null = whole scene
+    priorIds: Set<number>;
+    before: SceneSnap;
+  };
+  function snapCard(s: CardSprite): CardSnap {
+    const anyS: any = s as any;
+    // Floating (tilted) sprites keep their true top-left in __tlx/__tly
+    return {
+      id: s.__id,
+      x: anyS.__tiltActive ? (anyS.__tlx ?? s.x) : s.x,
+      y: anyS.__tiltActive ? (anyS.__tly ?? s.y) : s.y,
+      z: s.zIndex || 0,
+      group_id: s.__groupId ?? null,
+      card: s.__card ?? null,
+      scryfall_id: s.__scryfallId ?? null,
+      foil: !!s.__foil,
+      rotation: s.__rotation ?? 0,
+      scale: s.__scale ?? 1,
+      tags: InstancesRepo.get(s.__id)?.tags ?? null,
+    };
+  }
+  function snapGroup(gv: GroupVisual): GroupSnap {
+    return {
+      id: gv.id,
+      x: gv.gfx.x,
+      y: gv.gfx.y,
+      w: gv.w,
+      h: gv.h,
+      z: gv.gfx.zIndex || 0,
+      name: gv.name,
+      parent_id: gv.parentId,
+      collapsed: gv.collapsed,
+      expanded: gv.expandedSize ? { ...gv.expandedSize } : null,
+      layout: gv.layoutMode,
+      sort: gv.sortSpec,
+      stack: gv.stackDuplicates,
+      deck: gv.deck,
+      smart: gv.smart,
+      members: gv.order.map((s) => s.__id),
+    };
+  }
+  function captureScene(cardIds: Set<number> | null): SceneSnap {
+    const cards = new Map<number, CardSnap | null>();
+    for (const s of sprites)
+      if (!cardIds || cardIds.has(s.__id)) cards.set(s.__id, snapCard(s));
+    if (cardIds)
+      cardIds.forEach((id) => {
+        if (!cards.has(id)) cards.set(id, null);
+      });
+    const gs = new Map<number, GroupSnap | null>();
+    groups.forEach((gv) => gs.set(gv.id, snapGroup(gv)));
+    const ns = new Map<number, NoteRecord | null>();
+    notes.forEach((nv) => ns.set(nv.id, noteRecord(nv)));
+    const cs = new Map<number, ConnectorRecord | null>();
+    connectors.forEach((cv) => cs.set(cv.rec.id, cv.rec));
+    return { cards, groups: gs, notes: ns, connectors: cs };
+  }
+  function sameCardSnap(a: CardSnap | null, b: CardSnap | null) {
+    if (!a || !b) return a === b;
+    return (
+      a.x === b.x &&
+      a.y === b.y &&
+      a.z === b.z &&
+      a.group_id === b.group_id &&
+      a.rotation === b.rotation &&
+      a.scale === b.scale &&
+      a.tags === b.tags
+    );
+  }
+  function sameGroupSnap(a: GroupSnap | null, b: GroupSnap | null) {
+    if (!a || !b) return a === b;
+    return (
+      a.x === b.x &&
+      a.y === b.y &&
+      a.w === b.w &&
+      a.h === b.h &&
+      a.z === b.z &&
+      a.name === b.name &&
+      a.parent_id === b.parent_id &&
+      a.collapsed === b.collapsed &&
+      a.expanded?.w === b.expanded?.w &&
+      a.expanded?.h === b.expanded?.h &&
+      a.layout === b.layout &&
+      JSON.stringify(a.sort) === JSON.stringify(b.sort) &&
+      a.stack === b.stack &&
+      JSON.stringify(a.deck) === JSON.stringify(b.deck) &&
+      JSON.stringify(a.smart) === JSON.stringify(b.smart) &&
+      a.members.join(",") === b.members.join(",")
+    );
+  }
+  function sameNoteSnap(a: NoteRecord | null, b: NoteRecord | null) {
+    if (!a || !b) return a === b;
+    return (
+      a.x === b.x &&
+      a.y === b.y &&
+      a.w === b.w &&
+      a.h === b.h &&
+      a.z === b.z &&
+      a.text === b.text &&
+      a.color === b.color
+    );
+  }
+  // Start recording an edit. `scope` lists cards the edit may move/regroup/delete; cards created
+  // during the edit are picked up automatically. Returns null while undo/redo is applying.
+  function beginSceneChange(
+    label: string,
+    scope: CardSprite[] | "all" = [],
+    opts?: { mergeKey?: string },
+  ): SceneChange | null {
+    if (History.isApplying) return null;
+    const cardIds =
+      scope === "all" ? null : new Set(scope.map((s) => s.__id));
+    return {
+      label,
+      mergeKey: opts?.mergeKey,
+      cardIds,
+      priorIds: new Set(sprites.map((s) => s.__id)),
+      before: captureScene(cardIds),
+    };
+  }
+  // Record a synchronous edit as a single undo step
+  function recordSceneChange(
+    label: string,
+    scope: CardSprite[] | "all",
+    fn: () => void,
+  ) {
+    const ch = beginSceneChange(label, scope);
+    try {
+      fn();
+    } finally {
+      commitSceneChange(ch);
+    }
+  }
+  // Widen a pending change to cards it did not expect to touch (call before moving them)
+  function extendSceneChange(ch: SceneChange | null, cards: CardSprite[]) {
+    if (!ch || !ch.cardIds) return;
+    for (const s of cards) {
+      if (ch.cardIds.has(s.__id)) continue;
+      ch.cardIds.add(s.__id);
+      ch.before.cards.set(s.__id, snapCard(s));
+    }
+  }
+  function commitSceneChange(ch: SceneChange | null) {
+    markConnectorsDirty();
+    if (!ch || History.isApplying) return;
+    let ids = ch.cardIds;
+    if (ids) {
+      ids = new Set(ids);
+      for (const s of sprites) if (!ch.priorIds.has(s.__id)) ids.add(s.__id);
+    }
+    const after = captureScene(ids);
+    const undoSnap: SceneSnap = {
+      cards: new Map(),
+      groups: new Map(),
+      notes: new Map(),
+      connectors: new Map(),
+    };
+    const redoSnap: SceneSnap = {
+      cards: new Map(),
+      groups: new Map(),
+      notes: new Map(),
+      connectors: new Map(),
+    };
+    new Set([...ch.before.cards.keys(), ...after.cards.keys()]).forEach(
+      (id) => {
+        const b = ch.before.cards.get(id) ?? null;
+        const a = after.cards.get(id) ?? null;
+        if (sameCardSnap(b, a)) return;
+        undoSnap.cards.set(id, b);
+        redoSnap.cards.set(id, a);
+      },
+    );
+    new Set([...ch.before.groups.keys(), ...after.groups.keys()]).forEach(
+      (id) => {
+        const b = ch.before.groups.get(id) ?? null;
+        const a = after.groups.get(id) ?? null;
+        if (sameGroupSnap(b, a)) return;
+        undoSnap.groups.set(id, b);
+        redoSnap.groups.set(id, a);
+      },
+    );
+    new Set([...ch.before.notes.keys(), ...after.notes.keys()]).forEach(
+      (id) => {
+        const b = ch.before.notes.get(id) ?? null;
+        const a = after.notes.get(id) ?? null;
+        if (sameNoteSnap(b, a)) return;
+        undoSnap.notes.set(id, b);
+        redoSnap.notes.set(id, a);
+      },
+    );
+    new Set([
+      ...ch.before.connectors.keys(),
+      ...after.connectors.keys(),
+    ]).forEach((id) => {
+      const b = ch.before.connectors.get(id) ?? null;
+      const a = after.connectors.get(id) ?? null;
+      if (JSON.stringify(b) === JSON.stringify(a)) return;
+      undoSnap.connectors.set(id, b);
+      redoSnap.connectors.set(id, a);
+    });
+    if (
+      !undoSnap.cards.size &&
+      !undoSnap.groups.size &&
+      !undoSnap.notes.size &&
+      !undoSnap.connectors.size
+    )
+      return;
+    History.push({
+      label: ch.label,
+      mergeKey: ch.mergeKey,
+      undo: () => applySceneSnap(undoSnap),
+      redo: () => applySceneSnap(redoSnap),
+    });
+  }
+  function applySceneSnap(state: SceneSnap) {
+    const timer = createPhaseTimer("history:apply");
+    markConnectorsDirty();
+    const byId = new Map<number, CardSprite>();
+    for (const s of sprites) byId.set(s.__id, s);
+    // 1) Drop cards and groups that are absent in the target state
+    const doomed: CardSprite[] = [];
+    state.cards.forEach((cs, id) => {
+      const s = byId.get(id);
+      if (cs || !s) return;
+      doomed.push(s);
+      byId.delete(id);
+    });
+    if (doomed.length) void deleteSelectedCardsFast(doomed);
+    const removedGroups: number[] = [];
+    state.groups.forEach((gs, id) => {
+      if (gs || !groups.has(id)) return;
+      deleteGroupById(id);
+      removedGroups.push(id);
+    });
+    if (removedGroups.length) GroupsRepo.deleteMany(removedGroups);
+    // 2) Recreate cards that exist in the target state but not on the canvas
+    const revive: CardSnap[] = [];
+    state.cards.forEach((cs, id) => {
+      if (cs && !byId.has(id)) revive.push(cs);
+    });
+    if (revive.length) {
+      for (const cs of revive) {
+        try {
+          InstancesRepo.createWithId({
+            id: cs.id,
+            card_id: 1,
+            x: cs.x,
+            y: cs.y,
+            z: cs.z,
+            foil: cs.foil,
+            rotation: cs.rotation,
+            scale: cs.scale,
+            tags: cs.tags,
+          });
+        } catch {}
+      }
+      createSpritesBulk(
+        revive.map((cs) => ({
+          id: cs.id,
+          x: cs.x,
+          y: cs.y,
+          z: cs.z,
+          card: cs.card,
+          scryfall_id: cs.scryfall_id,
+          foil: cs.foil,
+          rotation: cs.rotation,
+          scale: cs.scale,
+        })),
+      ).forEach((s) => byId.set(s.__id, s));
+    }
+    // 3) Restore group frames (creating missing ones) and rebuild their membership
+    const touchedGroups = new Set<GroupVisual>();
+    state.groups.forEach((gs) => {
+      if (!gs) return;
+      let gv = groups.get(gs.id);
+      if (!gv) {
+        GroupsRepo.createWithId({
+          id: gs.id,
+          name: gs.name,
+          parent_id: gs.parent_id,
+          x: gs.x,
+          y: gs.y,
+          w: gs.w,
+          h: gs.h,
+        });
+        gv = createGroupVisual(gs.id, gs.x, gs.y, gs.w, gs.h);
+        groups.set(gs.id, gv);
+        world.addChild(gv.gfx);
+        attachResizeHandle(gv);
+        attachGroupInteractions(gv);
+      }
+      gv.gfx.x = gs.x;
+      gv.gfx.y = gs.y;
+      gv.w = gs.w;
+      gv.h = gs.h;
+      gv.gfx.zIndex = gs.z;
+      if (gv.name !== gs.name) {
+        gv.name = gs.name;
+        persistGroupRename(gv.id, gs.name);
+      }
+      if (gv.parentId !== gs.parent_id) {
+        // The previous parent loses this group's cards from its totals
+        const prevParent =
+          gv.parentId != null ? groups.get(gv.parentId) : undefined;
+        if (prevParent) touchedGroups.add(prevParent);
+        gv.parentId = gs.parent_id;
+        persistGroupParent(gv.id, gs.parent_id);
+      }
+      if (gv.collapsed !== gs.collapsed) {
+        gv.collapsed = gs.collapsed;
+        persistGroupCollapsed(gv.id, gs.collapsed, gs.expanded);
+      }
+      gv.expandedSize = gs.expanded ? { ...gs.expanded } : null;
+      if (gv.layoutMode !== gs.layout) {
+        gv.layoutMode = gs.layout;
+        persistGroupLayout(gv.id, gs.layout);
+      }
+      if (JSON.stringify(gv.sortSpec) !== JSON.stringify(gs.sort)) {
+        gv.sortSpec = gs.sort;
+        persistGroupSort(gv.id, gs.sort);
+      }
+      if (gv.stackDuplicates !== gs.stack) {
+        gv.stackDuplicates = gs.stack;
+        persistGroupStacking(gv.id, gs.stack);
+      }
+      if (JSON.stringify(gv.deck) !== JSON.stringify(gs.deck)) {
+        gv.deck = gs.deck;
+        persistGroupDeck(gv.id, gs.deck);
+      }
+      if (JSON.stringify(gv.smart) !== JSON.stringify(gs.smart)) {
+        gv.smart = gs.smart;
+        persistGroupSmart(gv.id, gs.smart);
+      }
+      for (const s of gv.order)
+        if (s.__groupId === gv.id) s.__groupId = undefined;
+      clearGroupMembers(gv);
+      for (const cid of gs.members) {
+        const s = byId.get(cid);
+        if (!s) continue;
+        const prev = s.__groupId ? groups.get(s.__groupId) : undefined;
+        if (prev && prev !== gv) {
+          removeCardFromGroup(prev, s);
+          touchedGroups.add(prev);
+        }
+        s.__groupId = gv.id;
+        addCardToGroupOrdered(gv, s, gv.order.length);
+      }
+      touchedGroups.add(gv);
+    });
+    // 4) Restore card positions / z / membership for groups not covered above
+    const cardUpdates: {
+      id: number;
+      x: number;
+      y: number;
+      z: number;
+      group_id: number | null;
+      rotation: number;
+      scale: number;
+      tags: string | null;
+    }[] = [];
+    const spatialItems: SpatialItem[] = [];
+    state.cards.forEach((cs) => {
+      if (!cs) return;
+      const s = byId.get(cs.id);
+      if (!s) return;
+      s.x = cs.x;
+      s.y = cs.y;
+      s.zIndex = cs.z;
+      (s as any).__baseZ = cs.z;
+      s.__rotation = cs.rotation || undefined;
+      s.__scale = cs.scale !== 1 ? cs.scale : undefined;
+      applyCardTransform(s);
+      const cur = s.__groupId ? groups.get(s.__groupId) : undefined;
+      const target = cs.group_id != null ? groups.get(cs.group_id) : undefined;
+      if (cur !== target) {
+        if (cur) {
+          removeCardFromGroup(cur, s);
+          touchedGroups.add(cur);
+        }
+        if (target && !state.groups.has(target.id)) {
+          addCardToGroupOrdered(target, s, target.order.length);
+          touchedGroups.add(target);
+        }
+      }
+      s.__groupId = target ? target.id : undefined;
+      if (!target) {
+        s.eventMode = "static";
+        s.cursor = "pointer";
+        s.visible = true;
+      }
+      updateCardSpriteAppearance(s, SelectionStore.state.cards.has(s));
+      spatialItems.push({
+        sprite: s,
+        ...cardBounds(s),
+      });
+      cardUpdates.push({
+        id: s.__id,
+        x: s.x,
+        y: s.y,
+        z: cs.z,
+        group_id: s.__groupId ?? null,
+        rotation: cs.rotation,
+        scale: cs.scale,
+        tags: cs.tags,
+      });
+      // Overwrite any pending (older) debounced position write for this card
+      queuePosition(s);
+    });
+    if (spatialItems.length) spatial.bulkUpdate(spatialItems);
+    if (cardUpdates.length) {
+      InstancesRepo.updateMany(cardUpdates);
+      // Also enqueue behind any pending debounced batch so stale writes can't land last
+      InstancesRepo.updateManyDebounced(cardUpdates);
+    }
+    // 5) Re-apply collapsed piles (membership may have changed), then redraw + persist
+    new Set(
+      [...touchedGroups]
+        .filter((gv) => groups.has(gv.id))
+        .map((gv) => outermostGroup(gv)),
+    ).forEach((root) => syncCollapsedMembers(root));
+    touchedGroups.forEach((gv) => {
+      if (!groups.has(gv.id)) return;
+      ensureMembersZOrder(gv);
+      updateGroupMetrics(gv, groups);
+      drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
+      updateGroupZoomPresentation(gv, world.scale.x);
+      persistGroupTransform(gv.id, {
+        x: gv.gfx.x,
+        y: gv.gfx.y,
+        w: gv.w,
+        h: gv.h,
+      });
+    });
+    // 6) Notes: drop, recreate or restore in place
+    let removedNotes = 0;
+    state.notes.forEach((rec, id) => {
+      if (!rec) {
+        if (notes.has(id)) removedNotes++;
+        removeNoteVisual(id);
+        return;
+      }
+      const nv = notes.get(id);
+      if (!nv) {
+        addNoteVisual(rec);
+        return;
+      }
+      nv.gfx.x = rec.x;
+      nv.gfx.y = rec.y;
+      nv.w = rec.w;
+      nv.h = rec.h;
+      nv.gfx.zIndex = rec.z;
+      nv.text = rec.text;
+      nv.color = rec.color;
+      drawNote(nv, SelectionStore.state.noteIds.has(id));
+    });
+    // 7) Connectors: records are replaced wholesale; the next frame redraws them
+    state.connectors.forEach((rec, id) => {
+      removeConnectorVisual(id);
+      if (rec) addConnectorVisual(rec);
+    });
+    // Drop selection entries that no longer exist
+    if (doomed.length || removedGroups.length || removedNotes)
+      SelectionStore.clear();
+    markDuplicateStacksDirty();
+    scheduleLocalSave();
+    scheduleGroupSave();
+    updateEmptyStateOverlay();
+    updateGroupInfoPanel();
+    timer.end({
+      cards: state.cards.size,
+      groups: state.groups.size,
+      notes: state.notes.size,
+      connectors: state.connectors.size,
+    });
   }
   // Memory mode group persistence helpers
   let lsGroupsTimer: any = null;
   let __groupSaveTouchFlag = false;
   function scheduleGroupSave(opts?: { touch?: boolean }) {
+    minimap?.invalidate();
+    // Membership, names or nesting changed: group:/in: results may differ
+    markSpotlightDirty();
     if (SUPPRESS_SAVES) return;
     if (lsGroupsTimer) return;
     // Default to touching updatedAt unless explicitly disabled
@@ -1607,9 +3305,19 @@ const splashEl = document.getElementById("splash");
       __groupSaveTouchFlag = false;
     }, 400);
   }
+  // Notes and connectors don't need groups, so a project without cards restores them too
+  function restoreMemoryAnnotations() {
+    if (!notes.size)
+      for (const rec of parseNoteRecords(memoryGroupsData?.notes))
+        addNoteVisual(rec);
+    if (!connectors.size)
+      for (const rec of parseConnectorRecords(memoryGroupsData?.connectors))
+        addConnectorVisual(rec);
+  }
   function restoreMemoryGroups() {
     if (groupsRestored) return;
     groupsRestored = true;
+    restoreMemoryAnnotations();
     const timer = createPhaseTimer("startup:restoreMemoryGroups");
     const idMap = new Map<number, CardSprite>();
     for (let i = 0; i < sprites.length; i++)
@@ -1631,7 +3339,20 @@ const splashEl = document.getElementById("splash");
         if (typeof gr.z === "number") {
           gv.gfx.zIndex = gr.z;
         }
-        // Collapse feature retired: ignoring persisted flag fully
+        if (typeof gr.parent_id === "number") gv.parentId = gr.parent_id;
+        if (isGroupLayoutMode(gr.layout)) gv.layoutMode = gr.layout;
+        gv.sortSpec = parseGroupSortSpec(gr.sort);
+        gv.stackDuplicates = gr.stack === true;
+        gv.deck = parseGroupDeck(gr.deck);
+        gv.smart = parseSmartGroupRule(gr.smart);
+        if (gr.collapsed === true) {
+          gv.collapsed = true;
+          const ex = gr.expanded;
+          gv.expandedSize =
+            ex && typeof ex.w === "number" && typeof ex.h === "number"
+              ? { w: ex.w, h: ex.h }
+              : null;
+        }
         groups.set(gid, gv);
         world.addChild(gv.gfx);
         attachResizeHandle(gv);
@@ -1655,6 +3376,12 @@ const splashEl = document.getElementById("splash");
         }
       }
     }
+    // Drop dangling or cyclic parent links
+    groups.forEach((gv) => {
+      if (gv.parentId == null) return;
+      const parent = groups.get(gv.parentId);
+      if (!parent || isWithinGroup(parent, gv.id, groups)) gv.parentId = null;
+    });
     // Finalize visuals/metrics and persist transforms
     groups.forEach((gv) => {
       ensureMembersZOrder(gv);
@@ -1662,7 +3389,7 @@ const splashEl = document.getElementById("splash");
       gv.order.forEach((sp) => {
         if (sp) (sp as any).__baseZ = sp.zIndex || (sp as any).__baseZ || 0;
       });
-      updateGroupMetrics(gv);
+      updateGroupMetrics(gv, groups);
       drawGroup(gv, false);
       persistGroupTransform(gv.id, {
         x: gv.gfx.x,
@@ -1670,7 +3397,18 @@ const splashEl = document.getElementById("splash");
         w: gv.w,
         h: gv.h,
       });
+      if (gv.collapsed) persistGroupCollapsed(gv.id, true, gv.expandedSize);
+      if (gv.layoutMode !== "grid") persistGroupLayout(gv.id, gv.layoutMode);
+      if (gv.sortSpec.length) persistGroupSort(gv.id, gv.sortSpec);
+      if (gv.stackDuplicates) persistGroupStacking(gv.id, true);
+      if (gv.deck) persistGroupDeck(gv.id, gv.deck);
+      if (gv.smart) persistGroupSmart(gv.id, gv.smart);
+    });
+    // Hide members of collapsed groups (whole trees, from the top)
+    groups.forEach((gv) => {
+      if (gv.parentId == null) syncCollapsedMembers(gv);
     });
+    markDuplicateStacksDirty();
     // Ensure new group creations won't collide with restored ids (memory only)
     const maxId = Math.max(...[...groups.keys(), 0]);
     (GroupsRepo as any).ensureNextId &&
@@ -1685,7 +3423,7 @@ const splashEl = document.getElementById("splash");
   if (sprites.length) {
     restoreMemoryGroups();
     tryInitialFit();
-  }
+  } else restoreMemoryAnnotations();
 
   world.sortableChildren = true;
 
@@ -1744,8 +3482,10 @@ const splashEl = document.getElementById("splash");
     function commit(save: boolean) {
       if (save) {
         const val = input.value.trim();
-        if (val) {
+        if (val && val !== gv.name) {
+          const ch = beginSceneChange("Rename group");
           gv.name = val;
+          commitSceneChange(ch);
           drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
           persistGroupRename(gv.id, val);
           // Flush names immediately so a quick reload preserves rename
@@ -1841,6 +3581,113 @@ const splashEl = document.getElementById("splash");
       "display:grid;grid-template-columns:auto 1fr;column-gap:calc(12px * var(--ui-scale));row-gap:calc(4px * var(--ui-scale));font-size:calc(16px * var(--ui-scale));min-width:calc(140px * var(--ui-scale));";
     scroll.appendChild(metrics);
 
+    // Deck statistics (curve, pips, types); refreshed through scheduleGroupMetrics
+    const stats = document.createElement("div");
+    stats.id = "group-info-stats";
+    stats.style.cssText =
+      "display:flex;flex-direction:column;gap:calc(6px * var(--ui-scale));font-size:calc(14px * var(--ui-scale));";
+    scroll.appendChild(stats);
+
+    // Layout mode: packed grid or column stacks
+    const layoutWrap = document.createElement("div");
+    layoutWrap.style.cssText =
+      "display:flex;align-items:center;gap:calc(8px * var(--ui-scale));font-size:calc(16px * var(--ui-scale));";
+    const layoutLabel = document.createElement("label");
+    layoutLabel.textContent = "Layout";
+    layoutLabel.style.opacity = "0.65";
+    const layoutSelect = document.createElement("select");
+    layoutSelect.id = "group-info-layout";
+    layoutSelect.className = "ui-input";
+    for (const { mode, label } of GROUP_LAYOUT_MODES)
+      layoutSelect.add(new Option(label, mode));
+    layoutSelect.onchange = () => {
+      const gv = currentPanelGroup();
+      const mode = layoutSelect.value;
+      if (gv && isGroupLayoutMode(mode)) setGroupLayoutMode(gv, mode);
+    };
+    layoutWrap.append(layoutLabel, layoutSelect);
+    scroll.appendChild(layoutWrap);
+
+    // Deck format and the rule violations it reports
+    const deckWrap = document.createElement("div");
+    deckWrap.style.cssText = layoutWrap.style.cssText;
+    const deckLabel = document.createElement("label");
+    deckLabel.textContent = "Deck";
+    deckLabel.style.opacity = "0.65";
+    const deckSelect = document.createElement("select");
+    deckSelect.id = "group-info-deck";
+    deckSelect.className = "ui-input";
+    deckSelect.add(new Option("Not a deck", ""));
+    for (const [format, rules] of Object.entries(DECK_FORMATS))
+      deckSelect.add(new Option(rules.label, format));
+    deckSelect.onchange = () => {
+      const gv = currentPanelGroup();
+      if (!gv) return;
+      const format = deckSelect.value;
+      setGroupDeck(
+        gv,
+        format ? { format, commanders: gv.deck?.commanders ?? [] } : null,
+      );
+    };
+    deckWrap.append(deckLabel, deckSelect);
+    scroll.appendChild(deckWrap);
+    const deckIssues = document.createElement("div");
+    deckIssues.id = "group-info-deck-issues";
+    deckIssues.style.cssText =
+      "display:flex;flex-direction:column;gap:calc(4px * var(--ui-scale));font-size:calc(14px * var(--ui-scale));";
+    scroll.appendChild(deckIssues);
+
+    // Smart group: a saved query keeps the members in sync with a scope of the canvas
+    const smartWrap = document.createElement("div");
+    smartWrap.style.cssText = layoutWrap.style.cssText;
+    const smartLabel = document.createElement("label");
+    smartLabel.textContent = "Smart";
+    smartLabel.style.opacity = "0.65";
+    const smartInput = document.createElement("input");
+    smartInput.id = "group-info-smart";
+    smartInput.type = "text";
+    smartInput.className = "ui-input";
+    smartInput.maxLength = SMART_QUERY_MAX;
+    smartInput.placeholder = "Query, e.g. t:land -t:basic";
+    smartInput.spellcheck = false;
+    smartInput.setAttribute("autocapitalize", "off");
+    smartInput.setAttribute("autocorrect", "off");
+    smartInput.style.flex = "1";
+    smartInput.style.minWidth = "0";
+    const smartScope = document.createElement("select");
+    smartScope.id = "group-info-smart-scope";
+    smartScope.className = "ui-input";
+    smartScope.title = "Where the query takes cards from";
+    smartWrap.append(smartLabel, smartInput, smartScope);
+    scroll.appendChild(smartWrap);
+    const smartNote = document.createElement("div");
+    smartNote.id = "group-info-smart-note";
+    smartNote.style.cssText =
+      "opacity:.65;font-size:calc(13px * var(--ui-scale));";
+    scroll.appendChild(smartNote);
+    function commitSmart() {
+      const gv = currentPanelGroup();
+      if (!gv) return;
+      setGroupSmart(
+        gv,
+        parseSmartGroupRule({
+          query: smartInput.value,
+          scope: parseScopeValue(smartScope.value),
+        }),
+      );
+    }
+    smartInput.addEventListener("keydown", (e) => {
+      if (e.key === "Enter") {
+        commitSmart();
+        smartInput.blur();
+      } else if (e.key === "Escape") {
+        smartInput.value = currentPanelGroup()?.smart?.query ?? "";
+        smartInput.blur();
+      }
+    });
+    smartInput.addEventListener("blur", () => commitSmart());
+    smartScope.onchange = () => commitSmart();
+
     // Actions
     const actions = document.createElement("div");
     actions.style.cssText =
@@ -1860,22 +3707,30 @@ const splashEl = document.getElementById("splash");
       const timer = createPhaseTimer("group-auto-pack(panel)");
       const gv = currentPanelGroup();
       if (!gv) return;
+      const ch = beginSceneChange(
+        "Auto-pack group",
+        groupTreeCards(outermostGroup(gv)),
+      );
       const items: SpatialItem[] = [];
-      autoPackGroup(gv, sprites, (s) => {
-        items.push({
-          sprite: s,
-          minX: s.x,
-          minY: s.y,
-          maxX: s.x + CARD_W_GLOBAL,
-          maxY: s.y + CARD_H_GLOBAL,
-        });
-      });
+      autoPackGroup(
+        gv,
+        sprites,
+        (s) => {
+          items.push({
+            sprite: s,
+            ...cardBounds(s),
+          });
+        },
+        groups,
+      );
+      relayoutAncestors(gv);
       timer.mark("auto-pack");
       if (items.length) spatial.bulkUpdate(items);
       timer.mark("spatial");
-      updateGroupMetrics(gv);
+      updateGroupMetrics(gv, groups);
       drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
       timer.mark("metrics+draw");
+      commitSceneChange(ch);
       scheduleGroupSave();
       updateGroupInfoPanel();
       timer.end({ cards: gv.items.size });
@@ -1883,7 +3738,10 @@ const splashEl = document.getElementById("splash");
     const deleteBtn = makeBtn("Delete", () => {
       const gv = currentPanelGroup();
       if (!gv) return;
+      const ch = beginSceneChange("Delete group", groupTreeCards(gv));
       deleteGroupById(gv.id);
+      refreshSmartGroups(ch);
+      commitSceneChange(ch);
       SelectionStore.clear();
       scheduleGroupSave();
       updateGroupInfoPanel();
@@ -1905,7 +3763,9 @@ const splashEl = document.getElementById("splash");
       if (!gv) return;
       const v = nameInput.value.trim();
       if (v && v !== gv.name) {
+        const ch = beginSceneChange("Rename group");
         gv.name = v;
+        commitSceneChange(ch);
         drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
         persistGroupRename(gv.id, v);
         // Flush names immediately so a quick reload preserves rename
@@ -1953,14 +3813,250 @@ const splashEl = document.getElementById("splash");
         vEl.textContent = v;
         metrics.append(kEl, vEl);
       };
-      updateGroupMetrics(gv);
-      addRow("Cards", gv.items.size.toString());
-      addRow("Price", `$${gv.totalPrice.toFixed(2)}`);
+      updateGroupMetrics(gv, groups);
+      addRow("Cards", (gv.items.size + gv.nestedCount).toString());
+      addRow("Price", `$${(gv.totalPrice + gv.nestedPrice).toFixed(2)}`);
+      const nested = descendantGroups(gv, groups).length;
+      if (nested) addRow("Nested groups", nested.toString());
+      addRow("Sort", describeGroupSort(gv.sortSpec));
+    }
+    renderGroupStats(gv);
+    const layoutSelect = panel.querySelector(
+      "#group-info-layout",
+    ) as HTMLSelectElement | null;
+    if (layoutSelect) layoutSelect.value = gv.layoutMode;
+    const deckSelect = panel.querySelector(
+      "#group-info-deck",
+    ) as HTMLSelectElement | null;
+    if (deckSelect) deckSelect.value = gv.deck?.format ?? "";
+    renderDeckIssues(gv);
+    renderSmartRule(gv);
+  }
+  // Scope <select> values: "ungrouped", "canvas" or "group:<id>"
+  function scopeValue(scope: SmartGroupScope): string {
+    return scope.kind === "group" ? `group:${scope.id}` : scope.kind;
+  }
+  function parseScopeValue(v: string): SmartGroupScope {
+    if (v === "canvas") return { kind: "canvas" };
+    const id = v.startsWith("group:") ? Number(v.slice(6)) : NaN;
+    return Number.isInteger(id) ? { kind: "group", id } : { kind: "ungrouped" };
+  }
+  function renderSmartRule(gv: GroupVisual) {
+    const panel = groupInfoPanel;
+    const input = panel?.querySelector(
+      "#group-info-smart",
+    ) as HTMLInputElement | null;
+    const select = panel?.querySelector(
+      "#group-info-smart-scope",
+    ) as HTMLSelectElement | null;
+    const note = panel?.querySelector(
+      "#group-info-smart-note",
+    ) as HTMLDivElement | null;
+    if (!input || !select || !note) return;
+    if (document.activeElement !== input) input.value = gv.smart?.query ?? "";
+    // Any group outside this one's tree can be a source
+    const own = new Set([gv, ...descendantGroups(gv, groups)]);
+    select.innerHTML = "";
+    select.add(new Option("Ungrouped", "ungrouped"));
+    select.add(new Option("Whole canvas", "canvas"));
+    [...groups.values()]
+      .filter((g) => !own.has(g))
+      .sort((a, b) => a.name.localeCompare(b.name))
+      .forEach((g) =>
+        select.add(new Option(`From: ${g.name}`, `group:${g.id}`)),
+      );
+    const scope = gv.smart?.scope ?? { kind: "ungrouped" };
+    const value = scopeValue(scope);
+    if (![...select.options].some((o) => o.value === value))
+      select.add(new Option("From: (deleted group)", value));
+    select.value = value;
+    // A query that stopped parsing (or tests membership) keeps its members; say why
+    const error = gv.smart ? smartQueryError(gv.smart.query) : null;
+    note.style.color = error ? "#e5a03a" : "";
+    if (error) note.textContent = `⚠ ${error}`;
+    else if (gv.smart) {
+      const from = describeSmartScope(scope, (id) => groups.get(id)?.name);
+      note.textContent = `Holds every card matching the query from ${from}.`;
+    } else
+      note.textContent =
+        "Enter a query to keep this group filled automatically.";
+  }
+  // Commander line plus one row per violation; rows with cards select them
+  function renderDeckIssues(gv: GroupVisual) {
+    const el = groupInfoPanel?.querySelector(
+      "#group-info-deck-issues",
+    ) as HTMLDivElement | null;
+    if (!el) return;
+    el.innerHTML = "";
+    if (!gv.deck) return;
+    const rules = DECK_FORMATS[gv.deck.format];
+    const addLine = (text: string, color: string, cards?: CardSprite[]) => {
+      const row = document.createElement("div");
+      row.textContent = text;
+      row.style.color = color;
+      if (cards?.length) {
+        row.style.cursor = "pointer";
+        row.title = "Select these cards";
+        row.onclick = () => selectStatCards(gv, cards);
+      }
+      el.appendChild(row);
+    };
+    if (rules?.commander) {
+      const ids = new Set(gv.deck.commanders);
+      const cmds = groupTreeCards(gv).filter((s) => ids.has(s.__id));
+      addLine(
+        cmds.length
+          ? `Commander: ${cmds.map((s) => s.__card?.name ?? "?").join(", ")}`
+          : "Choose a commander from a card's right-click menu",
+        "var(--panel-fg)",
+        cmds,
+      );
+    }
+    const issues = deckViolations(gv);
+    if (!issues.length) {
+      addLine(`✓ Valid ${rules?.label ?? ""} deck`, "var(--success-accent)");
+      return;
     }
+    for (const v of issues) addLine(`⚠ ${v.message}`, "#e5a03a", v.items);
   }
   function hideGroupInfoPanel() {
     if (groupInfoPanel) groupInfoPanel.style.display = "none";
   }
+  const PIP_COLORS: Record<string, string> = {
+    W: "#f4eec8",
+    U: "#3b82c4",
+    B: "#5b4b45",
+    R: "#d9483b",
+    G: "#2f8f4e",
+    C: "#a8a29e",
+  };
+  // Select a chart segment's cards; the group stays selected so the panel stays open
+  function selectStatCards(gv: GroupVisual, cards: CardSprite[]) {
+    SelectionStore.replace({
+      cards: new Set(cards.filter((s) => !s.__hidden)),
+      groupIds: new Set([gv.id]),
+      noteIds: new Set(),
+    });
+  }
+  function renderGroupStats(gv: GroupVisual) {
+    const el = groupInfoPanel?.querySelector(
+      "#group-info-stats",
+    ) as HTMLDivElement | null;
+    if (!el) return;
+    el.innerHTML = "";
+    const stats = computeDeckStats(groupTreeCards(gv), (s) => s.__card);
+    if (!stats.total) return;
+    const section = (title: string) => {
+      const h = document.createElement("div");
+      h.textContent = title;
+      h.style.cssText =
+        "opacity:.65;margin-top:calc(6px * var(--ui-scale));font-size:calc(12px * var(--ui-scale));text-transform:uppercase;letter-spacing:.5px;";
+      el.appendChild(h);
+    };
+    const clickable = (
+      node: HTMLElement,
+      b: StatBucket<CardSprite>,
+      title: string,
+    ) => {
+      node.title = title;
+      node.style.cursor = "pointer";
+      node.onclick = () => selectStatCards(gv, b.items);
+    };
+
+    const summary = document.createElement("div");
+    summary.style.cssText =
+      "display:grid;grid-template-columns:auto 1fr;column-gap:calc(12px * var(--ui-scale));font-size:calc(16px * var(--ui-scale));";
+    const addRow = (k: string, v: string, onClick?: () => void) => {
+      const kEl = document.createElement("div");
+      kEl.textContent = k;
+      kEl.style.opacity = "0.65";
+      const vEl = document.createElement("div");
+      vEl.textContent = v;
+      if (onClick) {
+        vEl.style.cursor = "pointer";
+        vEl.onclick = onClick;
+      }
+      summary.append(kEl, vEl);
+    };
+    addRow(
+      "Avg mana value",
+      stats.avgManaValue != null ? stats.avgManaValue.toFixed(2) : "–",
+    );
+    addRow("Lands", stats.lands.length.toString(), () =>
+      selectStatCards(gv, stats.lands),
+    );
+    addRow("Color identity", describeIdentity(stats.identity));
+    el.appendChild(summary);
+
+    // Mana curve: one bar per mana value (lands excluded)
+    if (stats.curve.length) {
+      section("Mana curve");
+      const curve = document.createElement("div");
+      curve.style.cssText =
+        "display:flex;align-items:flex-end;gap:calc(4px * var(--ui-scale));height:calc(96px * var(--ui-scale));";
+      const max = Math.max(...stats.curve.map((b) => b.count));
+      for (const b of stats.curve) {
+        const col = document.createElement("div");
+        col.style.cssText =
+          "flex:1;display:flex;flex-direction:column;align-items:center;justify-content:flex-end;height:100%;font-size:calc(12px * var(--ui-scale));";
+        const count = document.createElement("div");
+        count.textContent = b.count ? String(b.count) : "";
+        const bar = document.createElement("div");
+        bar.style.cssText = `width:100%;height:${(b.count / max) * 60}%;min-height:${b.count ? 2 : 0}px;background:var(--panel-accent);border-radius:calc(3px * var(--ui-scale)) calc(3px * var(--ui-scale)) 0 0;`;
+        const label = document.createElement("div");
+        label.textContent = b.label;
+        label.style.opacity = "0.65";
+        col.append(count, bar, label);
+        if (b.count) clickable(col, b, `${b.count} at mana value ${b.label}`);
+        curve.appendChild(col);
+      }
+      el.appendChild(curve);
+    }
+
+    // Colored pips: one segment per color, sized by symbol count
+    if (stats.pips.length) {
+      section("Mana symbols");
+      const bar = document.createElement("div");
+      bar.style.cssText =
+        "display:flex;height:calc(14px * var(--ui-scale));border-radius:calc(7px * var(--ui-scale));overflow:hidden;border:1px solid var(--panel-border);";
+      const legend = document.createElement("div");
+      legend.style.cssText =
+        "display:flex;flex-wrap:wrap;gap:calc(4px * var(--ui-scale)) calc(12px * var(--ui-scale));font-size:calc(13px * var(--ui-scale));";
+      for (const b of stats.pips) {
+        const title = `${b.count} {${b.label}} on ${b.items.length} cards`;
+        const seg = document.createElement("div");
+        seg.style.cssText = `flex:${b.count};background:${PIP_COLORS[b.key]};`;
+        clickable(seg, b, title);
+        bar.appendChild(seg);
+        const item = document.createElement("span");
+        item.innerHTML = `<span style="display:inline-block;width:.8em;height:.8em;border-radius:50%;vertical-align:-1px;margin-right:4px;background:${PIP_COLORS[b.key]};"></span>${b.label} ${b.count}`;
+        clickable(item, b, title);
+        legend.appendChild(item);
+      }
+      el.append(bar, legend);
+    }
+
+    // Type breakdown: one row per primary type
+    section("Types");
+    const types = document.createElement("div");
+    types.style.cssText =
+      "display:grid;grid-template-columns:auto 1fr auto;align-items:center;column-gap:calc(8px * var(--ui-scale));row-gap:calc(2px * var(--ui-scale));";
+    const maxType = Math.max(...stats.types.map((b) => b.count));
+    for (const b of stats.types) {
+      const label = document.createElement("div");
+      label.textContent = b.label;
+      const track = document.createElement("div");
+      const fill = document.createElement("div");
+      fill.style.cssText = `height:calc(8px * var(--ui-scale));width:${(b.count / maxType) * 100}%;background:var(--panel-accent);border-radius:calc(4px * var(--ui-scale));`;
+      track.appendChild(fill);
+      const count = document.createElement("div");
+      count.textContent = String(b.count);
+      for (const n of [label, track, count])
+        clickable(n, b, `${b.count} ${b.label}`);
+      types.append(label, track, count);
+    }
+    el.appendChild(types);
+  }
 
   // ---- Card Info Side Pane ----
   let cardInfoPanel: HTMLDivElement | null = null;
@@ -1996,7 +4092,8 @@ const splashEl = document.getElementById("splash");
   }
   function updateCardInfoPanel() {
     // Quick path: avoid doing heavy DOM work synchronously on selection; details are deferred.
-    const ids = SelectionStore.getCards();
+    // Copies under a stack ride along with the visible copy
+    const ids = SelectionStore.getCards().filter((s) => !s.__stackOf);
     // Only show when exactly 1 card is selected (ignore when groups selected or multi-selection)
     if (ids.length !== 1) {
       hideCardInfoPanel();
@@ -2300,43 +4397,12 @@ const splashEl = document.getElementById("splash");
     if (Object.prototype.hasOwnProperty.call(__firstYearFetches, key))
       return __firstYearFetches[key];
     __firstYearFetches[key] = (async () => {
-      let url = String((card as any)?.prints_search_uri || "");
-      if (url) {
-        try {
-          const u = new URL(url);
-          u.searchParams.set("order", "released");
-          u.searchParams.set("dir", "asc");
-          u.searchParams.set("unique", "prints");
-          url = u.toString();
-        } catch {}
-      } else if (oracleId) {
-        url = `https://api.scryfall.com/cards/search?q=oracleid:${encodeURIComponent(oracleId)}&order=released&dir=asc&unique=prints`;
-      } else if (card?.name) {
-        url = `https://api.scryfall.com/cards/search?q=%21${encodeURIComponent(String(card.name))}&order=released&dir=asc&unique=prints`;
-      } else {
-        return null;
-      }
-      try {
-        const res = await fetch(url, {
-          headers: { Accept: "application/json" },
-        });
-        if (!res.ok) return null;
-        const json: any = await res.json();
-        const data: any[] = Array.isArray(json?.data) ? json.data : [];
-        const first = data.length ? data[0] : null;
-        const y = first?.released_at
-          ? new Date(first.released_at).getUTCFullYear()
-          : card?.released_at
-            ? new Date(card.released_at).getUTCFullYear()
-            : null;
-        if (y != null && oracleId) {
-          __firstYearCache[oracleId] = y;
-          saveFirstYearCacheToStorageSoon();
-        }
-        return y ?? null;
-      } catch {
-        return null;
+      const y = await cardSource.firstPrintYear(card);
+      if (y != null && oracleId) {
+        __firstYearCache[oracleId] = y;
+        saveFirstYearCacheToStorageSoon();
       }
+      return y;
     })();
     return __firstYearFetches[key];
   }
@@ -2348,12 +4414,7 @@ const splashEl = document.getElementById("splash");
     if (Object.prototype.hasOwnProperty.call(__setIconFetches, k))
       return __setIconFetches[k];
     __setIconFetches[k] = (async () => {
-      const res = await fetch(
-        `https://api.scryfall.com/sets/${encodeURIComponent(k)}`,
-      );
-      if (!res.ok) return null;
-      const json: any = await res.json();
-      const uri = json && json.icon_svg_uri ? String(json.icon_svg_uri) : null;
+      const uri = await cardSource.setIconUri(k);
       if (uri) {
         __setIconCache[k] = uri;
         saveSetIconCacheToStorage();
@@ -2608,12 +4669,22 @@ const splashEl = document.getElementById("splash");
       __metricsScheduled = false;
       const ids = Array.from(__pendingMetricGroups);
       __pendingMetricGroups.clear();
+      const panelGroup = currentPanelGroup();
+      let panelStale = false;
       for (const id of ids) {
         const g = groups.get(id);
         if (!g) continue;
-        updateGroupMetrics(g);
+        updateGroupMetrics(g, groups);
         drawGroup(g, SelectionStore.state.groupIds.has(g.id));
+        // The panel's stats cover nested groups too
+        if (
+          panelGroup &&
+          (g === panelGroup ||
+            descendantGroups(panelGroup, groups).includes(g))
+        )
+          panelStale = true;
       }
+      if (panelStale && panelGroup) updateGroupInfoPanel();
     });
   }
 
@@ -2719,12 +4790,14 @@ const splashEl = document.getElementById("splash");
     const MIN_W = 160;
     const MIN_H = HEADER_HEIGHT + 80;
     const EDGE_PX = 16; // edge handle thickness in screen pixels
+    let resizeChange: SceneChange | null = null;
 
     function modeFromPoint(
       localX: number,
       localY: number,
       edgeWorld: number,
     ): typeof resizeMode {
+      if (gv.collapsed) return ""; // piles keep their compact size
       const w = gv.w,
         h = gv.h;
       const left = localX <= edgeWorld;
@@ -2762,11 +4835,11 @@ const splashEl = document.getElementById("splash");
 
     // Existing bottom-right triangle -> always se resize
     r.on("pointerdown", (e: PIXI.FederatedPointerEvent) => {
-      // collapse removed
-
+      if (gv.collapsed) return;
       e.stopPropagation();
       const local = world.toLocal(e.global);
       resizing = true;
+      resizeChange = beginSceneChange("Resize group");
       resizeMode = "se";
       startW = gv.w;
       startH = gv.h;
@@ -2800,6 +4873,7 @@ const splashEl = document.getElementById("splash");
       if (!mode) return; // not on edge -> allow other handlers (drag / marquee)
       e.stopPropagation();
       resizing = true;
+      resizeChange = beginSceneChange("Resize group");
       resizeMode = mode;
       startW = gv.w;
       startH = gv.h;
@@ -2826,6 +4900,7 @@ const splashEl = document.getElementById("splash");
       else if (left) mode = "w";
       else if (right) mode = "e";
       else if (top) mode = "n";
+      if (gv.collapsed) mode = "";
       gv.header.cursor = cursorFor(mode) || "move";
     });
     gv.header.on("pointerout", () => {
@@ -2847,9 +4922,11 @@ const splashEl = document.getElementById("splash");
       else if (left) mode = "w";
       else if (right) mode = "e";
       else if (top) mode = "n";
+      if (gv.collapsed) mode = "";
       if (!mode) return; // not near top edge -> let normal drag logic run
       e.stopPropagation(); // prevent header drag
       resizing = true;
+      resizeChange = beginSceneChange("Resize group");
       resizeMode = mode;
       startW = gv.w;
       startH = gv.h;
@@ -2914,15 +4991,18 @@ const splashEl = document.getElementById("splash");
       gv.h = newH;
       gv.gfx.x = clampedPos.x;
       gv.gfx.y = clampedPos.y;
+      markConnectorsDirty(null, [gv]);
 
       drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
-      updateGroupMetrics(gv);
+      updateGroupMetrics(gv, groups);
     });
     const endResize = () => {
       if (resizing) {
         resizing = false;
         resizeMode = "";
         gv.frame.cursor = "default";
+        commitSceneChange(resizeChange);
+        resizeChange = null;
       }
     };
     app.stage.on("pointerup", endResize);
@@ -2937,6 +5017,21 @@ const splashEl = document.getElementById("splash");
     let dy = 0;
     const g = gv.gfx;
     let memberOffsets: { sprite: CardSprite; ox: number; oy: number }[] = [];
+    let dragChange: SceneChange | null = null;
+    // Axes held by a smart guide (or Alt) on the last move; these skip the grid on drop
+    let alignKeep = { x: false, y: false };
+    // Groups moving in lockstep with this one: other selected groups plus all nested groups
+    let companions = new Set<number>();
+    function dragCompanions() {
+      const ids = new Set<number>(SelectionStore.getGroups());
+      ids.add(gv.id);
+      [...ids].forEach((id) => {
+        const og = groups.get(id);
+        if (og) descendantGroups(og, groups).forEach((c) => ids.add(c.id));
+      });
+      ids.delete(gv.id);
+      return ids;
+    }
     // For multi-group drags, precompute offsets for all selected groups once when drag begins
     let multiOffsets: Map<
       number,
@@ -2990,6 +5085,14 @@ const splashEl = document.getElementById("splash");
     gv.header.on("pointertap", (e: PIXI.FederatedPointerEvent) => {
       if (e.detail === 2 && e.button !== 2) startGroupRename(gv);
     });
+    // Collapse chevron: swallow the press so it neither drags nor selects
+    gv.toggle.on("pointerdown", (e: PIXI.FederatedPointerEvent) =>
+      e.stopPropagation(),
+    );
+    gv.toggle.on("pointertap", (e: PIXI.FederatedPointerEvent) => {
+      if (e.button === 2) return;
+      setGroupCollapsed(gv, !gv.collapsed);
+    });
     // Overlay drag surface (when zoomed out). Acts like header.
     if ((gv as any)._overlayDrag) {
       const ds: any = (gv as any)._overlayDrag;
@@ -3071,10 +5174,19 @@ const splashEl = document.getElementById("splash");
         if (dpx > threshold || dpy > threshold) {
           drag = true;
           maybeDrag = false;
+          companions = dragCompanions();
+          {
+            const members: CardSprite[] = [...gv.items];
+            companions.forEach((id) => {
+              const og = groups.get(id);
+              if (og) members.push(...og.items);
+            });
+            dragChange = beginSceneChange("Move group", members);
+          }
           beginGroupDragZRaise(gv);
           // Mark active group-drag set for edge auto-pan
           try {
-            const ids = new Set<number>(SelectionStore.getGroups());
+            const ids = new Set<number>(companions);
             ids.add(gv.id);
             (window as any).__mtgActiveGroupDrag = ids;
           } catch {}
@@ -3082,11 +5194,9 @@ const splashEl = document.getElementById("splash");
           memberOffsets = [...gv.items]
             .map((s) => ({ sprite: s, ox: s.x - g.x, oy: s.y - g.y }))
             .filter(Boolean) as any;
-          const selected = new Set(SelectionStore.getGroups());
-          selected.delete(gv.id);
-          if (selected.size) {
+          if (companions.size) {
             multiOffsets = new Map();
-            selected.forEach((id) => {
+            companions.forEach((id) => {
               const og = groups.get(id);
               if (!og) return;
               const arr = [...og.items]
@@ -3105,11 +5215,38 @@ const splashEl = document.getElementById("splash");
       const local = world.toLocal(e.global);
       let nx = local.x - dx;
       let ny = local.y - dy;
+      // Smart guides against everything outside the dragged groups
+      {
+        const dragIds = new Set<number>([gv.id, ...companions]);
+        const box = { minX: nx, minY: ny, maxX: nx + gv.w, maxY: ny + gv.h };
+        companions.forEach((id) => {
+          const og = groups.get(id);
+          if (!og) return;
+          const ox = og.gfx.x + nx - g.x;
+          const oy = og.gfx.y + ny - g.y;
+          box.minX = Math.min(box.minX, ox);
+          box.minY = Math.min(box.minY, oy);
+          box.maxX = Math.max(box.maxX, ox + og.w);
+          box.maxY = Math.max(box.maxY, oy + og.h);
+        });
+        const a = alignMove(
+          box,
+          (s) => s.__groupId != null && dragIds.has(s.__groupId),
+          (og) => dragIds.has(og.id),
+          e.altKey,
+        );
+        alignKeep = a
+          ? { x: a.snapX, y: a.snapY }
+          : { x: e.altKey, y: e.altKey };
+        if (a) {
+          nx += a.dx;
+          ny += a.dy;
+        }
+      }
       // Compute clamped delta allowed for the primary group
       let ddx = nx - g.x;
       let ddy = ny - g.y;
-      const selected = new Set(SelectionStore.getGroups());
-      selected.delete(gv.id);
+      const selected = companions;
       if (selected.size) {
         const c = clampDeltaForMultipleGroups(gv, ddx, ddy, selected);
         ddx = c.dx;
@@ -3119,93 +5256,935 @@ const splashEl = document.getElementById("splash");
         ddx = c.dx;
         ddy = c.dy;
       }
-      nx = g.x + ddx;
-      ny = g.y + ddy;
-      g.x = nx;
-      g.y = ny;
-      memberOffsets.forEach((m) => {
-        m.sprite.x = g.x + m.ox;
-        m.sprite.y = g.y + m.oy;
+      nx = g.x + ddx;
+      ny = g.y + ddy;
+      g.x = nx;
+      g.y = ny;
+      markConnectorsDirty(null, [gv]);
+      memberOffsets.forEach((m) => {
+        m.sprite.x = g.x + m.ox;
+        m.sprite.y = g.y + m.oy;
+      });
+      // If multiple groups are selected, move them in lockstep (already clamped above)
+      if (selected.size && multiOffsets) {
+        multiOffsets.forEach(({ gv: og, members }) => {
+          og.gfx.x += ddx;
+          og.gfx.y += ddy;
+          markConnectorsDirty(null, [og]);
+          members.forEach((m) => {
+            m.sprite.x = og.gfx.x + m.ox;
+            m.sprite.y = og.gfx.y + m.oy;
+          });
+        });
+      }
+    });
+    const endGroupDrag = () => {
+      if (!drag) {
+        maybeDrag = false;
+        startLocal = null;
+        return;
+      }
+      drag = false;
+      maybeDrag = false;
+      startLocal = null;
+      endGroupDragZRestore();
+      // Clear active group-drag marker
+      try {
+        (window as any).__mtgActiveGroupDrag = null;
+      } catch {}
+      guideLayer.clear();
+      // Snap and re-clamp to bounds the primary group (axes held by a guide stay put)
+      const keep = alignKeep;
+      alignKeep = { x: false, y: false };
+      const snapX = (v: number) => (keep.x ? Math.round(v) : snap(v));
+      const snapY = (v: number) => (keep.y ? Math.round(v) : snap(v));
+      const p = clampGroupXY(gv, snapX(g.x), snapY(g.y));
+      g.x = p.x;
+      g.y = p.y;
+      const primarySpatial: SpatialItem[] = [];
+      memberOffsets.forEach((m) => {
+        m.sprite.x = snapX(m.sprite.x);
+        m.sprite.y = snapY(m.sprite.y);
+        primarySpatial.push({
+          sprite: m.sprite,
+          ...cardBounds(m.sprite),
+        });
+      });
+      if (primarySpatial.length) spatial.bulkUpdate(primarySpatial);
+      persistGroupTransform(gv.id, { x: g.x, y: g.y, w: gv.w, h: gv.h });
+
+      // Snap and re-clamp any other selected groups moved in lockstep
+      const selected = companions;
+      if (selected.size && multiOffsets) {
+        const items: SpatialItem[] = [];
+        multiOffsets.forEach(({ gv: og, members }) => {
+          const p2 = clampGroupXY(og, snapX(og.gfx.x), snapY(og.gfx.y));
+          og.gfx.x = p2.x;
+          og.gfx.y = p2.y;
+          members.forEach((m) => {
+            m.sprite.x = snapX(m.sprite.x);
+            m.sprite.y = snapY(m.sprite.y);
+            items.push({
+              sprite: m.sprite,
+              ...cardBounds(m.sprite),
+            });
+          });
+          persistGroupTransform(og.id, {
+            x: og.gfx.x,
+            y: og.gfx.y,
+            w: og.w,
+            h: og.h,
+          });
+        });
+        if (items.length) spatial.bulkUpdate(items);
+      }
+      nestAfterDrag();
+      scheduleGroupSave();
+      commitSceneChange(dragChange);
+      dragChange = null;
+      multiOffsets = null;
+      companions = new Set();
+    };
+    // Dropping a lone group (header center) onto another group nests it; dropping it
+    // outside its parent moves it back to the top level.
+    function nestAfterDrag() {
+      const lone = [...companions].every((id) => {
+        const og = groups.get(id);
+        return !og || isWithinGroup(og, gv.id, groups);
+      });
+      if (!lone) return;
+      const target = topGroupAt(
+        g.x + gv.w / 2,
+        g.y + HEADER_HEIGHT / 2,
+        (og) => isWithinGroup(og, gv.id, groups),
+      );
+      const prev = gv.parentId != null ? groups.get(gv.parentId) : undefined;
+      if (!target && !prev) return;
+      // Never nest a group into itself or into one of its own nested groups
+      if (target && isWithinGroup(target, gv.id, groups)) return;
+      // Parent layouts also move cards outside the drag's scope
+      const roots = new Set<GroupVisual>();
+      if (target) roots.add(outermostGroup(target));
+      if (prev) roots.add(outermostGroup(prev));
+      roots.forEach((r) => extendSceneChange(dragChange, groupTreeCards(r)));
+      if ((target ?? null) !== (prev ?? null))
+        setGroupParent(gv, target ?? null);
+      const items: SpatialItem[] = [];
+      for (const parent of new Set([target, prev])) {
+        if (!parent || !groups.has(parent.id)) continue;
+        // Re-pack around the new set of children; the frame shrinks as well as grows
+        layoutGroup(
+          parent,
+          sprites,
+          (s) => {
+            items.push({
+              sprite: s,
+              ...cardBounds(s),
+            });
+          },
+          groups,
+          { fit: true },
+        );
+        relayoutAncestors(parent);
+      }
+      if (items.length) spatial.bulkUpdate(items);
+    }
+    app.stage.on("pointerup", endGroupDrag);
+    app.stage.on("pointerupoutside", endGroupDrag);
+  }
+
+  // ---- Smart guides ----
+  // Dragged cards and groups pull onto the edges and centers of nearby cards / groups and onto
+  // equal spacing with their neighbours, with guide lines while it applies. Alt moves freely
+  // (no guides and no grid on drop).
+  const ALIGN_THRESHOLD_PX = 6; // screen-space pull distance
+  function alignMove(
+    moving: GuideBox,
+    skipCard: (s: CardSprite) => boolean,
+    skipGroup: (gv: GroupVisual) => boolean,
+    free: boolean,
+  ) {
+    if (free) {
+      guideLayer.clear();
+      return null;
+    }
+    // Neighbours within about one box size (at least a few cards) on each side
+    const reach = Math.max(
+      moving.maxX - moving.minX,
+      moving.maxY - moving.minY,
+      CARD_W_GLOBAL * 3,
+    );
+    const area = {
+      minX: moving.minX - reach,
+      minY: moving.minY - reach,
+      maxX: moving.maxX + reach,
+      maxY: moving.maxY + reach,
+    };
+    const targets: GuideBox[] = [];
+    const near = spatial.search(area.minX, area.minY, area.maxX, area.maxY);
+    for (const it of near) {
+      if (it.sprite.__hidden || skipCard(it.sprite)) continue;
+      targets.push({
+        minX: it.minX,
+        minY: it.minY,
+        maxX: it.maxX,
+        maxY: it.maxY,
+      });
+    }
+    for (const gv of groups.values()) {
+      if (!gv.gfx.visible || skipGroup(gv)) continue;
+      const b = {
+        minX: gv.gfx.x,
+        minY: gv.gfx.y,
+        maxX: gv.gfx.x + gv.w,
+        maxY: gv.gfx.y + gv.h,
+      };
+      if (
+        b.maxX < area.minX ||
+        b.minX > area.maxX ||
+        b.maxY < area.minY ||
+        b.minY > area.maxY
+      )
+        continue;
+      targets.push(b);
+    }
+    const a = computeAlignment(
+      moving,
+      targets,
+      ALIGN_THRESHOLD_PX / (world.scale.x || 1),
+    );
+    guideLayer.show(a.guides);
+    return a;
+  }
+
+  // ---- Sticky notes ----
+  // Free-floating text frames. Dragging a note moves the whole selection (notes and cards),
+  // and selected notes follow card drags, so mixed selections travel together.
+  function noteRect(nv: NoteVisual): Rect {
+    return { x: nv.gfx.x, y: nv.gfx.y, w: nv.w, h: nv.h };
+  }
+  // Keep a note of the given size inside the canvas bounds
+  function clampNoteXY(x: number, y: number, w: number, h: number) {
+    const b = getCanvasBounds();
+    return {
+      x: Math.min(b.x + b.w - w, Math.max(b.x, x)),
+      y: Math.min(b.y + b.h - h, Math.max(b.y, y)),
+    };
+  }
+  function addNoteVisual(rec: NoteRecord): NoteVisual {
+    const nv = createNoteVisual(rec.id, rec.x, rec.y, rec.w, rec.h);
+    nv.text = rec.text;
+    nv.color = rec.color;
+    if (rec.z) nv.gfx.zIndex = rec.z;
+    notes.set(nv.id, nv);
+    world.addChild(nv.gfx);
+    attachNoteInteractions(nv);
+    drawNote(nv, SelectionStore.state.noteIds.has(nv.id));
+    return nv;
+  }
+  function removeNoteVisual(id: number) {
+    const nv = notes.get(id);
+    if (!nv) return;
+    notes.delete(id);
+    document.getElementById(`note-edit-${id}`)?.remove();
+    nv.gfx.destroy({ children: true });
+  }
+  // New note centered on a world point, opened for editing right away
+  function createNoteAt(x: number, y: number) {
+    let id = 1;
+    notes.forEach((_, k) => (id = Math.max(id, k + 1)));
+    const ch = beginSceneChange("Add note");
+    const nv = addNoteVisual({
+      id,
+      ...clampNoteXY(
+        snap(x - NOTE_DEFAULT_W / 2),
+        snap(y - NOTE_DEFAULT_H / 2),
+        NOTE_DEFAULT_W,
+        NOTE_DEFAULT_H,
+      ),
+      w: NOTE_DEFAULT_W,
+      h: NOTE_DEFAULT_H,
+      z: 0,
+      text: "",
+      color: "yellow",
+    });
+    commitSceneChange(ch);
+    SelectionStore.selectOnlyNote(nv.id);
+    scheduleGroupSave();
+    startNoteEdit(nv);
+  }
+  function deleteNotes(ids: number[]) {
+    if (!ids.length) return;
+    ids.forEach((id) => removeNoteVisual(id));
+    scheduleGroupSave();
+  }
+  function setNoteColor(ids: number[], color: NoteColor) {
+    const list = ids
+      .map((id) => notes.get(id))
+      .filter((nv): nv is NoteVisual => !!nv && nv.color !== color);
+    if (!list.length) return;
+    recordSceneChange("Note color", [], () => {
+      for (const nv of list) {
+        nv.color = color;
+        drawNote(nv, SelectionStore.state.noteIds.has(nv.id));
+      }
+    });
+    scheduleGroupSave();
+  }
+  // Edit in a textarea laid over the note (Esc cancels, Ctrl+Enter or blur saves)
+  function startNoteEdit(nv: NoteVisual) {
+    if (document.getElementById(`note-edit-${nv.id}`)) return;
+    const ta = document.createElement("textarea");
+    ta.id = `note-edit-${nv.id}`;
+    ta.value = nv.text;
+    ta.placeholder = "# Title\n- bullet\n- [ ] to do";
+    ta.spellcheck = false;
+    ta.setAttribute("data-gramm", "false");
+    const bounds = app.renderer.canvas.getBoundingClientRect();
+    const pt = world.toGlobal(new PIXI.Point(nv.gfx.x, nv.gfx.y));
+    const scale = world.scale.x; // approximate uniform scale
+    ta.style.position = "fixed";
+    ta.style.left = `${bounds.left + pt.x}px`;
+    ta.style.top = `${bounds.top + pt.y}px`;
+    ta.style.zIndex = "10000";
+    ta.style.boxSizing = "border-box";
+    ta.style.width = `${Math.max(160, nv.w * scale)}px`;
+    ta.style.height = `${Math.max(100, nv.h * scale)}px`;
+    ta.style.padding = `${Math.max(4, Math.round(12 * scale))}px`;
+    // Text stays readable when the canvas is zoomed far out
+    ta.style.font = `${Math.max(12, Math.round(14 * scale))}px/1.3 "Inter", system-ui, sans-serif`;
+    // Same pastel sheet and dark ink as the note itself
+    ta.style.color = "#1f1f1f";
+    ta.style.background = `#${NOTE_COLORS[nv.color].toString(16).padStart(6, "0")}`;
+    ta.style.border = "2px solid var(--input-border)";
+    ta.style.borderRadius = "4px";
+    ta.style.outline = "none";
+    ta.style.resize = "none";
+    document.body.appendChild(ta);
+    nv.body.visible = false;
+    ta.focus();
+    let done = false;
+    function commit(save: boolean) {
+      if (done) return;
+      done = true;
+      ta.remove();
+      if (!notes.has(nv.id)) return;
+      nv.body.visible = true;
+      if (save && ta.value !== nv.text) {
+        const ch = beginSceneChange("Edit note");
+        nv.text = ta.value;
+        commitSceneChange(ch);
+        scheduleGroupSave();
+      }
+      drawNote(nv, SelectionStore.state.noteIds.has(nv.id));
+    }
+    ta.addEventListener("keydown", (ev) => {
+      ev.stopPropagation();
+      if (ev.key === "Escape") commit(false);
+      else if (ev.key === "Enter" && (ev.ctrlKey || ev.metaKey)) commit(true);
+    });
+    ta.addEventListener("blur", () => commit(true));
+  }
+  function showNoteContextMenu(nv: NoteVisual, globalPt: PIXI.Point) {
+    // Acts on the whole note selection when the clicked note is part of it
+    const ids = SelectionStore.state.noteIds.has(nv.id)
+      ? SelectionStore.getNotes()
+      : [nv.id];
+    const el = ensureGroupMenu();
+    el.innerHTML = "";
+    function addItem(label: string, action: () => void) {
+      const it = document.createElement("div");
+      it.textContent = label;
+      it.className = "ui-menu-item";
+      it.onclick = () => {
+        action();
+        hideGroupMenu();
+      };
+      el.appendChild(it);
+    }
+    addItem("Edit", () => startNoteEdit(nv));
+    const swatches = document.createElement("div");
+    swatches.style.cssText =
+      "display:flex;gap:calc(6px * var(--ui-scale));padding:calc(6px * var(--ui-scale)) calc(14px * var(--ui-scale));";
+    (Object.keys(NOTE_COLORS) as NoteColor[]).forEach((color) => {
+      const sw = document.createElement("div");
+      sw.title = color[0].toUpperCase() + color.slice(1);
+      sw.style.cssText = `width:calc(20px * var(--ui-scale));height:calc(20px * var(--ui-scale));border-radius:50%;cursor:pointer;border:2px solid ${color === nv.color ? "var(--panel-fg)" : "transparent"};`;
+      sw.style.background = `#${NOTE_COLORS[color].toString(16).padStart(6, "0")}`;
+      sw.onclick = () => {
+        setNoteColor(ids, color);
+        hideGroupMenu();
+      };
+      swatches.appendChild(sw);
+    });
+    el.appendChild(swatches);
+    addItem(ids.length > 1 ? `Delete ${ids.length} notes` : "Delete", () => {
+      recordSceneChange("Delete note", [], () => deleteNotes(ids));
+      SelectionStore.clear();
+    });
+    const bounds = app.renderer.canvas.getBoundingClientRect();
+    el.style.left = `${bounds.left + globalPt.x + 4}px`;
+    el.style.top = `${bounds.top + globalPt.y + 4}px`;
+    el.style.display = "block";
+  }
+  // Drag started on a note: moves selected notes and selected cards by one delta
+  let noteDrag: {
+    start: { x: number; y: number };
+    active: boolean;
+    notes: { nv: NoteVisual; x0: number; y0: number }[];
+    cards: { s: CardSprite; x0: number; y0: number }[];
+    change: SceneChange | null;
+  } | null = null;
+  let noteResize: {
+    nv: NoteVisual;
+    start: { x: number; y: number };
+    w0: number;
+    h0: number;
+    change: SceneChange | null;
+  } | null = null;
+  // Selected notes riding along with a card drag (offsets from a reference card)
+  let noteFollow: {
+    ref: CardSprite;
+    rx0: number;
+    ry0: number;
+    notes: { nv: NoteVisual; x0: number; y0: number }[];
+  } | null = null;
+  function cardTopLeft(s: CardSprite) {
+    const anyS: any = s as any;
+    return anyS.__tiltActive
+      ? { x: anyS.__tlx ?? s.x, y: anyS.__tly ?? s.y }
+      : { x: s.x, y: s.y };
+  }
+  function selectedNoteStarts() {
+    return SelectionStore.getNotes()
+      .map((id) => notes.get(id))
+      .filter((nv): nv is NoteVisual => !!nv)
+      .map((nv) => ({ nv, x0: nv.gfx.x, y0: nv.gfx.y }));
+  }
+  function beginNoteFollow(dragged: CardSprite[]) {
+    const list = selectedNoteStarts();
+    if (!list.length || !dragged.length) {
+      noteFollow = null;
+      return;
+    }
+    const tl = cardTopLeft(dragged[0]);
+    noteFollow = { ref: dragged[0], rx0: tl.x, ry0: tl.y, notes: list };
+  }
+  function updateNoteFollow() {
+    if (!noteFollow) return;
+    const tl = cardTopLeft(noteFollow.ref);
+    const dx = tl.x - noteFollow.rx0;
+    const dy = tl.y - noteFollow.ry0;
+    for (const { nv, x0, y0 } of noteFollow.notes) {
+      const p = clampNoteXY(x0 + dx, y0 + dy, nv.w, nv.h);
+      nv.gfx.x = p.x;
+      nv.gfx.y = p.y;
+    }
+  }
+  // Cards have snapped by now; land the notes on the same (grid) delta
+  function endNoteFollow() {
+    if (!noteFollow) return;
+    updateNoteFollow();
+    noteFollow.notes.forEach(({ nv }) => {
+      const p = clampNoteXY(snap(nv.gfx.x), snap(nv.gfx.y), nv.w, nv.h);
+      nv.gfx.x = p.x;
+      nv.gfx.y = p.y;
+    });
+    noteFollow = null;
+    scheduleGroupSave();
+  }
+  function attachNoteInteractions(nv: NoteVisual) {
+    nv.sheet.on("pointerdown", (e: PIXI.FederatedPointerEvent) => {
+      if (e.button !== 0 || panning) return;
+      e.stopPropagation();
+      if (e.shiftKey) SelectionStore.toggleNote(nv.id);
+      else if (!SelectionStore.state.noteIds.has(nv.id))
+        SelectionStore.selectOnlyNote(nv.id);
+      if (!SelectionStore.state.noteIds.has(nv.id)) return;
+      const p = world.toLocal(e.global);
+      noteDrag = {
+        start: { x: p.x, y: p.y },
+        active: false,
+        notes: [],
+        cards: [],
+        change: null,
+      };
+    });
+    nv.sheet.on("pointertap", (e: PIXI.FederatedPointerEvent) => {
+      if (e.detail === 2 && e.button !== 2) startNoteEdit(nv);
+    });
+    nv.sheet.on("rightclick", (e: PIXI.FederatedPointerEvent) => {
+      e.stopPropagation();
+      if (rightPanning) return;
+      showNoteContextMenu(nv, e.global);
+    });
+    nv.resize.on("pointerdown", (e: PIXI.FederatedPointerEvent) => {
+      if (e.button !== 0) return;
+      e.stopPropagation();
+      const p = world.toLocal(e.global);
+      noteResize = {
+        nv,
+        start: { x: p.x, y: p.y },
+        w0: nv.w,
+        h0: nv.h,
+        change: beginSceneChange("Resize note"),
+      };
+    });
+  }
+  app.stage.on("pointermove", (e: PIXI.FederatedPointerEvent) => {
+    if (noteResize) {
+      const { nv, start, w0, h0 } = noteResize;
+      const p = world.toLocal(e.global);
+      const b = getCanvasBounds();
+      nv.w = Math.min(
+        b.x + b.w - nv.gfx.x,
+        Math.max(NOTE_MIN_W, snap(w0 + p.x - start.x)),
+      );
+      nv.h = Math.min(
+        b.y + b.h - nv.gfx.y,
+        Math.max(NOTE_MIN_H, snap(h0 + p.y - start.y)),
+      );
+      drawNote(nv, SelectionStore.state.noteIds.has(nv.id));
+      return;
+    }
+    if (!noteDrag) return;
+    const p = world.toLocal(e.global);
+    const dx = p.x - noteDrag.start.x;
+    const dy = p.y - noteDrag.start.y;
+    if (!noteDrag.active) {
+      const threshold = 3 / (world.scale.x || 1);
+      if (Math.abs(dx) <= threshold && Math.abs(dy) <= threshold) return;
+      noteDrag.active = true;
+      noteDrag.notes = selectedNoteStarts();
+      noteDrag.cards = SelectionStore.getCards().map((s) => ({
+        s,
+        x0: s.x,
+        y0: s.y,
+      }));
+      noteDrag.change = beginSceneChange(
+        noteDrag.cards.length ? "Move selection" : "Move notes",
+        noteDrag.cards.map((c) => c.s),
+      );
+    }
+    for (const { nv, x0, y0 } of noteDrag.notes) {
+      const q = clampNoteXY(x0 + dx, y0 + dy, nv.w, nv.h);
+      nv.gfx.x = q.x;
+      nv.gfx.y = q.y;
+    }
+    for (const { s, x0, y0 } of noteDrag.cards) {
+      s.x = x0 + dx;
+      s.y = y0 + dy;
+      spatial.update({ sprite: s, ...cardBounds(s) });
+    }
+  });
+  const endNoteGesture = () => {
+    if (noteResize) {
+      commitSceneChange(noteResize.change);
+      noteResize = null;
+      scheduleGroupSave();
+    }
+    if (!noteDrag) return;
+    const drag = noteDrag;
+    noteDrag = null;
+    if (!drag.active) return;
+    for (const { nv } of drag.notes) {
+      const q = clampNoteXY(snap(nv.gfx.x), snap(nv.gfx.y), nv.w, nv.h);
+      nv.gfx.x = q.x;
+      nv.gfx.y = q.y;
+    }
+    scheduleGroupSave();
+    if (!drag.cards.length) {
+      commitSceneChange(drag.change);
+      return;
+    }
+    const b = getCanvasBounds();
+    const moved = drag.cards.map(({ s }) => {
+      const { w, h } = cardSize(s);
+      s.x = Math.min(b.x + b.w - w, Math.max(b.x, snap(s.x)));
+      s.y = Math.min(b.y + b.h - h, Math.max(b.y, snap(s.y)));
+      return s;
+    });
+    // Regroup, persist and commit through the card drop path
+    pendingCardDrag = drag.change;
+    handleDroppedSprites(moved);
+  };
+  app.stage.on("pointerup", endNoteGesture);
+  app.stage.on("pointerupoutside", endNoteGesture);
+  function notesInRect(x1: number, y1: number, x2: number, y2: number) {
+    const ids: number[] = [];
+    notes.forEach((nv) => {
+      const r = noteRect(nv);
+      if (r.x <= x2 && r.x + r.w >= x1 && r.y <= y2 && r.y + r.h >= y1)
+        ids.push(nv.id);
+    });
+    return ids;
+  }
+
+  // ---- Connectors ----
+  // Directed arrows between cards or groups (combo lines, synergies). Endpoints are looked up
+  // by id every frame, so arrows follow cards through drags, drops, layouts and undo.
+  const connectorSprites = new Map<number, CardSprite>();
+  let connectorSpritesLen = -1;
+  function connectorSprite(id: number): CardSprite | null {
+    const s = connectorSprites.get(id);
+    if (s && !s.destroyed) return s;
+    // Rebuild the id lookup only when cards came or went since the last scan
+    if (s || sprites.length !== connectorSpritesLen) {
+      connectorSprites.clear();
+      for (const sp of sprites) connectorSprites.set(sp.__id, sp);
+      connectorSpritesLen = sprites.length;
+    }
+    return connectorSprites.get(id) ?? null;
+  }
+  function connectorEndRect(end: ConnectorEnd): Rect | null {
+    let gv: GroupVisual | undefined;
+    if (end.kind === "card") {
+      const s = connectorSprite(end.id);
+      if (!s) return null;
+      if (!s.__hidden) {
+        const tl = cardTopLeft(s);
+        const b = cardBounds(s, tl.x, tl.y);
+        return {
+          x: b.minX,
+          y: b.minY,
+          w: b.maxX - b.minX,
+          h: b.maxY - b.minY,
+        };
+      }
+      // Cards inside a collapsed pile point at the pile
+      gv = s.__groupId ? groups.get(s.__groupId) : undefined;
+    } else gv = groups.get(end.id);
+    const seen = new Set<number>();
+    while (gv && !gv.gfx.visible && !seen.has(gv.id)) {
+      seen.add(gv.id);
+      gv = gv.parentId != null ? groups.get(gv.parentId) : undefined;
+    }
+    return gv && gv.gfx.visible
+      ? { x: gv.gfx.x, y: gv.gfx.y, w: gv.w, h: gv.h }
+      : null;
+  }
+  function connectorEndSelected(end: ConnectorEnd) {
+    if (end.kind === "group") return SelectionStore.state.groupIds.has(end.id);
+    const s = connectorSprite(end.id);
+    return !!s && SelectionStore.state.cards.has(s);
+  }
+  // Connectors whose endpoints still exist; dangling ones stay in memory (undo may bring the
+  // card back) but are not saved
+  function liveConnectorRecords(): ConnectorRecord[] {
+    const exists = (e: ConnectorEnd) =>
+      e.kind === "card" ? !!connectorSprite(e.id) : groups.has(e.id);
+    return [...connectors.values()]
+      .map((cv) => cv.rec)
+      .filter((rec) => exists(rec.from) && exists(rec.to));
+  }
+  // Connector redraws are event driven: whatever moves a card or a group marks it, and
+  // the next frame redraws only the connectors attached to it. Calling with no arguments
+  // (selection, theme, undo, any committed scene change) redraws every connector.
+  const connectorEndKey = (e: ConnectorEnd) => `${e.kind}:${e.id}`;
+  function markConnectorsDirty(
+    cards?: Iterable<CardSprite> | null,
+    moved?: Iterable<GroupVisual>,
+  ) {
+    if (!cards && !moved) {
+      allConnectorsDirty = true;
+      return;
+    }
+    if (cards) for (const s of cards) dirtyConnectorCards.add(s.__id);
+    if (moved) for (const gv of moved) dirtyConnectorGroups.add(gv.id);
+  }
+  function drawConnectorNow(cv: ConnectorVisual) {
+    drawConnector(
+      cv,
+      connectorEndRect(cv.rec.from),
+      connectorEndRect(cv.rec.to),
+      connectorEndSelected(cv.rec.from) || connectorEndSelected(cv.rec.to),
+    );
+  }
+  // Arrows touching the selection are highlighted
+  function syncConnectors() {
+    if (allConnectorsDirty || !connectors.size) {
+      allConnectorsDirty = false;
+      connectorsByEnd = null;
+      dirtyConnectorCards.clear();
+      dirtyConnectorGroups.clear();
+      connectors.forEach(drawConnectorNow);
+      return;
+    }
+    if (!dirtyConnectorCards.size && !dirtyConnectorGroups.size) return;
+    if (!connectorsByEnd) {
+      const index = new Map<string, ConnectorVisual[]>();
+      connectorsByEnd = index;
+      connectors.forEach((cv) => {
+        for (const end of [cv.rec.from, cv.rec.to]) {
+          const key = connectorEndKey(end);
+          const list = index.get(key);
+          if (list) list.push(cv);
+          else index.set(key, [cv]);
+        }
       });
-      // If multiple groups are selected, move them in lockstep (already clamped above)
-      if (selected.size && multiOffsets) {
-        multiOffsets.forEach(({ gv: og, members }) => {
-          og.gfx.x += ddx;
-          og.gfx.y += ddy;
-          members.forEach((m) => {
-            m.sprite.x = og.gfx.x + m.ox;
-            m.sprite.y = og.gfx.y + m.oy;
-          });
-        });
+    }
+    const index = connectorsByEnd;
+    const todo = new Set<ConnectorVisual>();
+    const take = (key: string) => index.get(key)?.forEach((cv) => todo.add(cv));
+    dirtyConnectorCards.forEach((id) => take(`card:${id}`));
+    dirtyConnectorGroups.forEach((id) => {
+      const gv = groups.get(id);
+      if (!gv) return;
+      // Nested groups and member cards (or the pile they collapse into) move along
+      for (const g of [gv, ...descendantGroups(gv, groups)]) {
+        take(`group:${g.id}`);
+        for (const s of g.items) take(`card:${s.__id}`);
       }
     });
-    const endGroupDrag = () => {
-      if (!drag) {
-        maybeDrag = false;
-        startLocal = null;
-        return;
-      }
-      drag = false;
-      maybeDrag = false;
-      startLocal = null;
-      endGroupDragZRestore();
-      // Clear active group-drag marker
-      try {
-        (window as any).__mtgActiveGroupDrag = null;
-      } catch {}
-      // Snap and re-clamp to bounds the primary group
-      const p = clampGroupXY(gv, snap(g.x), snap(g.y));
-      g.x = p.x;
-      g.y = p.y;
-      const primarySpatial: SpatialItem[] = [];
-      memberOffsets.forEach((m) => {
-        m.sprite.x = snap(m.sprite.x);
-        m.sprite.y = snap(m.sprite.y);
-        primarySpatial.push({
-          sprite: m.sprite,
-          minX: m.sprite.x,
-          minY: m.sprite.y,
-          maxX: m.sprite.x + CARD_W_GLOBAL,
-          maxY: m.sprite.y + CARD_H_GLOBAL,
-        });
+    dirtyConnectorCards.clear();
+    dirtyConnectorGroups.clear();
+    todo.forEach(drawConnectorNow);
+  }
+  function addConnectorVisual(rec: ConnectorRecord): ConnectorVisual {
+    const cv = createConnectorVisual(rec);
+    connectors.set(rec.id, cv);
+    world.addChild(cv.gfx);
+    markConnectorsDirty();
+    attachConnectorInteractions(cv);
+    return cv;
+  }
+  function removeConnectorVisual(id: number) {
+    const cv = connectors.get(id);
+    if (!cv) return;
+    connectors.delete(id);
+    cv.gfx.destroy({ children: true });
+    markConnectorsDirty();
+  }
+  function createConnector(
+    from: ConnectorEnd,
+    to: ConnectorEnd,
+    label = "",
+  ): ConnectorVisual | null {
+    if (sameEnd(from, to)) return null;
+    for (const cv of connectors.values())
+      if (sameEnd(cv.rec.from, from) && sameEnd(cv.rec.to, to)) return null;
+    let id = 1;
+    connectors.forEach((_, k) => (id = Math.max(id, k + 1)));
+    return addConnectorVisual({ id, from, to, label });
+  }
+  function updateConnector(
+    id: number,
+    label: string,
+    patch: Partial<ConnectorRecord>,
+  ) {
+    const cv = connectors.get(id);
+    if (!cv) return;
+    recordSceneChange(label, [], () => {
+      // Records are replaced, never mutated, so undo snapshots stay intact
+      cv.rec = { ...cv.rec, ...patch };
+      cv.key = "-";
+      markConnectorsDirty();
+    });
+    scheduleGroupSave();
+  }
+  function deleteConnector(id: number) {
+    if (!connectors.has(id)) return;
+    recordSceneChange("Delete connector", [], () => removeConnectorVisual(id));
+    scheduleGroupSave();
+  }
+  // Label editor centered on the arrow (Enter saves, Esc cancels, empty clears the label)
+  function startConnectorLabelEdit(cv: ConnectorVisual) {
+    if (document.getElementById(`connector-label-${cv.rec.id}`)) return;
+    const a = connectorEndRect(cv.rec.from);
+    const b = connectorEndRect(cv.rec.to);
+    if (!a || !b) return;
+    const input = document.createElement("input");
+    input.id = `connector-label-${cv.rec.id}`;
+    input.type = "text";
+    input.value = cv.rec.label;
+    input.maxLength = CONNECTOR_LABEL_MAX;
+    input.placeholder = "Label (e.g. infinite mana)";
+    input.spellcheck = false;
+    input.setAttribute("data-gramm", "false");
+    const bounds = app.renderer.canvas.getBoundingClientRect();
+    const mid = world.toGlobal(
+      new PIXI.Point(
+        (a.x + a.w / 2 + b.x + b.w / 2) / 2,
+        (a.y + a.h / 2 + b.y + b.h / 2) / 2,
+      ),
+    );
+    input.style.position = "fixed";
+    input.style.left = `${bounds.left + mid.x - 110}px`;
+    input.style.top = `${bounds.top + mid.y - 14}px`;
+    input.style.width = "220px";
+    input.style.zIndex = "10000";
+    input.style.padding = "3px 6px";
+    input.style.font = '12px "Inter", system-ui, sans-serif';
+    input.style.color = "var(--input-fg)";
+    input.style.background = "var(--input-bg)";
+    input.style.border = "1px solid var(--input-border)";
+    input.style.borderRadius = "4px";
+    input.style.outline = "none";
+    document.body.appendChild(input);
+    input.select();
+    let done = false;
+    function commit(save: boolean) {
+      if (done) return;
+      done = true;
+      input.remove();
+      const val = input.value.trim();
+      if (save && connectors.has(cv.rec.id) && val !== cv.rec.label)
+        updateConnector(cv.rec.id, "Label connector", { label: val });
+    }
+    input.addEventListener("keydown", (ev) => {
+      ev.stopPropagation();
+      if (ev.key === "Enter") commit(true);
+      else if (ev.key === "Escape") commit(false);
+    });
+    input.addEventListener("blur", () => commit(true));
+  }
+  function showConnectorContextMenu(cv: ConnectorVisual, globalPt: PIXI.Point) {
+    const el = ensureGroupMenu();
+    el.innerHTML = "";
+    function addItem(label: string, action: () => void) {
+      const it = document.createElement("div");
+      it.textContent = label;
+      it.className = "ui-menu-item";
+      it.onclick = () => {
+        action();
+        hideGroupMenu();
+      };
+      el.appendChild(it);
+    }
+    addItem(cv.rec.label ? "Edit label" : "Add label", () =>
+      startConnectorLabelEdit(cv),
+    );
+    addItem("Reverse direction", () =>
+      updateConnector(cv.rec.id, "Reverse connector", {
+        from: cv.rec.to,
+        to: cv.rec.from,
+      }),
+    );
+    addItem("Delete", () => deleteConnector(cv.rec.id));
+    const bounds = app.renderer.canvas.getBoundingClientRect();
+    el.style.left = `${bounds.left + globalPt.x + 4}px`;
+    el.style.top = `${bounds.top + globalPt.y + 4}px`;
+    el.style.display = "block";
+  }
+  function attachConnectorInteractions(cv: ConnectorVisual) {
+    for (const target of [cv.line, cv.badge]) {
+      target.on("rightclick", (e: PIXI.FederatedPointerEvent) => {
+        e.stopPropagation();
+        if (rightPanning) return;
+        showConnectorContextMenu(cv, e.global);
       });
-      if (primarySpatial.length) spatial.bulkUpdate(primarySpatial);
-      persistGroupTransform(gv.id, { x: g.x, y: g.y, w: gv.w, h: gv.h });
-
-      // Snap and re-clamp any other selected groups moved in lockstep
-      const selected = new Set(SelectionStore.getGroups());
-      selected.delete(gv.id);
-      if (selected.size && multiOffsets) {
-        const items: SpatialItem[] = [];
-        multiOffsets.forEach(({ gv: og, members }) => {
-          const p2 = clampGroupXY(og, snap(og.gfx.x), snap(og.gfx.y));
-          og.gfx.x = p2.x;
-          og.gfx.y = p2.y;
-          members.forEach((m) => {
-            m.sprite.x = snap(m.sprite.x);
-            m.sprite.y = snap(m.sprite.y);
-            items.push({
-              sprite: m.sprite,
-              minX: m.sprite.x,
-              minY: m.sprite.y,
-              maxX: m.sprite.x + CARD_W_GLOBAL,
-              maxY: m.sprite.y + CARD_H_GLOBAL,
-            });
-          });
-          persistGroupTransform(og.id, {
-            x: og.gfx.x,
-            y: og.gfx.y,
-            w: og.w,
-            h: og.h,
-          });
-        });
-        if (items.length) spatial.bulkUpdate(items);
-      }
+      target.on("pointertap", (e: PIXI.FederatedPointerEvent) => {
+        if (e.detail === 2 && e.button !== 2) startConnectorLabelEdit(cv);
+      });
+    }
+  }
+  // Connect mode ("Connect to…" in the card / group menu): a rubber band follows the cursor
+  // and the next click on a card or group completes the arrow. Esc or clicking empty space
+  // cancels.
+  let connecting: { from: ConnectorEnd; preview: PIXI.Graphics } | null = null;
+  function startConnect(from: ConnectorEnd) {
+    cancelConnect();
+    const preview = new PIXI.Graphics();
+    preview.zIndex = 900001;
+    preview.eventMode = "none";
+    world.addChild(preview);
+    connecting = { from, preview };
+    app.canvas.style.cursor = "crosshair";
+  }
+  function cancelConnect() {
+    if (!connecting) return;
+    connecting.preview.destroy();
+    connecting = null;
+    app.canvas.style.cursor = "";
+  }
+  // Topmost visible card under a world point, else the topmost visible group frame
+  function connectTargetAt(x: number, y: number): ConnectorEnd | null {
+    let card: CardSprite | null = null;
+    for (const it of spatial.search(x, y, x, y)) {
+      const s = it.sprite;
+      if (!s.visible || s.__hidden) continue;
+      if (!card || (s.zIndex || 0) > (card.zIndex || 0)) card = s;
+    }
+    if (card) return { kind: "card", id: card.__id };
+    let best: GroupVisual | null = null;
+    for (const gv of groups.values()) {
+      if (!gv.gfx.visible) continue;
+      const inside =
+        x >= gv.gfx.x &&
+        x <= gv.gfx.x + gv.w &&
+        y >= gv.gfx.y &&
+        y <= gv.gfx.y + gv.h;
+      if (inside && (!best || (gv.gfx.zIndex || 0) > (best.gfx.zIndex || 0)))
+        best = gv;
+    }
+    return best ? { kind: "group", id: best.id } : null;
+  }
+  app.stage.on("pointermove", (e: PIXI.FederatedPointerEvent) => {
+    if (!connecting) return;
+    const from = connectorEndRect(connecting.from);
+    const p = world.toLocal(e.global);
+    connecting.preview.clear();
+    if (!from) return;
+    connecting.preview
+      .moveTo(from.x + from.w / 2, from.y + from.h / 2)
+      .lineTo(p.x, p.y)
+      .stroke({ color: Colors.accent(), width: 2, alpha: 0.8 });
+  });
+  // Capture phase: the completing click must not also select or start dragging the target
+  window.addEventListener(
+    "pointerdown",
+    (ev) => {
+      if (!connecting) return;
+      const from = connecting.from;
+      cancelConnect();
+      if (ev.target !== app.canvas) return; // clicked UI: just leave connect mode
+      ev.stopPropagation();
+      ev.preventDefault();
+      if (ev.button !== 0) return;
+      const bounds = app.canvas.getBoundingClientRect();
+      const p = world.toLocal(
+        new PIXI.Point(ev.clientX - bounds.left, ev.clientY - bounds.top),
+      );
+      const to = connectTargetAt(p.x, p.y);
+      if (!to) return;
+      const ch = beginSceneChange("Connect");
+      const cv = createConnector(from, to);
+      commitSceneChange(ch);
+      if (!cv) return;
       scheduleGroupSave();
-      multiOffsets = null;
-    };
-    app.stage.on("pointerup", endGroupDrag);
-    app.stage.on("pointerupoutside", endGroupDrag);
+      // New arrows ask for their label right away (Esc leaves it unlabeled)
+      requestAnimationFrame(() => startConnectorLabelEdit(cv));
+    },
+    true,
+  );
+  // Recreate "#combos" lines between cards / groups added by an import (matched by name)
+  function connectImportedCombos(
+    combos: ComboLine[],
+    priorCards: Set<number>,
+    priorGroups: Set<number>,
+  ) {
+    const byName = new Map<string, ConnectorEnd>();
+    for (const s of sprites) {
+      if (priorCards.has(s.__id)) continue;
+      const name = (s.__card?.name || "").split("//")[0].trim().toLowerCase();
+      if (name && !byName.has(name))
+        byName.set(name, { kind: "card", id: s.__id });
+    }
+    groups.forEach((gv) => {
+      const name = (gv.name || "").trim().toLowerCase();
+      if (!priorGroups.has(gv.id) && name && !byName.has(name))
+        byName.set(name, { kind: "group", id: gv.id });
+    });
+    let made = 0;
+    recordSceneChange("Import combos", [], () => {
+      for (const c of combos) {
+        const from = byName.get(c.from.toLowerCase());
+        const to = byName.get(c.to.toLowerCase());
+        const label = c.label.slice(0, CONNECTOR_LABEL_MAX);
+        if (from && to && createConnector(from, to, label)) made++;
+      }
+    });
+    if (made) scheduleGroupSave();
   }
 
   // ---- Group context menu (Groups V2) ----
@@ -3249,32 +6228,77 @@ const splashEl = document.getElementById("splash");
       };
       el.appendChild(it);
     }
-    // Collapse feature removed
+    addItem(gv.collapsed ? "Expand" : "Collapse", () =>
+      setGroupCollapsed(gv, !gv.collapsed),
+    );
     addItem("Auto-pack", () => {
       const timer = createPhaseTimer("group-auto-pack(context)");
+      const ch = beginSceneChange(
+        "Auto-pack group",
+        groupTreeCards(outermostGroup(gv)),
+      );
       const items: SpatialItem[] = [];
-      autoPackGroup(gv, sprites, (s) => {
-        items.push({
-          sprite: s,
-          minX: s.x,
-          minY: s.y,
-          maxX: s.x + CARD_W_GLOBAL,
-          maxY: s.y + CARD_H_GLOBAL,
-        });
-      });
+      autoPackGroup(
+        gv,
+        sprites,
+        (s) => {
+          items.push({
+            sprite: s,
+            ...cardBounds(s),
+          });
+        },
+        groups,
+      );
+      relayoutAncestors(gv);
       timer.mark("auto-pack");
       if (items.length) spatial.bulkUpdate(items);
       timer.mark("spatial");
-      updateGroupMetrics(gv);
+      updateGroupMetrics(gv, groups);
       drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
       timer.mark("metrics+draw");
+      commitSceneChange(ch);
       scheduleGroupSave();
       timer.end({ cards: gv.items.size });
     });
-    // Layout submenu removed (group sections/faceted layout no longer supported)
+    for (const { mode, label } of GROUP_LAYOUT_MODES) {
+      if (mode !== gv.layoutMode)
+        addItem(`Layout: ${label}`, () => setGroupLayoutMode(gv, mode));
+    }
+    {
+      const it = document.createElement("div");
+      it.textContent = `Sort: ${describeGroupSort(gv.sortSpec)}…`;
+      it.className = "ui-menu-item";
+      // Swap the menu contents for the sort picker (stays at the same spot)
+      it.onclick = () => showGroupSortMenu(gv);
+      el.appendChild(it);
+    }
+    addItem(
+      gv.stackDuplicates ? "Unstack duplicates" : "Stack duplicates",
+      () => setGroupStacking(gv, !gv.stackDuplicates),
+    );
+    addItem(gv.smart ? "Edit smart query…" : "Make smart group…", () => {
+      // The query lives in the info panel, which follows the selection
+      SelectionStore.replace({
+        cards: new Set(),
+        groupIds: new Set([gv.id]),
+        noteIds: new Set(),
+      });
+      updateGroupInfoPanel();
+      const input = groupInfoPanel?.querySelector(
+        "#group-info-smart",
+      ) as HTMLInputElement | null;
+      input?.focus();
+      input?.select();
+    });
+    if (gv.smart)
+      addItem("Make manual group", () => setGroupSmart(gv, null));
+    addItem("Connect to…", () => startConnect({ kind: "group", id: gv.id }));
     // Recolor removed; theme-driven
     addItem("Delete", () => {
+      const ch = beginSceneChange("Delete group", groupTreeCards(gv));
       deleteGroupById(gv.id);
+      refreshSmartGroups(ch);
+      commitSceneChange(ch);
       SelectionStore.clear();
       scheduleGroupSave();
     });
@@ -3285,6 +6309,45 @@ const splashEl = document.getElementById("splash");
     el.style.display = "block";
   }
 
+  // Sort picker shown in place of the group menu. Picking a key makes it the primary
+  // sort (again flips direction); previous keys remain as tie-breakers.
+  function showGroupSortMenu(gv: GroupVisual) {
+    const el = ensureGroupMenu();
+    el.innerHTML = "";
+    const head = document.createElement("div");
+    head.textContent = `Sort: ${describeGroupSort(gv.sortSpec)}`;
+    head.style.cssText =
+      "padding:calc(6px * var(--ui-scale)) calc(14px * var(--ui-scale));opacity:.7;";
+    el.appendChild(head);
+    function addItem(label: string, action: () => void) {
+      const it = document.createElement("div");
+      it.textContent = label;
+      it.className = "ui-menu-item";
+      it.onclick = () => {
+        action();
+        if (groups.has(gv.id)) showGroupSortMenu(gv);
+      };
+      el.appendChild(it);
+    }
+    addItem(`${gv.sortSpec.length ? "" : "✓ "}Manual order`, () =>
+      setGroupSort(gv, []),
+    );
+    const divider = document.createElement("div");
+    divider.className = "divider";
+    el.appendChild(divider);
+    for (const { key, label } of GROUP_SORT_KEYS) {
+      const rank = gv.sortSpec.findIndex((t) => t.key === key);
+      let mark = "";
+      if (rank >= 0) {
+        mark = gv.sortSpec[rank].dir === "desc" ? " ↓" : " ↑";
+        if (rank) mark += ` (${rank + 1})`;
+      }
+      addItem(`${label}${mark}`, () =>
+        setGroupSort(gv, promoteSortKey(gv.sortSpec, key)),
+      );
+    }
+  }
+
   // ---- Card context menu (Add to open group) ----
   let cardMenu: HTMLDivElement | null = null;
   function ensureCardMenu() {
@@ -3320,8 +6383,10 @@ const splashEl = document.getElementById("splash");
     header.style.cssText =
       "font-size:22px;font-weight:600;letter-spacing:.5px;text-transform:uppercase;opacity:.7;padding:4px 6px 10px;";
     el.appendChild(header);
-    // All groups considered open (collapse removed)
-    const openGroups = [...groups.values()];
+    // Collapsed piles don't take new cards
+    const openGroups = [...groups.values()].filter(
+      (gv) => !hiddenByCollapse(gv),
+    );
     function addItem(label: string, action: () => void, disabled = false) {
       const it = document.createElement("div");
       it.textContent = label;
@@ -3349,12 +6414,16 @@ const splashEl = document.getElementById("splash");
           const already = !!card.__groupId && card.__groupId === gv.id;
           const it = addItem(gv.name || `Group ${gv.id}`, () => {
             if (already) return; // no-op
+            const ch = beginSceneChange("Add card to group", [
+              card,
+              ...groupTreeCards(outermostGroup(gv)),
+            ]);
             // Remove from previous group if any
             if (card.__groupId) {
               const old = groups.get(card.__groupId);
               if (old) {
                 removeCardFromGroup(old, card);
-                updateGroupMetrics(old);
+                updateGroupMetrics(old, groups);
                 drawGroup(old, SelectionStore.state.groupIds.has(old.id));
               }
               // Ensure sprite reappears if group overlay had hidden it
@@ -3373,18 +6442,24 @@ const splashEl = document.getElementById("splash");
             // Fast path for large groups: reflow on the group's fixed grid once (O(n))
             // instead of expensive collision search inside dense groups.
             const moved: SpatialItem[] = [];
-            layoutGroup(gv, sprites, (s) => {
-              moved.push({
-                sprite: s,
-                minX: s.x,
-                minY: s.y,
-                maxX: s.x + CARD_W_GLOBAL,
-                maxY: s.y + CARD_H_GLOBAL,
-              });
-            });
+            layoutGroup(
+              gv,
+              sprites,
+              (s) => {
+                moved.push({
+                  sprite: s,
+                  ...cardBounds(s),
+                });
+              },
+              groups,
+            );
             if (moved.length) spatial.bulkUpdate(moved);
-            updateGroupMetrics(gv);
+            relayoutAncestors(gv);
+            updateGroupMetrics(gv, groups);
             drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
+            // Leaving a smart group or joining a scope group re-runs the rules
+            refreshSmartGroups(ch);
+            commitSceneChange(ch);
             scheduleGroupSave();
             // Update appearance for membership (non-image placeholder style) & selection outline
             updateCardSpriteAppearance(
@@ -3402,6 +6477,48 @@ const splashEl = document.getElementById("splash");
           }
         });
     }
+    // Commander formats: choose the deck's commander(s) from its cards
+    const deckGroup = deckGroupOf(card);
+    if (deckGroup?.deck && DECK_FORMATS[deckGroup.deck.format]?.commander) {
+      const divider = document.createElement("div");
+      divider.className = "divider";
+      el.appendChild(divider);
+      const isCmd = deckGroup.deck.commanders.includes(card.__id);
+      addItem(isCmd ? "Remove as commander" : "Set as commander", () =>
+        toggleCommander(deckGroup, card),
+      );
+    }
+    {
+      // Acts on the whole selection when the clicked card is part of it
+      const targets = SelectionStore.state.cards.has(card)
+        ? SelectionStore.getCards()
+        : [card];
+      const divider = document.createElement("div");
+      divider.className = "divider";
+      el.appendChild(divider);
+      addItem(targets.some((s) => !isTapped(s)) ? "Tap" : "Untap", () =>
+        toggleTapped(targets),
+      );
+      addItem(
+        targets.some((s) => (s.__scale ?? 1) === 1)
+          ? `Enlarge (${KEY_CARD_SCALE}x)`
+          : "Normal size",
+        () => toggleKeyCardScale(targets),
+      );
+      addItem("Connect to…", () =>
+        startConnect({ kind: "card", id: card.__id }),
+      );
+      addItem("Tags…", () => editCardTags(targets));
+    }
+    // Loose stacks can be fanned out; grouped ones follow the group's stacking setting
+    if (card.__stack?.length && !card.__groupId) {
+      const divider = document.createElement("div");
+      divider.className = "divider";
+      el.appendChild(divider);
+      addItem(`Split stack (x${card.__stack.length + 1})`, () =>
+        splitStack(card),
+      );
+    }
     // Intentionally no "Remove from current group" option per request
     const bounds = app.renderer.canvas.getBoundingClientRect();
     el.style.left = `${bounds.left + globalPt.x + 4}px`;
@@ -3478,10 +6595,11 @@ const splashEl = document.getElementById("splash");
     const ids = SelectionStore.getCards();
     if (!ids.length) return null;
     const selectedSprites = sprites.filter((s) => ids.includes(s));
-    const minX = Math.min(...selectedSprites.map((s) => s.x));
-    const minY = Math.min(...selectedSprites.map((s) => s.y));
-    const maxX = Math.max(...selectedSprites.map((s) => s.x + CARD_W_GLOBAL));
-    const maxY = Math.max(...selectedSprites.map((s) => s.y + CARD_H_GLOBAL));
+    const bounds = selectedSprites.map((s) => cardBounds(s));
+    const minX = Math.min(...bounds.map((b) => b.minX));
+    const minY = Math.min(...bounds.map((b) => b.minY));
+    const maxX = Math.max(...bounds.map((b) => b.maxX));
+    const maxY = Math.max(...bounds.map((b) => b.maxY));
     return { minX, minY, maxX, maxY };
   }
 
@@ -3503,6 +6621,7 @@ const splashEl = document.getElementById("splash");
     }
     if (e.key === "g" || e.key === "G") {
       const timer = createPhaseTimer("create-from-selection");
+      const ch = beginSceneChange("Create group", SelectionStore.getCards());
       let id = groups.size ? Math.max(...groups.keys()) + 1 : 1;
       const b = computeSelectionBounds();
       if (b) {
@@ -3546,27 +6665,30 @@ const splashEl = document.getElementById("splash");
         touchedOld.forEach((gid) => {
           const og = groups.get(gid);
           if (!og) return;
-          updateGroupMetrics(og);
+          updateGroupMetrics(og, groups);
           drawGroup(og, SelectionStore.state.groupIds.has(og.id));
         });
         timer.mark("update-old");
         {
           const items: SpatialItem[] = [];
-          layoutGroup(gv, sprites, (s) => {
-            items.push({
-              sprite: s,
-              minX: s.x,
-              minY: s.y,
-              maxX: s.x + CARD_W_GLOBAL,
-              maxY: s.y + CARD_H_GLOBAL,
-            });
-          });
+          layoutGroup(
+            gv,
+            sprites,
+            (s) => {
+              items.push({
+                sprite: s,
+                ...cardBounds(s),
+              });
+            },
+            groups,
+          );
           if (items.length) spatial.bulkUpdate(items);
         }
         timer.mark("layout+spatial");
-        updateGroupMetrics(gv);
+        updateGroupMetrics(gv, groups);
         drawGroup(gv, true);
         timer.mark("metrics+draw");
+        commitSceneChange(ch);
         // Persist transform for repo-backed persistence (no-op in memory mode)
         persistGroupTransform(gv.id, {
           x: gv.gfx.x,
@@ -3605,21 +6727,24 @@ const splashEl = document.getElementById("splash");
         timer2.mark("attach");
         {
           const items: SpatialItem[] = [];
-          layoutGroup(gv, sprites, (s) => {
-            items.push({
-              sprite: s,
-              minX: s.x,
-              minY: s.y,
-              maxX: s.x + CARD_W_GLOBAL,
-              maxY: s.y + CARD_H_GLOBAL,
-            });
-          });
+          layoutGroup(
+            gv,
+            sprites,
+            (s) => {
+              items.push({
+                sprite: s,
+                ...cardBounds(s),
+              });
+            },
+            groups,
+          );
           if (items.length) spatial.bulkUpdate(items);
         }
         timer2.mark("layout+spatial");
-        updateGroupMetrics(gv);
+        updateGroupMetrics(gv, groups);
         drawGroup(gv, true);
         timer2.mark("metrics+draw");
+        commitSceneChange(ch);
         scheduleGroupSave();
         SelectionStore.clear();
         SelectionStore.toggleGroup(id);
@@ -3633,10 +6758,15 @@ const splashEl = document.getElementById("splash");
       e.preventDefault();
       // Select-all should tint like marquee: mark all cards accordingly before selection update
       for (const s of sprites) (s as any).__tintByMarquee = true;
-      SelectionStore.replace({ cards: new Set(sprites), groupIds: new Set() });
+      SelectionStore.replace({
+        cards: new Set(sprites),
+        groupIds: new Set(),
+        noteIds: new Set(notes.keys()),
+      });
     }
     // Clear selection (Esc)
     if (e.key === "Escape") {
+      cancelConnect();
       SelectionStore.clear();
     }
     // Zoom / fit shortcuts (+ / - / 0 reset, F fit all (no modifier), Shift+F fit selection, Z fit selection)
@@ -3657,21 +6787,79 @@ const splashEl = document.getElementById("splash");
       if (e.shiftKey) fitSelection();
       else fitAll();
     }
-    if (e.key === "z" || e.key === "Z") {
+    // Undo / redo (Ctrl+Z, Ctrl+Shift+Z or Ctrl+Y)
+    if ((e.ctrlKey || e.metaKey) && (e.key === "z" || e.key === "Z")) {
+      e.preventDefault();
+      if (e.shiftKey) History.redo();
+      else History.undo();
+    } else if ((e.ctrlKey || e.metaKey) && (e.key === "y" || e.key === "Y")) {
+      e.preventDefault();
+      History.redo();
+    } else if (e.key === "z" || e.key === "Z") {
       fitSelection();
     }
+    // Camera bookmarks: 1..9 recall, Shift+1..9 save the current view
+    const digit = /^Digit([1-9])$/.exec(e.code);
+    if (digit && !e.ctrlKey && !e.metaKey && !e.altKey) {
+      const slot = Number(digit[1]);
+      if (e.shiftKey) saveBookmark(slot);
+      else recallBookmark(slot);
+    }
+    // Toggle the minimap (M)
+    if (
+      (e.key === "m" || e.key === "M") &&
+      !e.ctrlKey &&
+      !e.metaKey &&
+      !e.altKey
+    ) {
+      minimap?.toggle();
+    }
+    // Tap / untap selected cards (T)
+    if (
+      (e.key === "t" || e.key === "T") &&
+      !e.ctrlKey &&
+      !e.metaKey &&
+      !e.altKey
+    ) {
+      toggleTapped(SelectionStore.getCards());
+    }
+    // Sticky note under the cursor (N)
+    if (
+      (e.key === "n" || e.key === "N") &&
+      !e.ctrlKey &&
+      !e.metaKey &&
+      !e.altKey
+    ) {
+      // Keep the key from being typed into the editor that opens
+      e.preventDefault();
+      const global = __lastPointerGlobal
+        ? new PIXI.Point(__lastPointerGlobal.x, __lastPointerGlobal.y)
+        : new PIXI.Point(window.innerWidth / 2, window.innerHeight / 2);
+      const worldPt = world.toLocal(global);
+      createNoteAt(worldPt.x, worldPt.y);
+    }
     // Help hotkey disabled in favor of FAB
     if (e.key === "Delete") {
       const cardIds = SelectionStore.getCards();
       const groupIds = SelectionStore.getGroups();
+      const noteIds = SelectionStore.getNotes();
+      const scope = cardIds.slice();
+      groupIds.forEach((id) => {
+        const gv = groups.get(id);
+        if (gv) scope.push(...groupTreeCards(gv));
+      });
+      const ch = beginSceneChange("Delete", scope);
       if (cardIds.length) {
-        // Fast bulk deletion path
+        // Fast bulk deletion path (synchronous up to the cache purge)
         deleteSelectedCardsFast(cardIds);
       }
       if (groupIds.length) {
         groupIds.forEach((id) => deleteGroupById(id));
         scheduleGroupSave();
       }
+      deleteNotes(noteIds);
+      refreshSmartGroups(ch);
+      commitSceneChange(ch);
       // Clear any stale selection references
       SelectionStore.clear();
       // After group deletions, if empty, drop caches
@@ -3689,6 +6877,7 @@ const splashEl = document.getElementById("splash");
   // Selection visualization: update only changed items instead of scanning all
   let __prevSelectedCards = new Set<CardSprite>();
   let __prevSelectedGroups = new Set<number>();
+  let __prevSelectedNotes = new Set<number>();
   SelectionStore.on(() => {
     const curCards = new Set<CardSprite>(SelectionStore.getCards());
     const curGroups = new Set<number>(SelectionStore.getGroups());
@@ -3732,8 +6921,19 @@ const splashEl = document.getElementById("splash");
         if (gv) drawGroup(gv, false);
       }
     }
+    // Diff notes
+    const curNotes = new Set<number>(SelectionStore.getNotes());
+    __prevSelectedNotes.forEach((id) => {
+      const nv = notes.get(id);
+      if (nv && !curNotes.has(id)) drawNote(nv, false);
+    });
+    curNotes.forEach((id) => {
+      const nv = notes.get(id);
+      if (nv && !__prevSelectedNotes.has(id)) drawNote(nv, true);
+    });
     __prevSelectedCards = curCards;
     __prevSelectedGroups = curGroups;
+    __prevSelectedNotes = curNotes;
     // Panel updates are already scheduled elsewhere via requestAnimationFrame
   });
 
@@ -3771,13 +6971,18 @@ const splashEl = document.getElementById("splash");
         const cards = sprites.filter((s) => {
           if (!spriteSet.has(s)) return false;
           if (s.__groupId) return false;
-          const cx = s.x + CARD_W_GLOBAL * 0.5;
-          const cy = s.y + CARD_H_GLOBAL * 0.5;
+          const { w, h } = cardSize(s);
+          const cx = s.x + w * 0.5;
+          const cy = s.y + h * 0.5;
           return cx >= x1 && cx <= x2 && cy >= y1 && cy <= y2;
         });
-        return { groupIds: activeGroups, cards };
+        return {
+          groupIds: activeGroups,
+          cards,
+          noteIds: notesInRect(x1, y1, x2, y2),
+        };
       } else {
-        // Normal mode: select cards only
+        // Normal mode: select cards (and notes) only
         const found = spatial.search(
           rect.x,
           rect.y,
@@ -3787,11 +6992,12 @@ const splashEl = document.getElementById("splash");
         const spriteSet = new Set(found.map((f) => f.sprite));
         const cards = sprites.filter((s) => {
           if (!spriteSet.has(s)) return false;
-          const cx = s.x + CARD_W_GLOBAL * 0.5;
-          const cy = s.y + CARD_H_GLOBAL * 0.5;
+          const { w, h } = cardSize(s);
+          const cx = s.x + w * 0.5;
+          const cy = s.y + h * 0.5;
           return cx >= x1 && cx <= x2 && cy >= y1 && cy <= y2;
         });
-        return { cards, groupIds: [] };
+        return { cards, groupIds: [], noteIds: notesInRect(x1, y1, x2, y2) };
       }
     },
   );
@@ -3845,12 +7051,7 @@ const splashEl = document.getElementById("splash");
   }
   function computeBoundsFromSprites(list: CardSprite[]) {
     if (!list.length) return null;
-    const rects = list.map((s) => ({
-      x: s.x,
-      y: s.y,
-      w: CARD_W_GLOBAL,
-      h: CARD_H_GLOBAL,
-    }));
+    const rects = list.map((s) => ({ x: s.x, y: s.y, ...cardSize(s) }));
     return mergeRects(rects);
   }
   function mergeRects(rects: { x: number; y: number; w: number; h: number }[]) {
@@ -3876,7 +7077,7 @@ const splashEl = document.getElementById("splash");
     if (!cardSprites.length && !groupSprites.length) return null;
     const rects: { x: number; y: number; w: number; h: number }[] = [];
     cardSprites.forEach((s) =>
-      rects.push({ x: s.x, y: s.y, w: CARD_W_GLOBAL, h: CARD_H_GLOBAL }),
+      rects.push({ x: s.x, y: s.y, ...cardSize(s) }),
     );
     groupSprites.forEach((gv) =>
       rects.push({ x: gv.gfx.x, y: gv.gfx.y, w: gv.w, h: gv.h }),
@@ -3993,6 +7194,23 @@ const splashEl = document.getElementById("splash");
   ensureThemeStyles();
   // Ensure modern day/night toggle (flat pill)
   ensureThemeToggleButton();
+  // Corner overview (M); clusters come from the spatial index, so cost doesn't grow with cards
+  minimap = installMinimap({
+    getSceneBounds: () => computeSceneBounds(),
+    getGroupRects: () =>
+      [...groups.values()]
+        .filter((gv) => gv.gfx.visible)
+        .map((gv) => ({ x: gv.gfx.x, y: gv.gfx.y, w: gv.w, h: gv.h })),
+    getClusters: (limit) => spatial.clusters(limit),
+    getViewport: () => currentViewRect(),
+    centerOn: (x, y, animate) =>
+      camera.centerOn(
+        x,
+        y,
+        { w: window.innerWidth, h: window.innerHeight },
+        animate ? 300 : 0,
+      ),
+  });
   // Optional Status / Performance overlay
   const perfEl: HTMLDivElement | null = HIDE_STATUS_PANE
     ? null
@@ -4022,6 +7240,7 @@ const splashEl = document.getElementById("splash");
   // Keep a short history of FPS samples for watchdogs/throttles
   (window as any).__fpsSamples = (window as any).__fpsSamples || [];
   function scheduleLocalSave() {
+    minimap?.invalidate();
     if (SUPPRESS_SAVES) return;
     if (lsTimer) return;
     lsTimer = setTimeout(() => {
@@ -4178,9 +7397,11 @@ const splashEl = document.getElementById("splash");
       el!.appendChild(b);
     }
     addBtn("Auto-Layout", () => {
-      gridUngroupedCards();
-      gridGroupedCards();
-      gridRepositionGroups();
+      recordSceneChange("Auto-layout", "all", () => {
+        gridUngroupedCards();
+        gridGroupedCards();
+        gridRepositionGroups();
+      });
       // After tidying, center the view on the resulting content for clarity
       focusViewOnContent(180);
     });
@@ -4210,41 +7431,58 @@ const splashEl = document.getElementById("splash");
         if (seen.has(key)) toDelete.push(s);
         else seen.add(key);
       }
-      if (toDelete.length) await deleteSelectedCardsFast(toDelete);
+      if (!toDelete.length) return;
+      const ch = beginSceneChange("De-duplicate", toDelete);
+      const done = deleteSelectedCardsFast(toDelete);
+      refreshSmartGroups(ch);
+      commitSceneChange(ch);
+      await done;
     });
     // Auto-Group tools (hold Shift/Alt to include singletons)
     addBtn("Auto-Group by Set", (ev) => {
-      autoGroupUngroupedBy("set", {
-        includeSingletons: ev.shiftKey || ev.altKey,
-      });
+      recordSceneChange("Auto-group", "all", () =>
+        autoGroupUngroupedBy("set", {
+          includeSingletons: ev.shiftKey || ev.altKey,
+        }),
+      );
     });
     addBtn("Auto-Group by Color Identity", (ev) => {
-      autoGroupUngroupedBy("color-id", {
-        includeSingletons: ev.shiftKey || ev.altKey,
-      });
+      recordSceneChange("Auto-group", "all", () =>
+        autoGroupUngroupedBy("color-id", {
+          includeSingletons: ev.shiftKey || ev.altKey,
+        }),
+      );
     });
     addBtn("Auto-Group by Type", (ev) => {
-      autoGroupUngroupedBy("type", {
-        includeSingletons: ev.shiftKey || ev.altKey,
-      });
+      recordSceneChange("Auto-group", "all", () =>
+        autoGroupUngroupedBy("type", {
+          includeSingletons: ev.shiftKey || ev.altKey,
+        }),
+      );
     });
     addBtn("Auto-Group by Rarity", (ev) => {
-      autoGroupUngroupedBy("rarity", {
-        includeSingletons: ev.shiftKey || ev.altKey,
-      });
+      recordSceneChange("Auto-group", "all", () =>
+        autoGroupUngroupedBy("rarity", {
+          includeSingletons: ev.shiftKey || ev.altKey,
+        }),
+      );
     });
     addBtn("Auto-Group by CMC", (ev) => {
-      autoGroupUngroupedBy("cmc", {
-        includeSingletons: ev.shiftKey || ev.altKey,
-      });
+      recordSceneChange("Auto-group", "all", () =>
+        autoGroupUngroupedBy("cmc", {
+          includeSingletons: ev.shiftKey || ev.altKey,
+        }),
+      );
     });
     addBtn("Reset Layout", () => {
       const ok = window.confirm(
-        "Full Reset will clear all groups and auto-layout all card. This cannot be undone. Proceed?",
+        "Full Reset will clear all groups and auto-layout all cards (Ctrl+Z to undo). Proceed?",
       );
       if (!ok) return;
-      clearGroupsOnly();
-      resetLayout(true);
+      recordSceneChange("Reset layout", "all", () => {
+        clearGroupsOnly();
+        resetLayout(true);
+      });
     });
     // Clear persisted data (moved from Import/Export panel)
     addBtn("Clear All Data", async (ev) => {
@@ -4313,15 +7551,17 @@ const splashEl = document.getElementById("splash");
       // Pack to update w/h and card positions relative to group origin
       {
         const items: SpatialItem[] = [];
-        autoPackGroup(gv, sprites, (s) => {
-          items.push({
-            sprite: s,
-            minX: s.x,
-            minY: s.y,
-            maxX: s.x + CARD_W_GLOBAL,
-            maxY: s.y + CARD_H_GLOBAL,
-          });
-        });
+        autoPackGroup(
+          gv,
+          sprites,
+          (s) => {
+            items.push({
+              sprite: s,
+              ...cardBounds(s),
+            });
+          },
+          groups,
+        );
         if (items.length) spatial.bulkUpdate(items);
       }
       // Shift group so its center remains the same after pack
@@ -4341,10 +7581,7 @@ const splashEl = document.getElementById("splash");
           sp.y = snap(sp.y + dy);
           moved.push({
             sprite: sp,
-            minX: sp.x,
-            minY: sp.y,
-            maxX: sp.x + CARD_W_GLOBAL,
-            maxY: sp.y + CARD_H_GLOBAL,
+            ...cardBounds(sp),
           });
         });
         if (moved.length) spatial.bulkUpdate(moved);
@@ -4362,25 +7599,6 @@ const splashEl = document.getElementById("splash");
   }
   // Auto-group ungrouped cards by property
   type AutoGroupKind = "set" | "color-id" | "type" | "rarity" | "cmc";
-  function primaryType(typeLine: string | undefined): string {
-    if (!typeLine) return "Unknown";
-    const tl = typeLine;
-    const order = [
-      "Creature",
-      "Instant",
-      "Sorcery",
-      "Artifact",
-      "Enchantment",
-      "Planeswalker",
-      "Battle",
-      "Land",
-    ];
-    for (const t of order) if (tl.includes(t)) return t;
-    // Fallback: token before dash or first word
-    const beforeDash = tl.split("—")[0].trim();
-    const first = beforeDash.split(/\s+/)[0] || "Unknown";
-    return first;
-  }
   function autoGroupUngroupedBy(
     kind: AutoGroupKind,
     opts?: { includeSingletons?: boolean },
@@ -4482,15 +7700,17 @@ const splashEl = document.getElementById("splash");
       // Pack each new group (layout within group) with batched spatial updates
       created.forEach((gv) => {
         const items: SpatialItem[] = [];
-        autoPackGroup(gv, sprites, (s) => {
-          items.push({
-            sprite: s,
-            minX: s.x,
-            minY: s.y,
-            maxX: s.x + CARD_W_GLOBAL,
-            maxY: s.y + CARD_H_GLOBAL,
-          });
-        });
+        autoPackGroup(
+          gv,
+          sprites,
+          (s) => {
+            items.push({
+              sprite: s,
+              ...cardBounds(s),
+            });
+          },
+          groups,
+        );
         if (items.length) spatial.bulkUpdate(items);
       });
       timer.mark("pack-new+spatial");
@@ -4573,8 +7793,9 @@ const splashEl = document.getElementById("splash");
       GroupsRepo.deleteMany(ids);
     }
     groups.clear();
-    // Clear persisted group transforms so they don't rehydrate
-    localStorage.removeItem(LS_GROUPS_KEY);
+    // Clear persisted group transforms so they don't rehydrate (notes and connectors are kept)
+    if (notes.size || connectors.size) persistence.flushGroups();
+    else localStorage.removeItem(LS_GROUPS_KEY);
   }
   function resetLayout(alreadyCleared: boolean) {
     // Assign default grid positions based on current sprite order
@@ -4586,31 +7807,15 @@ const splashEl = document.getElementById("splash");
       s.visible = true;
       updateCardSpriteAppearance(s, SelectionStore.state.cards.has(s));
     });
-    const n = sprites.length || 1;
-    // Choose grid shape that maximizes squaredness (same as Auto-Layout)
-    let bestCols = 1;
-    let bestRows = n;
-    let bestW = bestRows * CARD_W_GLOBAL + (bestRows - 1) * GAP_X_GLOBAL;
-    let bestH = CARD_H_GLOBAL;
-    let bestScore = Number.POSITIVE_INFINITY;
-    for (let c = 1; c <= n; c++) {
-      const r = Math.ceil(n / c);
-      const w = c * CARD_W_GLOBAL + (c - 1) * GAP_X_GLOBAL;
-      const h = r * CARD_H_GLOBAL + (r - 1) * GAP_Y_GLOBAL;
-      let score = Math.abs(w - h);
-      const lastRowCount = n - (r - 1) * c;
-      const underfill = c > 0 ? (c - lastRowCount) / c : 0;
-      score += underfill * Math.min(CARD_W_GLOBAL, CARD_H_GLOBAL) * 0.2;
-      if (score < bestScore) {
-        bestScore = score;
-        bestCols = c;
-        bestRows = r;
-        bestW = w;
-        bestH = h;
-      }
-    }
-    const blockW = bestW;
-    const blockH = bestH;
+    // Grid shape that maximizes squaredness (same as Auto-Layout), sized per card
+    const plan = planCardGrid(
+      sprites.map((s) => cardSize(s)),
+      GAP_X_GLOBAL,
+      GAP_Y_GLOBAL,
+      GRID_SIZE,
+    );
+    const blockW = plan.w;
+    const blockH = plan.h;
     const b = getCanvasBounds();
     // Start centered within bounds, then clamp so the whole block fits
     let startX = snap(Math.round(b.x + (b.w - blockW) / 2));
@@ -4619,22 +7824,15 @@ const splashEl = document.getElementById("splash");
     startY = Math.min(Math.max(b.y, startY), b.y + b.h - blockH);
     const batch: { id: number; x: number; y: number }[] = [];
     sprites.forEach((s, idx) => {
-      const col = idx % bestCols;
-      const row = Math.floor(idx / bestCols);
-      let x = startX + col * (CARD_W_GLOBAL + GAP_X_GLOBAL);
-      let y = startY + row * (CARD_H_GLOBAL + GAP_Y_GLOBAL);
+      const { w, h } = cardSize(s);
+      let x = startX + plan.pos[idx].x;
+      let y = startY + plan.pos[idx].y;
       // Safety clamp per-card
-      x = Math.min(b.x + b.w - CARD_W_GLOBAL, Math.max(b.x, x));
-      y = Math.min(b.y + b.h - CARD_H_GLOBAL, Math.max(b.y, y));
+      x = Math.min(b.x + b.w - w, Math.max(b.x, x));
+      y = Math.min(b.y + b.h - h, Math.max(b.y, y));
       s.x = x;
       s.y = y;
-      spatial.update({
-        sprite: s,
-        minX: x,
-        minY: y,
-        maxX: x + CARD_W_GLOBAL,
-        maxY: y + CARD_H_GLOBAL,
-      });
+      spatial.update({ sprite: s, ...cardBounds(s) });
       batch.push({ id: s.__id, x, y });
     });
     if (batch.length) {
@@ -4656,10 +7854,7 @@ const splashEl = document.getElementById("splash");
     let maxX = Number.NEGATIVE_INFINITY;
     let maxY = Number.NEGATIVE_INFINITY;
     for (const s of ungrouped) {
-      const x1 = s.x;
-      const y1 = s.y;
-      const x2 = s.x + CARD_W_GLOBAL;
-      const y2 = s.y + CARD_H_GLOBAL;
+      const { minX: x1, minY: y1, maxX: x2, maxY: y2 } = cardBounds(s);
       if (x1 < minX) minX = x1;
       if (y1 < minY) minY = y1;
       if (x2 > maxX) maxX = x2;
@@ -4668,32 +7863,16 @@ const splashEl = document.getElementById("splash");
     if (!Number.isFinite(minX)) return;
     const cx = (minX + maxX) / 2;
     const cy = (minY + maxY) / 2;
-    // Choose the grid that maximizes "squaredness" (minimize |W - H| in pixels)
-    const n = ungrouped.length;
-    let bestCols = 1;
-    let bestRows = n;
-    let bestW = bestRows * CARD_W_GLOBAL + (bestRows - 1) * GAP_X_GLOBAL;
-    let bestH = CARD_H_GLOBAL;
-    let bestScore = Number.POSITIVE_INFINITY;
-    // Try all feasible column counts; O(n) arithmetic is cheap and avoids surprises
-    for (let c = 1; c <= n; c++) {
-      const r = Math.ceil(n / c);
-      const w = c * CARD_W_GLOBAL + (c - 1) * GAP_X_GLOBAL;
-      const h = r * CARD_H_GLOBAL + (r - 1) * GAP_Y_GLOBAL;
-      // Primary: pixel difference between width and height
-      let score = Math.abs(w - h);
-      // Gentle penalty for underfilled last row to avoid extremely ragged final line
-      const lastRowCount = n - (r - 1) * c;
-      const underfill = c > 0 ? (c - lastRowCount) / c : 0;
-      score += underfill * Math.min(CARD_W_GLOBAL, CARD_H_GLOBAL) * 0.2;
-      if (score < bestScore) {
-        bestScore = score;
-        bestCols = c;
-        bestRows = r;
-        bestW = w;
-        bestH = h;
-      }
-    }
+    // Choose the grid that maximizes "squaredness" (minimize |W - H| in pixels); columns
+    // and rows take the size of their largest card so tapped or enlarged cards fit
+    const plan = planCardGrid(
+      ungrouped.map((s) => cardSize(s)),
+      GAP_X_GLOBAL,
+      GAP_Y_GLOBAL,
+      GRID_SIZE,
+    );
+    const bestW = plan.w;
+    const bestH = plan.h;
     // Anchor the new grid so its center matches the previous cluster center (minimal translation)
     let startX = snap(Math.round(cx - bestW / 2));
     let startY = snap(Math.round(cy - bestH / 2));
@@ -4705,22 +7884,15 @@ const splashEl = document.getElementById("splash");
     startY = Math.min(Math.max(b.y, startY), maxStartY);
     const batch: { id: number; x: number; y: number }[] = [];
     ungrouped.forEach((s, idx) => {
-      const col = idx % bestCols;
-      const row = Math.floor(idx / bestCols);
-      let x = startX + col * (CARD_W_GLOBAL + GAP_X_GLOBAL);
-      let y = startY + row * (CARD_H_GLOBAL + GAP_Y_GLOBAL);
+      const { w, h } = cardSize(s);
+      let x = startX + plan.pos[idx].x;
+      let y = startY + plan.pos[idx].y;
       // Extra safety clamp for each card
-      x = Math.min(b.x + b.w - CARD_W_GLOBAL, Math.max(b.x, x));
-      y = Math.min(b.y + b.h - CARD_H_GLOBAL, Math.max(b.y, y));
+      x = Math.min(b.x + b.w - w, Math.max(b.x, x));
+      y = Math.min(b.y + b.h - h, Math.max(b.y, y));
       s.x = x;
       s.y = y;
-      spatial.update({
-        sprite: s,
-        minX: x,
-        minY: y,
-        maxX: x + CARD_W_GLOBAL,
-        maxY: y + CARD_H_GLOBAL,
-      });
+      spatial.update({ sprite: s, ...cardBounds(s) });
       batch.push({ id: s.__id, x, y });
     });
     if (batch.length) {
@@ -4731,7 +7903,8 @@ const splashEl = document.getElementById("splash");
     }
   }
   function gridRepositionGroups() {
-    const list = Array.from(groups.values());
+    // Nested groups move with their parents
+    const list = Array.from(groups.values()).filter((g) => g.parentId == null);
     // Tighter visual order: largest groups first, then by name, then by id for stability
     list.sort((a, b) => {
       const as = a.order.length;
@@ -4751,8 +7924,9 @@ const splashEl = document.getElementById("splash");
       uc = 0;
     for (const s of sprites) {
       if ((s as any).__groupId) continue; // only ungrouped
-      ux += s.x + CARD_W_GLOBAL / 2;
-      uy += s.y + CARD_H_GLOBAL / 2;
+      const { w, h } = cardSize(s);
+      ux += s.x + w / 2;
+      uy += s.y + h / 2;
       uc++;
     }
     if (uc > 0) {
@@ -4813,10 +7987,7 @@ const splashEl = document.getElementById("splash");
     if (uc > 0) {
       for (const s of sprites) {
         if ((s as any).__groupId) continue;
-        const x1 = s.x,
-          y1 = s.y,
-          x2 = s.x + CARD_W_GLOBAL,
-          y2 = s.y + CARD_H_GLOBAL;
+        const { minX: x1, minY: y1, maxX: x2, maxY: y2 } = cardBounds(s);
         if (x1 < uminX) uminX = x1;
         if (y1 < uminY) uminY = y1;
         if (x2 > umaxX) umaxX = x2;
@@ -5090,8 +8261,8 @@ const splashEl = document.getElementById("splash");
       attractStrength: 0,
       preserveOrder: true,
       // Avoid treating the current groups as obstacles; we are replacing their positions
-      excludeGroupIds: list.map((g) => g.id),
-      excludeSpriteGroupIds: list.map((g) => g.id),
+      excludeGroupIds: [...groups.keys()],
+      excludeSpriteGroupIds: [...groups.keys()],
       obstacleMode: "cardsOnly",
       desiredSeeds,
     });
@@ -5106,20 +8277,19 @@ const splashEl = document.getElementById("splash");
       const dx = newX - gv.gfx.x;
       const dy = newY - gv.gfx.y;
       if (dx === 0 && dy === 0) continue;
-      gv.gfx.x = newX;
-      gv.gfx.y = newY;
-      gv.order.forEach((sp) => {
-        sp.x = snap(sp.x + dx);
-        sp.y = snap(sp.y + dy);
-        spatialItems.push({
-          sprite: sp,
-          minX: sp.x,
-          minY: sp.y,
-          maxX: sp.x + CARD_W_GLOBAL,
-          maxY: sp.y + CARD_H_GLOBAL,
+      for (const g of [gv, ...descendantGroups(gv, groups)]) {
+        g.gfx.x += dx;
+        g.gfx.y += dy;
+        g.order.forEach((sp) => {
+          sp.x = snap(sp.x + dx);
+          sp.y = snap(sp.y + dy);
+          spatialItems.push({
+            sprite: sp,
+            ...cardBounds(sp),
+          });
+          batch.push({ id: sp.__id, x: sp.x, y: sp.y });
         });
-        batch.push({ id: sp.__id, x: sp.x, y: sp.y });
-      });
+      }
     }
     if (spatialItems.length) spatial.bulkUpdate(spatialItems);
     if (batch.length) {
@@ -5140,6 +8310,10 @@ const splashEl = document.getElementById("splash");
   ) {
     const timer = createPhaseTimer(`placeGroupSmart#${gv.id}`);
     const pad = opts?.pad ?? 16;
+    // Nested groups and their cards travel with gv and never count as obstacles
+    const tree = new Set([gv, ...descendantGroups(gv, groups)]);
+    const inTree = (s: CardSprite) =>
+      s.__groupId != null && tree.has(groups.get(s.__groupId)!);
     // Determine anchor
     let startX = 0,
       startY = 0;
@@ -5169,7 +8343,7 @@ const splashEl = document.getElementById("splash");
         gy2 = y + gv.h + pad;
       // Check other groups' frames first (groups are relatively few)
       for (const eg of groups.values()) {
-        if (eg.id === gv.id) continue;
+        if (tree.has(eg)) continue;
         const x1 = eg.gfx.x - pad,
           y1 = eg.gfx.y - pad,
           x2 = eg.gfx.x + eg.w + pad,
@@ -5182,7 +8356,7 @@ const splashEl = document.getElementById("splash");
         // If any hit is not one of our member sprites, we collide
         for (let i = 0; i < hits.length; i++) {
           const it = hits[i];
-          if (!gv.items.has(it.sprite)) return true;
+          if (!inTree(it.sprite)) return true;
         }
       }
       return false;
@@ -5214,17 +8388,18 @@ const splashEl = document.getElementById("splash");
         minY = 0;
       let initialized = false;
       sprites.forEach((s) => {
+        const right = cardBounds(s).maxX;
         if (!initialized) {
-          maxX = s.x + CARD_W_GLOBAL;
+          maxX = right;
           minY = s.y;
           initialized = true;
         } else {
-          if (s.x + CARD_W_GLOBAL > maxX) maxX = s.x + CARD_W_GLOBAL;
+          if (right > maxX) maxX = right;
           if (s.y < minY) minY = s.y;
         }
       });
       groups.forEach((eg) => {
-        if (eg.id === gv.id) return;
+        if (tree.has(eg)) return;
         if (eg.gfx.x + eg.w > maxX) maxX = eg.gfx.x + eg.w;
         if (eg.gfx.y < minY) minY = eg.gfx.y;
       });
@@ -5236,21 +8411,14 @@ const splashEl = document.getElementById("splash");
     const dx = clamped.x - gv.gfx.x;
     const dy = clamped.y - gv.gfx.y;
     if (dx || dy) {
-      gv.gfx.x = clamped.x;
-      gv.gfx.y = clamped.y;
-      // Shift member cards with the group pre-layout and batch spatial updates
+      // Shift member cards (and nested groups) with the group and batch spatial updates
       const items: SpatialItem[] = [];
-      for (const s of gv.order) {
-        s.x += dx;
-        s.y += dy;
+      translateGroupTree(gv, dx, dy, groups, (s) =>
         items.push({
           sprite: s,
-          minX: s.x,
-          minY: s.y,
-          maxX: s.x + CARD_W_GLOBAL,
-          maxY: s.y + CARD_H_GLOBAL,
-        });
-      }
+          ...cardBounds(s),
+        }),
+      );
       if (items.length) spatial.bulkUpdate(items);
       timer.mark("shift+spatial");
     }
@@ -5262,6 +8430,7 @@ const splashEl = document.getElementById("splash");
   // Centralized clear function used by Debug and Import/Export integration
   async function clearAllData(): Promise<void> {
     // Clear persisted artifacts
+    History.clear();
     SUPPRESS_SAVES = true;
     if (lsTimer) {
       clearTimeout(lsTimer);
@@ -5291,263 +8460,263 @@ const splashEl = document.getElementById("splash");
     } catch {}
   }
 
+  // Cards already on the canvas seed import lookups so they aren't refetched
+  const canvasCards = () =>
+    sprites.map((s) => s.__card).filter((c): c is any => !!c);
+
+  // Create sprites for resolved cards: one group per definition (placed smartly) plus an
+  // ungrouped block placed with the shared import planner. Shared by group/Arena imports.
+  // A definition deeper than the one before it (`depth`) is nested inside that group.
+  // With `stack`, copies of a card share one spot and the new groups stack duplicates.
+  type ImportItem = { card: any; foil?: boolean };
+  async function placeImportedGroups(
+    groupDefs: { name: string; depth?: number; cards: ImportItem[] }[],
+    ungroupedCards: ImportItem[],
+    stack = false,
+  ): Promise<{ imported: number; limited: number }> {
+    const ch = beginSceneChange("Import");
+    let imported = 0;
+    let limited = 0;
+    const open: { depth: number; gv: GroupVisual }[] = [];
+    const roots: GroupVisual[] = [];
+    // Create groups with cards
+    for (let gi = 0; gi < groupDefs.length; gi++) {
+      const g = groupDefs[gi];
+      const depth = g.depth ?? 1;
+      while (open.length && open[open.length - 1].depth >= depth) open.pop();
+      // Headings without cards are kept only when they hold nested groups
+      const hasNested = (groupDefs[gi + 1]?.depth ?? 1) > depth;
+      if (!g.cards.length && !hasNested) continue;
+      if (remainingCapacity() <= 0) break;
+      // Create instances for these cards; leverage existing placement/group helper
+      let maxId = sprites.length ? Math.max(...sprites.map((s) => s.__id)) : 0;
+      // Place temporarily at origin (they’ll be moved by group placement helper)
+      const bulkItems: Array<{
+        id: number;
+        x: number;
+        y: number;
+        z: number;
+        group_id?: number | null;
+        card?: any;
+        scryfall_id?: string | null;
+        foil?: boolean;
+      }> = [];
+      for (const it of g.cards) {
+        if (remainingCapacity() <= 0) {
+          limited += 1;
+          break;
+        }
+        let id: number;
+        const x = 0,
+          y = 0;
+        try {
+          id = InstancesRepo.create(1, x, y);
+        } catch {
+          id = ++maxId;
+        }
+        const z = zCounter++;
+        bulkItems.push({
+          id: id,
+          x: x,
+          y: y,
+          z: z,
+          card: it.card,
+          foil: it.foil,
+        });
+      }
+      let made: CardSprite[] = [];
+      if (bulkItems.length) {
+        made = createSpritesBulk(bulkItems);
+        imported += made.length;
+      }
+      if (!made.length && !hasNested) continue;
+      const gv = createGroupWithSpritesAndName(made, g.name, {
+        parent: open[open.length - 1]?.gv,
+        stackDuplicates: stack,
+      });
+      if (!open.length) roots.push(gv);
+      open.push({ depth, gv });
+    }
+    // Top-level groups were placed before their nested groups grew them: re-pack each
+    // tree into a compact block and find it a spot clear of its neighbours again
+    for (const root of roots) {
+      if (!childGroups(root, groups).length) continue;
+      const items: SpatialItem[] = [];
+      autoPackGroup(
+        root,
+        sprites,
+        (s) => items.push({ sprite: s, ...cardBounds(s) }),
+        groups,
+      );
+      if (items.length) spatial.bulkUpdate(items);
+      placeGroupSmart(root, { anchor: "centroid" });
+      [root, ...descendantGroups(root, groups)].forEach((g) =>
+        persistGroupTransform(g.id, { x: g.gfx.x, y: g.gfx.y, w: g.w, h: g.h }),
+      );
+    }
+    // Place ungrouped cards using the shared import planner (flow-around when applicable)
+    if (ungroupedCards.length && remainingCapacity() > 0) {
+      const cap = remainingCapacity();
+      const take = Math.min(cap, ungroupedCards.length);
+      if (take < ungroupedCards.length) limited += ungroupedCards.length - take;
+      // One planned spot per card, or per distinct printing when stacking
+      const spots = new Map<string, number>();
+      const spotOf = ungroupedCards.slice(0, take).map((it, i) => {
+        const key = stack ? `${it.card?.id}:${it.foil ? 1 : 0}` : String(i);
+        if (!spots.has(key)) spots.set(key, spots.size);
+        return spots.get(key)!;
+      });
+      const planned = planImportPositions(spots.size, buildPlacementContext());
+      const positions = planned.positions;
+      let maxId = sprites.length ? Math.max(...sprites.map((s) => s.__id)) : 0;
+      const bulkItems = spotOf.map((spot, i) => {
+        const { x, y } = positions[spot];
+        let id: number;
+        try {
+          id = InstancesRepo.create(1, x, y);
+        } catch {
+          id = ++maxId;
+        }
+        const it = ungroupedCards[i];
+        return { id, x, y, z: zCounter++, card: it.card, foil: it.foil };
+      });
+      const made = createSpritesBulk(bulkItems);
+      imported += made.length;
+      camera.fitBounds(planned.block, {
+        w: window.innerWidth,
+        h: window.innerHeight,
+      });
+    }
+    refreshSmartGroups(ch);
+    commitSceneChange(ch);
+    // Persist raw imported cards for rehydration
+    const allCards: any[] = [
+      ...groupDefs.flatMap((g) => g.cards),
+      ...ungroupedCards,
+    ].map((it) => it.card);
+    if (allCards.length) {
+      await addImportedCards(allCards);
+    }
+    // Persist positions
+    if (!SUPPRESS_SAVES) {
+      persistence.flushPositions();
+    }
+    return { imported, limited };
+  }
+
+  // Canvas side of a plain decklist import (services/deckImport.ts)
+  const deckImportTarget: DeckImportTarget = {
+    known: canvasCards,
+    capacity: remainingCapacity,
+    placement: buildPlacementContext,
+    place: async (planned, block) => {
+      let maxId = sprites.length ? Math.max(...sprites.map((s) => s.__id)) : 0;
+      const bulkItems = planned.map(({ card, foil, x, y }) => {
+        let id: number;
+        try {
+          id = InstancesRepo.create(1, x, y);
+        } catch {
+          id = ++maxId;
+        }
+        return { id, x, y, z: zCounter++, card, foil };
+      });
+      const ch = beginSceneChange("Import");
+      const created = createSpritesBulk(bulkItems);
+      refreshSmartGroups(ch);
+      commitSceneChange(ch);
+      // Persist raw imported cards so they rehydrate on reload
+      await addImportedCards(planned.map((p) => p.card));
+      if (!SUPPRESS_SAVES) persistence.flushPositions();
+      // Fit to planned block for predictable framing
+      if (created.length)
+        camera.fitBounds(block, { w: window.innerWidth, h: window.innerHeight });
+      return created.length;
+    },
+  };
+
   // Import/Export decklists (basic)
   const importExportUI = installImportExport({
     getSprites: () => sprites,
     getGroups: () => groups,
+    getConnectors: () =>
+      liveConnectorRecords().flatMap((rec) => {
+        const end = (e: ConnectorEnd) => {
+          if (e.kind === "group") {
+            const gv = groups.get(e.id);
+            return gv ? { group: gv } : null;
+          }
+          const s = connectorSprite(e.id);
+          return s ? { sprite: s } : null;
+        };
+        const from = end(rec.from);
+        const to = end(rec.to);
+        return from && to ? [{ from, to, label: rec.label }] : [];
+      }),
     getAllNames: () => sprites.map((s) => (s as any).__card?.name || ""),
     getSelectedNames: () =>
       SelectionStore.getCards().map((s) => s.__card?.name || ""),
     importGroups: async (data, opt) => {
-      // Build lookup by lowercase name from currently loaded sprites; extend by fetching from Scryfall if needed.
-      const byName = new Map<string, any>();
-      for (const s of sprites) {
-        const c = s.__card;
-        if (!c) continue;
-        const nm = (c.name || "").toLowerCase();
-        if (nm && !byName.has(nm)) byName.set(nm, c);
-      }
-      // Collect original input names preserving user casing for better error messages
-      const allInputNames: string[] = [
-        ...data.groups.flatMap((g) => g.cards),
-        ...data.ungrouped,
-      ].filter((n) => typeof n === "string" && n.trim().length);
-      // Resolve missing names using Scryfall collection API
-      let unknown: string[] = [];
-      const unresolvedOriginal = allInputNames.filter(
-        (n) => !byName.has((n || "").toLowerCase()),
-      );
-      if (unresolvedOriginal.length) {
-        try {
-          const { byName: fetched, unknown: notFound } =
-            await fetchScryfallByNames(unresolvedOriginal, {
-              signal: (opt as any)?.signal,
-              onProgress: opt?.onProgress,
-            });
-          // Merge into byName map
-          fetched.forEach((card, key) => {
-            if (!byName.has(key)) byName.set(key, card);
-          });
-          // Compute unknowns based on what remains unresolved
-          const notFoundSet = new Set<string>(notFound);
-          unknown = unresolvedOriginal.filter((n) =>
-            notFoundSet.has(n.toLowerCase()),
-          );
-        } catch (e) {
-          console.warn("[importGroups] scryfall collection failed", e);
-          // If the fetch failed entirely, mark all unresolved as unknown
-          unknown = unresolvedOriginal.slice();
-        }
-      }
-      // Helper to create instances for a list of names
-      function resolveCards(names: string[]): any[] {
-        return names
-          .map((n) => byName.get((n || "").toLowerCase()))
-          .filter(Boolean);
-      }
-      const groupDefs = data.groups.map((g) => ({
+      // Structured entries carry printings; older callers may only pass names
+      const toEntries = (names: string[], entries?: DeckEntry[]) =>
+        entries && entries.length === names.length
+          ? entries
+          : names.map((name) => ({ name }));
+      const groupEntries = data.groups.map((g) => ({
         name: g.name || "Group",
-        cards: resolveCards(g.cards),
+        depth: g.depth,
+        entries: toEntries(g.cards, g.entries),
       }));
-      const ungroupedCards = resolveCards(data.ungrouped);
-      // unknown was already computed from Scryfall not_found (if any).
-      let imported = 0;
-      let limited = 0;
-      // Create groups with cards
-      for (const g of groupDefs) {
-        if (!g.cards.length) continue;
-        if (remainingCapacity() <= 0) break;
-        // Create instances for these cards; leverage existing placement/group helper
-        let maxId = sprites.length
-          ? Math.max(...sprites.map((s) => s.__id))
-          : 0;
-        // Place temporarily at origin (they’ll be moved by group placement helper)
-        const bulkItems: Array<{
-          id: number;
-          x: number;
-          y: number;
-          z: number;
-          group_id?: number | null;
-          card?: any;
-          scryfall_id?: string | null;
-        }> = [];
-        for (const card of g.cards) {
-          if (remainingCapacity() <= 0) {
-            limited += 1;
-            break;
-          }
-          let id: number;
-          const x = 0,
-            y = 0;
-          try {
-            id = InstancesRepo.create(1, x, y);
-          } catch {
-            id = ++maxId;
-          }
-          const z = zCounter++;
-          bulkItems.push({
-            id: id,
-            x: x,
-            y: y,
-            z: z,
-            card: card,
-          });
-        }
-        let made: CardSprite[] = [];
-        if (bulkItems.length) {
-          made = createSpritesBulk(bulkItems);
-          imported += made.length;
-        }
-        if (made.length) createGroupWithSpritesAndName(made, g.name);
-      }
-      // Place ungrouped cards using the shared import planner (flow-around when applicable)
-      if (ungroupedCards.length && remainingCapacity() > 0) {
-        const cap = remainingCapacity();
-        const take = Math.min(cap, ungroupedCards.length);
-        if (take < ungroupedCards.length)
-          limited += ungroupedCards.length - take;
-        const planned = planImportPositions(take, buildPlacementContext());
-        const positions = planned.positions;
-        let maxId = sprites.length
-          ? Math.max(...sprites.map((s) => s.__id))
-          : 0;
-        const bulkItems = positions.map(
-          ({ x, y }: { x: number; y: number }, i: number) => {
-            let id: number;
-            try {
-              id = InstancesRepo.create(1, x, y);
-            } catch {
-              id = ++maxId;
-            }
-            return { id, x, y, z: zCounter++, card: ungroupedCards[i] };
-          },
-        );
-        const made = createSpritesBulk(bulkItems);
-        imported += made.length;
-        camera.fitBounds(planned.block, {
-          w: window.innerWidth,
-          h: window.innerHeight,
+      const ungroupedEntries = toEntries(data.ungrouped, data.ungroupedEntries);
+      const priorCards = new Set(sprites.map((s) => s.__id));
+      const priorGroups = new Set(groups.keys());
+      const { resolve, unknown } = await resolveDeckEntries(
+        cardSource,
+        [...groupEntries.flatMap((g) => g.entries), ...ungroupedEntries],
+        { ...opt, known: canvasCards() },
+      );
+      const toItems = (entries: DeckEntry[]): ImportItem[] =>
+        entries.flatMap((e) => {
+          const card = resolve(e);
+          return card ? [{ card, foil: e.foil }] : [];
         });
-      }
-      // Persist raw imported cards for rehydration
-      const allCards: any[] = [
-        ...groupDefs.flatMap((g) => g.cards),
-        ...ungroupedCards,
-      ];
-      if (allCards.length) {
-        await addImportedCards(allCards);
-      }
-      // Persist positions
-      if (!SUPPRESS_SAVES) {
-        persistence.flushPositions();
-      }
+      const { imported, limited } = await placeImportedGroups(
+        groupEntries.map((g) => ({
+          name: g.name,
+          depth: g.depth,
+          cards: toItems(g.entries),
+        })),
+        toItems(ungroupedEntries),
+        opt?.stackDuplicates,
+      );
+      if (data.combos?.length)
+        connectImportedCombos(data.combos, priorCards, priorGroups);
       return { imported, unknown, limited };
     },
-    importByNames: async (items, opt) => {
-      // Build lookup from currently loaded sprites by lowercase name -> card object
-      const byName = new Map<string, any>();
-      for (const s of sprites) {
-        const c = (s as any).__card;
-        if (!c) continue;
-        const nm = (c.name || "").toLowerCase();
-        if (nm && !byName.has(nm)) byName.set(nm, c);
-      }
-      let unknown: string[] = [];
-      // Prepare placement variables (anchor computed after we know total count)
-      // Choose near-square grid for the deck block; adjust after we know total count
-      let placed = 0;
-      let maxId = sprites.length ? Math.max(...sprites.map((s) => s.__id)) : 0;
-      const created: CardSprite[] = [];
-      let limited = 0;
-      const toPlace: { card: any; count: number }[] = [];
-      const originalUnknown: string[] = [];
-      for (const it of items) {
-        const card = byName.get((it.name || "").toLowerCase());
-        if (!card) {
-          originalUnknown.push(it.name);
-          continue;
-        }
-        toPlace.push({ card, count: Math.max(1, Math.min(999, it.count | 0)) });
-      }
-      // If any names weren't found locally, fetch them from Scryfall
-      if (originalUnknown.length) {
-        try {
-          const { byName: fetched } = await fetchScryfallByNames(
-            originalUnknown,
-            {
-              signal: (opt as any)?.signal,
-              onProgress: opt?.onProgress,
-            },
-          );
-          fetched.forEach((card, key) => {
-            if (!byName.has(key)) byName.set(key, card);
-          });
-          // Re-check unresolved and enqueue for placement
-          for (const nm of originalUnknown) {
-            const card = byName.get((nm || "").toLowerCase());
-            if (card) toPlace.push({ card, count: 1 });
-          }
-          // Now compute still-unknown by comparing items again
-          unknown = originalUnknown.filter(
-            (nm) => !byName.has((nm || "").toLowerCase()),
-          );
-        } catch (e) {
-          console.warn("[importByNames] scryfall collection failed", e);
-          unknown = originalUnknown.slice();
-        }
-      }
-      // Decide final block anchor and plan positions
-      let totalToPlace = toPlace.reduce((sum, p) => sum + p.count, 0);
-      if (remainingCapacity() <= 0) totalToPlace = 0;
-      else if (totalToPlace > remainingCapacity()) {
-        limited += totalToPlace - remainingCapacity();
-        totalToPlace = remainingCapacity();
-      }
-      const { positions: planned, block: blk } = planImportPositions(
-        totalToPlace,
-        buildPlacementContext(),
+    importArena: async (sections, opt) => {
+      const { resolve, unknown } = await resolveDeckEntries(
+        cardSource,
+        sections.flatMap((sec) => sec.cards),
+        { ...opt, known: canvasCards() },
       );
-      const persistedCards: any[] = [];
-      // Pre-allocate instance records and build bulk creation list
-      const bulkItems: {
-        id: number;
-        x: number;
-        y: number;
-        z: number;
-        card: any;
-      }[] = [];
-      let capLeft = totalToPlace;
-      for (const pl of toPlace) {
-        if (capLeft <= 0) break;
-        const n = Math.min(pl.count, capLeft);
-        for (let i = 0; i < n; i++) {
-          const pos = planned[placed++];
-          const x = pos.x,
-            y = pos.y;
-          let id: number;
-          try {
-            id = InstancesRepo.create(1, x, y);
-          } catch {
-            id = ++maxId;
-          }
-          bulkItems.push({ id, x, y, z: zCounter++, card: pl.card });
-          persistedCards.push(pl.card);
-        }
-        capLeft -= n;
-      }
-      const bulkSprites = createSpritesBulk(bulkItems);
-      created.push(...bulkSprites);
-      // Persist raw imported cards so they rehydrate on reload
-      if (persistedCards.length) await addImportedCards(persistedCards);
-      if (!SUPPRESS_SAVES) {
-        persistence.flushPositions();
-      }
-      // Fit to planned block for predictable framing
-      if (created.length) {
-        camera.fitBounds(blk, { w: window.innerWidth, h: window.innerHeight });
-      }
-      return { imported: created.length, unknown, limited } as any;
+      // Each Arena section becomes a group named after it
+      const groupDefs = sections.map((sec) => ({
+        name: sec.name,
+        cards: sec.cards.flatMap((c) => {
+          const card = resolve(c);
+          if (!card) return [];
+          const n = Math.min(999, c.count);
+          return Array.from({ length: n }, () => ({ card, foil: c.foil }));
+        }),
+      }));
+      const { imported, limited } = await placeImportedGroups(
+        groupDefs,
+        [],
+        opt?.stackDuplicates,
+      );
+      return { imported, unknown, limited };
     },
+    importByNames: (items, opt) =>
+      importDeckEntries(cardSource, items, deckImportTarget, opt),
     scryfallSearchAndPlace: async (query, opt) => {
       // Prevent overlapping runs at the backend level as well
       const anyWin = window as any;
@@ -5566,7 +8735,8 @@ const splashEl = document.getElementById("splash");
         const partial: any[] = [];
         let results: any[] = [];
         try {
-          const cards = await searchScryfall(query, {
+          // Offline catalog when loaded, otherwise the live API
+          const cards = await cardSource.search(query, {
             maxCards: fetchMax,
             signal: (opt as any).signal,
             onProgress: (n, total) => {
@@ -5628,8 +8798,11 @@ const splashEl = document.getElementById("splash");
             return { id, x, y, z: zCounter++, card: results[i] };
           },
         );
+        const ch = beginSceneChange("Scryfall import");
         const bulkSprites = createSpritesBulk(bulkItems);
         created.push(...bulkSprites);
+        refreshSmartGroups(ch);
+        commitSceneChange(ch);
         // Persist raw imported cards so they rehydrate on reload
         await addImportedCards(results);
         // Persist positions
@@ -5782,11 +8955,132 @@ const splashEl = document.getElementById("splash");
     },
     { capture: true },
   );
+  // ---- Canvas query context ----
+  // What the canvas search fields (group:, tag:, owned:, count …) see for each card
+  function canvasContextLookup() {
+    const sel = SelectionStore.state.cards;
+    return createCanvasContextLookup(sprites, (s) => {
+      const names: string[] = [];
+      const seen = new Set<number>();
+      let gv = s.__groupId ? groups.get(s.__groupId) : undefined;
+      while (gv && !seen.has(gv.id)) {
+        names.push(gv.name);
+        seen.add(gv.id);
+        gv = gv.parentId != null ? groups.get(gv.parentId) : undefined;
+      }
+      return {
+        name: s.__card?.name ?? "",
+        printing: duplicateKey(s),
+        groups: names,
+        tags: InstancesRepo.get(s.__id)?.tags ?? null,
+        foil: !!s.__foil,
+        selected: sel.has(s),
+        face: s.__faceIndex || 0,
+      };
+    });
+  }
+
+  // ---- Search spotlight ----
+  // The palette hands over its matches while typing; matches get an outline, every other
+  // card dims, and group headers count the cards matching the query without its
+  // All / Ungrouped / Grouped term. Cards created, deleted or regrouped while it is on
+  // are marked (markSpotlightDirty) and re-tested on the next frame.
+  function setSpotlight(hit: SpotlightHit | null) {
+    spotlightHit = hit;
+    spotlightPending.clear();
+    spotlightAllDirty = false;
+    spotlightMatches.clear();
+    spotlightCounted.clear();
+    hit?.matches.forEach((s) => spotlightMatches.add(s));
+    if (hit && hit.count === hit.test)
+      spotlightMatches.forEach((s) => spotlightCounted.add(s));
+    else if (hit) {
+      const ctxOf = canvasContextLookup();
+      for (const s of sprites)
+        if (s.__groupId && hit.count(s, ctxOf)) spotlightCounted.add(s);
+    }
+    for (const s of sprites) {
+      let next: CardSprite["__spotlight"];
+      if (hit) next = spotlightMatches.has(s) ? "match" : "dim";
+      if (s.__spotlight === next) continue;
+      s.__spotlight = next;
+      updateCardSpriteAppearance(s, SelectionStore.state.cards.has(s));
+    }
+    if (!hit) spotlightLayer.clear();
+    refreshSpotlightCounts();
+  }
+  // Cards to re-test on the next frame; no arguments re-tests every card
+  function markSpotlightDirty(cards?: Iterable<CardSprite>) {
+    if (!spotlightHit) return;
+    if (!cards) spotlightAllDirty = true;
+    else for (const s of cards) spotlightPending.add(s);
+  }
+  function classifySpotlight(
+    hit: SpotlightHit,
+    s: CardSprite,
+    ctxOf: ReturnType<typeof canvasContextLookup>,
+  ) {
+    if (s.destroyed) {
+      spotlightMatches.delete(s);
+      spotlightCounted.delete(s);
+      return;
+    }
+    const match = hit.test(s, ctxOf);
+    const counted =
+      hit.count === hit.test ? match : !!s.__groupId && hit.count(s, ctxOf);
+    if (match) spotlightMatches.add(s);
+    else spotlightMatches.delete(s);
+    if (counted) spotlightCounted.add(s);
+    else spotlightCounted.delete(s);
+    const next = match ? "match" : "dim";
+    if (s.__spotlight === next) return;
+    s.__spotlight = next;
+    updateCardSpriteAppearance(s, SelectionStore.state.cards.has(s));
+  }
+  function refreshSpotlightCounts() {
+    const direct = new Map<number, number>();
+    if (spotlightHit)
+      spotlightCounted.forEach((s) => {
+        const gid = s.__groupId;
+        if (gid) direct.set(gid, (direct.get(gid) ?? 0) + 1);
+      });
+    groups.forEach((gv) => {
+      let next: number | null = null;
+      if (spotlightHit) {
+        next = direct.get(gv.id) ?? 0;
+        for (const d of descendantGroups(gv, groups))
+          next += direct.get(d.id) ?? 0;
+      }
+      if (gv.spotlightCount === next) return;
+      gv.spotlightCount = next;
+      drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
+    });
+  }
+  // Per frame while on: re-test marked cards, keep outlines on the matches
+  function syncSpotlight() {
+    const hit = spotlightHit;
+    if (!hit) return;
+    if (spotlightAllDirty || spotlightPending.size) {
+      const todo = spotlightAllDirty ? sprites : [...spotlightPending];
+      if (spotlightAllDirty) {
+        spotlightMatches.clear();
+        spotlightCounted.clear();
+      }
+      spotlightAllDirty = false;
+      spotlightPending.clear();
+      const ctxOf = canvasContextLookup();
+      for (const s of todo) classifySpotlight(hit, s, ctxOf);
+      refreshSpotlightCounts();
+    }
+    spotlightLayer.sync(spotlightMatches);
+  }
+
   // Search palette setup
   const searchUI = installSearchPalette({
     getSprites: () => sprites,
     createGroupForSprites: (cards: CardSprite[], name: string) => {
       const timer = createPhaseTimer("create-from-search");
+      const ch = beginSceneChange("Create group", cards);
       let id = groups.size ? Math.max(...groups.keys()) + 1 : 1;
       id = (GroupsRepo as any).create
         ? (GroupsRepo as any).create(name, null, 0, 0, 300, 300)
@@ -5820,15 +9114,17 @@ const splashEl = document.getElementById("splash");
       // Batch spatial updates for all moved cards
       {
         const items: SpatialItem[] = [];
-        autoPackGroup(gv, sprites, (s) => {
-          items.push({
-            sprite: s,
-            minX: s.x,
-            minY: s.y,
-            maxX: s.x + CARD_W_GLOBAL,
-            maxY: s.y + CARD_H_GLOBAL,
-          });
-        });
+        autoPackGroup(
+          gv,
+          sprites,
+          (s) => {
+            items.push({
+              sprite: s,
+              ...cardBounds(s),
+            });
+          },
+          groups,
+        );
         if (items.length) spatial.bulkUpdate(items);
       }
       timer.mark("auto-pack+spatial");
@@ -5841,6 +9137,7 @@ const splashEl = document.getElementById("splash");
       // Non-overlapping placement using the shared helper; anchor near selection centroid
       placeGroupSmart(gv, { anchor: "centroid" });
       timer.mark("place");
+      commitSceneChange(ch);
       scheduleGroupSave();
       // Fit new group into view
       const b = { x: gv.gfx.x, y: gv.gfx.y, w: gv.w, h: gv.h };
@@ -5855,9 +9152,15 @@ const splashEl = document.getElementById("splash");
     },
     focusSprite: (s: CardSprite) => {
       // Center camera on sprite without changing zoom drastically.
-      const target = { x: s.x, y: s.y, w: CARD_W_GLOBAL, h: CARD_H_GLOBAL };
+      const target = { x: s.x, y: s.y, ...cardSize(s) };
       camera.fitBounds(target, { w: window.innerWidth, h: window.innerHeight });
     },
+    spotlight: (hit) => setSpotlight(hit),
+    canvasContext: () => canvasContextLookup(),
+    createSmartGroup: (query, scope) => {
+      const rule = parseSmartGroupRule({ query, scope });
+      if (rule) createSmartGroup(rule);
+    },
   });
 
   // Global search hotkeys
@@ -5894,13 +9197,23 @@ const splashEl = document.getElementById("splash");
   function createGroupWithSpritesAndName(
     cards: CardSprite[],
     name: string,
-    options?: { silent?: boolean },
-  ) {
+    options?: {
+      silent?: boolean;
+      parent?: GroupVisual;
+      stackDuplicates?: boolean;
+    },
+  ): GroupVisual {
     const timer = createPhaseTimer("create-from-ids");
+    const parent = options?.parent ?? null;
     let id = groups.size ? Math.max(...groups.keys()) + 1 : 1;
-    id = GroupsRepo.create(name, null, 0, 0, 300, 300);
+    id = GroupsRepo.create(name, parent ? parent.id : null, 0, 0, 300, 300);
     const gv = createGroupVisual(id, 0, 0, 300, 300);
     gv.name = name;
+    gv.parentId = parent ? parent.id : null;
+    if (options?.stackDuplicates) {
+      gv.stackDuplicates = true;
+      persistGroupStacking(id, true);
+    }
     groups.set(id, gv);
     world.addChild(gv.gfx);
     attachResizeHandle(gv);
@@ -5917,23 +9230,26 @@ const splashEl = document.getElementById("splash");
       cards.map((s) => ({ id: s.__id, group_id: s.__groupId })),
     );
     timer.mark("persist-members");
-    // Smart non-overlapping placement near current view
-    placeGroupSmart(gv, { anchor: "centroid" });
+    // Smart non-overlapping placement near current view (nested groups go inside the parent)
+    if (!parent) placeGroupSmart(gv, { anchor: "centroid" });
     timer.mark("place");
     // Now size and layout the group at its final position
     {
       const items: SpatialItem[] = [];
-      autoPackGroup(gv, sprites, (s) => {
-        items.push({
-          sprite: s,
-          minX: s.x,
-          minY: s.y,
-          maxX: s.x + CARD_W_GLOBAL,
-          maxY: s.y + CARD_H_GLOBAL,
-        });
-      });
+      autoPackGroup(
+        gv,
+        sprites,
+        (s) => {
+          items.push({
+            sprite: s,
+            ...cardBounds(s),
+          });
+        },
+        groups,
+      );
       if (items.length) spatial.bulkUpdate(items);
     }
+    if (parent) relayoutAncestors(gv);
     timer.mark("auto-pack+spatial");
     touchedOldGroupIds.forEach((gid) => {
       const og = groups.get(gid);
@@ -5943,7 +9259,8 @@ const splashEl = document.getElementById("splash");
     // Save and optionally fit
     scheduleGroupSave();
     if (!options?.silent) {
-      const b = { x: gv.gfx.x, y: gv.gfx.y, w: gv.w, h: gv.h };
+      const top = outermostGroup(gv);
+      const b = { x: top.gfx.x, y: top.gfx.y, w: top.w, h: top.h };
       camera.fitBounds(b, { w: window.innerWidth, h: window.innerHeight });
     }
     persistGroupTransform(gv.id, {
@@ -5953,6 +9270,7 @@ const splashEl = document.getElementById("splash");
       h: gv.h,
     });
     timer.end({ cards: cards.length });
+    return gv;
   }
 
   // Camera animation/render loop with idle-aware throttling
@@ -6027,6 +9345,9 @@ const splashEl = document.getElementById("splash");
     const dt = now - last;
     last = now;
     camera.update(dt);
+    minimap?.update();
+    syncConnectors();
+    syncSpotlight();
     // Estimate pan speed in screen pixels per second
     const dx = world.position.x - lastWorldPosX;
     const dy = world.position.y - lastWorldPosY;
@@ -6060,12 +9381,20 @@ const splashEl = document.getElementById("splash");
       if (view) {
         for (let i = 0; i < sprites.length; i++) {
           const s = sprites[i];
-          if (!s.__card) continue;
+          // A copy that left its stack (or was revealed) re-plans the stacks
+          if (s.__stackOf && (!s.__hidden || !stackIntact(s)))
+            markDuplicateStacksDirty();
+          if (s.__stack || s.__stackBadge) updateStackBadge(s);
+          // Cards behind a collapsed pile or a stack need no textures
+          if (!s.__card || s.__hidden) continue;
           if (!skip || (i & 1) === (frame & 1)) ensureTexture(s, view);
           // Keep the Flip FAB positioned and scaled to maintain constant screen size
           updateFlipFab(s);
         }
       }
+      // Stacks wait until a card drag ends (the drop re-plans them anyway)
+      if (!(window as any).__mtgActiveCardDrag && takeDuplicateStacksDirty())
+        refreshDuplicateStacks();
     }
     // Update any active drag float/tilt transforms (only needed during drags)
     {
@@ -6134,6 +9463,7 @@ const splashEl = document.getElementById("splash");
               }
               moved.push(s);
             }
+            markConnectorsDirty(moved);
             if (moved.length) {
               const items: SpatialItem[] = [] as any;
               for (const ms of moved) {
@@ -6145,10 +9475,7 @@ const splashEl = document.getElementById("splash");
                   : ms.y;
                 items.push({
                   sprite: ms,
-                  minX: x,
-                  minY: y,
-                  maxX: x + CARD_W_GLOBAL,
-                  maxY: y + CARD_H_GLOBAL,
+                  ...cardBounds(ms, x, y),
                 });
               }
               if (items.length) spatial.bulkUpdate(items);
@@ -6161,6 +9488,7 @@ const splashEl = document.getElementById("splash");
               if (!g) return;
               g.gfx.x += dxWorld;
               g.gfx.y += dyWorld;
+              markConnectorsDirty(null, [g]);
               // Move members accordingly
               g.items.forEach((sp: CardSprite) => {
                 sp.x += dxWorld;
@@ -6172,10 +9500,7 @@ const splashEl = document.getElementById("splash");
             for (const ms of sprites) {
               items.push({
                 sprite: ms,
-                minX: ms.x,
-                minY: ms.y,
-                maxX: ms.x + CARD_W_GLOBAL,
-                maxY: ms.y + CARD_H_GLOBAL,
+                ...cardBounds(ms),
               });
             }
             if (items.length) spatial.bulkUpdate(items);
@@ -2,6 +2,7 @@ import RBush from "rbush";
 import type * as PIXI from "pixi.js";
 import type { CardSprite } from "./scene/cardNode";
 import type { GroupVisual } from "./scene/groupNode";
+import { cardBounds } from "./scene/cardTransform";
 
 export type Rect = { x: number; y: number; w: number; h: number };
 export interface PlacementContext {
@@ -16,6 +17,8 @@ export interface PlacementContext {
   gapY: number;
   spacingX: number;
   spacingY: number;
+  // Free-floating frames that aren't cards or groups (sticky notes); avoided like groups
+  notes?: Rect[];
 }
 
 const snap = (v: number, grid: number) => Math.round(v / grid) * grid;
@@ -30,6 +33,10 @@ type BushItem = {
   maxY: number;
 };
 
+function rectBox(r: Rect) {
+  return { minX: r.x, minY: r.y, maxX: r.x + r.w, maxY: r.y + r.h };
+}
+
 function buildObstacleIndex(ctx: PlacementContext, extra: Rect[] = []) {
   const tree = new RBush<BushItem>();
   let id = 1;
@@ -44,15 +51,9 @@ function buildObstacleIndex(ctx: PlacementContext, extra: Rect[] = []) {
     });
   });
   // Cards
-  for (const s of ctx.sprites) {
-    tree.insert({
-      id: id++,
-      minX: s.x,
-      minY: s.y,
-      maxX: s.x + ctx.cardW,
-      maxY: s.y + ctx.cardH,
-    });
-  }
+  for (const s of ctx.sprites) tree.insert({ id: id++, ...cardBounds(s) });
+  // Notes
+  for (const r of ctx.notes ?? []) tree.insert({ id: id++, ...rectBox(r) });
   // Extra placed (during planning)
   for (const r of extra) {
     tree.insert({
@@ -209,7 +210,10 @@ function bestFirstFreeSpot(
   return null;
 }
 
-export function computeBestGrid(n: number, ctx: PlacementContext) {
+export function computeBestGrid(
+  n: number,
+  ctx: Pick<PlacementContext, "cardW" | "cardH" | "gapX" | "gapY">,
+) {
   let bestCols = 1;
   let bestRows = n;
   let bestW = bestRows * ctx.cardW + (bestRows - 1) * ctx.gapX;
@@ -234,6 +238,55 @@ export function computeBestGrid(n: number, ctx: PlacementContext) {
   return { cols: bestCols, rows: bestRows, w: bestW, h: bestH };
 }
 
+// Grid for cards of mixed footprints (tapped or enlarged cards): the column count comes
+// from computeBestGrid on the average footprint, then each column is as wide as its
+// widest card and each row as tall as its tallest, so no card overlaps its neighbours.
+// Offsets are relative to the block's top-left; steps are rounded up to the grid.
+export function planCardGrid(
+  sizes: { w: number; h: number }[],
+  gapX: number,
+  gapY: number,
+  grid: number,
+): { pos: { x: number; y: number }[]; w: number; h: number } {
+  const n = sizes.length;
+  if (!n) return { pos: [], w: 0, h: 0 };
+  let sumW = 0;
+  let sumH = 0;
+  for (const z of sizes) {
+    sumW += z.w;
+    sumH += z.h;
+  }
+  const { cols, rows } = computeBestGrid(n, {
+    cardW: sumW / n,
+    cardH: sumH / n,
+    gapX,
+    gapY,
+  });
+  const colW = new Array<number>(cols).fill(0);
+  const rowH = new Array<number>(rows).fill(0);
+  sizes.forEach((z, i) => {
+    const c = i % cols;
+    const r = Math.floor(i / cols);
+    colW[c] = Math.max(colW[c], z.w);
+    rowH[r] = Math.max(rowH[r], z.h);
+  });
+  const snapUp = (v: number) => Math.ceil(v / grid) * grid;
+  const colX = [0];
+  for (let c = 1; c < cols; c++)
+    colX[c] = colX[c - 1] + snapUp(colW[c - 1] + gapX);
+  const rowY = [0];
+  for (let r = 1; r < rows; r++)
+    rowY[r] = rowY[r - 1] + snapUp(rowH[r - 1] + gapY);
+  return {
+    pos: sizes.map((_, i) => ({
+      x: colX[i % cols],
+      y: rowY[Math.floor(i / cols)],
+    })),
+    w: colX[cols - 1] + colW[cols - 1],
+    h: rowY[rows - 1] + rowH[rows - 1],
+  };
+}
+
 export function findFreeSpotForBlock(
   w: number,
   h: number,
@@ -243,7 +296,7 @@ export function findFreeSpotForBlock(
   const pad = opts?.pad ?? PAD;
   const b = ctx.getCanvasBounds();
   // If the canvas is empty, center the block within world bounds for a sensible default
-  if (ctx.sprites.length === 0) {
+  if (ctx.sprites.length === 0 && !ctx.notes?.length) {
     let cx = snap(Math.round(b.x + (b.w - w) / 2), ctx.gridSize);
     let cy = snap(Math.round(b.y + (b.h - h) / 2), ctx.gridSize);
     cx = Math.min(Math.max(b.x, cx), b.x + b.w - w);
@@ -289,16 +342,30 @@ export function findFreeSpotForBlock(
       }
       if (!collides) {
         for (const s of ctx.sprites) {
-          const x1 = s.x - pad,
-            y1 = s.y - pad,
-            x2 = s.x + ctx.cardW + pad,
-            y2 = s.y + ctx.cardH + pad;
+          const cb = cardBounds(s);
+          const x1 = cb.minX - pad,
+            y1 = cb.minY - pad,
+            x2 = cb.maxX + pad,
+            y2 = cb.maxY + pad;
           if (sx < x2 && sx + w > x1 && sy < y2 && sy + h > y1) {
             collides = true;
             break;
           }
         }
       }
+      if (!collides) {
+        for (const r of ctx.notes ?? []) {
+          if (
+            sx < r.x + r.w + pad &&
+            sx + w > r.x - pad &&
+            sy < r.y + r.h + pad &&
+            sy + h > r.y - pad
+          ) {
+            collides = true;
+            break;
+          }
+        }
+      }
       if (!collides) return { x: sx, y: sy };
     }
   }
@@ -354,12 +421,10 @@ function buildOccupancyGrid(ctx: PlacementContext) {
     for (let j = j0; j <= j1; j++)
       for (let i = i0; i <= i1; i++) occ[idx(i, j)] = 1;
   });
-  // Mark cards
-  for (const s of ctx.sprites) {
-    const x1 = s.x,
-      y1 = s.y,
-      x2 = s.x + ctx.cardW,
-      y2 = s.y + ctx.cardH;
+  // Mark cards and notes
+  for (const s of [...ctx.sprites, ...(ctx.notes ?? [])]) {
+    const box = "__id" in s ? cardBounds(s) : rectBox(s);
+    const { minX: x1, minY: y1, maxX: x2, maxY: y2 } = box;
     const i0 = clampI(Math.floor((x1 - originX) / ctx.spacingX) - di);
     const i1 = clampI(Math.ceil((x2 - originX) / ctx.spacingX) - 1 + di);
     const j0 = clampJ(Math.floor((y1 - originY) / ctx.spacingY) - dj);
@@ -655,6 +720,9 @@ export function planRectangles(
         maxY: eg.gfx.y + eg.h,
       });
     });
+  // Existing notes (kept clear like groups)
+  if (includeGroups)
+    for (const r of ctx.notes ?? []) tree.insert({ id: id++, ...rectBox(r) });
   // Existing cards
   const excludeSpriteGroups = new Set<number>(
     opts?.excludeSpriteGroupIds || [],
@@ -662,13 +730,7 @@ export function planRectangles(
   for (const s of ctx.sprites) {
     const gid = (s as any).__groupId as number | undefined;
     if (gid != null && excludeSpriteGroups.has(gid)) continue;
-    tree.insert({
-      id: id++,
-      minX: s.x,
-      minY: s.y,
-      maxX: s.x + ctx.cardW,
-      maxY: s.y + ctx.cardH,
-    });
+    tree.insert({ id: id++, ...cardBounds(s) });
   }
   // Seed (desired center)
   let desiredX = b.x + b.w / 2;
@@ -9,16 +9,26 @@ export interface SpatialItem {
   maxY: number;
 }
 
+type Bounds = { minX: number; minY: number; maxX: number; maxY: number };
+// rbush's internal node shape (all nodes on a level are leaves or none are)
+type TreeNode = Bounds & {
+  leaf: boolean;
+  children: (TreeNode | SpatialItem)[];
+};
+
 export class SpatialIndex {
   private tree = new RBush<SpatialItem>();
 
+  // Members of collapsed groups are never indexed (no hits, no culling work)
   insert(item: SpatialItem) {
+    if (item.sprite.__hidden) return;
     this.tree.insert(item);
   }
   // Efficiently load many items at once
   bulkLoad(items: SpatialItem[]) {
     if (!items || !items.length) return;
-    this.tree.load(items);
+    const shown = items.filter((it) => !it.sprite.__hidden);
+    if (shown.length) this.tree.load(shown);
   }
   removeBySprite(sprite: CardSprite) {
     this.tree.remove(
@@ -41,6 +51,20 @@ export class SpatialIndex {
   search(minX: number, minY: number, maxX: number, maxY: number) {
     return this.tree.search({ minX, minY, maxX, maxY });
   }
+  // Overview boxes for the minimap: the bounds of one tree level, as deep as possible
+  // without exceeding `limit` boxes (the cards themselves once few enough). O(limit).
+  clusters(limit: number): Bounds[] {
+    let level: TreeNode[] = [(this.tree as any).data];
+    for (;;) {
+      if (level[0].leaf) {
+        const items = level.flatMap((n) => n.children as SpatialItem[]);
+        return items.length <= limit ? items : level;
+      }
+      const next = level.flatMap((n) => n.children as TreeNode[]);
+      if (next.length > limit) return level;
+      level = next;
+    }
+  }
   count() {
     return (this.tree as any).all().length as number;
   }
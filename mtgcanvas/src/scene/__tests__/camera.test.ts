@@ -27,4 +27,19 @@ describe("Camera", () => {
     expect(world.scale.x).toBeGreaterThan(0);
     expect(Math.abs(world.scale.x - before.s)).toBeLessThan(1);
   });
+  it("centers on a world point at the current zoom", () => {
+    const world = new PIXI.Container();
+    const cam = new Camera({ world });
+    world.scale.set(2);
+    cam.centerOn(100, 50, { w: 800, h: 600 });
+    expect(world.position.x).toBe(400 - 200);
+    expect(world.position.y).toBe(300 - 100);
+    expect(world.scale.x).toBe(2);
+    // Animated variant lands on the same spot
+    cam.centerOn(0, 0, { w: 800, h: 600 }, 100);
+    expect(cam.isAnimating()).toBe(true);
+    cam.update(100);
+    expect(world.position.x).toBe(400);
+    expect(world.position.y).toBe(300);
+  });
 });
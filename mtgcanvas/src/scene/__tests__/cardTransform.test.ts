@@ -0,0 +1,81 @@
+import { describe, it, expect } from "vitest";
+import {
+  cardBounds,
+  cardPivot,
+  cardSize,
+  isTapped,
+  normalizeRotation,
+  normalizeScale,
+} from "../cardTransform";
+import { CARD_W, CARD_H } from "../../config/dimensions";
+
+// Rotate a local point about the pivot the way Pixi does (clockwise, y down)
+function place(
+  rotation: number,
+  k: number,
+  local: { x: number; y: number },
+  localW: number,
+  localH: number,
+) {
+  const p = cardPivot(rotation, localW, localH);
+  const a = ((local.x - p.x) * CARD_W * k) / localW;
+  const b = ((local.y - p.y) * CARD_H * k) / localH;
+  const r = (rotation * Math.PI) / 180;
+  // `|| 0` folds -0 so corners compare equal to 0
+  return {
+    x: Math.round(a * Math.cos(r) - b * Math.sin(r)) || 0,
+    y: Math.round(a * Math.sin(r) + b * Math.cos(r)) || 0,
+  };
+}
+
+describe("card transform", () => {
+  it("normalizes stored rotation and scale", () => {
+    expect(normalizeRotation(90)).toBe(90);
+    expect(normalizeRotation(-90)).toBe(270);
+    expect(normalizeRotation(450)).toBe(90);
+    expect(normalizeRotation(80)).toBe(90);
+    expect(normalizeRotation("x")).toBe(0);
+    expect(normalizeScale(undefined)).toBe(1);
+    expect(normalizeScale(0)).toBe(1);
+    expect(normalizeScale(2)).toBe(2);
+    expect(normalizeScale(100)).toBe(4);
+  });
+
+  it("swaps the footprint for tapped cards and scales it", () => {
+    expect(cardSize({})).toEqual({ w: CARD_W, h: CARD_H });
+    expect(isTapped({ __rotation: 270 })).toBe(true);
+    expect(isTapped({ __rotation: 180 })).toBe(false);
+    expect(cardSize({ __rotation: 90, __scale: 2 })).toEqual({
+      w: CARD_H * 2,
+      h: CARD_W * 2,
+    });
+    expect(cardBounds({ x: 10, y: 20, __rotation: 90 })).toEqual({
+      minX: 10,
+      minY: 20,
+      maxX: 10 + CARD_H,
+      maxY: 20 + CARD_W,
+    });
+    expect(cardBounds({ x: 0, y: 0, __scale: 2 }, 5, 6).maxX).toBe(
+      5 + CARD_W * 2,
+    );
+  });
+
+  it("pivots so the turned card covers its footprint from x/y", () => {
+    // Texture size differs from the display size (image tiers)
+    const [lw, lh] = [488, 680];
+    for (const rot of [0, 90, 180, 270])
+      for (const k of [1, 2]) {
+        const corners = [
+          { x: 0, y: 0 },
+          { x: lw, y: 0 },
+          { x: 0, y: lh },
+          { x: lw, y: lh },
+        ].map((c) => place(rot, k, c, lw, lh));
+        const xs = corners.map((c) => c.x);
+        const ys = corners.map((c) => c.y);
+        const size = cardSize({ __rotation: rot, __scale: k });
+        expect([Math.min(...xs), Math.min(...ys)]).toEqual([0, 0]);
+        expect([Math.max(...xs), Math.max(...ys)]).toEqual([size.w, size.h]);
+      }
+  });
+});
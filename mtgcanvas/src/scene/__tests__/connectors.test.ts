@@ -0,0 +1,66 @@
+import { describe, it, expect } from "vitest";
+import { connectorSegment, parseConnectorRecords } from "../connectors";
+
+describe("connector records", () => {
+  it("keeps valid connectors and drops broken ones", () => {
+    const recs = parseConnectorRecords([
+      {
+        id: 1,
+        from: { kind: "card", id: 4 },
+        to: { kind: "group", id: 2 },
+        label: "  infinite mana ",
+      },
+      { id: 1, from: { kind: "card", id: 5 }, to: { kind: "card", id: 6 } },
+      { id: 2, from: { kind: "card", id: 5 }, to: { kind: "card", id: 5 } },
+      { id: 3, from: { kind: "note", id: 1 }, to: { kind: "card", id: 6 } },
+      { id: 4, from: { kind: "card", id: 5 }, to: { kind: "card", id: 6 } },
+      null,
+    ]);
+    expect(recs).toEqual([
+      {
+        id: 1,
+        from: { kind: "card", id: 4 },
+        to: { kind: "group", id: 2 },
+        label: "infinite mana",
+      },
+      {
+        id: 4,
+        from: { kind: "card", id: 5 },
+        to: { kind: "card", id: 6 },
+        label: "",
+      },
+    ]);
+    expect(parseConnectorRecords("nope")).toEqual([]);
+  });
+});
+
+describe("connector geometry", () => {
+  it("runs between the facing edges of the two rects", () => {
+    const seg = connectorSegment(
+      { x: 0, y: 0, w: 100, h: 50 },
+      { x: 300, y: 0, w: 100, h: 50 },
+    )!;
+    expect(seg.x1).toBeCloseTo(100);
+    expect(seg.x2).toBeCloseTo(300);
+    expect([seg.y1, seg.y2]).toEqual([25, 25]);
+  });
+  it("clips diagonal lines on the nearer border", () => {
+    const seg = connectorSegment(
+      { x: 0, y: 0, w: 100, h: 100 },
+      { x: 200, y: 400, w: 100, h: 100 },
+    )!;
+    // Leaves the first rect through its bottom edge, enters the second through its top
+    expect(seg.y1).toBeCloseTo(100);
+    expect(seg.x1).toBeCloseTo(75);
+    expect(seg.y2).toBeCloseTo(400);
+    expect(seg.x2).toBeCloseTo(225);
+  });
+  it("returns null for overlapping rects", () => {
+    expect(
+      connectorSegment(
+        { x: 0, y: 0, w: 100, h: 100 },
+        { x: 50, y: 50, w: 100, h: 100 },
+      ),
+    ).toBeNull();
+  });
+});
@@ -0,0 +1,112 @@
+import { describe, it, expect } from "vitest";
+import {
+  buildDuplicateStacks,
+  duplicateKey,
+  groupDuplicates,
+  stackIntact,
+  type StackMember,
+} from "../duplicateStacks";
+
+type Fake = StackMember<any>;
+const all = () => true;
+
+let nextId = 1;
+function card(sid: string | undefined, x = 0, y = 0, extra?: Partial<Fake>) {
+  const c: Fake = { __id: nextId++, x, y, zIndex: 1, __scryfallId: sid };
+  return Object.assign(c, extra);
+}
+
+describe("duplicate stacks", () => {
+  it("keys copies by printing and finish", () => {
+    expect(duplicateKey({ __scryfallId: "a" })).toBe("a");
+    expect(duplicateKey({ __scryfallId: "a", __foil: true })).not.toBe("a");
+    expect(duplicateKey({})).toBeNull();
+  });
+
+  it("buckets layout slots in first-seen order", () => {
+    const [a1, b, a2, u1, u2] = [
+      card("a"),
+      card("b"),
+      card("a"),
+      card(undefined),
+      card(undefined),
+    ];
+    expect(groupDuplicates([a1, b, a2, u1, u2])).toEqual([
+      [a1, a2],
+      [b],
+      [u1],
+      [u2],
+    ]);
+  });
+
+  it("piles copies on the same spot under the topmost one", () => {
+    const low = card("a", 10, 20);
+    const top = card("a", 10, 20, { zIndex: 5 });
+    const tie = card("a", 10, 20);
+    const elsewhere = card("a", 30, 20);
+    const foil = card("a", 10, 20, { __foil: true });
+    const grouped = card("a", 10, 20, { __groupId: 7 });
+    const changed = buildDuplicateStacks(
+      [low, top, tie, elsewhere, foil, grouped],
+      () => false,
+      all,
+    );
+    expect(top.__stack).toEqual([low, tie]);
+    expect(low.__stackOf).toBe(top);
+    expect(low.__hidden && tie.__hidden).toBe(true);
+    expect(changed).toEqual([low, tie]);
+    for (const s of [elsewhere, foil, grouped]) {
+      expect(s.__stack).toBeUndefined();
+      expect(s.__hidden).toBeFalsy();
+    }
+  });
+
+  it("reveals copies that left a stack", () => {
+    const a = card("a");
+    const b = card("a");
+    const c = card("a");
+    buildDuplicateStacks([a, b, c], () => false, all);
+    expect(a.__stack).toEqual([b, c]);
+    c.x = 100;
+    expect(stackIntact(b)).toBe(true);
+    expect(stackIntact(c)).toBe(false);
+    const changed = buildDuplicateStacks([a, b, c], (s) => s === c, all);
+    expect(a.__stack).toEqual([b]);
+    // Collapsed groups keep their former followers hidden
+    expect(c.__stackOf).toBeUndefined();
+    expect(c.__hidden).toBe(true);
+    expect(changed).toEqual([]);
+    b.x = 50;
+    expect(buildDuplicateStacks([a, b, c], () => false, all)).toEqual([b]);
+    expect(a.__stack).toBeUndefined();
+    expect(b.__hidden).toBe(false);
+  });
+
+  it("stacks only in scopes that opted in", () => {
+    const a = card("a", 0, 0, { __groupId: 1 });
+    const b = card("a", 0, 0, { __groupId: 1 });
+    const c = card("a", 5, 5);
+    const d = card("a", 5, 5);
+    const inGroup = (s: Fake) => s.__groupId === 1;
+    buildDuplicateStacks([a, b, c, d], () => false, inGroup);
+    expect(a.__stack).toEqual([b]);
+    expect(c.__stack ?? d.__stack).toBeUndefined();
+    expect(c.__hidden || d.__hidden).toBeFalsy();
+    // Turning the group's stacking off reveals its copies again
+    expect(
+      buildDuplicateStacks([a, b, c, d], () => false, () => false),
+    ).toEqual([b]);
+    expect(a.__stack).toBeUndefined();
+  });
+
+  it("uses the drag anchor while a card floats", () => {
+    const rep = card("a", 0, 0, { zIndex: 2 });
+    const copy = card("a", 0, 0);
+    buildDuplicateStacks([rep, copy], () => false, all);
+    Object.assign(rep, { __tiltActive: true, __tlx: 40, __tly: 0, x: 90 });
+    copy.x = 40;
+    expect(stackIntact(copy)).toBe(true);
+    rep.destroyed = true;
+    expect(stackIntact(copy)).toBe(false);
+  });
+});
@@ -0,0 +1,71 @@
+import { describe, it, expect } from "vitest";
+import {
+  describeGroupSort,
+  parseGroupSortSpec,
+  promoteSortKey,
+  sortByGroupSpec,
+} from "../groupSort";
+
+const CARDS = [
+  { name: "Bear", type_line: "Creature — Bear", cmc: 2, colors: ["G"] },
+  { name: "Bolt", type_line: "Instant", cmc: 1, colors: ["R"] },
+  { name: "Mystery", type_line: "Instant" },
+  { name: "angel", type_line: "Creature — Angel", cmc: 4, colors: ["W"] },
+  { name: "Charm", type_line: "Instant", cmc: 2, colors: ["R", "G"] },
+];
+
+const names = (spec: Parameters<typeof sortByGroupSpec>[1]) =>
+  sortByGroupSpec(CARDS, spec, (c) => c as any).map((c) => c.name);
+
+describe("group sort", () => {
+  it("keeps manual order for an empty spec", () => {
+    expect(names([])).toEqual(CARDS.map((c) => c.name));
+  });
+  it("sorts by several keys with missing values last", () => {
+    expect(
+      names([
+        { key: "cmc", dir: "asc" },
+        { key: "name", dir: "asc" },
+      ]),
+    ).toEqual(["Bolt", "Bear", "Charm", "angel", "Mystery"]);
+    expect(names([{ key: "cmc", dir: "desc" }])).toEqual([
+      "angel",
+      "Bear",
+      "Charm",
+      "Bolt",
+      "Mystery",
+    ]);
+    expect(names([{ key: "color", dir: "asc" }])).toEqual([
+      "angel",
+      "Bolt",
+      "Bear",
+      "Charm",
+      "Mystery",
+    ]);
+  });
+  it("promotes picked keys and flips the primary", () => {
+    let spec = promoteSortKey([], "cmc");
+    spec = promoteSortKey(spec, "name");
+    expect(spec).toEqual([
+      { key: "name", dir: "asc" },
+      { key: "cmc", dir: "asc" },
+    ]);
+    spec = promoteSortKey(spec, "name");
+    expect(describeGroupSort(spec)).toBe("Name ↓, Mana value ↑");
+    expect(describeGroupSort([])).toBe("Manual");
+  });
+  it("drops unknown or duplicate terms when parsing", () => {
+    expect(
+      parseGroupSortSpec([
+        { key: "price", dir: "desc" },
+        { key: "bogus" },
+        { key: "price", dir: "asc" },
+        { key: "type" },
+      ]),
+    ).toEqual([
+      { key: "price", dir: "desc" },
+      { key: "type", dir: "asc" },
+    ]);
+    expect(parseGroupSortSpec("name")).toEqual([]);
+  });
+});
@@ -0,0 +1,67 @@
+import { describe, it, expect } from "vitest";
+import {
+  NOTE_DEFAULT_H,
+  NOTE_DEFAULT_W,
+  NOTE_MIN_H,
+  parseNoteRecords,
+  parseNoteText,
+} from "../notes";
+
+describe("note text", () => {
+  it("parses headings, bullets and checklists", () => {
+    const text = "# Combos\n## Infinite\n- Kiki + Twin\n* [x] Done\n- [ ] Todo";
+    expect(parseNoteText(text)).toEqual([
+      { kind: "heading", level: 1, text: "Combos" },
+      { kind: "heading", level: 2, text: "Infinite" },
+      { kind: "bullet", text: "Kiki + Twin" },
+      { kind: "check", checked: true, text: "Done" },
+      { kind: "check", checked: false, text: "Todo" },
+    ]);
+  });
+  it("applies whole-line emphasis and keeps plain lines", () => {
+    expect(parseNoteText("**cut**\n- _maybe_\nplain #1\n")).toEqual([
+      { kind: "text", text: "cut", bold: true },
+      { kind: "bullet", text: "maybe", italic: true },
+      { kind: "text", text: "plain #1" },
+      { kind: "text", text: "" },
+    ]);
+  });
+});
+
+describe("note records", () => {
+  it("drops invalid and repeated ids and fills defaults", () => {
+    const recs = parseNoteRecords([
+      { id: 2, x: 10.4, y: -5, h: 10, text: "hi", color: "blue" },
+      { id: 2, x: 0, y: 0 },
+      { id: "x" },
+      null,
+      { id: 3, color: "neon" },
+    ]);
+    expect(recs).toEqual([
+      {
+        id: 2,
+        x: 10,
+        y: -5,
+        w: NOTE_DEFAULT_W,
+        h: NOTE_MIN_H,
+        z: 0,
+        text: "hi",
+        color: "blue",
+      },
+      {
+        id: 3,
+        x: 0,
+        y: 0,
+        w: NOTE_DEFAULT_W,
+        h: NOTE_DEFAULT_H,
+        z: 0,
+        text: "",
+        color: "yellow",
+      },
+    ]);
+  });
+  it("ignores payloads that are not arrays", () => {
+    expect(parseNoteRecords(undefined)).toEqual([]);
+    expect(parseNoteRecords({ id: 1 })).toEqual([]);
+  });
+});
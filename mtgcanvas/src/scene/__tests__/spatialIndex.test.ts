@@ -17,4 +17,50 @@ describe("SpatialIndex", () => {
     idx.clear();
     expect(idx.count()).toBe(0);
   });
+  it("skips sprites hidden by collapsed groups", () => {
+    const idx = new SpatialIndex();
+    const s1 = { __id: 1 } as unknown as CardSprite;
+    const s2 = { __id: 2, __hidden: true } as unknown as CardSprite;
+    const box = { minX: 0, minY: 0, maxX: 10, maxY: 10 };
+    idx.bulkLoad([
+      { sprite: s1, ...box },
+      { sprite: s2, ...box },
+    ]);
+    expect(idx.search(5, 5, 6, 6).map((h) => h.sprite)).toEqual([s1]);
+    // Hiding an indexed sprite drops it on the next update; revealing re-adds it
+    s1.__hidden = true;
+    s2.__hidden = false;
+    idx.bulkUpdate([
+      { sprite: s1, ...box },
+      { sprite: s2, ...box },
+    ]);
+    expect(idx.search(5, 5, 6, 6).map((h) => h.sprite)).toEqual([s2]);
+  });
+  it("summarizes content as at most `limit` cluster boxes", () => {
+    const idx = new SpatialIndex();
+    const items = Array.from({ length: 2000 }, (_, i) => {
+      const x = (i % 50) * 120;
+      const y = Math.floor(i / 50) * 160;
+      const sprite = { __id: i } as unknown as CardSprite;
+      return { sprite, minX: x, minY: y, maxX: x + 100, maxY: y + 140 };
+    });
+    idx.bulkLoad(items);
+    const boxes = idx.clusters(64);
+    expect(boxes.length).toBeGreaterThan(1);
+    expect(boxes.length).toBeLessThanOrEqual(64);
+    // Every card lies inside some box
+    for (const it of items.filter((_, i) => i % 97 === 0))
+      expect(
+        boxes.some(
+          (b) =>
+            b.minX <= it.minX &&
+            b.minY <= it.minY &&
+            b.maxX >= it.maxX &&
+            b.maxY >= it.maxY,
+        ),
+      ).toBe(true);
+    // Small scenes show the cards themselves
+    expect(idx.clusters(5000)).toHaveLength(2000);
+    expect(new SpatialIndex().clusters(10)).toEqual([]);
+  });
 });
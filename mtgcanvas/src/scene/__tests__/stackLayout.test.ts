@@ -0,0 +1,49 @@
+import { describe, it, expect } from "vitest";
+import { planStacks, primaryType } from "../stackLayout";
+
+const CARDS = [
+  { name: "Forest", type_line: "Basic Land — Forest", cmc: 0, colors: [] },
+  { name: "Bolt", type_line: "Instant", cmc: 1, colors: ["R"] },
+  { name: "Bear", type_line: "Creature — Bear", cmc: 2, colors: ["G"] },
+  { name: "Golem", type_line: "Artifact Creature", cmc: 3, colors: [] },
+  { name: "Charm", type_line: "Instant", cmc: 2, colors: ["R", "G"] },
+  { name: "Hound", type_line: "Creature — Dog", cmc: 2, colors: ["G"] },
+];
+
+const plan = (by: "cmc" | "type" | "color") =>
+  planStacks(CARDS, (c) => c, by).map((col) => [
+    col.label,
+    col.items.map((c) => c.name),
+  ]);
+
+describe("stack layout planning", () => {
+  it("picks the main type of a type line", () => {
+    expect(primaryType("Artifact Creature — Golem")).toBe("Creature");
+    expect(primaryType("Kindred Instant — Elf")).toBe("Instant");
+    expect(primaryType("Conspiracy")).toBe("Conspiracy");
+    expect(primaryType(undefined)).toBe("Unknown");
+  });
+  it("splits by mana value with lands last", () => {
+    expect(plan("cmc")).toEqual([
+      ["1", ["Bolt"]],
+      ["2", ["Bear", "Charm", "Hound"]],
+      ["3", ["Golem"]],
+      ["Land", ["Forest"]],
+    ]);
+  });
+  it("splits by primary type in the usual order", () => {
+    expect(plan("type")).toEqual([
+      ["Creature", ["Bear", "Golem", "Hound"]],
+      ["Instant", ["Bolt", "Charm"]],
+      ["Land", ["Forest"]],
+    ]);
+  });
+  it("splits by color with multicolor and colorless at the end", () => {
+    expect(plan("color")).toEqual([
+      ["Red", ["Bolt"]],
+      ["Green", ["Bear", "Hound"]],
+      ["Multicolor", ["Charm"]],
+      ["Colorless", ["Forest", "Golem"]],
+    ]);
+  });
+});
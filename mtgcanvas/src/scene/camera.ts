@@ -146,6 +146,26 @@ export class Camera {
     this.world.position.y = viewport.h / 2 - (b.y + b.h / 2) * s;
     this.clampToBounds();
   }
+  // Center the view on a world point at the current zoom; animated when duration > 0
+  centerOn(
+    x: number,
+    y: number,
+    viewport: { w: number; h: number },
+    duration = 0,
+  ) {
+    const s = this.world.scale.x;
+    const tx = viewport.w / 2 - x * s;
+    const ty = viewport.h / 2 - y * s;
+    if (duration > 0) {
+      this.animateTo({ x: tx, y: ty }, duration);
+      return;
+    }
+    this.anim = undefined;
+    this.vx = 0;
+    this.vy = 0;
+    this.world.position.set(tx, ty);
+    this.clampToBounds();
+  }
   get scale() {
     return this.world.scale.x;
   }
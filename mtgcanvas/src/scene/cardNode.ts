@@ -5,6 +5,7 @@ import { Colors, registerThemeListener } from "../ui/theme";
 import { SelectionStore } from "../state/selectionStore";
 import { KeyedMinHeap } from "./keyedMinHeap";
 import { CARD_W, CARD_H } from "../config/dimensions";
+import { cardPivot, cardSize } from "./cardTransform";
 import type { Card } from "../types/card";
 
 // --- Fast in-memory texture cache & loaders ---
@@ -125,8 +126,14 @@ export interface CardSprite extends PIXI.Sprite {
   __id: number;
   __baseZ: number;
   __groupId?: number;
+  __hidden?: boolean; // behind a collapsed pile or a stacked copy: not drawn, indexed or streamed
   __scryfallId?: string;
+  __foil?: boolean; // owned printing is foil (persisted per instance)
+  // Per-instance transform (see cardTransform.ts); x/y remain the footprint's top-left
+  __rotation?: number;
+  __scale?: number;
   __tintByMarquee?: boolean;
+  __spotlight?: "match" | "dim"; // set while a search spotlight is on
   __cardSprite?: true;
   __card?: Card | null;
   __imgUrl?: string;
@@ -140,6 +147,10 @@ export interface CardSprite extends PIXI.Sprite {
   __currentTexUrl?: string;
   // Flip FAB (for double-faced cards)
   __flipFab?: PIXI.Container & { bg: PIXI.Graphics; icon: PIXI.Graphics };
+  // Duplicate stacks (see duplicateStacks.ts)
+  __stack?: CardSprite[]; // hidden copies piled under this card
+  __stackOf?: CardSprite; // visible copy this card is piled under
+  __stackBadge?: StackBadge; // "x4" count badge plus the pile edges below the card
   // --- Transient drag float/tilt state ---
   __tiltActive?: boolean; // true while in special drag transform mode
   __elev?: number; // 0..1 lift amount (scales sprite slightly)
@@ -164,6 +175,8 @@ export interface CardVisualOptions {
   x: number;
   y: number;
   z: number;
+  rotation?: number;
+  scale?: number;
   renderer: PIXI.Renderer;
   card?: Card | null;
 }
@@ -251,6 +264,20 @@ function releaseSpriteTextureByUrl(sprite: CardSprite, url?: string) {
   if (!url) sprite.__currentTexUrl = undefined;
 }
 
+// Size the sprite for its scale and turn it about the pivot that keeps the footprint's
+// top-left at x/y. Texture swaps call this too, since they reset the display size.
+// While floating, only the size is refreshed; the float owns pivot and rotation.
+export function applyCardTransform(sprite: CardSprite) {
+  const k = sprite.__scale ?? 1;
+  sprite.width = CARD_W * k;
+  sprite.height = CARD_H * k;
+  if (sprite.__tiltActive) return;
+  const rot = sprite.__rotation ?? 0;
+  const p = cardPivot(rot, sprite.texture.width, sprite.texture.height);
+  sprite.pivot.set(p.x, p.y);
+  sprite.rotation = (rot * Math.PI) / 180;
+}
+
 export function createCardSprite(opts: CardVisualOptions) {
   const textures = ensureCardBaseTextures(opts.renderer);
   const sp = new PIXI.Sprite(textures.base) as CardSprite;
@@ -259,6 +286,9 @@ export function createCardSprite(opts: CardVisualOptions) {
   sp.__card = opts.card || null;
   sp.x = opts.x;
   sp.y = opts.y;
+  if (opts.rotation) sp.__rotation = opts.rotation;
+  if (opts.scale != null && opts.scale !== 1) sp.__scale = opts.scale;
+  applyCardTransform(sp);
   sp.zIndex = sp.__baseZ;
   sp.eventMode = "static";
   sp.cursor = "pointer";
@@ -268,6 +298,7 @@ export function createCardSprite(opts: CardVisualOptions) {
   // Ensure FAB is cleaned up when this sprite is removed or destroyed
   sp.on("removed", () => {
     cleanupFlipFab(sp);
+    cleanupStackBadge(sp);
     // Also ensure any active tilt mesh is torn down
     cleanupTiltMesh(sp);
     // If a decode for this sprite's scheduled URL exists, decrement desire
@@ -284,6 +315,7 @@ export function createCardSprite(opts: CardVisualOptions) {
   const __origDestroy = sp.destroy.bind(sp);
   sp.destroy = (...args: any[]) => {
     cleanupFlipFab(sp);
+    cleanupStackBadge(sp);
     // Tear down tilt mesh first so it doesn't try to render with a freed texture
     cleanupTiltMesh(sp);
     // Cancel any pending decode desire
@@ -398,8 +430,7 @@ function applyTexture(sprite: CardSprite, tex: PIXI.Texture, level: 0 | 1 | 2) {
       ts.autoGarbageCollect = true;
     }
   } catch {}
-  sprite.width = CARD_W;
-  sprite.height = CARD_H;
+  applyCardTransform(sprite);
   sprite.__imgLoaded = true;
   sprite.__imgLoading = false;
   sprite.__qualityLevel = level;
@@ -432,8 +463,7 @@ function forcePlaceholder(sprite: CardSprite) {
     sprite.texture = cachedTextures.base;
   } else {
     sprite.texture = PIXI.Texture.WHITE;
-    sprite.width = CARD_W;
-    sprite.height = CARD_H;
+    applyCardTransform(sprite);
   }
   if (prevUrl) {
     releaseSpriteTextureByUrl(sprite, prevUrl);
@@ -554,6 +584,9 @@ export function ensureTexture(sprite: CardSprite, view: ViewRect) {
   }
 }
 
+// Cards outside a search spotlight fade back so the matches stand out
+const SPOTLIGHT_DIM_ALPHA = 0.2;
+
 export function updateCardSpriteAppearance(s: CardSprite, selected: boolean) {
   if (s?.destroyed) return;
   // For loaded images and placeholders alike, only adjust tint. Avoid reassigning textures
@@ -563,6 +596,7 @@ export function updateCardSpriteAppearance(s: CardSprite, selected: boolean) {
     selected && marqueeSelected
       ? Colors.cardSelectedTint()
       : Colors.cardDefaultTint();
+  s.alpha = s.__spotlight === "dim" ? SPOTLIGHT_DIM_ALPHA : 1;
 }
 // --------------------------
 // Flip FAB for double-faced cards
@@ -680,7 +714,7 @@ function positionFlipFab(sprite: CardSprite) {
   // Use the intended top-left anchor during drag if available.
   const baseX = sprite.__tiltActive ? (sprite.__tlx ?? sprite.x) : sprite.x;
   const baseY = sprite.__tiltActive ? (sprite.__tly ?? sprite.y) : sprite.y;
-  fab.x = baseX + CARD_W - dx;
+  fab.x = baseX + cardSize(sprite).w - dx;
   fab.y = baseY + dy;
 }
 
@@ -730,6 +764,122 @@ function cleanupFlipFab(sprite: CardSprite) {
   sprite.__flipFab = undefined;
 }
 
+// Duplicate stack badge
+// --------------------------
+
+type StackBadge = PIXI.Container & {
+  bg: PIXI.Graphics;
+  label: PIXI.Text;
+  pile: PIXI.Graphics; // sibling drawn just below the card
+  count?: number; // last drawn count; cleared to force a redraw
+  pileW?: number; // last drawn pile size (unscaled footprint)
+};
+
+const STACK_LAYERS = 2; // card edges peeking out below a stack
+const STACK_OFFSET = 4; // world units between layers
+
+function drawStackBadge(
+  badge: StackBadge,
+  count: number,
+  pileW: number,
+  pileH: number,
+) {
+  badge.count = count;
+  badge.pileW = pileW;
+  badge.label.text = `x${count}`;
+  (badge.label.style as any).fill = Colors.panelFg();
+  const padX = 6;
+  const w = Math.ceil(badge.label.width) + padX * 2;
+  const h = Math.ceil(badge.label.height) + 4;
+  badge.label.position.set(padX, 2);
+  badge.bg.clear();
+  badge.bg
+    .roundRect(0, 0, w, h, h / 2)
+    .fill({ color: Colors.panelBg() as any, alpha: 0.92 })
+    .stroke({ color: Colors.accent() as any, width: 1 });
+  const layers = Math.min(STACK_LAYERS, count - 1);
+  badge.pile.clear();
+  for (let i = layers; i >= 1; i--)
+    badge.pile
+      .roundRect(i * STACK_OFFSET, i * STACK_OFFSET, pileW, pileH, 6)
+      .fill({ color: Colors.panelBg() as any })
+      .stroke({ color: Colors.panelBorder() as any, width: 2 });
+}
+
+function createStackBadge(sprite: CardSprite, parent: PIXI.Container) {
+  const badge = new PIXI.Container() as StackBadge;
+  badge.eventMode = "none";
+  badge.bg = new PIXI.Graphics();
+  badge.label = new PIXI.Text({
+    text: "",
+    style: {
+      fill: Colors.panelFg(),
+      fontSize: 14,
+      fontFamily: "Inter, system-ui, sans-serif",
+      fontWeight: "700",
+    },
+  });
+  badge.addChild(badge.bg, badge.label);
+  badge.pile = new PIXI.Graphics();
+  badge.pile.eventMode = "none";
+  parent.addChild(badge.pile, badge);
+  sprite.__stackBadge = badge;
+  return badge;
+}
+
+// Keep a stack's pile and count badge in step with the card; removes them once the
+// card no longer tops a stack
+export function updateStackBadge(sprite: CardSprite) {
+  const count = (sprite.__stack?.length ?? 0) + 1;
+  const parent = sprite.parent as PIXI.Container | null;
+  if (count < 2 || !parent) {
+    cleanupStackBadge(sprite);
+    return;
+  }
+  const badge = sprite.__stackBadge ?? createStackBadge(sprite, parent);
+  if (badge.parent !== parent) {
+    badge.parent?.removeChild(badge);
+    badge.pile.parent?.removeChild(badge.pile);
+    parent.addChild(badge.pile, badge);
+  }
+  // Same scale and anchor as the Flip FAB, so the badge tracks the card during drags
+  const sx = (sprite.width || CARD_W) / CARD_W || 1;
+  const sy = (sprite.height || CARD_H) / CARD_H || 1;
+  // The pile follows the footprint, so a tapped stack lies sideways
+  const size = cardSize(sprite);
+  const pileW = size.w / (sprite.__scale ?? 1);
+  const pileH = size.h / (sprite.__scale ?? 1);
+  if (badge.count !== count || badge.pileW !== pileW)
+    drawStackBadge(badge, count, pileW, pileH);
+  const baseX = sprite.__tiltActive ? (sprite.__tlx ?? sprite.x) : sprite.x;
+  const baseY = sprite.__tiltActive ? (sprite.__tly ?? sprite.y) : sprite.y;
+  const inset = 6;
+  badge.scale.set(sx, sy);
+  badge.x = baseX + size.w - inset - badge.bg.width * sx;
+  badge.y = baseY + size.h - inset - badge.bg.height * sy;
+  badge.zIndex = (sprite.zIndex || 0) + 1;
+  badge.pile.scale.set(sx, sy);
+  badge.pile.position.set(baseX, baseY);
+  badge.pile.zIndex = (sprite.zIndex || 0) - 0.5;
+  const visible = !!sprite.visible && !sprite.__hidden;
+  badge.visible = visible;
+  badge.pile.visible = visible;
+}
+
+function cleanupStackBadge(sprite: CardSprite) {
+  const badge = sprite.__stackBadge;
+  if (!badge) return;
+  try {
+    badge.pile.parent?.removeChild(badge.pile);
+    badge.pile.destroy();
+  } catch {}
+  try {
+    badge.parent?.removeChild(badge);
+    badge.destroy({ children: true });
+  } catch {}
+  sprite.__stackBadge = undefined;
+}
+
 function cleanupTiltMesh(sprite: CardSprite) {
   const mesh = sprite.__tiltMesh;
   if (!mesh) return;
@@ -759,6 +909,9 @@ registerThemeListener(() => {
               })
             | undefined;
           if (fab) applyFlipFabTheme(fab);
+          // Stack badges redraw with the new colors on their next update
+          const badge = (s as CardSprite).__stackBadge;
+          if (badge) badge.count = undefined;
         }
       }
     }
@@ -935,6 +1088,10 @@ function projectCardQuad(
   }
 }
 
+function baseRotation(sprite: CardSprite) {
+  return ((sprite.__rotation ?? 0) * Math.PI) / 180;
+}
+
 function applyTransformFromCenter(
   sprite: CardSprite,
   cx: number,
@@ -957,7 +1114,8 @@ function applyTransformFromCenter(
   const totalScaleY = baseScaleY * extraScale;
   sprite.scale.set(totalScaleX, totalScaleY);
   sprite.__lastExtraScale = extraScale;
-  sprite.rotation = angle;
+  // Tapped cards keep their quarter turn while floating
+  sprite.rotation = angle + baseRotation(sprite);
   // With center pivot, x/y correspond to visual center
   sprite.x = cx;
   sprite.y = cy;
@@ -974,9 +1132,11 @@ export function beginDragFloat(
     if (mesh) {
       mesh.visible = true;
       mesh.zIndex = (sprite.zIndex || 0) + 1;
-      const cx = sprite.__cx ?? sprite.x + (sprite.width || CARD_W) / 2;
-      const cy = sprite.__cy ?? sprite.y + (sprite.height || CARD_H) / 2;
+      const size = cardSize(sprite);
+      const cx = sprite.__cx ?? sprite.x + size.w / 2;
+      const cy = sprite.__cy ?? sprite.y + size.h / 2;
       mesh.position.set(cx, cy);
+      mesh.rotation = baseRotation(sprite);
     }
     sprite.renderable = false;
     return;
@@ -989,11 +1149,10 @@ export function beginDragFloat(
   sprite.__tly = sprite.y;
   sprite.__lastExtraScale = 1;
   // Initialize center so scale originates from the card center without visual jump
-  // Use displayed size (accounts for current base scale) to get accurate center
-  const dispW = sprite.width || CARD_W;
-  const dispH = sprite.height || CARD_H;
-  sprite.__cx = sprite.x + dispW / 2;
-  sprite.__cy = sprite.y + dispH / 2;
+  // Use the footprint (accounts for instance scale and rotation) to get accurate center
+  const size = cardSize(sprite);
+  sprite.__cx = sprite.x + size.w / 2;
+  sprite.__cy = sprite.y + size.h / 2;
   sprite.__useCenterAnchor = true;
   applyTransformFromCenter(sprite, sprite.__cx, sprite.__cy, 0, 1);
   // Prepare 3D mesh and hide the 2D sprite only when an actual drag begins
@@ -1003,6 +1162,7 @@ export function beginDragFloat(
       mesh.visible = true;
       mesh.zIndex = (sprite.zIndex || 0) + 1;
       mesh.position.set(sprite.__cx!, sprite.__cy!);
+      mesh.rotation = baseRotation(sprite);
     }
     sprite.renderable = false;
   }
@@ -1034,10 +1194,9 @@ export function updateDraggedTopLeft(
   sprite.__tly = tly;
   // Update intended center by the same world delta
   if (sprite.__cx == null || sprite.__cy == null) {
-    const dispW = sprite.width || CARD_W;
-    const dispH = sprite.height || CARD_H;
-    sprite.__cx = tlx + dispW / 2;
-    sprite.__cy = tly + dispH / 2;
+    const size = cardSize(sprite);
+    sprite.__cx = tlx + size.w / 2;
+    sprite.__cy = tly + size.h / 2;
   } else {
     sprite.__cx += dX;
     sprite.__cy += dY;
@@ -1072,10 +1231,11 @@ export function endDragFloat(sprite: CardSprite) {
   const baseScaleX = (sprite.scale?.x || 1) / prevExtra;
   const baseScaleY = (sprite.scale?.y || 1) / prevExtra;
   sprite.scale.set(baseScaleX, baseScaleY);
-  sprite.rotation = 0;
   sprite.x = tlx;
   sprite.y = tly;
   sprite.__tiltActive = false;
+  // Back to the top-left anchored pivot and base rotation
+  applyCardTransform(sprite);
   // no tilt state
   sprite.__elev = 0;
   sprite.__lastExtraScale = 1;
@@ -1134,6 +1294,7 @@ export function updateFloatAndTilt(all: CardSprite[], dtMs: number) {
     mesh.zIndex = (s.zIndex || 0) + 1;
     // Keep mesh centered at the intended center
     if (s.__cx != null && s.__cy != null) mesh.position.set(s.__cx, s.__cy);
+    mesh.rotation = baseRotation(s);
     // Use displayed size (already includes elevation extra via sprite scale tracking)
     const w = s.width || CARD_W;
     const h = s.height || CARD_H;
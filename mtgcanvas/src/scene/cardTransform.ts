@@ -0,0 +1,71 @@
+// Per-instance card transform: rotation in quarter turns (tapped cards) and a display
+// scale (e.g. a commander shown at 2x). x/y stay the top-left of the card's footprint,
+// the axis-aligned box it covers once rotated and scaled, so bounds only need its size.
+import { CARD_W, CARD_H } from "../config/dimensions";
+
+export interface CardTransform {
+  __rotation?: number; // degrees clockwise: 0, 90, 180 or 270
+  __scale?: number; // display scale; absent means 1
+}
+
+export const TAPPED_ROTATION = 90;
+// Scale applied by "Enlarge" (key cards such as a commander)
+export const KEY_CARD_SCALE = 2;
+const MIN_SCALE = 0.25;
+const MAX_SCALE = 4;
+
+// Snap any stored angle to a quarter turn in [0, 360)
+export function normalizeRotation(deg: unknown): number {
+  const n = Number(deg);
+  if (!Number.isFinite(n)) return 0;
+  const q = Math.round(n / 90) % 4;
+  return ((q + 4) % 4) * 90;
+}
+
+export function normalizeScale(v: unknown): number {
+  const n = Number(v);
+  if (!Number.isFinite(n) || n <= 0) return 1;
+  return Math.min(MAX_SCALE, Math.max(MIN_SCALE, n));
+}
+
+export function isTapped(s: CardTransform): boolean {
+  return normalizeRotation(s.__rotation) % 180 !== 0;
+}
+
+// Footprint size in world units
+export function cardSize(s: CardTransform): { w: number; h: number } {
+  const k = s.__scale ?? 1;
+  const w = CARD_W * k;
+  const h = CARD_H * k;
+  return isTapped(s) ? { w: h, h: w } : { w, h };
+}
+
+// Spatial-index style bounds; pass x/y to measure at another top-left (e.g. mid-drag)
+export function cardBounds(
+  s: CardTransform & { x: number; y: number },
+  x = s.x,
+  y = s.y,
+): { minX: number; minY: number; maxX: number; maxY: number } {
+  const { w, h } = cardSize(s);
+  return { minX: x, minY: y, maxX: x + w, maxY: y + h };
+}
+
+// Pivot (in the sprite's local, unscaled units) that keeps the rotated card's footprint
+// anchored at x/y. Rotation is about the pivot, so each quarter turn moves it to the
+// corner that ends up top-left.
+export function cardPivot(
+  rotation: number,
+  localW: number,
+  localH: number,
+): { x: number; y: number } {
+  switch (normalizeRotation(rotation)) {
+    case 90:
+      return { x: 0, y: localH };
+    case 180:
+      return { x: localW, y: localH };
+    case 270:
+      return { x: localW, y: 0 };
+    default:
+      return { x: 0, y: 0 };
+  }
+}
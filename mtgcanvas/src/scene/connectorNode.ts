@@ -0,0 +1,110 @@
+// Connector visuals: an arrow between two endpoint rects with an optional label badge.
+// Main redraws a connector when something it is attached to moves (or on a full refresh);
+// the drawing itself is skipped when the geometry, label and theme are unchanged.
+import * as PIXI from "pixi.js";
+import { Colors } from "../ui/theme";
+import type { Rect } from "../types/geometry";
+import { connectorSegment, type ConnectorRecord } from "./connectors";
+
+export interface ConnectorVisual {
+  rec: ConnectorRecord;
+  gfx: PIXI.Container; // root in world space (drawn in world coordinates)
+  line: PIXI.Graphics; // shaft + arrow head; right-click surface
+  badge: PIXI.Container; // label background + text at the midpoint
+  text: PIXI.Text;
+  key: string; // last drawn geometry/label/color
+}
+
+const LINE_WIDTH = 3;
+const HIT_WIDTH = 14; // invisible wide stroke so the thin line is easy to right-click
+const HEAD_LEN = 16;
+const HEAD_HALF = 8;
+const BADGE_PAD_X = 8;
+const BADGE_PAD_Y = 4;
+// Above cards (whose z grows as they are raised) but below the marquee rectangle
+const CONNECTOR_Z = 900000;
+
+export function createConnectorVisual(rec: ConnectorRecord): ConnectorVisual {
+  const gfx = new PIXI.Container();
+  gfx.zIndex = CONNECTOR_Z;
+  gfx.eventMode = "passive";
+  const line = new PIXI.Graphics();
+  line.eventMode = "static";
+  line.cursor = "context-menu";
+  (line as any).__connector = rec.id;
+  const badge = new PIXI.Container();
+  badge.eventMode = "static";
+  badge.cursor = "pointer";
+  (badge as any).__connector = rec.id;
+  const bg = new PIXI.Graphics();
+  const text = new PIXI.Text({
+    text: "",
+    style: {
+      fill: Colors.panelFg(),
+      fontSize: 14,
+      fontFamily: "Inter, system-ui, sans-serif",
+      fontWeight: "500",
+    },
+    resolution: 2,
+  });
+  badge.addChild(bg, text);
+  gfx.addChild(line, badge);
+  return { rec, gfx, line, badge, text, key: "-" }; // "-" forces the first draw
+}
+
+// Redraw for the current endpoint rects; hides the connector while an endpoint is missing
+export function drawConnector(
+  cv: ConnectorVisual,
+  from: Rect | null,
+  to: Rect | null,
+  selected: boolean,
+) {
+  const seg = from && to ? connectorSegment(from, to) : null;
+  const color = selected ? Colors.cardSelectedStroke() : Colors.accent();
+  const key = seg
+    ? `${Math.round(seg.x1)},${Math.round(seg.y1)},${Math.round(seg.x2)},${Math.round(seg.y2)}|${cv.rec.label}|${color}|${Colors.panelFg()}`
+    : "";
+  if (key === cv.key) return;
+  cv.key = key;
+  cv.gfx.visible = !!seg;
+  if (!seg) return;
+  const { x1, y1, x2, y2 } = seg;
+  const len = Math.hypot(x2 - x1, y2 - y1) || 1;
+  const ux = (x2 - x1) / len;
+  const uy = (y2 - y1) / len;
+  const head = Math.min(HEAD_LEN, len * 0.5);
+  const bx = x2 - ux * head;
+  const by = y2 - uy * head;
+  cv.line
+    .clear()
+    .moveTo(x1, y1)
+    .lineTo(x2, y2)
+    .stroke({ color: 0x000000, width: HIT_WIDTH, alpha: 0.001 })
+    .moveTo(x1, y1)
+    .lineTo(bx, by)
+    .stroke({ color, width: selected ? LINE_WIDTH + 1 : LINE_WIDTH })
+    .poly([
+      x2,
+      y2,
+      bx - uy * HEAD_HALF,
+      by + ux * HEAD_HALF,
+      bx + uy * HEAD_HALF,
+      by - ux * HEAD_HALF,
+    ])
+    .fill({ color });
+  cv.badge.visible = !!cv.rec.label;
+  if (!cv.rec.label) return;
+  cv.text.text = cv.rec.label;
+  cv.text.style.fill = Colors.panelFg();
+  const w = cv.text.width + BADGE_PAD_X * 2;
+  const h = cv.text.height + BADGE_PAD_Y * 2;
+  const bg = cv.badge.children[0] as PIXI.Graphics;
+  bg.clear()
+    .roundRect(0, 0, w, h, h / 2)
+    .fill({ color: Colors.badgeBg() })
+    .stroke({ color, width: 1.5 });
+  cv.text.x = BADGE_PAD_X;
+  cv.text.y = BADGE_PAD_Y;
+  cv.badge.x = (x1 + x2) / 2 - w / 2;
+  cv.badge.y = (y1 + y2) / 2 - h / 2;
+}
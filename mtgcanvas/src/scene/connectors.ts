@@ -0,0 +1,85 @@
+// Connectors: directed arrows between two cards or groups with an optional label, used to
+// document combo lines and synergies (Kiki-Jiki -> Zealous Conscripts, "infinite tokens").
+// This module is the pure half (stored shape, geometry); the Pixi visuals live in
+// connectorNode.ts. Connectors are saved with the groups payload of a project.
+import type { Rect } from "../types/geometry";
+
+export type ConnectorEnd = { kind: "card" | "group"; id: number };
+
+export interface ConnectorRecord {
+  id: number;
+  from: ConnectorEnd;
+  to: ConnectorEnd;
+  label: string;
+}
+
+export const CONNECTOR_LABEL_MAX = 80;
+
+export function sameEnd(a: ConnectorEnd, b: ConnectorEnd) {
+  return a.kind === b.kind && a.id === b.id;
+}
+
+function parseEnd(raw: unknown): ConnectorEnd | null {
+  if (!raw || typeof raw !== "object") return null;
+  const r = raw as Record<string, unknown>;
+  const id = Number(r.id);
+  if (r.kind !== "card" && r.kind !== "group") return null;
+  if (!Number.isInteger(id) || id <= 0) return null;
+  return { kind: r.kind, id };
+}
+
+// Validate stored connectors; drops broken endpoints, self-loops and repeats of an id
+export function parseConnectorRecords(raw: unknown): ConnectorRecord[] {
+  if (!Array.isArray(raw)) return [];
+  const out: ConnectorRecord[] = [];
+  const seen = new Set<number>();
+  for (const r of raw) {
+    if (!r || typeof r !== "object") continue;
+    const id = Number(r.id);
+    if (!Number.isInteger(id) || id <= 0 || seen.has(id)) continue;
+    const from = parseEnd(r.from);
+    const to = parseEnd(r.to);
+    if (!from || !to || sameEnd(from, to)) continue;
+    seen.add(id);
+    out.push({
+      id,
+      from,
+      to,
+      label:
+        typeof r.label === "string"
+          ? r.label.trim().slice(0, CONNECTOR_LABEL_MAX)
+          : "",
+    });
+  }
+  return out;
+}
+
+// Segment along the line between the two centers, clipped to the rect borders so the
+// arrow runs edge to edge. Null when the rects overlap along that line (nothing to draw).
+export function connectorSegment(
+  a: Rect,
+  b: Rect,
+): { x1: number; y1: number; x2: number; y2: number } | null {
+  const ax = a.x + a.w / 2;
+  const ay = a.y + a.h / 2;
+  const bx = b.x + b.w / 2;
+  const by = b.y + b.h / 2;
+  const dx = bx - ax;
+  const dy = by - ay;
+  if (!dx && !dy) return null;
+  // Fraction of the center-to-center vector spent inside a rect of the given half size
+  const inside = (hw: number, hh: number) =>
+    Math.min(
+      dx ? hw / Math.abs(dx) : Infinity,
+      dy ? hh / Math.abs(dy) : Infinity,
+    );
+  const ta = inside(a.w / 2, a.h / 2);
+  const tb = inside(b.w / 2, b.h / 2);
+  if (ta + tb >= 1) return null;
+  return {
+    x1: ax + dx * ta,
+    y1: ay + dy * ta,
+    x2: bx - dx * tb,
+    y2: by - dy * tb,
+  };
+}
@@ -0,0 +1,132 @@
+// Duplicate stacks: identical cards (same printing and finish) lying on exactly the same
+// spot in the same group render as one pile with a count badge. The topmost copy stays
+// visible (the representative); the others are hidden followers that move with it.
+// Instances stay individual, so selection, export and deletion still count every copy.
+
+export interface StackMember<T> {
+  __id: number;
+  x: number;
+  y: number;
+  zIndex: number;
+  __groupId?: number;
+  __scryfallId?: string;
+  __foil?: boolean;
+  __hidden?: boolean;
+  __tiltActive?: boolean;
+  __tlx?: number;
+  __tly?: number;
+  destroyed?: boolean;
+  __stack?: T[]; // followers, on the representative
+  __stackOf?: T; // representative, on a follower
+}
+
+// Set when something co-located copies; main re-plans the stacks on the next frame
+let stacksDirty = false;
+export function markDuplicateStacksDirty() {
+  stacksDirty = true;
+}
+export function takeDuplicateStacksDirty(): boolean {
+  const dirty = stacksDirty;
+  stacksDirty = false;
+  return dirty;
+}
+
+// Copies share a key when they are the same printing and finish; unknown cards never stack
+export function duplicateKey(s: {
+  __scryfallId?: string;
+  __foil?: boolean;
+}): string | null {
+  if (!s.__scryfallId) return null;
+  return s.__foil ? `${s.__scryfallId}:foil` : s.__scryfallId;
+}
+
+// Top-left while floating during a drag (x/y then describe the center)
+function topLeft(s: StackMember<unknown>): { x: number; y: number } {
+  return s.__tiltActive
+    ? { x: s.__tlx ?? s.x, y: s.__tly ?? s.y }
+    : { x: s.x, y: s.y };
+}
+
+function spotKey(s: StackMember<unknown>): string | null {
+  const key = duplicateKey(s);
+  if (key == null) return null;
+  const p = topLeft(s);
+  return `${key}|${s.__groupId ?? ""}|${p.x},${p.y}`;
+}
+
+// False once a follower no longer shares its representative's spot
+export function stackIntact<T extends StackMember<T>>(follower: T): boolean {
+  const rep = follower.__stackOf;
+  if (!rep || rep.destroyed) return false;
+  return spotKey(rep) === spotKey(follower);
+}
+
+// Items bucketed by duplicate key in first-seen order (one layout slot per bucket)
+export function groupDuplicates<T extends StackMember<T>>(items: T[]): T[][] {
+  const out: T[][] = [];
+  const byKey = new Map<string, T[]>();
+  for (const it of items) {
+    const key = duplicateKey(it);
+    const bucket = key != null ? byKey.get(key) : undefined;
+    if (bucket) {
+      bucket.push(it);
+      continue;
+    }
+    const fresh = [it];
+    if (key != null) byKey.set(key, fresh);
+    out.push(fresh);
+  }
+  return out;
+}
+
+// Re-plan every stack. Only cards whose scope opted in (`stacks`: a group with stacking
+// on, or the canvas setting for loose cards) pile up; copies elsewhere stay separate even
+// when they share a spot. The representative is the topmost copy (lowest id on ties).
+// Former followers are revealed unless `keepHidden` says they sit in a collapsed group.
+// Returns the sprites whose hidden state changed.
+export function buildDuplicateStacks<T extends StackMember<T>>(
+  sprites: T[],
+  keepHidden: (s: T) => boolean,
+  stacks: (s: T) => boolean,
+): T[] {
+  const keyOf = (s: T) => (stacks(s) ? spotKey(s) : null);
+  const spots = new Map<string, T[]>();
+  for (const s of sprites) {
+    const key = keyOf(s);
+    if (key == null) continue;
+    const list = spots.get(key);
+    if (list) list.push(s);
+    else spots.set(key, [s]);
+  }
+  const changed: T[] = [];
+  const setHidden = (s: T, hidden: boolean) => {
+    if (!!s.__hidden === hidden) return;
+    s.__hidden = hidden;
+    changed.push(s);
+  };
+  for (const s of sprites) {
+    const key = keyOf(s);
+    const list = key != null ? spots.get(key) : undefined;
+    if (list && list.length > 1) continue;
+    s.__stack = undefined;
+    if (s.__stackOf) {
+      s.__stackOf = undefined;
+      setHidden(s, keepHidden(s));
+    }
+  }
+  spots.forEach((list) => {
+    if (list.length < 2) return;
+    list.sort((a, b) => b.zIndex - a.zIndex || a.__id - b.__id);
+    const [rep, ...followers] = list;
+    const wasFollower = !!rep.__stackOf;
+    rep.__stackOf = undefined;
+    rep.__stack = followers;
+    if (wasFollower) setHidden(rep, keepHidden(rep));
+    for (const f of followers) {
+      f.__stack = undefined;
+      f.__stackOf = rep;
+      setHidden(f, true);
+    }
+  });
+  return changed;
+}
@@ -10,6 +10,18 @@ import * as PIXI from "pixi.js";
 import { SelectionStore } from "../state/selectionStore";
 import { Colors } from "../ui/theme";
 import type { CardSprite } from "./cardNode";
+import { cardSize } from "./cardTransform";
+import { planStacks, type GroupLayoutMode } from "./stackLayout";
+import { sortByGroupSpec, type GroupSortSpec } from "./groupSort";
+import { groupDuplicates, markDuplicateStacksDirty } from "./duplicateStacks";
+import type { GroupDeck } from "../services/deckValidation";
+import type { SmartGroupRule } from "../services/smartGroups";
+
+// Deck badge contents: "✓ Commander" when valid, "⚠ Commander · 3" otherwise
+export interface DeckBadge {
+  text: string;
+  ok: boolean;
+}
 
 // Public shape used elsewhere. Keep name for integration, but surface is simplified.
 export interface GroupVisual {
@@ -21,6 +33,11 @@ export interface GroupVisual {
   count: PIXI.Text; // item count (right aligned)
   price: PIXI.Text; // total price text (rightmost)
   resize: PIXI.Graphics; // resize affordance (triangle)
+  toggle: PIXI.Graphics; // collapse/expand chevron (header, left of the name)
+  pile: PIXI.Graphics; // stacked-card pile shown while collapsed
+  stackLabels: PIXI.Container; // column headers with counts (stacks layout)
+  deckBadge: PIXI.Text; // deck validation result (header, left of the count)
+  smartBadge: PIXI.Text; // marks a smart group (header, left of the deck badge)
   name: string;
   w: number;
   h: number;
@@ -28,6 +45,18 @@ export interface GroupVisual {
   order: CardSprite[]; // ordering for layout (sprites)
   _lastTextRes?: number; // internal: last applied resolution for dynamic crispness
   totalPrice: number; // cached aggregate price
+  parentId: number | null; // enclosing group when nested (GroupRow.parent_id)
+  nestedCount: number; // cards held by descendant groups
+  nestedPrice: number; // aggregate price of cards held by descendant groups
+  collapsed: boolean; // shown as a compact pile; members hidden
+  expandedSize: { w: number; h: number } | null; // size to restore on expand
+  layoutMode: GroupLayoutMode; // packed grid, or overlapping columns by a card property
+  sortSpec: GroupSortSpec; // member order on layout; empty = manual
+  stackDuplicates: boolean; // copies of a card share one slot as a counted pile
+  deck: GroupDeck | null; // format and commanders when the group is a deck
+  deckStatus: DeckBadge | null; // header badge, computed by the caller (main.ts)
+  spotlightCount: number | null; // matches while a search spotlight is on ("3 / 40")
+  smart: SmartGroupRule | null; // membership query when this is a smart group
   _zoomLabel?: PIXI.Text; // large centered label when zoomed far out
   _overlayDrag?: PIXI.Graphics; // transparent drag surface when overlay visible
 }
@@ -45,6 +74,10 @@ let HEADER_TEXT_COLOR = Colors.panelFg();
 let COUNT_TEXT_COLOR = Colors.panelFgDim();
 let PRICE_TEXT_COLOR = Colors.panelFg();
 let OVERLAY_TEXT_COLOR = Colors.overlayText(); // zoom overlay text color (theme-aware)
+let DECK_OK_COLOR = Colors.successAccent();
+let SPOTLIGHT_TEXT_COLOR = Colors.accent();
+let SMART_BADGE_COLOR = Colors.accent();
+const DECK_WARN_COLOR = 0xe5a03a; // readable on both themes
 // Shared presentation constants
 export const GROUP_DIM_ALPHA = 0.4; // frame/header opacity in normal view
 // Outline thickness
@@ -60,6 +93,9 @@ export function applyGroupTheme() {
   PRICE_TEXT_COLOR = HEADER_TEXT_COLOR;
   // Overlay text: theme-aware via centralized helper
   OVERLAY_TEXT_COLOR = Colors.overlayText();
+  DECK_OK_COLOR = Colors.successAccent();
+  SPOTLIGHT_TEXT_COLOR = Colors.accent();
+  SMART_BADGE_COLOR = Colors.accent();
 }
 // Initial sample (safe if executed before DOM ready; will be resampled on first theme ensure anyway)
 applyGroupTheme();
@@ -86,8 +122,20 @@ const PAD_BOTTOM_EXTRA = 6; // extra bottom padding so cards don't touch frame
 // 100 + 4 = 104, 140 + 4 = 144, both divisible by 8. Visually ≈8px at common zooms.
 const GAP_X = 4;
 const GAP_Y = 4;
+// Space between nested group blocks inside a parent (grid multiple)
+const CHILD_GAP = 16;
+// Collapsed presentation: header chevron and the card pile replacing members
+const TOGGLE_SIZE = 16;
+const PILE_LAYERS = 3;
+const PILE_OFFSET = 8;
+const COLLAPSED_MIN_W = 264; // room for name, count and price in the header
+// Stacks layout: visible top slice of each overlapped card and the column header band
+const STACK_OFFSET = 24;
+const STACK_HEADER_H = 24;
 import { snap as globalSnap } from "../utils/snap";
 const snap = (v: number) => globalSnap(v, GRID_SIZE);
+// Round up instead of to nearest so packed content never loses a few pixels
+const snapUp = (v: number) => Math.ceil(v / GRID_SIZE) * GRID_SIZE;
 
 // No per-group color; colors come from theme variables for consistency.
 
@@ -160,6 +208,44 @@ export function createGroupVisual(
   resize.eventMode = "static";
   resize.cursor = "nwse-resize";
   resize.zIndex = 3;
+  const toggle = new PIXI.Graphics();
+  toggle.eventMode = "static";
+  toggle.cursor = "pointer";
+  toggle.zIndex = 3;
+  const pile = new PIXI.Graphics();
+  pile.eventMode = "none";
+  pile.visible = false;
+  pile.zIndex = 1;
+  const stackLabels = new PIXI.Container();
+  stackLabels.eventMode = "none";
+  stackLabels.zIndex = 2;
+  const deckBadge = new PIXI.Text({
+    text: "",
+    style: {
+      fill: DECK_OK_COLOR,
+      fontSize: 12,
+      fontFamily: FONT_FAMILY,
+      fontWeight: "600",
+      lineHeight: 12,
+    },
+  });
+  // Clicks fall through to the header (selecting the group shows the issues)
+  deckBadge.eventMode = "none";
+  deckBadge.visible = false;
+  deckBadge.zIndex = 3;
+  const smartBadge = new PIXI.Text({
+    text: "✦ Smart",
+    style: {
+      fill: SMART_BADGE_COLOR,
+      fontSize: 12,
+      fontFamily: FONT_FAMILY,
+      fontWeight: "600",
+      lineHeight: 12,
+    },
+  });
+  smartBadge.eventMode = "none";
+  smartBadge.visible = false;
+  smartBadge.zIndex = 3;
   const gv: GroupVisual = {
     id,
     gfx,
@@ -169,12 +255,29 @@ export function createGroupVisual(
     count,
     price,
     resize,
+    toggle,
+    pile,
+    stackLabels,
+    deckBadge,
+    smartBadge,
     name: `Group ${id}`,
     w,
     h,
     items: new Set(),
     order: [],
     totalPrice: 0,
+    parentId: null,
+    nestedCount: 0,
+    nestedPrice: 0,
+    collapsed: false,
+    expandedSize: null,
+    layoutMode: "grid",
+    sortSpec: [],
+    stackDuplicates: false,
+    deck: null,
+    deckStatus: null,
+    spotlightCount: null,
+    smart: null,
   };
   // Zoom-out overlay label (initially hidden)
   const zoomLabel = new PIXI.Text({
@@ -199,11 +302,16 @@ export function createGroupVisual(
   gv._overlayDrag = overlayDrag;
   gfx.addChild(
     frame,
+    pile,
+    stackLabels,
     header,
     label,
     count,
     price,
+    deckBadge,
+    smartBadge,
     resize,
+    toggle,
     overlayDrag,
     zoomLabel,
   );
@@ -262,11 +370,14 @@ export function drawGroup(gv: GroupVisual, selected: boolean) {
 
   header.hitArea = new PIXI.Rectangle(0, 0, w, HEADER_HEIGHT);
 
+  drawToggle(gv, bw);
+  drawPile(gv);
+  drawStackLabels(gv);
+
   // Label text (truncate if needed)
   label.text = gv.name;
-  // Keep header text away from thick borders
-  label.x = bw + 8;
-  truncateLabelIfNeeded(gv);
+  // Keep header text away from thick borders (and the collapse chevron)
+  label.x = bw + 8 + TOGGLE_SIZE + 8;
   // y set later with common baseline
 
   // Price & count text (always show) - price rightmost, count just left of it
@@ -274,14 +385,24 @@ export function drawGroup(gv: GroupVisual, selected: boolean) {
   (price.style as any).fontSize = commonSize;
   (price.style as any).fontWeight = "500";
   (price.style as any).lineHeight = commonSize;
-  price.text = `$${gv.totalPrice.toFixed(2)}`;
+  price.text = `$${(gv.totalPrice + gv.nestedPrice).toFixed(2)}`;
   price.x = w - bw - price.width - 8;
   // y set later with common baseline
   // Update price style and opacity after price is in scope
   (price.style as any).fill = PRICE_TEXT_COLOR;
-  count.text = gv.items.size.toString();
+  const total = gv.items.size + gv.nestedCount;
+  // While a spotlight search is on the count reads "matches / total"
+  count.text =
+    gv.spotlightCount != null
+      ? `${gv.spotlightCount} / ${total}`
+      : total.toString();
+  if (gv.spotlightCount) (count.style as any).fill = SPOTLIGHT_TEXT_COLOR;
   count.x = Math.max(bw + 8, price.x - count.width - 6);
   // y set later with common baseline
+  drawDeckBadge(gv, commonSize);
+  drawSmartBadge(gv, commonSize);
+  // Truncate once the right-hand texts are measured
+  truncateLabelIfNeeded(gv);
 
   // Align all header texts to the same vertical baseline with a slight upward optical lift
   const headerTop = bw; // inner top after border thickness
@@ -291,6 +412,8 @@ export function drawGroup(gv: GroupVisual, selected: boolean) {
   label.y = yCommon;
   price.y = yCommon;
   count.y = yCommon;
+  gv.deckBadge.y = yCommon;
+  gv.smartBadge.y = yCommon;
 
   // Legacy resize triangle removed (edge/corner resize active everywhere). Keep graphic hidden & non-interactive.
   resize.visible = false;
@@ -313,10 +436,132 @@ export function drawGroup(gv: GroupVisual, selected: boolean) {
   if (gv._zoomLabel && gv._zoomLabel.visible) positionZoomOverlay(gv);
 }
 
+// Header chevron: points down while expanded, right while collapsed
+function drawToggle(gv: GroupVisual, bw: number) {
+  const t = gv.toggle;
+  t.clear();
+  const x = bw + 8;
+  const cy = bw + Math.max(0, HEADER_HEIGHT - bw) / 2;
+  const s = TOGGLE_SIZE;
+  if (gv.collapsed) {
+    const l = x + s * 0.25;
+    t.poly([l, cy - s / 2, x + s * 0.85, cy, l, cy + s / 2]);
+  } else {
+    const top = cy - s * 0.3;
+    t.poly([x, top, x + s, top, x + s / 2, cy + s * 0.35]);
+  }
+  t.fill({ color: HEADER_TEXT_COLOR });
+  // Generous hit target so the chevron is easy to click at any zoom
+  const hitH = Math.max(0, HEADER_HEIGHT - bw);
+  t.hitArea = new PIXI.Rectangle(bw, bw, s + 16, hitH);
+}
+
+// Deck badge, left of the count; gv.deckStatus is filled in by the caller
+function drawDeckBadge(gv: GroupVisual, fontSize: number) {
+  const b = gv.deckBadge;
+  // Header texts are hidden while the zoom overlay is up
+  b.visible = !!gv.deckStatus && gv.header.visible;
+  if (!gv.deckStatus) return;
+  const { text, ok } = gv.deckStatus;
+  if (b.text !== text) b.text = text;
+  (b.style as any).fill = ok ? DECK_OK_COLOR : DECK_WARN_COLOR;
+  (b.style as any).fontSize = fontSize;
+  (b.style as any).lineHeight = fontSize;
+  b.x = gv.count.x - b.width - 12;
+}
+
+// Smart badge: "✦ Smart" left of the deck badge (or the count)
+function drawSmartBadge(gv: GroupVisual, fontSize: number) {
+  const b = gv.smartBadge;
+  b.visible = !!gv.smart && gv.header.visible;
+  if (!gv.smart) return;
+  (b.style as any).fill = SMART_BADGE_COLOR;
+  (b.style as any).fontSize = fontSize;
+  (b.style as any).lineHeight = fontSize;
+  const right = gv.deckBadge.visible ? gv.deckBadge.x : gv.count.x;
+  b.x = right - b.width - 12;
+}
+
+// Collapsed body: a few card outlines offset like a stacked pile
+function drawPile(gv: GroupVisual) {
+  const p = gv.pile;
+  p.clear();
+  p.visible = gv.collapsed;
+  if (!gv.collapsed) return;
+  const total = gv.items.size + gv.nestedCount;
+  const layers = Math.max(1, Math.min(PILE_LAYERS, total));
+  const pileW = CARD_W + (PILE_LAYERS - 1) * PILE_OFFSET;
+  const x0 = Math.round((gv.w - pileW) / 2);
+  const y0 = HEADER_HEIGHT + PAD_Y;
+  for (let i = layers - 1; i >= 0; i--) {
+    p.roundRect(x0 + i * PILE_OFFSET, y0 + i * PILE_OFFSET, CARD_W, CARD_H, 6)
+      .fill({ color: BODY_BG })
+      .stroke({ color: BORDER_COLOR, width: 2 });
+  }
+}
+
+// Column headers for the stacks layout, e.g. "Creature (12)". Text objects are reused
+// across redraws; grid and collapsed groups have none.
+function drawStackLabels(gv: GroupVisual) {
+  const box = gv.stackLabels;
+  const mode = gv.layoutMode;
+  const columns =
+    gv.collapsed || mode === "grid"
+      ? []
+      : planStacks(gv.order, (s) => s.__card, mode);
+  while (box.children.length > columns.length)
+    box.removeChildAt(box.children.length - 1).destroy();
+  columns.forEach((col, i) => {
+    let t = box.children[i] as PIXI.Text | undefined;
+    if (!t) {
+      t = new PIXI.Text({
+        text: "",
+        style: {
+          fill: COUNT_TEXT_COLOR,
+          fontSize: 12,
+          fontFamily: FONT_FAMILY,
+          fontWeight: "500",
+          lineHeight: 12,
+        },
+      });
+      box.addChild(t);
+    }
+    const text = `${col.label} (${col.items.length})`;
+    if (t.text !== text) t.text = text;
+    (t.style as any).fill = COUNT_TEXT_COLOR;
+    t.x = PAD_X + i * (CARD_W + GAP_X);
+    t.y = HEADER_HEIGHT + PAD_Y + 4;
+  });
+}
+
+// Size of the stacks block: one column per key, each card overlapping the one above
+function stacksExtent(columns: { items: unknown[] }[]) {
+  if (!columns.length) return { w: 0, h: 0 };
+  const deepest = Math.max(...columns.map((c) => c.items.length));
+  return {
+    w: columns.length * (CARD_W + GAP_X) - GAP_X,
+    h: STACK_HEADER_H + CARD_H + (deepest - 1) * STACK_OFFSET,
+  };
+}
+
+// Frame size of a collapsed group: a header wide enough for its totals over one pile
+export function collapsedGroupSize(): { w: number; h: number } {
+  const pileW = CARD_W + (PILE_LAYERS - 1) * PILE_OFFSET;
+  const pileH = CARD_H + (PILE_LAYERS - 1) * PILE_OFFSET;
+  return {
+    w: Math.max(COLLAPSED_MIN_W, snapUp(pileW + PAD_X * 2)),
+    h: snapUp(HEADER_HEIGHT + PAD_Y + pileH + PAD_Y + PAD_BOTTOM_EXTRA),
+  };
+}
+
 function truncateLabelIfNeeded(gv: GroupVisual) {
   // Simple ellipsis if label + count overlap
-  // Reserve space for count + price on right
-  const maxLabelWidth = gv.w - 16 - (gv.count.width + 6 + gv.price.width) - 10; // padding and gap
+  // Reserve space for the chevron on the left and count + price on the right
+  const badgeW =
+    (gv.deckBadge.visible ? gv.deckBadge.width + 12 : 0) +
+    (gv.smartBadge.visible ? gv.smartBadge.width + 12 : 0);
+  const totalsW = badgeW + gv.count.width + 6 + gv.price.width;
+  const maxLabelWidth = gv.w - 16 - TOGGLE_SIZE - 8 - totalsW - 10; // padding and gap
   if (gv.label.width <= maxLabelWidth) return;
   const original = gv.name;
   let txt = original;
@@ -329,51 +574,152 @@ function truncateLabelIfNeeded(gv: GroupVisual) {
 // Layout cards into a grid inside group body.
 // Note: For freeform-in-group behavior
 // Regardless of strategy, card positions are always snapped to the global grid.
+// When `groups` is given, nested groups are placed as blocks below the cards.
+// With `fit` the frame is sized to its content (it can shrink); otherwise it only grows,
+// so a width the user dragged out is kept.
 export function layoutGroup(
   gv: GroupVisual,
   sprites: CardSprite[],
   onMoved?: (s: CardSprite) => void,
+  groups?: Map<number, GroupVisual>,
+  opts?: { fit?: boolean },
 ) {
+  if (gv.collapsed) return; // members stay put until the group is expanded
+  applyGroupSort(gv);
   const items = gv.order.slice();
-  if (!items.length) return;
-  const usableW = Math.max(1, gv.w - PAD_X * 2);
-  const cols = Math.max(1, Math.floor((usableW + GAP_X) / (CARD_W + GAP_X)));
-  items.forEach((s, i) => {
-    const col = i % cols;
-    const row = Math.floor(i / cols);
-    const tx = gv.gfx.x + PAD_X + col * (CARD_W + GAP_X);
-    const ty = gv.gfx.y + HEADER_HEIGHT + PAD_Y + row * (CARD_H + GAP_Y);
-    // Always snap to global grid so cards align inside and outside groups.
-    const nx = snap(tx);
-    const ny = snap(ty);
-    if (s.x !== nx || s.y !== ny) {
-      s.x = nx;
-      s.y = ny;
-      onMoved && onMoved(s);
-    }
-    // Ensure grouped cards render above group frame/background.
-    const desiredZ = gv.gfx.zIndex + 1;
-    if (s.zIndex < desiredZ) {
-      s.zIndex = desiredZ;
-      (s as any).__baseZ = desiredZ;
-    }
-  });
-  const rows = Math.ceil(items.length / cols);
-  const neededH =
-    HEADER_HEIGHT +
-    PAD_Y +
-    rows * CARD_H +
-    (rows - 1) * GAP_Y +
-    PAD_Y +
-    PAD_BOTTOM_EXTRA;
-  if (neededH > gv.h) {
-    gv.h = snap(neededH);
+  const children = groups ? childGroups(gv, groups) : [];
+  if (!items.length && !children.length) return;
+  const mode = gv.layoutMode;
+  // Copies of a card share one slot when the group stacks duplicates
+  const slots = layoutSlots(gv, items);
+  const slotOf = new Map(slots.map((slot) => [slot[0], slot]));
+  let contentH = 0;
+  let contentW = 0;
+  if (mode !== "grid" && items.length) {
+    // Columns keyed by card property; later cards overlap earlier ones (higher z)
+    const columns = planStacks([...slotOf.keys()], (s) => s.__card, mode);
+    const extent = stacksExtent(columns);
+    if (extent.w + PAD_X * 2 > gv.w) gv.w = snapUp(extent.w + PAD_X * 2);
+    const top = snap(gv.gfx.y + HEADER_HEIGHT + PAD_Y + STACK_HEADER_H);
+    columns.forEach((col, c) => {
+      const nx = snap(gv.gfx.x + PAD_X + c * (CARD_W + GAP_X));
+      col.items.forEach((first, row) => {
+        const ny = top + row * STACK_OFFSET;
+        for (const s of slotOf.get(first)!) {
+          if (s.x !== nx || s.y !== ny) {
+            s.x = nx;
+            s.y = ny;
+            onMoved && onMoved(s);
+          }
+          const z = gv.gfx.zIndex + 1 + row;
+          if (s.zIndex !== z) {
+            s.zIndex = z;
+            (s as any).__baseZ = z;
+          }
+        }
+      });
+    });
+    contentH = extent.h;
+    contentW = extent.w;
+  } else if (items.length) {
+    const usableW = Math.max(1, gv.w - PAD_X * 2);
+    const flow = flowSlots(slots.map(slotSize), usableW);
+    slots.forEach((slot, i) => {
+      const tx = gv.gfx.x + PAD_X + flow.pos[i].x;
+      const ty = gv.gfx.y + HEADER_HEIGHT + PAD_Y + flow.pos[i].y;
+      // Always snap to global grid so cards align inside and outside groups.
+      const nx = snap(tx);
+      const ny = snap(ty);
+      for (const s of slot) {
+        if (s.x !== nx || s.y !== ny) {
+          s.x = nx;
+          s.y = ny;
+          onMoved && onMoved(s);
+        }
+        // Ensure grouped cards render above group frame/background.
+        const desiredZ = gv.gfx.zIndex + 1;
+        if (s.zIndex < desiredZ) {
+          s.zIndex = desiredZ;
+          (s as any).__baseZ = desiredZ;
+        }
+      }
+    });
+    contentH = flow.h;
+    contentW = flow.w;
+  }
+  if (children.length && groups) {
+    const usableW = Math.max(1, gv.w - PAD_X * 2);
+    const packed = packBlocks(children, usableW);
+    const top = HEADER_HEIGHT + PAD_Y + (contentH ? contentH + CHILD_GAP : 0);
+    children.forEach((c, i) => {
+      const nx = snap(gv.gfx.x + PAD_X + packed.pos[i].x);
+      const ny = snap(gv.gfx.y + top + packed.pos[i].y);
+      translateGroupTree(c, nx - c.gfx.x, ny - c.gfx.y, groups, onMoved);
+    });
+    contentH = top - HEADER_HEIGHT - PAD_Y + packed.h;
+    contentW = Math.max(contentW, packed.w);
+    raiseNestedGroups(gv, groups);
+  }
+  const neededW = contentW + PAD_X * 2;
+  const neededH = HEADER_HEIGHT + PAD_Y + contentH + PAD_Y + PAD_BOTTOM_EXTRA;
+  if (opts?.fit) {
+    gv.w = snapUp(neededW);
+    gv.h = snapUp(neededH);
+  } else {
+    if (neededW > gv.w) gv.w = snapUp(neededW);
+    if (neededH > gv.h) gv.h = snapUp(neededH);
   }
   drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
   // Maintain overlay position after layout growth when overlay is currently visible
   if (gv._zoomLabel && gv._zoomLabel.visible) positionZoomOverlay(gv);
 }
 
+// Layout slots in member order: one per card, or one per distinct card when stacking
+function layoutSlots(gv: GroupVisual, items: CardSprite[]): CardSprite[][] {
+  if (!gv.stackDuplicates) return items.map((s) => [s]);
+  // The piles themselves are planned by main once the copies share a spot
+  markDuplicateStacksDirty();
+  return groupDuplicates(items);
+}
+
+// Footprint of a slot: its largest card (tapped or enlarged cards take more room)
+function slotSize(slot: CardSprite[]): { w: number; h: number } {
+  let w = 0;
+  let h = 0;
+  for (const s of slot) {
+    const size = cardSize(s);
+    w = Math.max(w, size.w);
+    h = Math.max(h, size.h);
+  }
+  return { w, h };
+}
+
+// Row-major flow of slot footprints within maxW: each slot advances by its own width and
+// each row by its tallest slot. Steps are rounded up to the grid so snap() keeps the gaps;
+// with plain cards this is the fixed 104 x 144 grid.
+function flowSlots(
+  sizes: { w: number; h: number }[],
+  maxW: number,
+): { pos: { x: number; y: number }[]; w: number; h: number } {
+  const pos: { x: number; y: number }[] = [];
+  let x = 0;
+  let y = 0;
+  let rowH = 0;
+  let w = 0;
+  for (const size of sizes) {
+    if (x > 0 && x + size.w > maxW) {
+      y += snapUp(rowH + GAP_Y);
+      x = 0;
+      rowH = 0;
+    }
+    pos.push({ x, y });
+    w = Math.max(w, x + size.w);
+    rowH = Math.max(rowH, size.h);
+    x += snapUp(size.w + GAP_X);
+  }
+  return { pos, w, h: sizes.length ? y + rowH : 0 };
+}
+
 // ---- Freeform helpers (no grid) ----
 function rectsOverlap(
   ax: number,
@@ -444,7 +790,12 @@ export function placeCardInGroup(
   preferredWorldX?: number,
   preferredWorldY?: number,
 ) {
-  const others = memberSprites(gv, sprites, sprite.__id);
+  const others = memberSprites(gv, sprites, sprite.__id).map((o) => ({
+    x: o.x,
+    y: o.y,
+    ...cardSize(o),
+  }));
+  const { w: cw, h: ch } = cardSize(sprite);
   // Compute inner bounds
   const left = snap(gv.gfx.x + PAD_X);
   const top = snap(gv.gfx.y + HEADER_HEIGHT + PAD_Y);
@@ -455,32 +806,19 @@ export function placeCardInGroup(
   const startX = snap(
     Math.min(
       Math.max(preferredWorldX ?? left, left),
-      Math.max(left, right - CARD_W),
+      Math.max(left, right - cw),
     ),
   );
   const startY = snap(
     Math.min(
       Math.max(preferredWorldY ?? top, top),
-      Math.max(top, bottom - CARD_H),
+      Math.max(top, bottom - ch),
     ),
   );
 
   function fitsAt(x: number, y: number) {
     for (const o of others) {
-      if (
-        rectsOverlap(
-          x,
-          y,
-          CARD_W,
-          CARD_H,
-          o.x,
-          o.y,
-          CARD_W,
-          CARD_H,
-          GAP_X,
-          GAP_Y,
-        )
-      )
+      if (rectsOverlap(x, y, cw, ch, o.x, o.y, o.w, o.h, GAP_X, GAP_Y))
         return false;
     }
     return true;
@@ -494,8 +832,8 @@ export function placeCardInGroup(
   outer: for (let radius = 0; radius <= maxRadius; radius += CARD_W / 2) {
     for (let dy = -radius; dy <= radius; dy += step) {
       for (let dx = -radius; dx <= radius; dx += step) {
-        const tx = snap(Math.min(Math.max(startX + dx, left), right - CARD_W));
-        const ty = snap(Math.min(Math.max(startY + dy, top), bottom - CARD_H));
+        const tx = snap(Math.min(Math.max(startX + dx, left), right - cw));
+        const ty = snap(Math.min(Math.max(startY + dy, top), bottom - ch));
         if (fitsAt(tx, ty)) {
           px = tx;
           py = ty;
@@ -510,11 +848,11 @@ export function placeCardInGroup(
     let bestX = snap(left),
       bestY = snap(top);
     let bestScore = Number.POSITIVE_INFINITY;
-    for (let y = snap(top); y <= bottom - CARD_H; y += step) {
-      for (let x = snap(left); x <= right - CARD_W; x += step) {
+    for (let y = snap(top); y <= bottom - ch; y += step) {
+      for (let x = snap(left); x <= right - cw; x += step) {
         let score = 0;
         for (const o of others)
-          score += overlapArea(x, y, CARD_W, CARD_H, o.x, o.y, CARD_W, CARD_H);
+          score += overlapArea(x, y, cw, ch, o.x, o.y, o.w, o.h);
         if (score < bestScore) {
           bestScore = score;
           bestX = x;
@@ -546,18 +884,54 @@ export function autoPackGroup(
   gv: GroupVisual,
   sprites: CardSprite[],
   onMoved?: (s: CardSprite) => void,
+  groups?: Map<number, GroupVisual>,
 ) {
+  if (gv.collapsed) return;
   const items = gv.order.slice();
-  if (!items.length) return;
-  const n = items.length;
-  const idealCols = Math.max(1, Math.round(Math.sqrt(n * (CARD_H / CARD_W))));
-  const cols = idealCols;
-  const rows = Math.ceil(n / cols);
-  const innerW = cols * CARD_W + (cols - 1) * GAP_X;
-  const innerH = rows * CARD_H + (rows - 1) * GAP_Y;
-  gv.w = snap(innerW + PAD_X * 2);
-  gv.h = snap(HEADER_HEIGHT + PAD_Y + innerH + PAD_Y + PAD_BOTTOM_EXTRA);
-  layoutGroup(gv, sprites, onMoved);
+  const children = groups ? childGroups(gv, groups) : [];
+  if (!items.length && !children.length) return;
+  // Pack nested groups first so their block sizes are final
+  children.forEach((c) => autoPackGroup(c, sprites, onMoved, groups));
+  const slots = layoutSlots(gv, items);
+  const firsts = slots.map((slot) => slot[0]);
+  const n = firsts.length;
+  let innerW = 0;
+  let innerH = 0;
+  if (n && gv.layoutMode !== "grid") {
+    const mode = gv.layoutMode;
+    const extent = stacksExtent(planStacks(firsts, (s) => s.__card, mode));
+    innerW = extent.w;
+    innerH = extent.h;
+  } else if (n) {
+    // Roughly square block of the slots' real footprints, never narrower than the widest
+    const sizes = slots.map(slotSize);
+    const area = sizes.reduce(
+      (sum, z) => sum + (z.w + GAP_X) * (z.h + GAP_Y),
+      0,
+    );
+    const widest = Math.max(...sizes.map((z) => z.w));
+    const flow = flowSlots(sizes, Math.max(widest, Math.sqrt(area)));
+    innerW = flow.w;
+    innerH = flow.h;
+  }
+  if (children.length) {
+    // Aim for a roughly square block of children, never narrower than the widest one
+    const area = children.reduce(
+      (sum, c) => sum + (c.w + CHILD_GAP) * (c.h + CHILD_GAP),
+      0,
+    );
+    const widest = Math.max(...children.map((c) => c.w));
+    const packed = packBlocks(
+      children,
+      Math.max(innerW, widest, Math.sqrt(area)),
+    );
+    innerW = Math.max(innerW, packed.w);
+    innerH += (n ? CHILD_GAP : 0) + packed.h;
+  }
+  gv.w = snapUp(innerW + PAD_X * 2);
+  gv.h = snapUp(HEADER_HEIGHT + PAD_Y + innerH + PAD_Y + PAD_BOTTOM_EXTRA);
+  // The frame ends up fitting what layout actually placed
+  layoutGroup(gv, sprites, onMoved, groups, { fit: true });
   drawGroup(gv, SelectionStore.state.groupIds.has(gv.id));
   if (gv._zoomLabel && gv._zoomLabel.visible) positionZoomOverlay(gv);
 }
@@ -593,15 +967,24 @@ export function addCardToGroupOrdered(
   else gv.order.push(sprite);
 }
 
+// Reorder members by the group's sort spec (manual groups keep their order)
+export function applyGroupSort(gv: GroupVisual) {
+  if (!gv.sortSpec.length) return;
+  const sorted = sortByGroupSpec(gv.order, gv.sortSpec, (s) => s.__card);
+  gv.order.splice(0, gv.order.length, ...sorted);
+}
+
 export function removeCardFromGroup(gv: GroupVisual, sprite: CardSprite) {
   if (!gv.items.has(sprite)) return;
   gv.items.delete(sprite);
+  sprite.__hidden = false; // no longer behind a collapsed pile
   const idx = gv.order.indexOf(sprite);
   if (idx >= 0) gv.order.splice(idx, 1);
 }
 
 // Helper to fully clear group membership references (used on group destroy)
 export function clearGroupMembers(gv: GroupVisual) {
+  gv.items.forEach((sp) => (sp.__hidden = false));
   try {
     gv.items.clear();
   } catch {}
@@ -613,6 +996,111 @@ export function clearGroupMembers(gv: GroupVisual) {
   }
 }
 
+// ---- Nesting (GroupRow.parent_id) ----
+// Direct children ordered by position, so dragging a child within its parent reorders it.
+export function childGroups(
+  gv: GroupVisual,
+  groups: Map<number, GroupVisual>,
+): GroupVisual[] {
+  const out: GroupVisual[] = [];
+  groups.forEach((g) => {
+    if (g !== gv && g.parentId === gv.id) out.push(g);
+  });
+  return out.sort(
+    (a, b) => a.gfx.y - b.gfx.y || a.gfx.x - b.gfx.x || a.id - b.id,
+  );
+}
+
+// All nested groups, parents before their children. Tolerates cycles in restored data.
+export function descendantGroups(
+  gv: GroupVisual,
+  groups: Map<number, GroupVisual>,
+): GroupVisual[] {
+  const out: GroupVisual[] = [];
+  const seen = new Set<number>([gv.id]);
+  const walk = (g: GroupVisual) => {
+    for (const c of childGroups(g, groups)) {
+      if (seen.has(c.id)) continue;
+      seen.add(c.id);
+      out.push(c);
+      walk(c);
+    }
+  };
+  walk(gv);
+  return out;
+}
+
+// True when gv is the group `ancestorId` or nested (at any depth) inside it
+export function isWithinGroup(
+  gv: GroupVisual,
+  ancestorId: number,
+  groups: Map<number, GroupVisual>,
+): boolean {
+  const seen = new Set<number>();
+  let cur: GroupVisual | undefined = gv;
+  while (cur && !seen.has(cur.id)) {
+    if (cur.id === ancestorId) return true;
+    seen.add(cur.id);
+    cur = cur.parentId != null ? groups.get(cur.parentId) : undefined;
+  }
+  return false;
+}
+
+// Move a group together with its cards and nested groups
+export function translateGroupTree(
+  gv: GroupVisual,
+  dx: number,
+  dy: number,
+  groups: Map<number, GroupVisual>,
+  onMoved?: (s: CardSprite) => void,
+) {
+  if (!dx && !dy) return;
+  for (const g of [gv, ...descendantGroups(gv, groups)]) {
+    g.gfx.x += dx;
+    g.gfx.y += dy;
+    for (const s of g.items) {
+      s.x += dx;
+      s.y += dy;
+      onMoved && onMoved(s);
+    }
+  }
+}
+
+// Keep nested frames (and their cards) above the enclosing group's cards
+function raiseNestedGroups(
+  gv: GroupVisual,
+  groups: Map<number, GroupVisual>,
+) {
+  for (const c of descendantGroups(gv, groups)) {
+    const parent = c.parentId != null ? groups.get(c.parentId) : undefined;
+    const minZ = (parent?.gfx.zIndex ?? 0) + 2;
+    if (c.gfx.zIndex < minZ) c.gfx.zIndex = minZ;
+    ensureMembersZOrder(c);
+  }
+}
+
+// Shelf-pack blocks into rows no wider than maxW (a wider block gets a row of its own).
+// Positions are relative to the packing origin.
+function packBlocks(blocks: GroupVisual[], maxW: number) {
+  const pos: { x: number; y: number }[] = [];
+  let x = 0;
+  let y = 0;
+  let rowH = 0;
+  let w = 0;
+  for (const b of blocks) {
+    if (x > 0 && x + b.w > maxW) {
+      y += rowH + CHILD_GAP;
+      x = 0;
+      rowH = 0;
+    }
+    pos.push({ x, y });
+    w = Math.max(w, x + b.w);
+    x += b.w + CHILD_GAP;
+    rowH = Math.max(rowH, b.h);
+  }
+  return { pos, w, h: blocks.length ? y + rowH : 0 };
+}
+
 // ---- Dynamic Text Resolution (crisp zoom) ----
 // Call this each frame (cheap; early exit unless threshold crossed)
 export function updateGroupTextQuality(
@@ -667,7 +1155,11 @@ export function updateGroupTextQuality(
 }
 
 // ---- Metrics (price + count) ----
-export function updateGroupMetrics(gv: GroupVisual) {
+// With `groups`, nested totals are rolled up through every enclosing group.
+export function updateGroupMetrics(
+  gv: GroupVisual,
+  groups?: Map<number, GroupVisual>,
+) {
   let total = 0;
   for (const sp of gv.items) {
     const card = sp?.__card;
@@ -681,6 +1173,24 @@ export function updateGroupMetrics(gv: GroupVisual) {
     }
   }
   gv.totalPrice = total;
+  if (groups) {
+    let cur: GroupVisual | undefined = gv;
+    const seen = new Set<number>();
+    while (cur && !seen.has(cur.id)) {
+      seen.add(cur.id);
+      let count = 0;
+      let price = 0;
+      for (const c of childGroups(cur, groups)) {
+        count += c.items.size + c.nestedCount;
+        price += c.totalPrice + c.nestedPrice;
+      }
+      cur.nestedCount = count;
+      cur.nestedPrice = price;
+      if (cur !== gv)
+        drawGroup(cur, SelectionStore.state.groupIds.has(cur.id));
+      cur = cur.parentId != null ? groups.get(cur.parentId) : undefined;
+    }
+  }
   // Refresh overlay (visible only) so totals stay in sync at macro zoom
   if (gv._zoomLabel && gv._zoomLabel.visible) positionZoomOverlay(gv);
 }
@@ -693,7 +1203,13 @@ export const ZOOM_OVERLAY_THRESH = 0.85;
 function positionZoomOverlay(gv: GroupVisual) {
   if (!gv._zoomLabel) return;
   const zl = gv._zoomLabel;
-  zl.text = `${gv.name}\n${gv.items.size} cards  $${gv.totalPrice.toFixed(2)}`;
+  const cards = gv.items.size + gv.nestedCount;
+  const total = gv.totalPrice + gv.nestedPrice;
+  const summary =
+    gv.spotlightCount != null
+      ? `${gv.spotlightCount} of ${cards} match`
+      : `${cards} cards`;
+  zl.text = `${gv.name}\n${summary}  $${total.toFixed(2)}`;
   // Ensure color stays theme-appropriate (dark in light mode)
   (zl.style as any).fill = OVERLAY_TEXT_COLOR;
   // Constrain width and adjust font size downward if necessary (simple heuristic)
@@ -736,6 +1252,10 @@ export function updateGroupZoomPresentation(
   gv.label.visible = !overlayActive;
   gv.count.visible = !overlayActive;
   gv.price.visible = !overlayActive;
+  gv.toggle.visible = !overlayActive;
+  gv.stackLabels.visible = !overlayActive;
+  gv.deckBadge.visible = !overlayActive && !!gv.deckStatus;
+  gv.smartBadge.visible = !overlayActive && !!gv.smart;
   // Maintain a dedicated transparent drag surface with an inset hitArea so edges remain clickable.
   const dragSurf = gv._overlayDrag as PIXI.Graphics | undefined;
   if (dragSurf) {
@@ -758,12 +1278,14 @@ export function updateGroupZoomPresentation(
   (window as any).__overlayToggles = ((window as any).__overlayToggles | 0) + 1;
   // Adjust member card sprite visibility/alpha (idempotent)
   for (const sp of gv.items) {
+    // Members of collapsed groups stay hidden at every zoom level
+    const hide = overlayActive || !!sp.__hidden;
     // Flag overlay activity for interaction layer so card drags are suppressed when overlay intended for group drag.
     // Toggle eventMode so sibling overlay can receive pointer events (cards are siblings, not children of group)
-    const desiredMode = overlayActive ? "none" : "static";
+    const desiredMode = hide ? "none" : "static";
     if ((sp as any).eventMode !== desiredMode)
       (sp as any).eventMode = desiredMode as any;
-    if (overlayActive) {
+    if (hide) {
       if (sp.cursor !== "default") sp.cursor = "default";
       if (sp.visible) {
         sp.visible = false;
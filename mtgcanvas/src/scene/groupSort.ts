@@ -0,0 +1,149 @@
+// Per-group sort spec (persisted in GroupRow.transform_json). An empty spec means manual
+// order: GroupVisual.order is left as inserted/arranged by the user.
+import type { Card } from "../types/card";
+import { colorRank, primaryType, typeRank } from "./stackLayout";
+
+export type GroupSortKey =
+  | "name"
+  | "cmc"
+  | "color"
+  | "type"
+  | "rarity"
+  | "price"
+  | "released";
+
+export interface GroupSortTerm {
+  key: GroupSortKey;
+  dir: "asc" | "desc";
+}
+
+// Primary key first; later terms break ties
+export type GroupSortSpec = GroupSortTerm[];
+
+export const GROUP_SORT_KEYS: { key: GroupSortKey; label: string }[] = [
+  { key: "name", label: "Name" },
+  { key: "cmc", label: "Mana value" },
+  { key: "color", label: "Color" },
+  { key: "type", label: "Type" },
+  { key: "rarity", label: "Rarity" },
+  { key: "price", label: "Price" },
+  { key: "released", label: "Release date" },
+];
+
+export const MAX_SORT_TERMS = 3;
+
+const RARITY_ORDER = [
+  "common",
+  "uncommon",
+  "rare",
+  "mythic",
+  "special",
+  "bonus",
+];
+
+// Validate a persisted spec; anything unrecognized is dropped
+export function parseGroupSortSpec(v: unknown): GroupSortSpec {
+  if (!Array.isArray(v)) return [];
+  const out: GroupSortSpec = [];
+  for (const t of v) {
+    if (!t || !GROUP_SORT_KEYS.some((k) => k.key === t.key)) continue;
+    if (out.some((o) => o.key === t.key)) continue;
+    out.push({ key: t.key, dir: t.dir === "desc" ? "desc" : "asc" });
+  }
+  return out.slice(0, MAX_SORT_TERMS);
+}
+
+// "Mana value ↑, Name ↑" or "Manual"
+export function describeGroupSort(spec: GroupSortSpec): string {
+  if (!spec.length) return "Manual";
+  return spec
+    .map((t) => {
+      const label = GROUP_SORT_KEYS.find((k) => k.key === t.key)?.label;
+      return `${label ?? t.key} ${t.dir === "desc" ? "↓" : "↑"}`;
+    })
+    .join(", ");
+}
+
+// Make `key` the primary sort; picking the current primary flips its direction.
+// Earlier keys stay on as tie-breakers.
+export function promoteSortKey(
+  spec: GroupSortSpec,
+  key: GroupSortKey,
+): GroupSortSpec {
+  if (spec[0]?.key === key) {
+    const dir: GroupSortTerm["dir"] = spec[0].dir === "asc" ? "desc" : "asc";
+    return [{ key, dir }, ...spec.slice(1)];
+  }
+  const next: GroupSortSpec = [
+    { key, dir: "asc" },
+    ...spec.filter((t) => t.key !== key),
+  ];
+  return next.slice(0, MAX_SORT_TERMS);
+}
+
+// Sortable value for a key; null sorts last in either direction
+function sortValue(
+  card: Card | null | undefined,
+  key: GroupSortKey,
+): number | string | null {
+  if (!card) return null;
+  switch (key) {
+    case "name":
+      return card.name ? String(card.name) : null;
+    case "cmc":
+      return typeof card.cmc === "number" && Number.isFinite(card.cmc)
+        ? card.cmc
+        : null;
+    case "color":
+      return colorRank(card);
+    case "type":
+      return typeRank(primaryType(card.type_line));
+    case "rarity": {
+      const i = RARITY_ORDER.indexOf(String(card.rarity || "").toLowerCase());
+      return i >= 0 ? i : null;
+    }
+    case "price": {
+      const p = card.prices || {};
+      const v = parseFloat(p.usd ?? p.usd_foil ?? "");
+      return Number.isFinite(v) ? v : null;
+    }
+    case "released":
+      return card.released_at ? String(card.released_at) : null;
+  }
+}
+
+function compareValues(
+  a: number | string | null,
+  b: number | string | null,
+): number {
+  if (typeof a === "string" && typeof b === "string")
+    return a.localeCompare(b, undefined, { sensitivity: "base" });
+  return (a as number) - (b as number);
+}
+
+// Stable sort by the spec; items equal on every term keep their current order
+export function sortByGroupSpec<T>(
+  items: T[],
+  spec: GroupSortSpec,
+  cardOf: (item: T) => Card | null | undefined,
+): T[] {
+  if (!spec.length) return items.slice();
+  const rows = items.map((it, i) => ({
+    it,
+    i,
+    vals: spec.map((t) => sortValue(cardOf(it), t.key)),
+  }));
+  rows.sort((a, b) => {
+    for (let k = 0; k < spec.length; k++) {
+      const va = a.vals[k];
+      const vb = b.vals[k];
+      if (va === vb) continue;
+      if (va == null) return 1;
+      if (vb == null) return -1;
+      const c = compareValues(va, vb);
+      if (c) return spec[k].dir === "desc" ? -c : c;
+    }
+    return a.i - b.i;
+  });
+  return rows.map((r) => r.it);
+}
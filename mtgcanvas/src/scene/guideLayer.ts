@@ -0,0 +1,61 @@
+// Temporary smart-guide lines shown while dragging (see utils/alignGuides.ts).
+// Drawn in world space with a constant on-screen width, above cards and groups.
+import * as PIXI from "pixi.js";
+import { Colors } from "../ui/theme";
+import type { Guide } from "../utils/alignGuides";
+
+export interface GuideLayer {
+  show(guides: Guide[]): void;
+  clear(): void;
+}
+
+const GUIDE_Z = 999990; // just below the marquee rectangle
+const TICK_PX = 5; // half length of the end ticks on spacing markers
+
+export function createGuideLayer(world: PIXI.Container): GuideLayer {
+  const g = new PIXI.Graphics();
+  g.zIndex = GUIDE_Z;
+  g.eventMode = "none";
+  world.addChild(g);
+  let shown = false;
+  return {
+    show(guides) {
+      g.clear();
+      shown = guides.length > 0;
+      if (!shown) return;
+      const px = 1 / (world.scale.x || 1);
+      const color = Colors.accent();
+      for (const gd of guides) {
+        if (gd.kind === "line") {
+          if (gd.axis === "x") g.moveTo(gd.pos, gd.from).lineTo(gd.pos, gd.to);
+          else g.moveTo(gd.from, gd.pos).lineTo(gd.to, gd.pos);
+          g.stroke({ color, width: px, alpha: 0.9 });
+          continue;
+        }
+        // Spacing marker: the gap with ticks at both ends
+        const t = TICK_PX * px;
+        if (gd.axis === "x") {
+          g.moveTo(gd.from, gd.at)
+            .lineTo(gd.to, gd.at)
+            .moveTo(gd.from, gd.at - t)
+            .lineTo(gd.from, gd.at + t)
+            .moveTo(gd.to, gd.at - t)
+            .lineTo(gd.to, gd.at + t);
+        } else {
+          g.moveTo(gd.at, gd.from)
+            .lineTo(gd.at, gd.to)
+            .moveTo(gd.at - t, gd.from)
+            .lineTo(gd.at + t, gd.from)
+            .moveTo(gd.at - t, gd.to)
+            .lineTo(gd.at + t, gd.to);
+        }
+        g.stroke({ color, width: 2 * px, alpha: 0.9 });
+      }
+    },
+    clear() {
+      if (!shown) return;
+      shown = false;
+      g.clear();
+    },
+  };
+}
@@ -0,0 +1,175 @@
+// Sticky note visuals: a flat colored sheet with markdown-lite text and a resize corner.
+// Notes keep their own pastel palette on both themes; only the selection outline follows
+// the theme accent. Interaction (drag, resize, editing) is wired up by main.
+import * as PIXI from "pixi.js";
+import { Colors } from "../ui/theme";
+import {
+  NOTE_COLORS,
+  NOTE_DEFAULT_H,
+  NOTE_DEFAULT_W,
+  parseNoteText,
+  type NoteColor,
+  type NoteLine,
+  type NoteRecord,
+} from "./notes";
+
+export interface NoteVisual {
+  id: number;
+  gfx: PIXI.Container; // root container positioned in world space
+  sheet: PIXI.Graphics; // background + outline; drag / select surface
+  body: PIXI.Container; // rendered text lines (masked to the sheet)
+  resize: PIXI.Graphics; // bottom-right resize corner
+  text: string;
+  color: NoteColor;
+  w: number;
+  h: number;
+}
+
+const PAD = 12;
+const RADIUS = 4;
+const RESIZE_SIZE = 14;
+const TEXT_COLOR = 0x1f1f1f; // dark ink on the pastel sheets
+const FONT_FAMILY = "Inter, system-ui, sans-serif";
+const FONT_SIZE = 14;
+const LINE_GAP = 4;
+const INDENT = 18; // bullets and checklists
+// Rendered above 1x so text stays legible when zoomed in on a note
+const TEXT_RESOLUTION = 2;
+// Above groups (40) so notes stay readable over frames, below dragged cards
+const NOTE_Z = 45;
+
+export function createNoteVisual(
+  id: number,
+  x: number,
+  y: number,
+  w = NOTE_DEFAULT_W,
+  h = NOTE_DEFAULT_H,
+): NoteVisual {
+  const gfx = new PIXI.Container();
+  gfx.sortableChildren = true;
+  gfx.x = x;
+  gfx.y = y;
+  gfx.zIndex = NOTE_Z;
+  gfx.eventMode = "static";
+  const sheet = new PIXI.Graphics();
+  sheet.eventMode = "static";
+  sheet.cursor = "move";
+  (sheet as any).__noteSheet = true;
+  const body = new PIXI.Container();
+  body.eventMode = "none";
+  body.zIndex = 1;
+  const mask = new PIXI.Graphics();
+  body.mask = mask;
+  const resize = new PIXI.Graphics();
+  resize.eventMode = "static";
+  resize.cursor = "nwse-resize";
+  resize.zIndex = 2;
+  gfx.addChild(sheet, body, mask, resize);
+  const nv: NoteVisual = {
+    id,
+    gfx,
+    sheet,
+    body,
+    resize,
+    text: "",
+    color: "yellow",
+    w,
+    h,
+  };
+  drawNote(nv, false);
+  return nv;
+}
+
+export function noteRecord(nv: NoteVisual): NoteRecord {
+  return {
+    id: nv.id,
+    x: nv.gfx.x,
+    y: nv.gfx.y,
+    w: nv.w,
+    h: nv.h,
+    z: nv.gfx.zIndex || 0,
+    text: nv.text,
+    color: nv.color,
+  };
+}
+
+// Redraw the sheet and outline; text is re-laid out only when content or width changed
+export function drawNote(nv: NoteVisual, selected: boolean) {
+  const fill = NOTE_COLORS[nv.color];
+  nv.sheet
+    .clear()
+    .roundRect(0, 0, nv.w, nv.h, RADIUS)
+    .fill({ color: fill })
+    .stroke(
+      selected
+        ? { color: Colors.accent(), width: 3 }
+        : { color: 0x000000, width: 1, alpha: 0.18 },
+    );
+  nv.resize
+    .clear()
+    .moveTo(nv.w, nv.h - RESIZE_SIZE)
+    .lineTo(nv.w, nv.h)
+    .lineTo(nv.w - RESIZE_SIZE, nv.h)
+    .closePath()
+    .fill({ color: 0x000000, alpha: 0.15 });
+  (nv.body.mask as PIXI.Graphics)
+    .clear()
+    .rect(PAD / 2, PAD / 2, nv.w - PAD, nv.h - PAD)
+    .fill({ color: 0xffffff });
+  const key = `${nv.w}|${nv.text}`;
+  if ((nv.body as any).__layoutKey === key) return;
+  (nv.body as any).__layoutKey = key;
+  layoutNoteText(nv);
+}
+
+function layoutNoteText(nv: NoteVisual) {
+  nv.body.removeChildren().forEach((c) => c.destroy());
+  const width = Math.max(20, nv.w - PAD * 2);
+  let y = PAD;
+  const lines: NoteLine[] = nv.text.trim()
+    ? parseNoteText(nv.text)
+    : [{ kind: "text", text: "Double-click to edit", italic: true }];
+  for (const line of lines) {
+    if (!line.text && line.kind === "text") {
+      y += FONT_SIZE * 0.6; // blank line = paragraph gap
+      continue;
+    }
+    const size =
+      line.kind === "heading" ? (line.level === 1 ? 20 : 16) : FONT_SIZE;
+    const indented = line.kind === "bullet" || line.kind === "check";
+    const style: PIXI.TextStyleOptions = {
+      fill: TEXT_COLOR,
+      fontSize: size,
+      fontFamily: FONT_FAMILY,
+      fontWeight: line.kind === "heading" || line.bold ? "600" : "400",
+      fontStyle: line.italic ? "italic" : "normal",
+      lineHeight: Math.round(size * 1.3),
+      wordWrap: true,
+      breakWords: true,
+      wordWrapWidth: indented ? width - INDENT : width,
+    };
+    if (indented) {
+      const marker = new PIXI.Text({
+        text: line.kind === "check" ? (line.checked ? "☑" : "☐") : "•",
+        style: { ...style, fontWeight: "400", fontStyle: "normal" },
+        resolution: TEXT_RESOLUTION,
+      });
+      marker.x = PAD;
+      marker.y = y;
+      nv.body.addChild(marker);
+    }
+    const t = new PIXI.Text({
+      text: line.text,
+      style,
+      resolution: TEXT_RESOLUTION,
+    });
+    t.x = indented ? PAD + INDENT : PAD;
+    t.y = y;
+    // Done checklist items read as struck off
+    if (line.kind === "check" && line.checked) t.alpha = 0.55;
+    if (!nv.text.trim()) t.alpha = 0.45;
+    nv.body.addChild(t);
+    y += t.height + LINE_GAP;
+    if (y > nv.h) break; // the rest is clipped anyway
+  }
+}
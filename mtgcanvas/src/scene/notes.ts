@@ -0,0 +1,102 @@
+// Sticky notes: free-floating text frames in world space ("combo: X + Y", "cut candidates").
+// This module is the pure half (stored shape, palette, markdown-lite parsing); the Pixi
+// visuals live in noteNode.ts. Notes are saved with the groups payload of a project.
+
+export const NOTE_COLORS = {
+  yellow: 0xfff1a8,
+  orange: 0xffd3a1,
+  pink: 0xffc9dc,
+  green: 0xc8f0c4,
+  blue: 0xc4e1ff,
+  purple: 0xdccbff,
+  gray: 0xe3e3e3,
+} as const;
+export type NoteColor = keyof typeof NOTE_COLORS;
+
+export const NOTE_DEFAULT_W = 240;
+export const NOTE_DEFAULT_H = 160;
+export const NOTE_MIN_W = 120;
+export const NOTE_MIN_H = 64;
+
+export interface NoteRecord {
+  id: number;
+  x: number;
+  y: number;
+  w: number;
+  h: number;
+  z: number;
+  text: string;
+  color: NoteColor;
+}
+
+export function isNoteColor(v: unknown): v is NoteColor {
+  return typeof v === "string" && Object.hasOwn(NOTE_COLORS, v);
+}
+
+// Validate stored notes; drops entries without a numeric id and repeats of an id
+export function parseNoteRecords(raw: unknown): NoteRecord[] {
+  if (!Array.isArray(raw)) return [];
+  const out: NoteRecord[] = [];
+  const seen = new Set<number>();
+  for (const r of raw) {
+    if (!r || typeof r !== "object") continue;
+    const id = Number(r.id);
+    if (!Number.isInteger(id) || id <= 0 || seen.has(id)) continue;
+    seen.add(id);
+    const num = (v: unknown, d: number) =>
+      Number.isFinite(Number(v)) ? Number(v) : d;
+    out.push({
+      id,
+      x: Math.round(num(r.x, 0)),
+      y: Math.round(num(r.y, 0)),
+      w: Math.max(NOTE_MIN_W, Math.round(num(r.w, NOTE_DEFAULT_W))),
+      h: Math.max(NOTE_MIN_H, Math.round(num(r.h, NOTE_DEFAULT_H))),
+      z: num(r.z, 0),
+      text: typeof r.text === "string" ? r.text : "",
+      color: isNoteColor(r.color) ? r.color : "yellow",
+    });
+  }
+  return out;
+}
+
+// Markdown-lite, one block per line: "# " / "## " headings, "- " / "* " bullets,
+// "- [ ]" / "- [x]" checklists, and a line wrapped in ** or _ for bold / italic.
+export type NoteLine = {
+  kind: "heading" | "bullet" | "check" | "text";
+  text: string;
+  level?: number; // headings: 1 or 2
+  checked?: boolean; // checklists
+  bold?: boolean;
+  italic?: boolean;
+};
+
+export function parseNoteText(text: string): NoteLine[] {
+  return text.split(/\r?\n/).map((raw) => {
+    const line = raw.trimEnd();
+    const heading = /^(#{1,2})\s+(.*)$/.exec(line);
+    if (heading)
+      return emphasis({
+        kind: "heading",
+        level: heading[1].length,
+        text: heading[2],
+      });
+    const check = /^\s*[-*]\s+\[([ xX])\]\s*(.*)$/.exec(line);
+    if (check)
+      return emphasis({
+        kind: "check",
+        checked: check[1] !== " ",
+        text: check[2],
+      });
+    const bullet = /^\s*[-*]\s+(.*)$/.exec(line);
+    if (bullet) return emphasis({ kind: "bullet", text: bullet[1] });
+    return emphasis({ kind: "text", text: line });
+  });
+}
+
+function emphasis(l: NoteLine): NoteLine {
+  const bold = /^\*\*(.+)\*\*$/.exec(l.text);
+  if (bold) return { ...l, text: bold[1], bold: true };
+  const italic = /^_(.+)_$/.exec(l.text);
+  if (italic) return { ...l, text: italic[1], italic: true };
+  return l;
+}
@@ -0,0 +1,57 @@
+// Outlines around the cards matched by a search spotlight (the rest are dimmed by
+// updateCardSpriteAppearance). Synced every frame by main; redraws only when the matched
+// cards moved or the zoom changed, so a still spotlight costs one pass over the matches.
+import * as PIXI from "pixi.js";
+import { Colors } from "../ui/theme";
+import type { CardSprite } from "./cardNode";
+import { cardSize } from "./cardTransform";
+
+export interface SpotlightLayer {
+  sync(matches: Iterable<CardSprite>): void;
+  clear(): void;
+}
+
+const SPOTLIGHT_Z = 899000; // above cards, below connectors and the marquee
+const OUTLINE_PX = 3; // on-screen width
+const RADIUS = 6;
+
+export function createSpotlightLayer(world: PIXI.Container): SpotlightLayer {
+  const g = new PIXI.Graphics();
+  g.zIndex = SPOTLIGHT_Z;
+  g.eventMode = "none";
+  world.addChild(g);
+  let key = "";
+  return {
+    sync(matches) {
+      const scale = world.scale.x || 1;
+      const color = Colors.accent();
+      // Cheap signature of what would be drawn
+      let n = 0;
+      let hash = 0;
+      for (const s of matches) {
+        if (s.__hidden || s.destroyed) continue;
+        const { w, h } = cardSize(s);
+        hash = (hash * 31 + Math.round(s.x) * 7 + Math.round(s.y) * 13) | 0;
+        hash = (hash * 31 + Math.round(w + h)) | 0;
+        n++;
+      }
+      const next = `${n}|${hash}|${scale}|${color}`;
+      if (next === key) return;
+      key = next;
+      g.clear();
+      if (!n) return;
+      const px = OUTLINE_PX / scale;
+      for (const s of matches) {
+        if (s.__hidden || s.destroyed) continue;
+        const { w, h } = cardSize(s);
+        g.roundRect(s.x - px / 2, s.y - px / 2, w + px, h + px, RADIUS);
+      }
+      g.stroke({ color, width: px, alpha: 0.95 });
+    },
+    clear() {
+      if (!key) return;
+      key = "";
+      g.clear();
+    },
+  };
+}
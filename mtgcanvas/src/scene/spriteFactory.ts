@@ -6,6 +6,7 @@ import {
   endDragFloat,
   updateDraggedTopLeft,
 } from "./cardNode";
+import { cardBounds, cardSize } from "./cardTransform";
 import { SelectionStore } from "../state/selectionStore";
 import { snap } from "../utils/snap";
 import type { Card } from "../types/card";
@@ -18,12 +19,25 @@ export type CreateSpriteDeps = {
   getAll: () => CardSprite[];
   onDrop: (moved: CardSprite[]) => void;
   onDragMove: (moved: CardSprite[]) => void;
+  // Fired once when a drag actually begins (before z/positions change)
+  onDragStart?: (sprites: CardSprite[]) => void;
   cardW: number;
   cardH: number;
   isPanning?: () => boolean;
   startMarquee?: (global: PIXI.Point, additive: boolean) => void;
+  // Ctrl/Cmd+drag on a duplicate stack: take this copy off the stack before dragging it
+  onSplitStack?: (s: CardSprite) => void;
+  // Smart guides: adjust the drag delta; `keepX`/`keepY` skip grid snapping on drop
+  alignDrag?: AlignDrag;
 };
 
+export type AlignDrag = (
+  starts: { sprite: CardSprite; x0: number; y0: number }[],
+  dX: number,
+  dY: number,
+  free: boolean, // Alt held: no snapping at all
+) => { dX: number; dY: number; keepX: boolean; keepY: boolean };
+
 export function createSprite(
   inst: {
     id: number;
@@ -33,6 +47,9 @@ export function createSprite(
     group_id?: number | null;
     card?: Card | null;
     scryfall_id?: string | null;
+    foil?: boolean;
+    rotation?: number;
+    scale?: number;
   },
   deps: CreateSpriteDeps,
 ): CardSprite {
@@ -41,10 +58,13 @@ export function createSprite(
     x: inst.x,
     y: inst.y,
     z: inst.z,
+    rotation: inst.rotation,
+    scale: inst.scale,
     renderer: deps.renderer,
     card: inst.card,
   });
   if (inst.group_id) s.__groupId = inst.group_id;
+  if (inst.foil) s.__foil = true;
   if (inst.scryfall_id) s.__scryfallId = String(inst.scryfall_id);
   else if (inst.card) {
     const sid = (inst.card as any).id || (inst.card as any)?.data?.id;
@@ -61,6 +81,9 @@ export function createSprite(
     deps.isPanning,
     deps.startMarquee,
     deps.onDragMove,
+    deps.onDragStart,
+    deps.onSplitStack,
+    deps.alignDrag,
   );
   return s;
 }
@@ -74,6 +97,9 @@ export function createSpritesBulk(
     group_id?: number | null;
     card?: Card | null;
     scryfall_id?: string | null;
+    foil?: boolean;
+    rotation?: number;
+    scale?: number;
   }>,
   deps: CreateSpriteDeps & { spatial: Pick<SpatialIndex, "bulkLoad"> },
 ): CardSprite[] {
@@ -84,13 +110,7 @@ export function createSpritesBulk(
   }
   // Batch insert into spatial index
   deps.spatial.bulkLoad(
-    created.map<SpatialItem>((s) => ({
-      sprite: s,
-      minX: s.x,
-      minY: s.y,
-      maxX: s.x + deps.cardW,
-      maxY: s.y + deps.cardH,
-    })),
+    created.map<SpatialItem>((s) => ({ sprite: s, ...cardBounds(s) })),
   );
   return created;
 }
@@ -104,12 +124,17 @@ function attachCardInteractions(
   isPanning?: () => boolean,
   startMarquee?: (global: PIXI.Point, additive: boolean) => void,
   onDragMove?: (moved: CardSprite[]) => void,
+  onDragStart?: (sprites: CardSprite[]) => void,
+  onSplitStack?: (s: CardSprite) => void,
+  alignDrag?: AlignDrag,
 ) {
   // Small movement threshold before we consider a drag (screen-space, conservative)
   const LEFT_DRAG_THRESHOLD_PX = 4;
   let pendingStartLocal: { x: number; y: number } | null = null;
   // Sprites elevated on pointerdown before the drag threshold is crossed
   let preFloatSprites: CardSprite[] | null = null;
+  // Alt held on pointerdown over a stack: the drag takes only this copy
+  let splitOnDrag = false;
   let dragState: null | {
     sprites: CardSprite[];
     // Original positions at drag start (for rigid-body delta application)
@@ -118,10 +143,20 @@ function attachCardInteractions(
     startLocal: { x: number; y: number };
     lastGlobalX: number;
     lastTs: number;
+    // Axes held by a smart guide (or Alt) on the last move; these skip the grid on drop
+    keepX: boolean;
+    keepY: boolean;
   } = null;
   function beginDrag(atLocal: { x: number; y: number }) {
+    if (splitOnDrag && onSplitStack) {
+      onSplitStack(s);
+      // Only this copy was floated on pointerdown; the rest of the stack stays put
+      preFloatSprites = [s];
+    }
+    splitOnDrag = false;
     // Compute selected sprites lazily (only when we actually start dragging)
     const dragSprites: CardSprite[] = SelectionStore.getCards();
+    onDragStart && onDragStart(dragSprites);
     // Raise above current content using global max z; avoid full normalization on drag start
     const w: any = window as any;
     const getMax: any = w.__mtgMaxContentZ;
@@ -146,9 +181,11 @@ function attachCardInteractions(
       startLocal: { x: atLocal.x, y: atLocal.y },
       lastGlobalX: 0,
       lastTs: now,
+      keepX: false,
+      keepY: false,
     };
-    // Engage float/tilt mode for visual feedback
-    for (const cs of dragSprites) beginDragFloat(cs);
+    // Engage float/tilt mode for visual feedback (copies hidden under a stack just follow)
+    for (const cs of dragSprites) if (!cs.__hidden) beginDragFloat(cs);
     // Visual elevation applied above; nothing else to do
   }
   s.on("pointerdown", (e: PIXI.FederatedPointerEvent) => {
@@ -168,11 +205,13 @@ function attachCardInteractions(
       (s as any).__tintByMarquee = false;
       SelectionStore.toggleCard(s);
     }
+    // Not Alt: that frees the drag from guides and snapping, and must not split piles
+    splitOnDrag = (e.ctrlKey || e.metaKey) && !!s.__stack?.length;
     // Record local start; we’ll start drag only after a tiny movement threshold
     const startLocal = world.toLocal(e.global);
     pendingStartLocal = { x: startLocal.x, y: startLocal.y };
     // Immediately elevate currently selected sprites for visual feedback on click-and-hold
-    preFloatSprites = SelectionStore.getCards().slice();
+    preFloatSprites = SelectionStore.getCards().filter((cs) => !cs.__hidden);
     // Elevate lightly but defer creating the mesh until true drag begins
     for (const cs of preFloatSprites) beginDragFloat(cs, false);
   });
@@ -210,18 +249,20 @@ function attachCardInteractions(
       const hasBounds = ha && typeof ha.x === "number";
       const minX = hasBounds ? ha.x : -Infinity;
       const minY = hasBounds ? ha.y : -Infinity;
-      const maxX = hasBounds ? ha.x + ha.width - 100 : Infinity;
-      const maxY = hasBounds ? ha.y + ha.height - 140 : Infinity;
+      const maxX = hasBounds ? ha.x + ha.width : Infinity;
+      const maxY = hasBounds ? ha.y + ha.height : Infinity;
+      const { keepX, keepY } = dragState;
       dragState.sprites.forEach((cs) => {
         // Exit float mode and restore TL-based transform before snapping
         endDragFloat(cs);
-        let nx = snap(cs.x);
-        let ny = snap(cs.y);
+        let nx = keepX ? Math.round(cs.x) : snap(cs.x);
+        let ny = keepY ? Math.round(cs.y) : snap(cs.y);
         if (hasBounds) {
+          const size = cardSize(cs);
           if (nx < minX) nx = minX;
           if (ny < minY) ny = minY;
-          if (nx > maxX) nx = maxX;
-          if (ny > maxY) ny = maxY;
+          if (nx > maxX - size.w) nx = maxX - size.w;
+          if (ny > maxY - size.h) ny = maxY - size.h;
         }
         cs.x = nx;
         cs.y = ny;
@@ -287,12 +328,19 @@ function attachCardInteractions(
     const hasBounds = ha && typeof ha.x === "number";
     const minX = hasBounds ? ha.x : -Infinity;
     const minY = hasBounds ? ha.y : -Infinity;
-    const maxX = hasBounds ? ha.x + ha.width - 100 : Infinity;
-    const maxY = hasBounds ? ha.y + ha.height - 140 : Infinity;
+    const maxX = hasBounds ? ha.x + ha.width : Infinity;
+    const maxY = hasBounds ? ha.y + ha.height : Infinity;
 
     // Intended deltas from drag start
     let dX = local.x - dragState.startLocal.x;
     let dY = local.y - dragState.startLocal.y;
+    if (alignDrag) {
+      const a = alignDrag(dragState.starts, dX, dY, e.altKey);
+      dX = a.dX;
+      dY = a.dY;
+      dragState.keepX = a.keepX;
+      dragState.keepY = a.keepY;
+    }
 
     if (hasBounds) {
       // Compute shared delta limits so that all sprites remain in-bounds
@@ -301,12 +349,13 @@ function attachCardInteractions(
       let lowDY = -Infinity;
       let highDY = Infinity;
       for (const st of dragState.starts) {
-        // For X: minX <= x0 + dX <= maxX
+        const size = cardSize(st.sprite);
+        // For X: minX <= x0 + dX <= maxX - w
         lowDX = Math.max(lowDX, minX - st.x0);
-        highDX = Math.min(highDX, maxX - st.x0);
-        // For Y: minY <= y0 + dY <= maxY
+        highDX = Math.min(highDX, maxX - size.w - st.x0);
+        // For Y: minY <= y0 + dY <= maxY - h
         lowDY = Math.max(lowDY, minY - st.y0);
-        highDY = Math.min(highDY, maxY - st.y0);
+        highDY = Math.min(highDY, maxY - size.h - st.y0);
       }
       // Clamp intended delta to allowable range
       if (dX < lowDX) dX = lowDX;
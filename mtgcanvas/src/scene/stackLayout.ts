@@ -0,0 +1,124 @@
+// Column ("stacks") layout for groups: cards split into overlapping vertical columns by
+// mana value, primary type or color, the way deck-building sites show a deck.
+// Pure planning only; groupNode positions the sprites.
+import type { Card } from "../types/card";
+
+export type StackKey = "cmc" | "type" | "color";
+export type GroupLayoutMode = "grid" | StackKey;
+
+export const GROUP_LAYOUT_MODES: { mode: GroupLayoutMode; label: string }[] = [
+  { mode: "grid", label: "Grid" },
+  { mode: "cmc", label: "Stacks by mana value" },
+  { mode: "type", label: "Stacks by type" },
+  { mode: "color", label: "Stacks by color" },
+];
+
+export function isGroupLayoutMode(v: unknown): v is GroupLayoutMode {
+  return GROUP_LAYOUT_MODES.some((m) => m.mode === v);
+}
+
+const TYPE_ORDER = [
+  "Creature",
+  "Instant",
+  "Sorcery",
+  "Artifact",
+  "Enchantment",
+  "Planeswalker",
+  "Battle",
+  "Land",
+];
+
+// Main card type used for grouping ("Artifact Creature" -> "Creature")
+export function primaryType(typeLine: string | undefined): string {
+  if (!typeLine) return "Unknown";
+  const tl = typeLine;
+  for (const t of TYPE_ORDER) if (tl.includes(t)) return t;
+  // Fallback: token before dash or first word
+  const beforeDash = tl.split("—")[0].trim();
+  const first = beforeDash.split(/\s+/)[0] || "Unknown";
+  return first;
+}
+
+// Position of a type in the usual deck order (unlisted types after Land)
+export function typeRank(type: string): number {
+  const i = TYPE_ORDER.indexOf(type);
+  return i >= 0 ? i : TYPE_ORDER.length;
+}
+
+export const COLOR_NAMES: [string, string][] = [
+  ["W", "White"],
+  ["U", "Blue"],
+  ["B", "Black"],
+  ["R", "Red"],
+  ["G", "Green"],
+];
+
+// Printed colors; double-faced cards carry them on the front face
+function cardColors(card: Card | null | undefined): string[] {
+  return card?.colors ?? card?.card_faces?.[0]?.colors ?? [];
+}
+
+// WUBRG position of a mono-colored card; multicolor 5, colorless 6
+export function colorRank(card: Card | null | undefined): number {
+  const colors = cardColors(card);
+  if (colors.length > 1) return 5;
+  const i = COLOR_NAMES.findIndex(([c]) => c === colors[0]);
+  return i >= 0 ? i : 6;
+}
+
+export interface StackColumn<T> {
+  key: string;
+  label: string;
+  items: T[];
+}
+
+// Column a card belongs to; rank orders columns left to right
+function columnFor(
+  card: Card | null | undefined,
+  by: StackKey,
+): { key: string; label: string; rank: number } {
+  switch (by) {
+    case "cmc": {
+      // Lands get their own column instead of crowding mana value 0
+      if (primaryType(card?.type_line) === "Land")
+        return { key: "land", label: "Land", rank: 1001 };
+      const cmc = card?.cmc;
+      if (typeof cmc !== "number" || !Number.isFinite(cmc))
+        return { key: "?", label: "?", rank: 1000 };
+      const n = Math.floor(cmc);
+      return { key: String(n), label: String(n), rank: n };
+    }
+    case "type": {
+      const t = primaryType(card?.type_line);
+      return { key: t, label: t, rank: typeRank(t) };
+    }
+    case "color": {
+      const rank = colorRank(card);
+      if (rank === 5) return { key: "multi", label: "Multicolor", rank };
+      if (rank === 6) return { key: "colorless", label: "Colorless", rank };
+      const [key, label] = COLOR_NAMES[rank];
+      return { key, label, rank };
+    }
+  }
+}
+
+// Split items into columns. Items keep their relative order within a column.
+export function planStacks<T>(
+  items: T[],
+  cardOf: (item: T) => Card | null | undefined,
+  by: StackKey,
+): StackColumn<T>[] {
+  const cols = new Map<string, StackColumn<T> & { rank: number }>();
+  for (const it of items) {
+    const { key, label, rank } = columnFor(cardOf(it), by);
+    let col = cols.get(key);
+    if (!col) {
+      col = { key, label, rank, items: [] };
+      cols.set(key, col);
+    }
+    col.items.push(it);
+  }
+  return [...cols.values()]
+    .sort((a, b) => a.rank - b.rank || a.label.localeCompare(b.label))
+    .map(({ key, label, items }) => ({ key, label, items }));
+}
@@ -0,0 +1,86 @@
+import { describe, it, expect } from "vitest";
+import { parseScryfallQuery, type CanvasContext } from "../scryfallQuery";
+import {
+  createCanvasContextLookup,
+  parseInstanceTags,
+  type CanvasCardFacts,
+} from "../canvasContext";
+
+const bolt = { name: "Lightning Bolt", type_line: "Instant" };
+const ctx = (over: Partial<CanvasContext> = {}): CanvasContext => ({
+  groups: [],
+  tags: [],
+  owned: 1,
+  count: 1,
+  foil: false,
+  selected: false,
+  face: 0,
+  ...over,
+});
+const matches = (q: string, c?: CanvasContext) =>
+  parseScryfallQuery(q)!(bolt, c);
+
+describe("canvas query fields", () => {
+  it("matches the card's group and enclosing groups", () => {
+    const inDeck = ctx({ groups: ["removal", "omo deck"] });
+    expect(matches('group:"Omo deck"', inDeck)).toBe(true);
+    expect(matches("group:removal t:instant", inDeck)).toBe(true);
+    expect(matches("group:ramp", inDeck)).toBe(false);
+    expect(matches("in:grouped", inDeck)).toBe(true);
+    expect(matches("in:ungrouped", inDeck)).toBe(false);
+    expect(matches("-in:grouped", ctx())).toBe(true);
+  });
+
+  it("tests tags, selection, face and finish", () => {
+    const c = ctx({ tags: ["combo"], selected: true, face: 1, foil: true });
+    expect(matches("tag:combo", c)).toBe(true);
+    expect(matches("tag:cut", c)).toBe(false);
+    expect(matches("is:selected", c)).toBe(true);
+    expect(matches("not:selected", c)).toBe(false);
+    expect(matches("face:back", c)).toBe(true);
+    expect(matches("owned:foil", c)).toBe(true);
+  });
+
+  it("compares copy counts", () => {
+    const c = ctx({ owned: 4, count: 1 });
+    expect(matches("owned>=4", c)).toBe(true);
+    expect(matches("count>1", c)).toBe(false);
+    expect(matches("dup:name", c)).toBe(true);
+    expect(matches("dup:print", c)).toBe(false);
+  });
+
+  it("never matches canvas fields without a canvas", () => {
+    expect(matches("in:ungrouped")).toBe(false);
+    expect(matches("count>=1")).toBe(false);
+    expect(matches("-tag:combo")).toBe(true);
+  });
+});
+
+describe("createCanvasContextLookup", () => {
+  it("counts copies by name and by printing", () => {
+    const facts = (p: string | null, name = "Lightning Bolt") =>
+      ({
+        name,
+        printing: p,
+        groups: [],
+        tags: "Combo, cut",
+        foil: false,
+        selected: false,
+        face: 0,
+      }) as CanvasCardFacts;
+    const items = [facts("a"), facts("a"), facts("b"), facts(null, "Opt")];
+    const lookup = createCanvasContextLookup(items, (f) => f);
+    expect(lookup(items[0])).toMatchObject({ owned: 3, count: 2 });
+    expect(lookup(items[2])).toMatchObject({ owned: 3, count: 1 });
+    expect(lookup(items[3])).toMatchObject({ owned: 1, count: 1 });
+    expect(lookup(items[0]).tags).toEqual(["combo", "cut"]);
+  });
+
+  it("reads tags as a list or a JSON array", () => {
+    expect(parseInstanceTags('["Combo", "wincon"]')).toEqual([
+      "combo",
+      "wincon",
+    ]);
+    expect(parseInstanceTags(null)).toEqual([]);
+  });
+});
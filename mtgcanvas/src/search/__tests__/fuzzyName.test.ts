@@ -0,0 +1,31 @@
+import { describe, it, expect } from "vitest";
+import { editDistance, normalizeCardName, suggestNames } from "../fuzzyName";
+
+const NAMES = [
+  "Lim-Dûl's Vault",
+  "Delver of Secrets // Insectile Aberration",
+  "Lightning Bolt",
+  "Lightning Helix",
+  "Counterspell",
+];
+
+describe("fuzzy card names", () => {
+  it("normalizes accents, punctuation and case", () => {
+    expect(normalizeCardName("Lim-Dûl's  Vault")).toBe("lim dul s vault");
+    expect(normalizeCardName("LIM DUL'S VAULT")).toBe("lim dul s vault");
+  });
+  it("bounds edit distance", () => {
+    expect(editDistance("bolt", "bolt")).toBe(0);
+    expect(editDistance("lightnig", "lightning")).toBe(1);
+    expect(editDistance("abc", "xyzxyz", 2)).toBe(3);
+  });
+  it("suggests accent-less, partial DFC and misspelled names", () => {
+    expect(suggestNames("Lim-Dul's Vault", NAMES)[0]).toBe("Lim-Dûl's Vault");
+    expect(suggestNames("Delver of Secrets // ...", NAMES)[0]).toBe(
+      "Delver of Secrets // Insectile Aberration",
+    );
+    expect(suggestNames("Lightnign Bolt", NAMES)[0]).toBe("Lightning Bolt");
+    expect(suggestNames("Counterspel", NAMES)).toEqual(["Counterspell"]);
+    expect(suggestNames("Black Lotus", NAMES)).toEqual([]);
+  });
+});
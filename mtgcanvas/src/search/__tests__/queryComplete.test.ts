@@ -0,0 +1,40 @@
+import { describe, it, expect } from "vitest";
+import { completeQuery } from "../queryComplete";
+
+const sources = { sets: ["m10", "mh2", "neo"], formats: ["modern", "pauper"] };
+const texts = (q: string, caret = q.length) =>
+  completeQuery(q, caret, sources)?.items.map((i) => i.text) ?? [];
+
+describe("completeQuery", () => {
+  it("completes field names, shortest first", () => {
+    expect(texts("kw")).toEqual(["kw:"]);
+    expect(texts("st")).toEqual(["st:", "stamp:"]);
+    const c = completeQuery("t:elf -key", 10, sources)!;
+    expect(c.items[0]).toEqual({ text: "keyword:", hint: "keywords" });
+    // The "-" stays; only the word after it is replaced
+    expect([c.start, c.end]).toEqual([7, 10]);
+  });
+
+  it("completes values for flag, set and format fields", () => {
+    expect(texts("is:mdf")).toEqual(["mdfc"]);
+    expect(texts("-not:sel")).toEqual(["selected"]);
+    expect(texts("e:m")).toEqual(["m10", "mh2"]);
+    expect(texts("f:pa")).toEqual(["pauper"]);
+    const c = completeQuery("c:g is:fo", 9, sources)!;
+    expect([c.start, c.end]).toEqual([7, 9]);
+  });
+
+  it("replaces the whole word around the caret", () => {
+    const c = completeQuery("is:fo t:elf", 5, sources)!;
+    expect([c.start, c.end]).toEqual([3, 5]);
+    expect(completeQuery("is:foxx t:elf", 5, sources)?.end).toBe(7);
+  });
+
+  it("stays quiet for free text, phrases, regexes and complete values", () => {
+    expect(texts("goblin")).toEqual([]);
+    expect(texts('o:"dra')).toEqual([]);
+    expect(texts("o:/^{t")).toEqual([]);
+    expect(texts("o:dra")).toEqual([]);
+    expect(texts("is:foil")).toEqual([]);
+  });
+});
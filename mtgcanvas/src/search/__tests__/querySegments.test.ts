@@ -0,0 +1,58 @@
+import { describe, it, expect } from "vitest";
+import { compileScryfallQuery, segmentQuery } from "../scryfallQuery";
+
+const kinds = (q: string) =>
+  segmentQuery(q).map((s) => [q.slice(s.start, s.end), s.kind]);
+
+describe("segmentQuery", () => {
+  it("splits fields, operators and values", () => {
+    expect(kinds("t:elf -cmc>=3")).toEqual([
+      ["t", "field"],
+      [":", "operator"],
+      ["elf", "value"],
+      ["-", "operator"],
+      ["cmc", "field"],
+      [">=", "operator"],
+      ["3", "value"],
+    ]);
+  });
+
+  it("marks regexes, free text, OR and parentheses", () => {
+    expect(kinds('(o:/draw (a|two)/ or !"Opt") bolt')).toEqual([
+      ["(", "paren"],
+      ["o", "field"],
+      [":", "operator"],
+      ["/draw (a|two)/", "regex"],
+      ["or", "keyword"],
+      ["!", "operator"],
+      ['"Opt"', "text"],
+      [")", "paren"],
+      ["bolt", "text"],
+    ]);
+  });
+
+  it("leaves unknown fields as text", () => {
+    expect(kinds("foo:bar")).toEqual([["foo:bar", "text"]]);
+  });
+});
+
+describe("query error positions", () => {
+  const errorAt = (q: string) => {
+    const { error, errorAt } = compileScryfallQuery(q);
+    return errorAt && [q.slice(errorAt.start, errorAt.end), error];
+  };
+
+  it("points at the token that failed", () => {
+    expect(errorAt("t:elf foo:bar")).toEqual([
+      "foo:bar",
+      "Unknown field alias: foo",
+    ]);
+    expect(errorAt("  -o:/(x/ t:elf")?.[0]).toBe("-o:/(x/");
+  });
+
+  it("points at unbalanced parentheses", () => {
+    expect(errorAt("(t:elf or t:goblin")).toEqual(["(", "Missing )"]);
+    expect(errorAt("t:elf) c:g")).toEqual([")", "Unmatched )"]);
+    expect(errorAt("(t:elf) c:g")).toBeNull();
+  });
+});
@@ -0,0 +1,74 @@
+import { describe, it, expect } from "vitest";
+import { compileScryfallQuery, type CardLike } from "../scryfallQuery";
+
+const elves = {
+  name: "Llanowar Elves",
+  type_line: "Creature — Elf Druid",
+  oracle_text: "{T}: Add {G}.",
+  artist: "Anson Maddocks",
+};
+const bolt = {
+  name: "Lightning Bolt",
+  type_line: "Instant",
+  oracle_text: "Lightning Bolt deals 3 damage to any target.",
+  flavor_text: "The sparkmage shrieked, calling on the rage of the storms.",
+};
+const matches = (q: string, card: CardLike) => {
+  const { predicate, error } = compileScryfallQuery(q);
+  expect(error).toBeNull();
+  return predicate!(card);
+};
+
+describe("regex operands", () => {
+  it("matches oracle, name, type, flavor and artist patterns", () => {
+    expect(matches("o:/^{T}: add/", elves)).toBe(true);
+    expect(matches("o:/^{T}: add/", bolt)).toBe(false);
+    expect(matches("name:/^l.*s$/", elves)).toBe(true);
+    expect(matches("t:/elf (druid|warrior)/", elves)).toBe(true);
+    expect(matches("ft:/\\bstorms?\\./", bolt)).toBe(true);
+    expect(matches("a:/^anson/", elves)).toBe(true);
+    expect(matches("-o:/\\d+ damage/", bolt)).toBe(false);
+  });
+
+  it("substitutes the card's name for ~", () => {
+    expect(matches("o:/^~ deals/", bolt)).toBe(true);
+    expect(matches("o:/^~ deals/", elves)).toBe(false);
+  });
+
+  it("keeps literal matching for other fields and plain operands", () => {
+    expect(matches("o:{T}:", elves)).toBe(true);
+    expect(matches("o:a.d", elves)).toBe(false);
+  });
+
+  it("reports bad patterns instead of matching nothing", () => {
+    const bad = (q: string) => compileScryfallQuery(q).error;
+    expect(bad("o:/(unclosed/")).toMatch(/not a valid regular expression/);
+    expect(bad("o:/abc")).toMatch(/missing its closing/);
+    expect(bad("name:/(a+)+$/")).toMatch(/repeats a repetition/);
+    expect(bad(`o:/${"a".repeat(201)}/`)).toMatch(/too long/);
+    expect(compileScryfallQuery("o:/(unclosed/").predicate).toBeNull();
+  });
+
+  it("rejects catastrophic patterns before running them", () => {
+    const bad = (q: string) => compileScryfallQuery(q).error;
+    for (const q of [
+      "o:/(\\w+\\s?)+$/",
+      "o:/(\\w*)*x/",
+      "o:/(x{2,})+/",
+      "o:/((ab)?c)*$/",
+      "o:/(?:a|\\w+\\s)+$/",
+    ])
+      expect(bad(q)).toMatch(/repeats a repetition/);
+    // Never run: a near miss on this one backtracks for minutes
+    const { predicate } = compileScryfallQuery("o:/(\\w+\\s?)+$/");
+    expect(predicate).toBeNull();
+  });
+
+  it("keeps repetition outside repeated groups", () => {
+    expect(matches("o:/(add|deals)+ /", bolt)).toBe(true);
+    expect(matches("o:/\\d+ damage( to)?/", bolt)).toBe(true);
+    expect(matches("o:/(any target)?\\.$/", bolt)).toBe(true);
+    expect(matches("o:/[(+)]+/", elves)).toBe(false);
+    expect(matches("o:/(?<w>\\w+) deals/", bolt)).toBe(true);
+  });
+});
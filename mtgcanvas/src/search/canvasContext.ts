@@ -0,0 +1,69 @@
+// Builds the CanvasContext the canvas query fields (group:, tag:, owned:, count, dup: …)
+// test against. Copy counts are taken once over the whole canvas; the rest of a card's
+// context is worked out the first time it is asked for.
+import type { CanvasContext } from "./scryfallQuery";
+
+// What the caller knows about one card instance
+export interface CanvasCardFacts {
+  name: string; // copies by name count towards owned
+  printing: string | null; // copies by printing + finish count towards count (null = unknown)
+  groups: string[]; // its group's name, then enclosing groups'
+  tags: string | null; // CardInstance.tags
+  foil: boolean;
+  selected: boolean;
+  face: number;
+}
+
+// CardInstance.tags: a JSON array or a comma separated list
+export function parseInstanceTags(raw: string | null | undefined): string[] {
+  const src = (raw || "").trim();
+  if (!src) return [];
+  if (src.startsWith("[")) {
+    try {
+      const arr = JSON.parse(src);
+      if (Array.isArray(arr))
+        return arr.map((t) => String(t).trim().toLowerCase()).filter(Boolean);
+    } catch {
+      /* fall through to the list form */
+    }
+  }
+  return src
+    .split(",")
+    .map((t) => t.trim().toLowerCase())
+    .filter(Boolean);
+}
+
+export function createCanvasContextLookup<T>(
+  items: T[],
+  facts: (item: T) => CanvasCardFacts,
+): (item: T) => CanvasContext {
+  const known = new Map<T, CanvasCardFacts>();
+  const byName = new Map<string, number>();
+  const byPrinting = new Map<string, number>();
+  for (const it of items) {
+    const f = facts(it);
+    known.set(it, f);
+    const name = f.name.toLowerCase();
+    byName.set(name, (byName.get(name) || 0) + 1);
+    if (f.printing)
+      byPrinting.set(f.printing, (byPrinting.get(f.printing) || 0) + 1);
+  }
+  const cache = new Map<T, CanvasContext>();
+  return (item) => {
+    let ctx = cache.get(item);
+    if (ctx) return ctx;
+    // Items created after the lookup was built count as one copy
+    const f = known.get(item) ?? facts(item);
+    ctx = {
+      groups: f.groups.map((g) => g.toLowerCase()),
+      tags: parseInstanceTags(f.tags),
+      owned: Math.max(1, byName.get(f.name.toLowerCase()) || 0),
+      count: f.printing ? Math.max(1, byPrinting.get(f.printing) || 0) : 1,
+      foil: f.foil,
+      selected: f.selected,
+      face: f.face,
+    };
+    cache.set(item, ctx);
+    return ctx;
+  };
+}
@@ -0,0 +1,75 @@
+// "Did you mean" matching for card names: normalization plus bounded edit distance.
+// Handles missing accents ("Lim-Dul"), punctuation/spacing variants and partial DFC names.
+
+// Lower-case, strip diacritics and punctuation, collapse spaces. "Lim-Dûl's Vault" -> "lim dul s vault"
+export function normalizeCardName(raw: string): string {
+  return (raw || "")
+    .normalize("NFD")
+    .replace(/[\u0300-\u036f]/g, "")
+    .toLowerCase()
+    .replace(/[^a-z0-9/]+/g, " ")
+    .replace(/\s+/g, " ")
+    .trim();
+}
+
+// Front face of "A // B" (normalized); names without faces are returned unchanged
+function frontFace(norm: string): string {
+  const i = norm.indexOf("//");
+  return i >= 0 ? norm.slice(0, i).trim() : norm;
+}
+
+// Levenshtein distance; gives up (returns max + 1) once every path exceeds max
+export function editDistance(a: string, b: string, max = Infinity): number {
+  if (a === b) return 0;
+  if (Math.abs(a.length - b.length) > max) return max + 1;
+  let prev = new Array(b.length + 1);
+  let cur = new Array(b.length + 1);
+  for (let j = 0; j <= b.length; j++) prev[j] = j;
+  for (let i = 1; i <= a.length; i++) {
+    cur[0] = i;
+    let rowMin = cur[0];
+    for (let j = 1; j <= b.length; j++) {
+      const cost = a.charCodeAt(i - 1) === b.charCodeAt(j - 1) ? 0 : 1;
+      cur[j] = Math.min(prev[j] + 1, cur[j - 1] + 1, prev[j - 1] + cost);
+      if (cur[j] < rowMin) rowMin = cur[j];
+    }
+    if (rowMin > max) return max + 1;
+    [prev, cur] = [cur, prev];
+  }
+  return prev[b.length];
+}
+
+// Rank candidate names for an unresolved one. Lower score is better:
+// 0 exact after normalization, 1 front-face match, 2 prefix, then 3 + edit distance.
+export function suggestNames(
+  query: string,
+  candidates: Iterable<string>,
+  opts?: { limit?: number },
+): string[] {
+  const limit = opts?.limit ?? 3;
+  const q = normalizeCardName(query);
+  const qFront = frontFace(q);
+  if (!qFront) return [];
+  // Allow roughly one typo per four characters (at least two)
+  const maxDist = Math.max(2, Math.floor(qFront.length / 4));
+  const scored: { name: string; score: number }[] = [];
+  const seen = new Set<string>();
+  for (const name of candidates) {
+    if (!name || seen.has(name)) continue;
+    seen.add(name);
+    const n = normalizeCardName(name);
+    const nFront = frontFace(n);
+    let score: number;
+    if (n === q) score = 0;
+    else if (nFront === qFront) score = 1;
+    else if (qFront.length >= 4 && nFront.startsWith(qFront)) score = 2;
+    else {
+      const d = editDistance(qFront, nFront, maxDist);
+      if (d > maxDist) continue;
+      score = 3 + d;
+    }
+    scored.push({ name, score });
+  }
+  scored.sort((a, b) => a.score - b.score || a.name.localeCompare(b.name));
+  return scored.slice(0, limit).map((s) => s.name);
+}
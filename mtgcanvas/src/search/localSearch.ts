@@ -0,0 +1,145 @@
+// Scryfall-style search over an in-memory list of cards: query filtering plus the
+// display options (unique, order, dir) the API applies server-side.
+import type { Card } from "../types/card";
+import type { SearchOptions } from "../services/scryfall";
+import { compileScryfallQuery, RARITY_ORDER } from "./scryfallQuery";
+
+// Directions Scryfall picks for dir:auto; everything else sorts ascending
+const AUTO_DESC = new Set(["released", "usd", "eur", "tix", "rarity"]);
+const EXTRA_LAYOUTS = new Set([
+  "token",
+  "double_faced_token",
+  "emblem",
+  "art_series",
+]);
+const COLOR_ORDER = "WUBRG";
+
+function num(v: unknown): number {
+  const n = typeof v === "number" ? v : parseFloat(String(v ?? ""));
+  return Number.isFinite(n) ? n : NaN;
+}
+
+// Comparator for Scryfall's order: values (ascending). Missing values sort last.
+function compareBy(order: string): (a: Card, b: Card) => number {
+  const byNum = (get: (c: Card) => unknown) => (a: Card, b: Card) => {
+    const x = num(get(a));
+    const y = num(get(b));
+    if (isNaN(x)) return isNaN(y) ? 0 : 1;
+    if (isNaN(y)) return -1;
+    return x - y;
+  };
+  const byStr = (get: (c: Card) => unknown) => (a: Card, b: Card) =>
+    String(get(a) ?? "").localeCompare(String(get(b) ?? ""));
+  switch (order) {
+    case "name":
+      return byStr((c) => c.name);
+    case "set":
+      return (a, b) =>
+        byStr((c) => c.set)(a, b) ||
+        byNum((c) => c.collector_number)(a, b);
+    case "rarity":
+      return byNum((c) => RARITY_ORDER[String(c.rarity || "")]);
+    case "color":
+      return byStr((c) => {
+        const cols: string[] = Array.isArray(c.colors) ? c.colors : [];
+        // Mono colors in WUBRG order, then multicolor, then colorless
+        if (!cols.length) return "9";
+        if (cols.length > 1) return "8" + cols.length;
+        return String(COLOR_ORDER.indexOf(cols[0]));
+      });
+    case "usd":
+    case "eur":
+    case "tix":
+      return byNum((c) => c.prices?.[order]);
+    case "cmc":
+    case "power":
+    case "toughness":
+      return byNum((c) => c[order]);
+    case "edhrec":
+      return byNum((c) => c.edhrec_rank);
+    case "artist":
+      return byStr((c) => c.artist);
+    case "released":
+    default:
+      return byStr((c) => c.released_at);
+  }
+}
+
+// Pull display keywords (unique:, order:, dir:) out of the query text; they override opts
+function inlineOptions(query: string): {
+  unique?: SearchOptions["unique"];
+  order?: string;
+  dir?: "asc" | "desc";
+} {
+  const out: ReturnType<typeof inlineOptions> = {};
+  const re = /(?:^|\s)(unique|order|dir|direction):(\w+)/gi;
+  let m: RegExpExecArray | null;
+  while ((m = re.exec(query))) {
+    const key = m[1].toLowerCase();
+    const val = m[2].toLowerCase();
+    if (key === "unique") {
+      if (val === "cards" || val === "art" || val === "prints")
+        out.unique = val;
+    } else if (key === "order") out.order = val;
+    else if (val === "asc" || val === "desc") out.dir = val;
+  }
+  return out;
+}
+
+// Build the per-card filter for a query: the predicate plus the include_extras /
+// include_multilingual defaults the API applies. Throws when the query can't be parsed.
+export function createLocalSearchFilter(
+  query: string,
+  opts: SearchOptions = {},
+): (card: Card) => boolean {
+  const { predicate: pred, error } = compileScryfallQuery(query);
+  if (!pred) throw new Error(error ?? "Could not parse query");
+  const { includeExtras = false, includeMultilingual = false } = opts;
+  return (c) => {
+    if (!includeExtras) {
+      if (EXTRA_LAYOUTS.has(String(c.layout || ""))) return false;
+      if (c.set_type === "memorabilia" || c.set_type === "token") return false;
+    }
+    if (!includeMultilingual && c.lang && c.lang !== "en") return false;
+    return pred(c);
+  };
+}
+
+// Order, de-duplicate (unique) and cap matching cards like the API would
+export function finishLocalSearch(
+  matches: Card[],
+  query: string,
+  opts: SearchOptions = {},
+): Card[] {
+  const inline = inlineOptions(query);
+  const unique = inline.unique ?? opts.unique ?? "cards";
+  const order = inline.order ?? opts.order ?? "released";
+  const dir =
+    inline.dir ??
+    (inline.order
+      ? AUTO_DESC.has(order)
+        ? "desc"
+        : "asc"
+      : (opts.dir ?? "desc"));
+  const cmp = compareBy(order);
+  const sign = dir === "desc" ? -1 : 1;
+  // Stable tie-break by name keeps results deterministic across orders
+  const byName = compareBy("name");
+  let out = matches
+    .slice()
+    .sort((a, b) => sign * cmp(a, b) || byName(a, b));
+  if (unique !== "prints") {
+    const seen = new Set<string>();
+    out = out.filter((c) => {
+      const key =
+        unique === "art"
+          ? c.illustration_id || c.id
+          : c.oracle_id || String(c.name || "").toLowerCase();
+      if (seen.has(key)) return false;
+      seen.add(key);
+      return true;
+    });
+  }
+  const max = opts.maxCards ?? Infinity;
+  return out.length > max ? out.slice(0, max) : out;
+}
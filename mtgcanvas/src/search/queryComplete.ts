@@ -0,0 +1,118 @@
+// Autocomplete for the search palette. The word under the caret completes to a field name
+// ("kw" -> "kw:", "keyword:") or, after a colon, to that field's values: is: / not: / has:
+// flags, rarities, canvas values, and the set codes and formats of the loaded cards.
+import {
+  HAS_VALUES,
+  IS_VALUES,
+  QUERY_FIELDS,
+  RARITY_ORDER,
+  queryFieldTarget,
+} from "./scryfallQuery";
+
+export interface CompletionSources {
+  sets: string[]; // set codes of the cards on the canvas
+  formats: string[]; // format names from their legalities
+}
+
+export interface QueryCompletion {
+  start: number; // span of the input an item replaces
+  end: number;
+  items: { text: string; hint: string }[];
+}
+
+const MAX_ITEMS = 8;
+const GAMES = ["paper", "mtgo", "arena"];
+
+// Hints for fields that are not plain aliases of a card property
+const FIELD_HINTS: Record<string, string> = {
+  is: "card flags",
+  not: "negated is:",
+  has: "card has…",
+  f: "legal in format",
+  format: "legal in format",
+  banned: "banned in format",
+  restricted: "restricted in format",
+  in: "game, or ungrouped / grouped",
+  devotion: "mana symbols",
+  produces: "mana produced",
+  group: "group on the canvas",
+  tag: "instance tag",
+  owned: "copies by name, or foil",
+  count: "copies of the printing",
+  dup: "print / name",
+  face: "front / back",
+};
+
+function valuesFor(field: string, sources: CompletionSources): string[] | null {
+  switch (field) {
+    case "is":
+    case "not":
+      return IS_VALUES;
+    case "has":
+      return HAS_VALUES;
+    case "e":
+    case "s":
+    case "set":
+    case "edition":
+      return sources.sets;
+    case "f":
+    case "format":
+    case "banned":
+    case "restricted":
+      return sources.formats;
+    case "r":
+    case "rarity":
+      return Object.keys(RARITY_ORDER);
+    case "in":
+      return ["ungrouped", "grouped", ...GAMES];
+    case "game":
+      return GAMES;
+    case "face":
+      return ["front", "back"];
+    case "dup":
+      return ["print", "name"];
+    case "owned":
+      return ["foil", "nonfoil"];
+  }
+  return null;
+}
+
+export function completeQuery(
+  input: string,
+  caret: number,
+  sources: CompletionSources,
+): QueryCompletion | null {
+  // The word around the caret, up to whitespace or a parenthesis
+  const sep = /[\s()]/;
+  let start = caret;
+  while (start > 0 && !sep.test(input[start - 1])) start--;
+  let end = caret;
+  while (end < input.length && !sep.test(input[end])) end++;
+  const word = input.slice(start, caret);
+  // Nothing to offer inside phrases and regular expressions
+  if (/["/]/.test(word)) return null;
+  const body = word.replace(/^[-!]+/, "");
+  start += word.length - body.length;
+  const colon = body.indexOf(":");
+  if (colon < 0) {
+    const lower = body.toLowerCase();
+    if (!/^[a-z_]+$/.test(lower)) return null;
+    const items = QUERY_FIELDS.filter((f) => f.startsWith(lower))
+      .sort((a, b) => a.length - b.length || a.localeCompare(b))
+      .slice(0, MAX_ITEMS)
+      .map((f) => ({
+        text: `${f}:`,
+        hint: queryFieldTarget(f) ?? FIELD_HINTS[f] ?? "",
+      }));
+    return items.length ? { start, end, items } : null;
+  }
+  const values = valuesFor(body.slice(0, colon).toLowerCase(), sources);
+  if (!values) return null;
+  const typed = body.slice(colon + 1).toLowerCase();
+  const items = [...new Set(values)]
+    .filter((v) => v.startsWith(typed) && v !== typed)
+    .sort()
+    .slice(0, MAX_ITEMS)
+    .map((v) => ({ text: v, hint: "" }));
+  return items.length ? { start: start + colon + 1, end, items } : null;
+}
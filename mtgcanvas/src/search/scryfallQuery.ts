@@ -48,7 +48,17 @@
 // Wildcards:
 
 // expands to .* (greedy) in a case-insensitive regex; no ? or other regex metacharacters are honored.
-// All other regex special characters are escaped literally (no native regex mode).
+// All other regex special characters are escaped literally, except in /.../ operands below.
+// Regular expressions:
+
+// name, o/oracle, t/type, ft/flavor and a/artist take /.../ operands: o:/^{T}: add/ name:/^a.*z$/
+// Case-insensitive; ^ and $ match at line breaks too. ~ stands for the card's own name.
+// Spaces and parentheses inside the slashes belong to the pattern. Patterns are capped at
+// REGEX_MAX_LENGTH characters, a repeated group holding any repetition like (a+)+ or
+// (\w+\s?)+ is rejected up front (one such test() can hang the tab, which no time budget
+// can interrupt), and a query stops matching once its patterns used up REGEX_BUDGET_MS.
+// Bad patterns are reported as errors by compileScryfallQuery instead of quietly
+// matching nothing.
 // Case handling:
 
 // All text comparisons are case-insensitive.
@@ -87,6 +97,12 @@
 // Mana cost pattern searching.
 // Regex mode for bare tokens, fuzzy (~) searching, distance/word boundary logic.
 // Implicit phrase semantics (currently all substring).
+// Canvas fields (need the sprite's CanvasContext; card-only searches never match them)
+
+// group:"Removal" card's group or an enclosing one; in:ungrouped / in:grouped
+// tag:combo instance tags; is:selected; face:back (a flipped DFC)
+// owned>=4 copies with this name on the canvas, owned:foil this copy's finish
+// count>1 copies of this exact printing; dup:print (same as count>1) / dup:name (owned>1)
 // Edge/caveats
 
 // Token OR inside quotes is treated as literal text, not a logical OR separator.
@@ -156,6 +172,7 @@ export interface CardLike {
     oracle_text?: string;
     type_line?: string;
     mana_cost?: string | null;
+    colors?: string[] | null;
     watermark?: string | null;
     color_indicator?: string[] | null;
     artist?: string | null;
@@ -171,7 +188,20 @@ export interface CardLike {
   > | null;
 }
 
-type Predicate = (card: CardLike) => boolean;
+// Canvas state of the card instance being tested, for the fields that are about the canvas
+// rather than the card: group:, in:ungrouped, tag:, owned:, count, dup:, is:selected, face:.
+// Card-only callers (catalog search) pass none and those fields never match.
+export interface CanvasContext {
+  groups: string[]; // its group's name, then enclosing groups' (empty = ungrouped)
+  tags: string[];
+  owned: number; // copies on the canvas with the same name, any printing
+  count: number; // copies of this exact printing and finish
+  foil: boolean; // this copy is foil
+  selected: boolean;
+  face: number; // face shown (0 = front)
+}
+
+type Predicate = (card: CardLike, ctx?: CanvasContext) => boolean;
 
 interface TokenNode {
   type: "pred";
@@ -277,7 +307,57 @@ const REGEX_TEXT_FIELDS = new Set([
   "oracle_text",
   "flavor_text",
   "type_line",
+  "artist",
 ]);
+const REGEX_MAX_LENGTH = 200; // characters between the slashes
+const REGEX_MAX_INPUT = 4000; // characters of card text a pattern is run against
+const REGEX_BUDGET_MS = 250; // total time one query may spend in its patterns
+
+// A group repeated with * + or {…} that holds a repetition anywhere inside: (a+)+, (\w*)*,
+// (x{2,})+, (\w+\s?)+, ((ab)?c)*. These backtrack exponentially on a near miss, and a
+// single runaway test() can't be interrupted. An optional group, (a+)?, is fine.
+function repeatsRepetition(body: string): boolean {
+  const quantifierAt = (i: number) =>
+    body[i] === "*" ||
+    body[i] === "+" ||
+    body[i] === "?" ||
+    (body[i] === "{" && /^\{\d+(,\d*)?\}/.test(body.slice(i)));
+  // One entry per open group (plus the pattern itself): holds a repetition
+  const stack = [false];
+  for (let i = 0; i < body.length; i++) {
+    const c = body[i];
+    if (c === "\\") {
+      i++;
+    } else if (c === "[") {
+      // A class is one atom; skip to its closing ]
+      for (i++; i < body.length && body[i] !== "]"; i++)
+        if (body[i] === "\\") i++;
+    } else if (c === "(") {
+      stack.push(false);
+      // (?: (?= (?! (?<= (?<! (?<name> are group syntax, not quantifiers
+      if (body[i + 1] === "?") {
+        i += 2;
+        if (body[i] === "<" && body[i + 1] !== "=" && body[i + 1] !== "!")
+          while (i < body.length && body[i] !== ">") i++;
+        else if (body[i] === "<") i++;
+      }
+    } else if (c === ")") {
+      const inner = stack.length > 1 ? stack.pop()! : false;
+      const quantified = quantifierAt(i + 1);
+      if (inner && quantified && body[i + 1] !== "?") return true;
+      if (inner || quantified) stack[stack.length - 1] = true;
+    } else if (quantifierAt(i)) {
+      stack[stack.length - 1] = true;
+    }
+  }
+  return false;
+}
+
+// Time spent in /.../ operands by one compiled query
+interface RegexBudget {
+  spentMs: number;
+  exceeded: boolean;
+}
 
 // Color nicknames and full names -> letters
 const COLOR_WORDS: Record<string, string> = {
@@ -354,7 +434,7 @@ function parseColorLetters(spec: string): {
 }
 
 // Rarity order for comparisons
-const RARITY_ORDER: Record<string, number> = {
+export const RARITY_ORDER: Record<string, number> = {
   common: 1,
   uncommon: 2,
   rare: 3,
@@ -375,32 +455,148 @@ const NOOP_FIELDS = new Set([
   "new",
 ]);
 
-export function parseScryfallQuery(input: string): Predicate | null {
-  const src = (input || "").trim();
-  if (!src) return null;
+// Fields handled outside FIELD_ALIASES (see buildTokenPredicate / canvasFieldPredicate)
+const SPECIAL_FIELDS = [
+  "is",
+  "not",
+  "has",
+  "f",
+  "format",
+  "banned",
+  "restricted",
+  "in",
+  "devotion",
+  "produces",
+];
+const CANVAS_FIELDS = ["group", "tag", "owned", "count", "dup", "face"];
+
+// Every field name the parser accepts, for autocomplete and highlighting
+export const QUERY_FIELDS: string[] = [
+  ...Object.keys(FIELD_ALIASES),
+  ...SPECIAL_FIELDS,
+  ...CANVAS_FIELDS,
+  ...NOOP_FIELDS,
+];
+// Canonical field behind an alias (o -> oracle_text), for completion hints
+export function queryFieldTarget(alias: string): string | undefined {
+  return FIELD_ALIASES[alias.toLowerCase()];
+}
+
+export interface CompiledQuery {
+  predicate: Predicate | null; // null for an empty query or when `error` is set
+  error: string | null; // why the query could not be parsed, for showing inline
+  errorAt: { start: number; end: number } | null; // offending span in the input
+  timedOut(): boolean; // a /.../ operand used up the time budget; later cards never match
+}
+
+// A parse error tied to the token that caused it (offsets into the query)
+class QuerySyntaxError extends Error {
+  constructor(
+    message: string,
+    readonly start: number,
+    readonly end: number,
+  ) {
+    super(message);
+  }
+}
+
+export function compileScryfallQuery(input: string): CompiledQuery {
+  const src = input || "";
+  const budget: RegexBudget = { spentMs: 0, exceeded: false };
+  const timedOut = () => budget.exceeded;
+  if (!src.trim())
+    return { predicate: null, error: null, errorAt: null, timedOut };
   try {
     const tokens = lexical(src);
     // No special-casing of plain queries: default free text will search name + type + oracle (Scryfall-like)
-    const node = parse(tokens);
-    return (card) => evalNode(node, card);
+    const node = parse(tokens, budget);
+    return {
+      predicate: (card, ctx) => evalNode(node, card, ctx),
+      error: null,
+      errorAt: null,
+      timedOut,
+    };
   } catch (e) {
-    console.warn("[scryfallQuery] parse failed – simple contains", e);
-    return null;
+    return {
+      predicate: null,
+      error: e instanceof Error ? e.message : String(e),
+      errorAt:
+        e instanceof QuerySyntaxError ? { start: e.start, end: e.end } : null,
+      timedOut,
+    };
   }
 }
 
+export function parseScryfallQuery(input: string): Predicate | null {
+  const { predicate, error } = compileScryfallQuery(input);
+  if (error) console.warn("[scryfallQuery] parse failed:", error);
+  return predicate;
+}
+
+// Highlighting: the query split into colored spans (offsets into the input). Gaps between
+// spans are whitespace.
+export type QuerySegmentKind =
+  | "field" // o, t, cmc …
+  | "operator" // : = >= -negation !exact
+  | "value"
+  | "regex" // a /.../ operand
+  | "text" // free text
+  | "keyword" // OR
+  | "paren";
+export interface QuerySegment {
+  start: number;
+  end: number;
+  kind: QuerySegmentKind;
+}
+
+const KNOWN_FIELDS = new Set(QUERY_FIELDS);
+const FIELD_TOKEN = /^([a-zA-Z_][a-zA-Z0-9_]*)(:?(?:<=|>=|!=|=|<|>)?)(.*)$/;
+
+export function segmentQuery(input: string): QuerySegment[] {
+  const out: QuerySegment[] = [];
+  const push = (start: number, end: number, kind: QuerySegmentKind) => {
+    if (end > start) out.push({ start, end, kind });
+  };
+  for (const tok of lexical(input || "")) {
+    if (tok.v === "(" || tok.v === ")") {
+      push(tok.start, tok.end, "paren");
+      continue;
+    }
+    let p = tok.start;
+    if (tok.neg) push(p, ++p, "operator");
+    if (tok.exact) push(p, ++p, "operator");
+    if (!tok.neg && !tok.exact && tok.v.toLowerCase() === "or") {
+      push(p, tok.end, "keyword");
+      continue;
+    }
+    // field, then ":" and/or a comparison, then the value
+    const m = tok.v.match(FIELD_TOKEN);
+    if (m && m[2] && KNOWN_FIELDS.has(m[1].toLowerCase())) {
+      push(p, (p += m[1].length), "field");
+      push(p, (p += m[2].length), "operator");
+      push(p, tok.end, m[3].startsWith("/") ? "regex" : "value");
+      continue;
+    }
+    push(p, tok.end, "text");
+  }
+  return out;
+}
+
 interface LexToken {
   v: string;
   neg?: boolean;
   exact?: boolean;
+  start: number; // offsets into the source, prefixes included
+  end: number;
 }
 
 function lexical(src: string): LexToken[] {
   const out: LexToken[] = [];
   let buf = "";
+  let bufStart = 0;
   let inQ = false;
   let escaped = false;
-  function pushBuf() {
+  function pushBuf(end: number) {
     if (!buf) return;
     let raw = buf;
     let neg = false;
@@ -414,11 +610,22 @@ function lexical(src: string): LexToken[] {
       raw = raw.slice(1);
     }
     // Note: do not strip inner quotes here; buildTokenPredicate handles field-value quotes
-    out.push({ v: raw, neg, exact });
+    out.push({ v: raw, neg, exact, start: bufStart, end });
     buf = "";
   }
   for (let i = 0; i < src.length; i++) {
     const ch = src[i];
+    if (!buf && !escaped) bufStart = i;
+    // Regex operand right after a field (o:/.../): take it whole, backslashes,
+    // spaces and parentheses included
+    if (!inQ && ch === "/" && /^-?!?[a-zA-Z_][a-zA-Z0-9_]*:$/.test(buf)) {
+      let j = i + 1;
+      while (j < src.length && src[j] !== "/") j += src[j] === "\\" ? 2 : 1;
+      const end = Math.min(j + 1, src.length);
+      buf += src.slice(i, end);
+      i = end - 1;
+      continue;
+    }
     if (escaped) {
       buf += ch;
       escaped = false;
@@ -434,21 +641,21 @@ function lexical(src: string): LexToken[] {
       continue;
     }
     if (!inQ && (ch === "(" || ch === ")")) {
-      pushBuf();
-      out.push({ v: ch });
+      pushBuf(i);
+      out.push({ v: ch, start: i, end: i + 1 });
       continue;
     }
     if (!inQ && /\s/.test(ch)) {
-      pushBuf();
+      pushBuf(i);
       continue;
     }
     buf += ch;
   }
-  pushBuf();
+  pushBuf(src.length);
   return out;
 }
 
-function parse(tokens: LexToken[]): Node {
+function parse(tokens: LexToken[], budget: RegexBudget): Node {
   // Recursive descent with precedence: OR between terms, AND within terms, parentheses supported
   let i = 0;
   function parseExpression(): Node {
@@ -468,22 +675,31 @@ function parse(tokens: LexToken[]): Node {
       if (tok.v === "(") {
         i++;
         const inner = parseExpression();
-        if (tokens[i]?.v === ")") i++;
-        const fn: Predicate = (card) => evalNode(inner, card);
+        if (tokens[i]?.v !== ")")
+          throw new QuerySyntaxError("Missing )", tok.start, tok.end);
+        i++;
+        const fn: Predicate = (card, ctx) => evalNode(inner, card, ctx);
         preds.push(fn);
         continue;
       }
       i++;
-      const p = buildTokenPredicate(tok.v, !!tok.neg, !!tok.exact);
-      if (p) preds.push(p);
+      try {
+        const p = buildTokenPredicate(tok.v, !!tok.neg, !!tok.exact, budget);
+        if (p) preds.push(p);
+      } catch (e) {
+        const msg = e instanceof Error ? e.message : String(e);
+        throw new QuerySyntaxError(msg, tok.start, tok.end);
+      }
     }
     if (!preds.length) return { type: "pred", fn: () => true } as TokenNode;
     return {
       type: "pred",
-      fn: (card) => preds.every((fn) => fn(card)),
+      fn: (card, ctx) => preds.every((fn) => fn(card, ctx)),
     } as TokenNode;
   }
   const ast = parseExpression();
+  if (i < tokens.length)
+    throw new QuerySyntaxError("Unmatched )", tokens[i].start, tokens[i].end);
   return ast;
 }
 
@@ -491,6 +707,7 @@ function buildTokenPredicate(
   raw: string,
   neg: boolean,
   exact: boolean,
+  budget: RegexBudget,
 ): Predicate | null {
   // field? token
   const m = raw.match(/^([a-zA-Z_][a-zA-Z0-9_]*):(.+)$/);
@@ -510,8 +727,10 @@ function buildTokenPredicate(
     if (NOOP_FIELDS.has(fieldAlias)) return () => true;
     // not:foo -> is:foo with negation
     if (fieldAlias === "not") {
-      return buildTokenPredicate("is:" + value, !neg, exact);
+      return buildTokenPredicate("is:" + value, !neg, exact, budget);
     }
+    const canvas = canvasFieldPredicate(fieldAlias, value, neg);
+    if (canvas) return canvas;
     const field = FIELD_ALIASES[fieldAlias];
     if (!field) {
       if (fieldAlias === "is") return isPredicate(value, neg);
@@ -558,6 +777,8 @@ function buildTokenPredicate(
         return mvParityPredicate(v === "even", neg);
       return numericFieldPredicate(field, value, neg);
     }
+    if (REGEX_TEXT_FIELDS.has(field) && value.startsWith("/"))
+      return regexFieldPredicate(fieldAlias, field, value, neg, budget);
     if (TEXT_FIELDS.has(field)) return textFieldPredicate(field, value, neg);
     return null;
   }
@@ -584,6 +805,12 @@ function buildTokenPredicate(
     const spec = (idBare[2] || "") + idBare[3];
     return colorIdentityPredicate(spec, neg);
   }
+  // Bare copy counts: count>1, owned>=4
+  const countBare = raw.match(/^(count|owned)(<=|>=|!=|=|<|>)(\d+)$/i);
+  if (countBare) {
+    const key = countBare[1].toLowerCase() as "count" | "owned";
+    return canvasCountPredicate(key, countBare[2] + countBare[3], neg);
+  }
   // Cross-field numeric comparisons: pow>tou, power<=toughness, etc.
   const cross = raw.match(
     /^(pow|power|tou|toughness|loy|loyalty)\s*(<=|>=|!=|=|<|>)\s*(pow|power|tou|toughness|loy|loyalty)$/i,
@@ -634,29 +861,74 @@ function textFieldPredicate(
     const needle = tmpl.includes("~")
       ? tmpl.replace(/~/g, String(card.name || "").toLowerCase())
       : tmpl;
-    // Only allow /regex/ on approved fields; otherwise treat as literal
-    const isRegex =
-      needle.length >= 2 && needle.startsWith("/") && needle.endsWith("/");
-    const rx =
-      isRegex && REGEX_TEXT_FIELDS.has(field)
-        ? textToRegex(needle)
-        : new RegExp(needle.replace(/[.*+?^${}()|[\]\\]/g, "\\$&"), "i");
-    const val = (card as any)[field];
-    let got: string | null = null;
-    if (typeof val === "string") got = val;
-    else if (
-      val == null &&
-      (field === "oracle_text" || field === "watermark")
-    ) {
-      // search faces if card-level missing
-      const txt = facesConcat(card, field);
-      got = txt || null;
-    }
+    // Literal match (/regex/ operands go through regexFieldPredicate)
+    const rx = new RegExp(escapeRegex(needle), "i");
+    const got = fieldText(card, field);
     const hit = !!(got && rx.test(got.toLowerCase()));
     return neg ? !hit : hit;
   };
 }
 
+function escapeRegex(s: string): string {
+  return s.replace(/[.*+?^${}()|[\]\\]/g, "\\$&");
+}
+
+function fieldText(card: CardLike, field: string): string | null {
+  const val = (card as any)[field];
+  if (typeof val === "string") return val;
+  if (val == null && (field === "oracle_text" || field === "watermark")) {
+    // search faces if card-level missing
+    return facesConcat(card, field) || null;
+  }
+  return null;
+}
+
+// o:/^{T}: add/ and friends. The pattern is checked once up front so a typo is reported
+// as an error; `~` is swapped for each card's (escaped) name before matching.
+function regexFieldPredicate(
+  alias: string,
+  field: string,
+  raw: string,
+  neg: boolean,
+  budget: RegexBudget,
+): Predicate {
+  if (raw.length < 2 || !raw.endsWith("/"))
+    throw new Error(`${alias}:${raw} is missing its closing /`);
+  const body = raw.slice(1, -1);
+  if (!body) throw new Error(`${alias}:// has an empty pattern`);
+  if (body.length > REGEX_MAX_LENGTH)
+    throw new Error(
+      `${alias}:/…/ is too long (${body.length} characters, max ${REGEX_MAX_LENGTH})`,
+    );
+  if (repeatsRepetition(body))
+    throw new Error(
+      `${alias}:${raw} repeats a repetition, which can hang the search; simplify it`,
+    );
+  const compile = (src: string) => new RegExp(src, "im");
+  try {
+    compile(body.replace(/~/g, "x"));
+  } catch (e) {
+    const why = e instanceof Error ? e.message.split(": ").pop() : "";
+    throw new Error(
+      `${alias}:${raw} is not a valid regular expression (${why})`,
+    );
+  }
+  const fixed = body.includes("~") ? null : compile(body);
+  return (card) => {
+    if (budget.exceeded) return false;
+    const got = fieldText(card, field);
+    if (!got) return neg;
+    const rx =
+      fixed ??
+      compile(body.replace(/~/g, escapeRegex(String(card.name || ""))));
+    const t0 = performance.now();
+    const hit = rx.test(got.slice(0, REGEX_MAX_INPUT));
+    budget.spentMs += performance.now() - t0;
+    if (budget.spentMs > REGEX_BUDGET_MS) budget.exceeded = true;
+    return neg ? !hit : hit;
+  };
+}
+
 function numericFieldPredicate(
   field: string,
   raw: string,
@@ -798,12 +1070,89 @@ function eqSet(a: Set<string>, b: Set<string>): boolean {
   return a.size === b.size && subset(a, b);
 }
 
-function evalNode(node: Node, card: CardLike): boolean {
-  if (node.type === "pred") return node.fn(card);
-  if (node.type === "or") return node.parts.some((p) => evalNode(p, card));
+function evalNode(node: Node, card: CardLike, ctx?: CanvasContext): boolean {
+  if (node.type === "pred") return node.fn(card, ctx);
+  if (node.type === "or")
+    return node.parts.some((p) => evalNode(p, card, ctx));
   return true;
 }
 
+// -------------------- Canvas fields --------------------
+
+// group:"Removal" (the card's group or an enclosing one), in:ungrouped / in:grouped,
+// tag:x, owned:foil / owned>=4, count>1, dup:print / dup:name, is:selected, face:back.
+// Returns null for anything else so in:arena, is:foil etc. reach the card predicates.
+function canvasFieldPredicate(
+  field: string,
+  raw: string,
+  neg: boolean,
+): Predicate | null {
+  const v = raw.trim().toLowerCase();
+  let test: ((ctx: CanvasContext) => boolean) | null = null;
+  if (field === "group") {
+    const rx = textToRegex(v);
+    test = (ctx) => ctx.groups.some((g) => rx.test(g));
+  } else if (field === "in" && (v === "ungrouped" || v === "grouped")) {
+    test = (ctx) => (ctx.groups.length > 0) === (v === "grouped");
+  } else if (field === "tag") {
+    const rx = textToRegex(v);
+    test = (ctx) => ctx.tags.some((t) => rx.test(t));
+  } else if (field === "owned" && (v === "foil" || v === "nonfoil")) {
+    test = (ctx) => ctx.foil === (v === "foil");
+  } else if (field === "owned" || field === "count") {
+    return canvasCountPredicate(field === "owned" ? "owned" : "count", v, neg);
+  } else if (field === "dup") {
+    test = (ctx) => (v === "name" ? ctx.owned : ctx.count) > 1;
+  } else if (field === "is" && v === "selected") {
+    test = (ctx) => ctx.selected;
+  } else if (field === "face" && (v === "back" || v === "front")) {
+    test = (ctx) => (ctx.face > 0) === (v === "back");
+  }
+  if (!test) return null;
+  const t = test;
+  return (_card, ctx) => {
+    const hit = !!ctx && t(ctx);
+    return neg ? !hit : hit;
+  };
+}
+
+function canvasCountPredicate(
+  field: "owned" | "count",
+  raw: string,
+  neg: boolean,
+): Predicate {
+  const m = raw.match(/^(<=|>=|!=|=|<|>)?\s*(\d+)$/);
+  if (!m) return () => true; // malformed -> no-op
+  const op = m[1] || "=";
+  const rhs = parseInt(m[2], 10);
+  return (_card, ctx) => {
+    if (!ctx) return neg;
+    const v = ctx[field];
+    let pass = false;
+    switch (op) {
+      case "=":
+        pass = v === rhs;
+        break;
+      case "!=":
+        pass = v !== rhs;
+        break;
+      case ">":
+        pass = v > rhs;
+        break;
+      case ">=":
+        pass = v >= rhs;
+        break;
+      case "<":
+        pass = v < rhs;
+        break;
+      case "<=":
+        pass = v <= rhs;
+        break;
+    }
+    return neg ? !pass : pass;
+  };
+}
+
 // -------------------- Helpers & extended predicates --------------------
 
 function facesConcat(
@@ -839,159 +1188,144 @@ function exactNamePredicate(value: string, neg: boolean): Predicate {
   };
 }
 
+// What the is: tests look at besides the card, worked out once per card
+interface IsFacts {
+  type: string;
+  layout: string;
+  oracle: string;
+  keywords: string[];
+}
+type IsTest = (card: CardLike, f: IsFacts) => boolean;
+
+const FRENCH_VANILLA_KEYWORDS = [
+  "flying",
+  "vigilance",
+  "lifelink",
+  "trample",
+  "haste",
+  "first strike",
+  "double strike",
+  "menace",
+  "deathtouch",
+  "reach",
+  "indestructible",
+  "hexproof",
+  "prowess",
+  "ward",
+  "flash",
+];
+
+const canBeCommander: IsTest = (_card, { type, oracle }) =>
+  /legendary\s+creature\b/.test(type) || /can be your commander/.test(oracle);
+
+// is:x -> test; the keys are also the is: values offered by autocomplete
+const IS_TESTS: Record<string, IsTest> = {
+  split: (_c, { layout }) => layout.includes("split"),
+  flip: (_c, { layout }) => layout.includes("flip"),
+  transform: (_c, { layout }) => layout.includes("transform"),
+  meld: (_c, { layout }) => layout.includes("meld"),
+  leveler: (_c, { layout }) => layout.includes("leveler"),
+  dfc: (card) => !!(card.card_faces && card.card_faces.length >= 2),
+  mdfc: (_c, { layout }) => layout.includes("modal"),
+  spell: (_c, { type }) => !/\bland\b/.test(type),
+  permanent: (_c, { type }) =>
+    /(artifact|creature|enchantment|land|planeswalker|battle)\b/.test(type),
+  historic: (_c, { type }) =>
+    /legendary\b/.test(type) || /artifact\b/.test(type) || /saga\b/.test(type),
+  party: (_c, { type }) =>
+    /creature\b/.test(type) && /(cleric|rogue|warrior|wizard)\b/.test(type),
+  modal: (_c, { oracle }) => /choose (one|two)/.test(oracle),
+  vanilla: (_c, { type, oracle }) =>
+    /creature\b/.test(type) && oracle.length === 0,
+  frenchvanilla: (_c, { type, oracle }) => {
+    if (!/creature\b/.test(type)) return false;
+    // Heuristic: only contains allowed keywords and punctuation
+    const stripped = oracle
+      .replace(/[,:.;()\-—\u2014\u2212\s]+/g, " ")
+      .trim();
+    return (
+      stripped.length > 0 &&
+      stripped
+        .split(" ")
+        .every(
+          (tok) =>
+            FRENCH_VANILLA_KEYWORDS.includes(tok) ||
+            tok === "+1/+1" ||
+            tok === "flying,",
+        )
+    );
+  },
+  bear: (card, { type }) =>
+    /creature\b/.test(type) &&
+    num(card.power) === 2 &&
+    num(card.toughness) === 2 &&
+    (card.cmc || 0) === 2,
+  hybrid: (card) =>
+    hasManaSymbol(card.mana_cost, /\{[^}]*\/[WUBRGCX][^}]*\}/i),
+  phyrexian: (card) => hasManaSymbol(card.mana_cost, /\{[WUBRGC]\/[pP]\}/),
+  funny: (card) =>
+    (card.set_type || "").toLowerCase() === "funny" ||
+    (card.security_stamp || "") === "acorn",
+  foil: (card) => hasFinish(card, "foil"),
+  nonfoil: (card) => hasFinish(card, "nonfoil"),
+  etched: (card) => hasFinish(card, "etched"),
+  glossy: (card) => hasFinish(card, "glossy"),
+  hires: (card) => !!card.highres_image,
+  promo: (card) => !!card.promo,
+  spotlight: (card) => !!card.story_spotlight,
+  reprint: (card) => (card as any).reprint === true,
+  unique: (card) =>
+    ((card as any).prints_count || (card as any).set_count || 0) <= 1,
+  full: (card) => (card as any).full_art === true,
+  old: (card) => (card.frame || "") === "1993" || (card.frame || "") === "1997",
+  new: (card) => (card.frame || "") === "2015",
+  scryfallpreview: (card) =>
+    Array.isArray((card as any).promo_types) &&
+    ((card as any).promo_types as string[]).some((x) =>
+      String(x).toLowerCase().includes("scryfall"),
+    ),
+  digital: (card) => !!card.digital,
+  reserved: (card) => !!card.reserved,
+  commander: canBeCommander,
+  brawler: canBeCommander,
+  companion: (_c, { keywords }) => keywords.includes("companion"),
+  duelcommander: canBeCommander,
+  universesbeyond: (card) =>
+    (card.set_type || "").toLowerCase().includes("universes"),
+};
+
+// has:x -> test
+const HAS_TESTS: Record<string, (card: CardLike) => boolean> = {
+  indicator: (card) =>
+    !!(
+      card.color_indicator ||
+      (card.card_faces || []).some(
+        (f) =>
+          f &&
+          (f as any).color_indicator &&
+          (f as any).color_indicator!.length,
+      )
+    ),
+  watermark: (card) => !!(card.watermark || facesConcat(card, "watermark")),
+  flavor: (card) => !!card.flavor_text,
+  security_stamp: (card) => !!card.security_stamp,
+};
+
+// What is: / has: understand (plus the canvas is:selected), for autocomplete
+export const IS_VALUES = [...Object.keys(IS_TESTS), "selected"];
+export const HAS_VALUES = Object.keys(HAS_TESTS);
+
 function isPredicate(valueRaw: string, neg: boolean): Predicate {
   const v = valueRaw.toLowerCase();
-  const fn: Predicate = (card: CardLike) => {
-    const type = (card.type_line || "").toLowerCase();
-    const layout = (card.layout || "").toLowerCase();
-    const oracle = oracleAggregate(card).toLowerCase();
-    const keywords = (card.keywords || []).map((k) => k.toLowerCase());
-    switch (v) {
-      case "split":
-        return layout.includes("split");
-      case "flip":
-        return layout.includes("flip");
-      case "transform":
-        return layout.includes("transform");
-      case "meld":
-        return layout.includes("meld");
-      case "leveler":
-        return layout.includes("leveler");
-      case "dfc":
-        return !!(card.card_faces && card.card_faces.length >= 2);
-      case "mdfc":
-        return layout.includes("modal");
-      case "spell":
-        return !/\bland\b/.test(type);
-      case "permanent":
-        return /(artifact|creature|enchantment|land|planeswalker|battle)\b/.test(
-          type,
-        );
-      case "historic":
-        return (
-          /legendary\b/.test(type) ||
-          /artifact\b/.test(type) ||
-          /saga\b/.test(type)
-        );
-      case "party":
-        return (
-          /creature\b/.test(type) &&
-          /(cleric|rogue|warrior|wizard)\b/.test(type)
-        );
-      case "modal":
-        return /choose (one|two)/.test(oracle);
-      case "vanilla":
-        return /creature\b/.test(type) && oracle.length === 0;
-      case "frenchvanilla": {
-        if (!/creature\b/.test(type)) return false;
-        const allowed = [
-          "flying",
-          "vigilance",
-          "lifelink",
-          "trample",
-          "haste",
-          "first strike",
-          "double strike",
-          "menace",
-          "deathtouch",
-          "reach",
-          "indestructible",
-          "hexproof",
-          "prowess",
-          "ward",
-          "flash",
-        ];
-        const text = oracle; // already aggregated and lowercased
-        // Heuristic: only contains allowed keywords and punctuation
-        const stripped = text
-          .replace(/[,:.;()\-—\u2014\u2212\s]+/g, " ")
-          .trim();
-        return (
-          stripped.length > 0 &&
-          stripped
-            .split(" ")
-            .every(
-              (tok) =>
-                allowed.includes(tok) || tok === "+1/+1" || tok === "flying,",
-            )
-        );
-      }
-      case "bear":
-        return (
-          /creature\b/.test(type) &&
-          num(card.power) === 2 &&
-          num(card.toughness) === 2 &&
-          (card.cmc || 0) === 2
-        );
-      case "hybrid":
-        return hasManaSymbol(card.mana_cost, /\{[^}]*\/[WUBRGCX][^}]*\}/i);
-      case "phyrexian":
-        return hasManaSymbol(card.mana_cost, /\{[WUBRGC]\/[pP]\}/);
-      case "funny":
-        return (
-          (card.set_type || "").toLowerCase() === "funny" ||
-          (card.security_stamp || "") === "acorn"
-        );
-      case "foil":
-        return hasFinish(card, "foil");
-      case "nonfoil":
-        return hasFinish(card, "nonfoil");
-      case "etched":
-        return hasFinish(card, "etched");
-      case "glossy":
-        return hasFinish(card, "glossy");
-      case "hires":
-        return !!card.highres_image;
-      case "promo":
-        return !!card.promo;
-      case "spotlight":
-        return !!card.story_spotlight;
-      case "reprint":
-        return (card as any).reprint === true;
-      case "unique":
-        return (
-          ((card as any).prints_count || (card as any).set_count || 0) <= 1
-        );
-      case "full":
-        return (card as any).full_art === true;
-      case "old":
-        return (card.frame || "") === "1993" || (card.frame || "") === "1997";
-      case "new":
-        return (card.frame || "") === "2015";
-      case "scryfallpreview":
-        return (
-          Array.isArray((card as any).promo_types) &&
-          ((card as any).promo_types as string[]).some((x) =>
-            String(x).toLowerCase().includes("scryfall"),
-          )
-        );
-      case "digital":
-        return !!card.digital;
-      case "reserved":
-        return !!card.reserved;
-      case "commander":
-        return (
-          /legendary\s+creature\b/.test(type) ||
-          /can be your commander/.test(oracle)
-        );
-      case "brawler":
-        return (
-          /legendary\s+creature\b/.test(type) ||
-          /can be your commander/.test(oracle)
-        );
-      case "companion":
-        return keywords.includes("companion");
-      case "duelcommander":
-        return (
-          /legendary\s+creature\b/.test(type) ||
-          /can be your commander/.test(oracle)
-        );
-      case "universesbeyond":
-        return (card.set_type || "").toLowerCase().includes("universes");
-      default:
-        return false;
-    }
-  };
+  const test = Object.hasOwn(IS_TESTS, v) ? IS_TESTS[v] : null;
+  const fn: Predicate = (card: CardLike) =>
+    !!test &&
+    test(card, {
+      type: (card.type_line || "").toLowerCase(),
+      layout: (card.layout || "").toLowerCase(),
+      oracle: oracleAggregate(card).toLowerCase(),
+      keywords: (card.keywords || []).map((k) => k.toLowerCase()),
+    });
   return neg ? (c) => !fn(c) : fn;
 }
 
@@ -1008,28 +1342,8 @@ function hasFinish(
 
 function hasPredicate(valueRaw: string, neg: boolean): Predicate {
   const v = valueRaw.toLowerCase();
-  const fn: Predicate = (card: CardLike) => {
-    switch (v) {
-      case "indicator":
-        return !!(
-          card.color_indicator ||
-          (card.card_faces || []).some(
-            (f) =>
-              f &&
-              (f as any).color_indicator &&
-              (f as any).color_indicator!.length,
-          )
-        );
-      case "watermark":
-        return !!(card.watermark || facesConcat(card, "watermark"));
-      case "flavor":
-        return !!card.flavor_text;
-      case "security_stamp":
-        return !!card.security_stamp;
-      default:
-        return false;
-    }
-  };
+  const test = Object.hasOwn(HAS_TESTS, v) ? HAS_TESTS[v] : null;
+  const fn: Predicate = (card: CardLike) => !!test && test(card);
   return neg ? (c) => !fn(c) : fn;
 }
 
@@ -0,0 +1,152 @@
+import { describe, it, expect } from "vitest";
+import {
+  createJsonArrayScanner,
+  importBulkFile,
+  type CatalogBackend,
+  type CatalogInfo,
+  type CatalogRow,
+  type CatalogStoreName,
+} from "../cardCatalog";
+
+describe("bulk JSON scanner", () => {
+  const text = JSON.stringify([
+    { id: "a", name: "Fire // Ice", oracle_text: 'Deals 2 {R} "damage"' },
+    { id: "b", name: "Forest", card_faces: [{ name: "x}]\\" }] },
+    { id: "c", name: "Brace { yourself" },
+  ]);
+
+  it("parses every element in one chunk", () => {
+    const out: any[] = [];
+    const sc = createJsonArrayScanner((v) => out.push(v));
+    sc.push(text);
+    sc.end();
+    expect(out.map((c) => c.id)).toEqual(["a", "b", "c"]);
+  });
+  it("handles elements split at every possible position", () => {
+    for (let size = 1; size <= 7; size++) {
+      const out: any[] = [];
+      const sc = createJsonArrayScanner((v) => out.push(v));
+      for (let i = 0; i < text.length; i += size) {
+        sc.push(text.slice(i, i + size));
+      }
+      sc.end();
+      expect(out).toEqual(JSON.parse(text));
+    }
+  });
+  it("reports truncated input", () => {
+    const sc = createJsonArrayScanner(() => {});
+    sc.push(text.slice(0, 30));
+    expect(() => sc.end()).toThrow();
+  });
+});
+
+describe("bulk JSON scanner edge cases", () => {
+  const cards = [
+    { id: "e1", name: 'Quote \\" and backslash \\\\', flavor_text: "a\\\\" },
+    { id: "e2", name: "Escaped { brace ]", oracle_text: "\\u007b" },
+    { id: "e3", name: "Lim-Dûl the Necromancer", artist: "🔥 ✨ 🐉" },
+    { id: "e4", name: "Ætherize", printed_name: "😀" },
+  ];
+  // Written by hand so the input holds \uXXXX escapes, not only raw characters
+  const text =
+    "[\n" +
+    cards.map((c) => JSON.stringify(c)).join(",\n") +
+    ',\n{"id":"e5","name":"\\u00c6ther \\ud83d\\udd25 \\"}\\" ]"}\n]';
+
+  it("keeps escapes and surrogate pairs split across string chunks", () => {
+    for (let size = 1; size <= 5; size++) {
+      const out: any[] = [];
+      const sc = createJsonArrayScanner((v) => out.push(v));
+      for (let i = 0; i < text.length; i += size) {
+        sc.push(text.slice(i, i + size));
+      }
+      sc.end();
+      expect(out).toEqual(JSON.parse(text));
+    }
+    expect(JSON.parse(text)[4].name).toBe('Æther 🔥 "}" ]');
+  });
+
+  it("keeps multi-byte characters split across byte chunks", () => {
+    // Same path as importBulkFile: bytes -> streaming TextDecoder -> scanner
+    const bytes = new TextEncoder().encode(text);
+    for (let size = 1; size <= 4; size++) {
+      const out: any[] = [];
+      const sc = createJsonArrayScanner((v) => out.push(v));
+      const decoder = new TextDecoder();
+      for (let i = 0; i < bytes.length; i += size) {
+        sc.push(decoder.decode(bytes.subarray(i, i + size), { stream: true }));
+      }
+      sc.push(decoder.decode());
+      sc.end();
+      expect(out).toEqual(JSON.parse(text));
+    }
+  });
+});
+
+describe("importBulkFile", () => {
+  // In-memory stand-in for the IndexedDB stores
+  function memoryBackend() {
+    const stores = new Map<CatalogStoreName, CatalogRow[]>([
+      ["cards", []],
+      ["cards_b", []],
+    ]);
+    let published: CatalogInfo | null = null;
+    const backend: CatalogBackend = {
+      info: async () => published,
+      clear: async (store) => void stores.set(store, []),
+      put: async (store, rows) => void stores.get(store)!.push(...rows),
+      publish: async (info) => void (published = info),
+    };
+    // What a reader sees: the rows of the published store
+    const readable = () =>
+      published ? stores.get(published.store ?? "cards")!.map((r) => r.id) : [];
+    return { backend, stores, readable };
+  }
+  const bulk = (name: string, ids: string[]) =>
+    Object.assign(
+      new Blob([JSON.stringify(ids.map((id) => ({ id, name: `Card ${id}` })))]),
+      { name },
+    );
+
+  it("swaps in the new catalog only after the whole file parsed", async () => {
+    const mem = memoryBackend();
+    await importBulkFile(bulk("a.json", ["a1", "a2"]), {}, mem.backend);
+    expect(mem.readable()).toEqual(["a1", "a2"]);
+    const info = await importBulkFile(bulk("b.json", ["b1"]), {}, mem.backend);
+    expect(info).toMatchObject({ count: 1, source: "b.json" });
+    expect(mem.readable()).toEqual(["b1"]);
+    // The old rows are dropped once the new ones are live
+    expect([...mem.stores.values()].flat().map((r) => r.id)).toEqual(["b1"]);
+  });
+
+  it("keeps the previous catalog when an import is aborted", async () => {
+    const mem = memoryBackend();
+    await importBulkFile(bulk("a.json", ["a1", "a2"]), {}, mem.backend);
+    const ctrl = new AbortController();
+    await expect(
+      importBulkFile(
+        bulk("b.json", ["b1", "b2"]),
+        { signal: ctrl.signal, onProgress: () => ctrl.abort() },
+        mem.backend,
+      ),
+    ).rejects.toMatchObject({ name: "AbortError" });
+    expect(mem.readable()).toEqual(["a1", "a2"]);
+    expect((await mem.backend.info())?.source).toBe("a.json");
+    // Staged rows are gone
+    expect([...mem.stores.values()].flat().map((r) => r.id)).toEqual([
+      "a1",
+      "a2",
+    ]);
+  });
+
+  it("keeps the previous catalog when the file is truncated", async () => {
+    const mem = memoryBackend();
+    await importBulkFile(bulk("a.json", ["a1"]), {}, mem.backend);
+    const text = JSON.stringify([{ id: "b1", name: "B" }, { id: "b2" }]);
+    const broken = Object.assign(new Blob([text.slice(0, -8)]), {
+      name: "b.json",
+    });
+    await expect(importBulkFile(broken, {}, mem.backend)).rejects.toThrow();
+    expect(mem.readable()).toEqual(["a1"]);
+  });
+});
@@ -0,0 +1,84 @@
+import { describe, it, expect } from "vitest";
+import {
+  computeDeckStats,
+  countPips,
+  describeIdentity,
+} from "../deckStats";
+
+const CARDS = [
+  {
+    name: "Forest",
+    type_line: "Basic Land — Forest",
+    cmc: 0,
+    color_identity: ["G"],
+  },
+  {
+    name: "Bolt",
+    type_line: "Instant",
+    cmc: 1,
+    mana_cost: "{R}",
+    color_identity: ["R"],
+  },
+  {
+    name: "Bear",
+    type_line: "Creature — Bear",
+    cmc: 2,
+    mana_cost: "{1}{G}",
+    color_identity: ["G"],
+  },
+  {
+    name: "Kitchen Finks",
+    type_line: "Creature — Ouphe",
+    cmc: 3,
+    mana_cost: "{1}{G/W}{G/W}",
+    color_identity: ["G", "W"],
+  },
+  {
+    name: "Emrakul",
+    type_line: "Legendary Creature — Eldrazi",
+    cmc: 15,
+    mana_cost: "{15}",
+    color_identity: [],
+  },
+];
+
+describe("deck stats", () => {
+  it("counts colored symbols including hybrid and phyrexian", () => {
+    expect(countPips("{2}{W}{W}{U/B}{R/P}{C}{X}")).toEqual({
+      W: 2,
+      U: 1,
+      B: 1,
+      R: 1,
+      C: 1,
+    });
+    expect(countPips(null)).toEqual({});
+  });
+  it("builds curve, pips, types and identity", () => {
+    const stats = computeDeckStats(CARDS, (c) => c);
+    expect(stats.total).toBe(5);
+    expect(stats.lands.map((c) => c.name)).toEqual(["Forest"]);
+    expect(stats.avgManaValue).toBe((1 + 2 + 3 + 15) / 4);
+    expect(stats.curve.map((b) => [b.label, b.count])).toEqual([
+      ["0", 0],
+      ["1", 1],
+      ["2", 1],
+      ["3", 1],
+      ["4", 0],
+      ["5", 0],
+      ["6", 0],
+      ["7+", 1],
+    ]);
+    expect(stats.pips.map((b) => [b.key, b.count, b.items.length])).toEqual([
+      ["W", 2, 1],
+      ["R", 1, 1],
+      ["G", 3, 2],
+    ]);
+    expect(stats.types.map((b) => [b.label, b.count])).toEqual([
+      ["Creature", 3],
+      ["Instant", 1],
+      ["Land", 1],
+    ]);
+    expect(describeIdentity(stats.identity)).toBe("White, Red, Green");
+    expect(describeIdentity([])).toBe("Colorless");
+  });
+});
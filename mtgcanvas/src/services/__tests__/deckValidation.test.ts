@@ -0,0 +1,122 @@
+import { describe, it, expect } from "vitest";
+import {
+  isSideboardName,
+  parseGroupDeck,
+  validateDeck,
+} from "../deckValidation";
+
+const legal = (formats: string[]) =>
+  Object.fromEntries(formats.map((f) => [f, "legal"]));
+
+const FOREST = {
+  name: "Forest",
+  type_line: "Basic Land — Forest",
+  color_identity: ["G"],
+  legalities: legal(["modern", "commander"]),
+};
+const BOLT = {
+  name: "Lightning Bolt",
+  type_line: "Instant",
+  color_identity: ["R"],
+  legalities: legal(["modern", "commander"]),
+};
+const OKO = {
+  name: "Oko, Thief of Crowns",
+  type_line: "Legendary Planeswalker — Oko",
+  color_identity: ["G", "U"],
+  legalities: { modern: "banned", commander: "legal" },
+};
+const TITANIA = {
+  name: "Titania, Protector of Argoth",
+  type_line: "Legendary Creature — Elemental",
+  color_identity: ["G"],
+  legalities: legal(["modern", "commander"]),
+};
+
+const copies = <T>(card: T, n: number) => Array.from({ length: n }, () => card);
+const messages = (
+  format: string,
+  parts: { main: any[]; sideboard?: any[]; commanders?: any[] },
+) =>
+  validateDeck(
+    format,
+    { sideboard: [], commanders: [], ...parts },
+    (c) => c,
+  ).map((v) => v.message);
+
+describe("deck validation", () => {
+  it("accepts a legal constructed deck", () => {
+    const main = [...copies(FOREST, 56), ...copies(BOLT, 4)];
+    expect(messages("modern", { main })).toEqual([]);
+  });
+  it("reports size, copies, bans and sideboard", () => {
+    const main = [...copies(FOREST, 50), ...copies(BOLT, 5), OKO];
+    expect(
+      messages("modern", { main, sideboard: copies(FOREST, 16) }),
+    ).toEqual([
+      "Deck has 56 cards (needs at least 60)",
+      "Sideboard has 16 cards (max 15)",
+      "5 copies of Lightning Bolt (max 4)",
+      "Oko, Thief of Crowns is banned",
+    ]);
+  });
+  it("checks commander choice, singleton and color identity", () => {
+    const main = [TITANIA, ...copies(FOREST, 96), BOLT, BOLT, OKO];
+    expect(messages("commander", { main, commanders: [TITANIA] })).toEqual([
+      "2 copies of Lightning Bolt (max 1)",
+      "3 cards outside the commander's color identity (G)",
+    ]);
+    expect(messages("commander", { main })).toContain("No commander chosen");
+    expect(
+      messages("commander", { main: [OKO], commanders: [OKO] }),
+    ).toContain("Oko, Thief of Crowns can't be your commander");
+  });
+  it("accepts partners and a commander with its Background", () => {
+    const commander = (name: string, over: object) => ({
+      name,
+      type_line: "Legendary Creature — Human",
+      color_identity: ["W"],
+      legalities: legal(["commander"]),
+      ...over,
+    });
+    const WILSON = commander("Wilson, Refined Grizzly", {
+      oracle_text: "Choose a Background",
+    });
+    const NOBLE = commander("Noble Heritage", {
+      type_line: "Legendary Enchantment — Background",
+      oracle_text: "Commander creatures you own have ward {3}.",
+    });
+    const THRASIOS = commander("Thrasios, Triton Hero", {
+      keywords: ["Partner"],
+    });
+    const TYMNA = commander("Tymna the Weaver", { keywords: ["Partner"] });
+    const main = copies(FOREST, 98);
+    const commanderIssues = (cmds: object[]) =>
+      validateDeck(
+        "commander",
+        { main, sideboard: [], commanders: cmds },
+        (c: any) => c,
+      )
+        .filter((v) => v.kind === "commander")
+        .map((v) => v.message);
+    expect(commanderIssues([WILSON, NOBLE])).toEqual([]);
+    expect(commanderIssues([THRASIOS, TYMNA])).toEqual([]);
+    // A Background needs a partner that chooses one
+    expect(commanderIssues([NOBLE])).toEqual([
+      "Noble Heritage can't be your commander",
+    ]);
+    expect(commanderIssues([THRASIOS, NOBLE])).toEqual([
+      "Noble Heritage can't be your commander",
+      "Thrasios, Triton Hero, Noble Heritage can't share command",
+    ]);
+  });
+  it("parses stored settings and finds the sideboard group", () => {
+    expect(parseGroupDeck({ format: "pauper", commanders: [3, "x"] })).toEqual(
+      { format: "pauper", commanders: [3] },
+    );
+    expect(parseGroupDeck({ format: "nope" })).toBeNull();
+    expect(isSideboardName(" Sideboard ")).toBe(true);
+    expect(isSideboardName("Side board")).toBe(true);
+    expect(isSideboardName("Maybeboard")).toBe(false);
+  });
+});
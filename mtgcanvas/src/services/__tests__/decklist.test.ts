@@ -1,8 +1,13 @@
 import { describe, it, expect } from "vitest";
 import {
   extractBaseCardName,
+  parseCardEntry,
   parseDecklist,
   parseGroupsText,
+  parseArenaDeck,
+  formatArenaDeck,
+  formatComboLine,
+  parseComboLine,
 } from "../decklist";
 
 describe("decklist parsing utils", () => {
@@ -43,4 +48,148 @@ describe("decklist parsing utils", () => {
       "Lightning Bolt",
     ]);
   });
+  it("parseCardEntry keeps set, collector number and foil", () => {
+    expect(parseCardEntry("Omo, Queen of Vesuva (M3C) 2 *F*")).toEqual({
+      name: "Omo, Queen of Vesuva",
+      set: "M3C",
+      collector_number: "2",
+      foil: true,
+    });
+    expect(parseCardEntry("Arcane Signet [m3c] 283")).toEqual({
+      name: "Arcane Signet",
+      set: "M3C",
+      collector_number: "283",
+    });
+    expect(parseCardEntry("Sol Ring *Foil*")).toEqual({
+      name: "Sol Ring",
+      foil: true,
+    });
+  });
+  it("parseDecklist combines duplicates per printing", () => {
+    const text = [
+      "2 Forest (MH3) 318",
+      "1 Forest (MH3) 318",
+      "1 Forest (MH3) 318 *F*",
+      "1 Forest",
+    ].join("\n");
+    expect(parseDecklist(text)).toEqual([
+      { name: "Forest", set: "MH3", collector_number: "318", count: 3 },
+      {
+        name: "Forest",
+        set: "MH3",
+        collector_number: "318",
+        foil: true,
+        count: 1,
+      },
+      { name: "Forest", count: 1 },
+    ]);
+  });
+  it("parseGroupsText returns printing entries parallel to names", () => {
+    const res = parseGroupsText("# Lands\n2 Forest (MH3) 318 *F*\nIsland");
+    expect(res!.groups[0].cards).toEqual(["Forest", "Forest", "Island"]);
+    expect(res!.groups[0].entries[1]).toEqual({
+      name: "Forest",
+      set: "MH3",
+      collector_number: "318",
+      foil: true,
+    });
+    expect(res!.groups[0].entries[2]).toEqual({ name: "Island" });
+  });
+  it("parseGroupsText records heading depth for nested groups", () => {
+    const txt = ["# Deck", "## Ramp", "Sol Ring", "### Rocks", "Arcane Signet"];
+    const res = parseGroupsText(txt.join("\n"));
+    expect(res!.groups.map((g) => [g.name, g.depth])).toEqual([
+      ["Deck", 1],
+      ["Ramp", 2],
+      ["Rocks", 3],
+    ]);
+    expect(res!.groups[0].cards).toEqual([]);
+  });
+  it("parseGroupsText reads the combos section without importing cards", () => {
+    const txt = [
+      "# Combo",
+      "1 Kiki-Jiki, Mirror Breaker",
+      "1 Zealous Conscripts",
+      "#combos",
+      "Kiki-Jiki, Mirror Breaker -> Zealous Conscripts | infinite tokens",
+      "- Sol Ring -> Combo",
+      "not a combo",
+      "# Lands",
+      "Forest",
+    ];
+    const res = parseGroupsText(txt.join("\n"));
+    expect(res!.groups.map((g) => g.name)).toEqual(["Combo", "Lands"]);
+    expect(res!.groups[1].cards).toEqual(["Forest"]);
+    expect(res!.ungrouped).toEqual([]);
+    expect(res!.combos).toEqual([
+      {
+        from: "Kiki-Jiki, Mirror Breaker",
+        to: "Zealous Conscripts",
+        label: "infinite tokens",
+      },
+      { from: "Sol Ring", to: "Combo", label: "" },
+    ]);
+  });
+  it("combo lines round-trip", () => {
+    const c = { from: "Circle of Protection: Red", to: "Ramp", label: "a | b" };
+    expect(parseComboLine(formatComboLine(c))).toEqual(c);
+    expect(formatComboLine({ ...c, label: "" })).toBe(
+      "Circle of Protection: Red -> Ramp",
+    );
+    expect(parseComboLine("Sol Ring")).toBeNull();
+  });
+  it("parseArenaDeck maps sections and keeps printings", () => {
+    const txt = [
+      "About",
+      "Name Omo Lands",
+      "",
+      "Commander",
+      "1 Omo, Queen of Vesuva (M3C) 2",
+      "",
+      "Deck",
+      "1 Arcane Signet (M3C) 283",
+      "30 Forest (MH3) 318",
+      "",
+      "Sideboard",
+      "2 Vault 112: Sadistic Simulation",
+    ].join("\n");
+    const res = parseArenaDeck(txt);
+    expect(res).not.toBeNull();
+    expect(res!.map((s) => s.name)).toEqual(["Commander", "Deck", "Sideboard"]);
+    expect(res![1].cards[1]).toEqual({
+      count: 30,
+      name: "Forest",
+      set: "MH3",
+      collector_number: "318",
+    });
+    expect(res![2].cards[0]).toEqual({
+      count: 2,
+      name: "Vault 112: Sadistic Simulation",
+    });
+  });
+  it("parseArenaDeck ignores plain lists and grouped text", () => {
+    expect(parseArenaDeck("4 Lightning Bolt\n2 Counterspell")).toBeNull();
+    expect(parseArenaDeck("# Deck\n4 Lightning Bolt")).toBeNull();
+    expect(parseArenaDeck("1 Forest (MH3) 318")).toBeNull();
+  });
+  it("formatArenaDeck round-trips through parseArenaDeck", () => {
+    const sections = [
+      {
+        name: "Sideboard",
+        cards: [
+          { count: 1, name: "Negate", set: "M20", collector_number: "69" },
+        ],
+      },
+      {
+        name: "Deck",
+        cards: [
+          { count: 4, name: "Opt", set: "XLN", collector_number: "65" },
+          { count: 2, name: "Island" },
+        ],
+      },
+    ];
+    const txt = formatArenaDeck(sections);
+    expect(txt.split("\n")[0]).toBe("Deck");
+    expect(parseArenaDeck(txt)).toEqual([sections[1], sections[0]]);
+  });
 });
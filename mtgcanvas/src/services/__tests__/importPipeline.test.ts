@@ -0,0 +1,294 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from "vitest";
+import {
+  createFixtureSource,
+  resolveDeckEntries,
+  scryfallSource,
+  SourceUnavailableError,
+  withFallback,
+} from "../cardSource";
+import { parseDecklist } from "../decklist";
+import { importDeckEntries, type PlannedCard } from "../deckImport";
+import { chooseTextImport } from "../../ui/importExport";
+import { createLocalPersistence, LS_POSITIONS_KEY } from "../persistence";
+import type { PlacementContext } from "../../placement";
+import { SpatialIndex } from "../../scene/SpatialIndex";
+import {
+  GRID_SIZE,
+  CARD_W,
+  CARD_H,
+  GAP_X,
+  GAP_Y,
+  SPACING_X,
+  SPACING_Y,
+} from "../../config/dimensions";
+
+const CARDS = [
+  {
+    id: "forest-mh3",
+    oracle_id: "o-forest",
+    name: "Forest",
+    set: "mh3",
+    collector_number: "318",
+    released_at: "2024-06-14",
+  },
+  {
+    id: "forest-lea",
+    oracle_id: "o-forest",
+    name: "Forest",
+    set: "lea",
+    collector_number: "294",
+    released_at: "1993-08-05",
+  },
+  {
+    id: "bolt-m10",
+    oracle_id: "o-bolt",
+    name: "Lightning Bolt",
+    set: "m10",
+    collector_number: "146",
+    type_line: "Instant",
+    released_at: "2009-07-17",
+  },
+  {
+    id: "fire-ice",
+    oracle_id: "o-fire",
+    name: "Fire // Ice",
+    set: "mh2",
+    collector_number: "290",
+    type_line: "Instant // Instant",
+    released_at: "2021-06-18",
+  },
+];
+
+function emptyContext(): PlacementContext {
+  return {
+    sprites: [],
+    groups: new Map(),
+    world: {} as any,
+    getCanvasBounds: () => ({ x: 0, y: 0, w: 20000, h: 20000 }),
+    gridSize: GRID_SIZE,
+    cardW: CARD_W,
+    cardH: CARD_H,
+    gapX: GAP_X,
+    gapY: GAP_Y,
+    spacingX: SPACING_X,
+    spacingY: SPACING_Y,
+  };
+}
+
+describe("fixture card source", () => {
+  const source = createFixtureSource(CARDS, {
+    sets: { mh3: "https://example.test/mh3.svg" },
+  });
+  it("answers names, front faces and printings", async () => {
+    const { byName, unknown } = await source.byNames([
+      "forest",
+      "Fire",
+      "Nope",
+    ]);
+    expect(byName.get("forest")?.name).toBe("Forest");
+    expect(byName.get("fire")?.id).toBe("fire-ice");
+    expect(unknown).toEqual(["Nope"]);
+    const { byPrinting } = await source.byPrintings([
+      { set: "LEA", collector_number: "294" },
+    ]);
+    expect(byPrinting.get("lea/294")?.id).toBe("forest-lea");
+  });
+  it("searches with Scryfall syntax and display options", async () => {
+    const prints = await source.search("t:instant", {
+      order: "name",
+      dir: "asc",
+    });
+    expect(prints.map((c) => c.id)).toEqual(["fire-ice", "bolt-m10"]);
+    const unique = await source.search("forest", { unique: "cards" });
+    expect(unique).toHaveLength(1);
+    expect(await source.search("forest unique:prints")).toHaveLength(2);
+  });
+  it("derives first print year and set icons", async () => {
+    expect(await source.firstPrintYear(CARDS[0])).toBe(1993);
+    expect(await source.setIconUri("MH3")).toBe(
+      "https://example.test/mh3.svg",
+    );
+    expect(await source.setIconUri("zzz")).toBeNull();
+  });
+});
+
+describe("unknown name review", () => {
+  it("applies accepted suggestions before placement", async () => {
+    const source = createFixtureSource(CARDS);
+    const items = parseDecklist("2 Lightnig Bolt\n1 Black Lotus");
+    let offered: { name: string; suggestions: string[] }[] = [];
+    const { resolve, unknown } = await resolveDeckEntries(source, items, {
+      reviewUnknown: async (list) => {
+        offered = list;
+        return new Map([["Lightnig Bolt", "Lightning Bolt"]]);
+      },
+    });
+    expect(offered).toEqual([
+      { name: "Lightnig Bolt", suggestions: ["Lightning Bolt"] },
+      { name: "Black Lotus", suggestions: [] },
+    ]);
+    expect(resolve(items[0])?.id).toBe("bolt-m10");
+    expect(unknown).toEqual(["Black Lotus"]);
+  });
+});
+
+describe("live source suggestions", () => {
+  // Stub Scryfall: autocomplete completes exact prefixes only; fuzzy knows the bolt
+  const NAMES = [
+    "Lim-Dûl the Necromancer",
+    "Lim-Dûl's Vault",
+    "Lightning Bolt",
+  ];
+  beforeEach(() => {
+    vi.stubGlobal("fetch", async (url: string) => {
+      const u = new URL(url);
+      const q = (u.searchParams.get("q") || "").toLowerCase();
+      const body = u.pathname.endsWith("/autocomplete")
+        ? { data: NAMES.filter((n) => n.toLowerCase().startsWith(q)) }
+        : /lightn/i.test(u.searchParams.get("fuzzy") || "")
+          ? { name: "Lightning Bolt" }
+          : null;
+      return new Response(JSON.stringify(body), { status: body ? 200 : 404 });
+    });
+  });
+  afterEach(() => vi.unstubAllGlobals());
+
+  it("ranks accent-free and misspelled names without the catalog", async () => {
+    expect(await scryfallSource.suggest("Lim-Dul the Necromancer")).toEqual([
+      "Lim-Dûl the Necromancer",
+    ]);
+    expect(await scryfallSource.suggest("Lightnig Bolt")).toEqual([
+      "Lightning Bolt",
+    ]);
+    expect(await scryfallSource.suggest("Black Lotus")).toEqual([]);
+  });
+});
+
+describe("withFallback search", () => {
+  const local = createFixtureSource(CARDS);
+  const remote = createFixtureSource([
+    { id: "remote", name: "Remote Card", type_line: "Instant" },
+  ]);
+
+  it("reports local parse errors instead of asking the fallback", async () => {
+    const source = withFallback(local, remote);
+    await expect(source.search("foo:bar")).rejects.toThrow(/foo/);
+    expect((await source.search("t:instant")).map((c) => c.id)).toContain(
+      "bolt-m10",
+    );
+  });
+
+  it("falls back only when the primary source is unavailable", async () => {
+    const missing = {
+      ...local,
+      search: async () => {
+        throw new SourceUnavailableError("No offline card database loaded");
+      },
+    };
+    const found = await withFallback(missing, remote).search("t:instant");
+    expect(found.map((c) => c.id)).toEqual(["remote"]);
+  });
+});
+
+describe("import pipeline (resolve → place → persist)", () => {
+  const store = new Map<string, string>();
+  beforeEach(() => {
+    store.clear();
+    (globalThis as any).localStorage = {
+      getItem: (k: string) => store.get(k) ?? null,
+      setItem: (k: string, v: string) => store.set(k, v),
+      removeItem: (k: string) => store.delete(k),
+    };
+  });
+
+  it("places resolved printings and persists them", async () => {
+    const source = createFixtureSource(CARDS);
+    const ctx = emptyContext();
+    // Sprite stand-ins: persistence only reads ids, transforms and card data
+    const sprites: any[] = [];
+    let placed: PlannedCard[] = [];
+    const persistence = createLocalPersistence({
+      getSprites: () => sprites,
+      getGroups: () => new Map(),
+      spatial: new SpatialIndex(),
+      getCanvasBounds: ctx.getCanvasBounds,
+      cardW: CARD_W,
+      cardH: CARD_H,
+    });
+    // The panel's own format pick, then the decklist import main.ts runs
+    const text = ["2 Forest (LEA) 294 *F*", "Lightning Bolt", "1 Black Lotus"];
+    const picked = chooseTextImport(
+      text.join("\n"),
+      {
+        importByNames: (items, opt) =>
+          importDeckEntries(
+            source,
+            items,
+            {
+              known: () => [],
+              capacity: () => 100,
+              placement: () => ctx,
+              place: (cards) => {
+                placed = cards;
+                cards.forEach((p, i) =>
+                  sprites.push({
+                    __id: i + 1,
+                    x: p.x,
+                    y: p.y,
+                    zIndex: i + 1,
+                    __card: p.card,
+                    __foil: p.foil,
+                  }),
+                );
+                persistence.flushPositions();
+                return cards.length;
+              },
+            },
+            opt,
+          ),
+      },
+      {},
+    );
+    expect(picked?.label).toBe("Importing…");
+    const res = await picked!.run();
+    expect(res).toMatchObject({ imported: 3, unknown: ["Black Lotus"] });
+    expect(placed.map((p) => p.card.id)).toEqual([
+      "forest-lea",
+      "forest-lea",
+      "bolt-m10",
+    ]);
+    const positions = placed.map((p) => ({ x: p.x, y: p.y }));
+    const keys = new Set(positions.map((p) => `${p.x},${p.y}`));
+    expect(keys.size).toBe(3);
+    persistence.flushPositions();
+    const saved = JSON.parse(store.get(LS_POSITIONS_KEY) || "{}");
+    expect(saved.instances).toHaveLength(3);
+    expect(saved.instances[0]).toMatchObject({
+      id: 1,
+      x: positions[0].x,
+      y: positions[0].y,
+      scryfall_id: "forest-lea",
+      foil: true,
+    });
+    expect(saved.instances[2].foil).toBeUndefined();
+  });
+});
+
+describe("text import routing", () => {
+  it("sends header-less lists with printings to the decklist", async () => {
+    const importByNames = vi.fn(async () => ({ imported: 1, unknown: [] }));
+    const importArena = vi.fn(async () => ({ imported: 1, unknown: [] }));
+    const picked = chooseTextImport(
+      "1 Forest (MH3) 318",
+      { importByNames, importArena } as any,
+      {},
+    );
+    expect(picked?.label).toBe("Importing…");
+    await picked!.run();
+    expect(importArena).not.toHaveBeenCalled();
+    expect(importByNames).toHaveBeenCalledWith(
+      [expect.objectContaining({ name: "Forest", set: "MH3" })],
+      {},
+    );
+  });
+});
@@ -0,0 +1,66 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import { createLocalPersistence, LS_POSITIONS_KEY } from "../persistence";
+import { SpatialIndex } from "../../scene/SpatialIndex";
+import { CARD_W, CARD_H } from "../../config/dimensions";
+
+const bounds = () => ({ x: 0, y: 0, w: 20000, h: 20000 });
+
+describe("card transform persistence", () => {
+  const store = new Map<string, string>();
+  beforeEach(() => {
+    store.clear();
+    (globalThis as any).localStorage = {
+      getItem: (k: string) => store.get(k) ?? null,
+      setItem: (k: string, v: string) => store.set(k, v),
+      removeItem: (k: string) => store.delete(k),
+    };
+  });
+
+  it("saves rotation and scale and restores the turned footprint", () => {
+    // Sprite stand-ins: persistence only reads ids, transforms and card data
+    const sprites: any[] = [
+      { __id: 1, x: 0, y: 0, zIndex: 1, __card: { id: "forest" } },
+      // Tapped and shown at 2x
+      {
+        __id: 2,
+        x: 400,
+        y: 0,
+        zIndex: 2,
+        __card: { id: "bolt" },
+        __rotation: 90,
+        __scale: 2,
+      },
+    ];
+    createLocalPersistence({
+      getSprites: () => sprites,
+      getGroups: () => new Map(),
+      spatial: new SpatialIndex(),
+      getCanvasBounds: bounds,
+      cardW: CARD_W,
+      cardH: CARD_H,
+    }).flushPositions();
+    const saved = JSON.parse(store.get(LS_POSITIONS_KEY) || "{}");
+    expect(saved.instances[0].rotation).toBeUndefined();
+    expect(saved.instances[0].scale).toBeUndefined();
+    expect(saved.instances[1]).toMatchObject({ rotation: 90, scale: 2 });
+
+    // Restoring brings the transform back and indexes the turned footprint
+    const spatial = new SpatialIndex();
+    const restored = sprites.map((s) => ({ __id: s.__id, x: 0, y: 0 }));
+    createLocalPersistence({
+      getSprites: () => restored as any[],
+      getGroups: () => new Map(),
+      spatial,
+      getCanvasBounds: bounds,
+      cardW: CARD_W,
+      cardH: CARD_H,
+    }).applyStoredPositions();
+    const bolt = restored[1] as any;
+    expect(bolt).toMatchObject({ x: 400, y: 0, __rotation: 90, __scale: 2 });
+    expect((restored[0] as any).__rotation).toBeUndefined();
+    // Past the untapped width, inside the tapped one
+    const px = bolt.x + CARD_W * 2 + 10;
+    const hits = spatial.search(px, bolt.y, px + 1, bolt.y + 1);
+    expect(hits.map((h) => h.sprite)).toContain(bolt);
+  });
+});
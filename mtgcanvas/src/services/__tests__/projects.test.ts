@@ -0,0 +1,90 @@
+import { describe, it, expect, beforeEach } from "vitest";
+import {
+  createProject,
+  duplicateProject,
+  exportProject,
+  getProjectBookmarks,
+  importProject,
+  listProjects,
+  normalizeBookmarks,
+  projectGroupsKey,
+  projectPositionsKey,
+  setProjectBookmarks,
+} from "../projects";
+
+describe("project bookmarks", () => {
+  const store = new Map<string, string>();
+  beforeEach(() => {
+    store.clear();
+    (globalThis as any).localStorage = {
+      getItem: (k: string) => store.get(k) ?? null,
+      setItem: (k: string, v: string) => store.set(k, v),
+      removeItem: (k: string) => store.delete(k),
+    };
+  });
+
+  it("normalizes slots and views", () => {
+    expect(
+      normalizeBookmarks([
+        { slot: 3, name: " Cube ", x: 0, y: 0, w: 100, h: 50 },
+        { slot: 1, name: "", x: 5, y: 5, w: 10, h: 10 },
+        { slot: 3, name: "Binder", x: 1, y: 2, w: 3, h: 4 },
+        { slot: 10, name: "Out of range", x: 0, y: 0, w: 1, h: 1 },
+        { slot: 2, name: "Empty", x: 0, y: 0, w: 0, h: 10 },
+        null,
+      ]),
+    ).toEqual([
+      { slot: 1, name: "View 1", x: 5, y: 5, w: 10, h: 10 },
+      { slot: 3, name: "Binder", x: 1, y: 2, w: 3, h: 4 },
+    ]);
+    expect(normalizeBookmarks("nope")).toEqual([]);
+  });
+
+  it("stores bookmarks with the meta and carries them into copies", () => {
+    const p = createProject("Cube");
+    const omo = { slot: 2, name: "Omo deck", x: -40, y: 80, w: 1600, h: 900 };
+    setProjectBookmarks(p.id, [omo]);
+    expect(getProjectBookmarks(p.id)).toEqual([omo]);
+
+    localStorage.setItem(projectPositionsKey(p.id), '{"instances":[]}');
+    const copy = duplicateProject(p.id);
+    expect(copy && getProjectBookmarks(copy.id)).toEqual([omo]);
+    // Copies are independent
+    setProjectBookmarks(copy!.id, []);
+    expect(getProjectBookmarks(p.id)).toEqual([omo]);
+
+    const bundle = JSON.parse(exportProject(p.id)!);
+    expect(bundle.name).toBe("Cube");
+    expect(bundle.bookmarks).toEqual([omo]);
+    expect(bundle.positions).toEqual({ instances: [] });
+    expect(bundle.groups).toBeNull();
+  });
+
+  it("restores an exported project with its bookmarks", () => {
+    const p = createProject("Cube");
+    const views = [
+      { slot: 1, name: "Commanders", x: 0, y: 0, w: 800, h: 600 },
+      { slot: 4, name: "Sideboard", x: 900, y: -20, w: 400, h: 300 },
+    ];
+    setProjectBookmarks(p.id, views);
+    localStorage.setItem(projectPositionsKey(p.id), '{"instances":[{"id":1}]}');
+    localStorage.setItem(projectGroupsKey(p.id), '{"groups":[]}');
+    const json = exportProject(p.id)!;
+
+    const restored = importProject(json)!;
+    expect(restored.id).not.toBe(p.id);
+    expect(restored.name).toBe("Cube");
+    expect(getProjectBookmarks(restored.id)).toEqual(views);
+    const positions = localStorage.getItem(projectPositionsKey(restored.id));
+    expect(JSON.parse(positions!)).toEqual({ instances: [{ id: 1 }] });
+    expect(JSON.parse(exportProject(restored.id)!)).toMatchObject({
+      name: "Cube",
+      bookmarks: views,
+      groups: { groups: [] },
+    });
+    expect(listProjects().map((m) => m.id)).toContain(restored.id);
+    // Not a backup
+    expect(importProject("[1, 2]")).toBeNull();
+    expect(importProject("not json")).toBeNull();
+  });
+});
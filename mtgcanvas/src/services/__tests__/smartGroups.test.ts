@@ -0,0 +1,167 @@
+import { describe, it, expect } from "vitest";
+import {
+  describeSmartScope,
+  parseSmartGroupRule,
+  planSmartGroup,
+  smartQueryError,
+  type SmartGroupInfo,
+} from "../smartGroups";
+import { parseScryfallQuery } from "../../search/scryfallQuery";
+
+// Groups: 1 = "Omo deck" with nested smart group 2, 3 = another smart group, 4 = plain
+const groups = new Map<number, SmartGroupInfo>([
+  [1, { parentId: null, smart: false }],
+  [2, { parentId: 1, smart: true }],
+  [3, { parentId: null, smart: true }],
+  [4, { parentId: null, smart: false }],
+]);
+const cards = [
+  { id: 10, groupId: null },
+  { id: 11, groupId: null },
+  { id: 12, groupId: 1 },
+  { id: 13, groupId: 2 },
+  { id: 14, groupId: 3 },
+  { id: 15, groupId: 4 },
+];
+const matching = (...ids: number[]) => (id: number) => ids.includes(id);
+
+describe("parseSmartGroupRule", () => {
+  it("keeps a query with a known scope", () => {
+    expect(
+      parseSmartGroupRule({
+        query: " t:land -t:basic ",
+        scope: { kind: "group", id: 4 },
+      }),
+    ).toEqual({ query: "t:land -t:basic", scope: { kind: "group", id: 4 } });
+    expect(
+      parseSmartGroupRule({ query: "t:land", scope: { kind: "canvas" } }),
+    ).toEqual({ query: "t:land", scope: { kind: "canvas" } });
+  });
+
+  it("falls back to ungrouped for unknown scopes and drops empty queries", () => {
+    expect(parseSmartGroupRule({ query: "t:land" })?.scope).toEqual({
+      kind: "ungrouped",
+    });
+    expect(
+      parseSmartGroupRule({ query: "t:land", scope: { kind: "group", id: -1 } })
+        ?.scope,
+    ).toEqual({ kind: "ungrouped" });
+    expect(parseSmartGroupRule({ query: "  " })).toBeNull();
+    expect(parseSmartGroupRule("t:land")).toBeNull();
+  });
+});
+
+describe("describeSmartScope", () => {
+  it("names the scope", () => {
+    const name = (id: number) => (id === 1 ? "Omo deck" : undefined);
+    expect(describeSmartScope({ kind: "ungrouped" }, name)).toBe(
+      "ungrouped cards",
+    );
+    expect(describeSmartScope({ kind: "group", id: 1 }, name)).toBe(
+      '"Omo deck"',
+    );
+    expect(describeSmartScope({ kind: "group", id: 9 }, name)).toBe(
+      "a deleted group",
+    );
+  });
+});
+
+describe("planSmartGroup", () => {
+  it("pulls matching ungrouped cards only", () => {
+    const rule = { query: "x", scope: { kind: "ungrouped" as const } };
+    const plan = planSmartGroup(
+      3,
+      rule,
+      cards,
+      groups,
+      matching(10, 12, 14, 15),
+    );
+    expect(plan).toEqual({ pull: [10], release: [] });
+  });
+
+  it("takes from plain groups on the whole canvas but not from smart groups", () => {
+    const rule = { query: "x", scope: { kind: "canvas" as const } };
+    const plan = planSmartGroup(
+      3,
+      rule,
+      cards,
+      groups,
+      matching(10, 12, 13, 14, 15),
+    );
+    expect(plan.pull).toEqual([10, 12, 15]);
+  });
+
+  it("draws from a scope group and releases non-matches back to it", () => {
+    const rule = { query: "x", scope: { kind: "group" as const, id: 1 } };
+    const plan = planSmartGroup(2, rule, cards, groups, matching(10, 12));
+    expect(plan).toEqual({ pull: [12], release: [{ id: 13, to: 1 }] });
+  });
+
+  it("releases out of any group when the scope group is gone", () => {
+    const rule = { query: "x", scope: { kind: "group" as const, id: 9 } };
+    const plan = planSmartGroup(3, rule, cards, groups, matching(10, 15));
+    expect(plan).toEqual({ pull: [], release: [{ id: 14, to: null }] });
+  });
+});
+
+describe("smartQueryError", () => {
+  it("rejects the membership fields and keeps the rest", () => {
+    expect(smartQueryError("t:land -t:basic")).toBeNull();
+    expect(smartQueryError("in:arena t:land")).toBeNull();
+    expect(smartQueryError("in:ungrouped t:land")).toMatch(/^in:/);
+    expect(smartQueryError("t:land -IN:Grouped")).toMatch(/^in:/);
+    expect(smartQueryError('-group:"Omo deck"')).toMatch(/^group:/);
+    expect(smartQueryError("foo:bar")).not.toBeNull();
+  });
+});
+
+describe("refreshing a smart group", () => {
+  // Plans and applies `rounds` refreshes of smart group 3 over the ungrouped cards,
+  // with the real query against each card's membership; returns the cards moved per round
+  const refresh = (query: string, rounds = 3) => {
+    const pred = parseScryfallQuery(query)!;
+    const placed = [
+      { id: 10, groupId: null as number | null },
+      { id: 11, groupId: null as number | null },
+    ];
+    const card = (id: number) => ({
+      type_line: id === 10 ? "Land" : "Instant",
+    });
+    const moved: number[] = [];
+    for (let r = 0; r < rounds; r++) {
+      const plan = planSmartGroup(
+        3,
+        { query, scope: { kind: "ungrouped" } },
+        placed,
+        groups,
+        (id) => {
+          const c = placed.find((p) => p.id === id)!;
+          return pred(card(id), {
+            groups: c.groupId == null ? [] : [`group ${c.groupId}`],
+            tags: [],
+            owned: 1,
+            count: 1,
+            foil: false,
+            selected: false,
+            face: 0,
+          });
+        },
+      );
+      for (const id of plan.pull) placed.find((p) => p.id === id)!.groupId = 3;
+      for (const rel of plan.release)
+        placed.find((p) => p.id === rel.id)!.groupId = rel.to;
+      moved.push(plan.pull.length + plan.release.length);
+    }
+    return moved;
+  };
+
+  it("oscillates on a membership query, which is why those are rejected", () => {
+    expect(refresh("in:ungrouped t:land")).toEqual([1, 1, 1]);
+    expect(smartQueryError("in:ungrouped t:land")).not.toBeNull();
+  });
+
+  it("settles after one refresh on card fields", () => {
+    expect(refresh("t:land")).toEqual([1, 0, 0]);
+    expect(smartQueryError("t:land")).toBeNull();
+  });
+});
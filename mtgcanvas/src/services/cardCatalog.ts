@@ -0,0 +1,430 @@
+// Offline card catalog built from Scryfall bulk data files (oracle-cards / default-cards).
+// Lives in its own IndexedDB database so imports never touch the imported_cards store.
+// Two card stores take turns: an import fills the idle one and only points the catalog at
+// it once the whole file parsed, so a canceled or broken import keeps the old catalog.
+import type { Card } from "../types/card";
+import { printingKey } from "./scryfall";
+import type { SearchOptions } from "./scryfall";
+import {
+  createLocalSearchFilter,
+  finishLocalSearch,
+} from "../search/localSearch";
+
+const DB_NAME = "mtgCanvasCatalog";
+const DB_VERSION = 2;
+const STORES = ["cards", "cards_b"] as const;
+export type CatalogStoreName = (typeof STORES)[number];
+const META_STORE = "meta";
+const WRITE_BATCH = 1000;
+
+// Stored row: the card plus lookup keys (lower-cased full name and front face name)
+export interface CatalogRow {
+  id: string;
+  names: string[];
+  printing: string | null;
+  card: Card;
+}
+
+export interface CatalogInfo {
+  count: number;
+  source: string; // bulk file name, e.g. "oracle-cards-20250101.json"
+  importedAt: number;
+  store?: CatalogStoreName; // store holding the rows (absent: "cards", version 1)
+}
+
+let dbPromise: Promise<IDBDatabase> | null = null;
+let namesCache: Promise<string[]> | null = null; // see catalogNames()
+let liveCache: Promise<CatalogStoreName> | null = null; // see liveStore()
+function openDB(): Promise<IDBDatabase> {
+  if (dbPromise) return dbPromise;
+  dbPromise = new Promise((resolve, reject) => {
+    if (!("indexedDB" in window)) {
+      reject(new Error("IndexedDB not supported"));
+      return;
+    }
+    const req = indexedDB.open(DB_NAME, DB_VERSION);
+    req.onupgradeneeded = () => {
+      const db = req.result;
+      for (const name of STORES) {
+        if (db.objectStoreNames.contains(name)) continue;
+        const os = db.createObjectStore(name, { keyPath: "id" });
+        os.createIndex("names", "names", { multiEntry: true });
+        os.createIndex("printing", "printing");
+      }
+      if (!db.objectStoreNames.contains(META_STORE)) {
+        db.createObjectStore(META_STORE);
+      }
+    };
+    req.onerror = () => reject(req.error!);
+    req.onsuccess = () => resolve(req.result);
+  });
+  return dbPromise;
+}
+
+// Incremental scanner for a top-level JSON array of objects. Chunks may split objects,
+// strings or escapes anywhere; each complete element is parsed and handed to onItem.
+// Not stream-json's StreamArray: that is built on Node's stream.Transform, which the
+// browser bundle has no shim for, and the import reads a File through a web stream.
+// The scanner only finds element boundaries; JSON.parse does the decoding.
+export function createJsonArrayScanner(onItem: (value: any) => void): {
+  push: (chunk: string) => void;
+  end: () => void;
+} {
+  let depth = 0;
+  let inString = false;
+  let escape = false;
+  let buf = "";
+  function push(chunk: string) {
+    let start = depth > 0 ? 0 : -1;
+    for (let i = 0; i < chunk.length; i++) {
+      const ch = chunk.charCodeAt(i);
+      if (depth === 0) {
+        // Between elements: skip '[', ',', ']' and whitespace until the next object
+        if (ch === 123 /* { */) {
+          depth = 1;
+          start = i;
+        }
+        continue;
+      }
+      if (inString) {
+        if (escape) escape = false;
+        else if (ch === 92 /* \ */) escape = true;
+        else if (ch === 34 /* " */) inString = false;
+        continue;
+      }
+      if (ch === 34) inString = true;
+      else if (ch === 123 || ch === 91 /* [ */) depth++;
+      else if (ch === 125 /* } */ || ch === 93 /* ] */) {
+        depth--;
+        if (depth === 0) {
+          const text = buf + chunk.slice(start, i + 1);
+          buf = "";
+          start = -1;
+          onItem(JSON.parse(text));
+        }
+      }
+    }
+    if (depth > 0 && start >= 0) buf += chunk.slice(start);
+  }
+  function end() {
+    if (depth > 0) throw new Error("Unexpected end of JSON input");
+  }
+  return { push, end };
+}
+
+function toRow(card: Card): CatalogRow | null {
+  if (!card || typeof card.id !== "string" || !card.name) return null;
+  const full = String(card.name).toLowerCase();
+  const names = [full];
+  const i = full.indexOf("//");
+  if (i >= 0) names.push(full.slice(0, i).trim());
+  return {
+    id: card.id,
+    names,
+    printing:
+      card.set && card.collector_number
+        ? printingKey(card.set, card.collector_number)
+        : null,
+    card,
+  };
+}
+
+// Where an import writes and how it publishes the result. IndexedDB in the app; tests
+// pass an in-memory one.
+export interface CatalogBackend {
+  info(): Promise<CatalogInfo | null>;
+  clear(store: CatalogStoreName): Promise<void>;
+  put(store: CatalogStoreName, rows: CatalogRow[]): Promise<void>;
+  publish(info: CatalogInfo): Promise<void>; // one write: readers switch to info.store
+}
+
+const idbBackend: CatalogBackend = {
+  info: getCatalogInfo,
+  async clear(store) {
+    const db = await openDB();
+    await new Promise<void>((resolve, reject) => {
+      const tx = db.transaction(store, "readwrite");
+      tx.objectStore(store).clear();
+      tx.oncomplete = () => resolve();
+      tx.onabort = tx.onerror = () => reject(tx.error!);
+    });
+  },
+  async put(store, rows) {
+    const db = await openDB();
+    await new Promise<void>((resolve, reject) => {
+      const tx = db.transaction(store, "readwrite");
+      const os = tx.objectStore(store);
+      rows.forEach((r) => os.put(r));
+      tx.oncomplete = () => resolve();
+      tx.onabort = tx.onerror = () => reject(tx.error!);
+    });
+  },
+  async publish(info) {
+    const db = await openDB();
+    await new Promise<void>((resolve, reject) => {
+      const tx = db.transaction(META_STORE, "readwrite");
+      tx.objectStore(META_STORE).put(info, "info");
+      tx.oncomplete = () => resolve();
+      tx.onabort = tx.onerror = () => reject(tx.error!);
+    });
+  },
+};
+
+// Replace the catalog with the cards in a Scryfall bulk JSON file. The file is streamed
+// so multi-hundred-MB dumps never have to be held in memory as one string. Rows go to the
+// idle store; the old catalog stays readable until the file has parsed to the end, and a
+// cancel or a bad file only drops the staged rows.
+export async function importBulkFile(
+  file: Blob & { name?: string },
+  opt?: {
+    onProgress?: (bytesRead: number, bytesTotal: number, cards: number) => void;
+    signal?: AbortSignal;
+  },
+  backend: CatalogBackend = idbBackend,
+): Promise<CatalogInfo> {
+  const prev = await backend.info();
+  const live: CatalogStoreName = prev?.store ?? "cards";
+  const stage: CatalogStoreName = live === "cards" ? "cards_b" : "cards";
+  // Left over from an import that was interrupted (tab closed) before it published
+  await backend.clear(stage);
+  let pending: CatalogRow[] = [];
+  let count = 0;
+  const scanner = createJsonArrayScanner((v) => {
+    const row = toRow(v);
+    if (row) pending.push(row);
+  });
+  const reader = file.stream().getReader();
+  const decoder = new TextDecoder();
+  let bytesRead = 0;
+  try {
+    for (;;) {
+      if (opt?.signal?.aborted) {
+        throw new DOMException("Aborted", "AbortError");
+      }
+      const { done, value } = await reader.read();
+      if (done) break;
+      bytesRead += value.byteLength;
+      scanner.push(decoder.decode(value, { stream: true }));
+      while (pending.length >= WRITE_BATCH) {
+        const batch = pending.slice(0, WRITE_BATCH);
+        pending = pending.slice(WRITE_BATCH);
+        await backend.put(stage, batch);
+        count += batch.length;
+      }
+      opt?.onProgress?.(bytesRead, file.size, count + pending.length);
+    }
+    scanner.push(decoder.decode());
+    scanner.end();
+    if (pending.length) {
+      await backend.put(stage, pending);
+      count += pending.length;
+      pending = [];
+    }
+  } catch (e) {
+    try {
+      await reader.cancel();
+    } catch {}
+    try {
+      await backend.clear(stage);
+    } catch {}
+    throw e;
+  }
+  const info: CatalogInfo = {
+    count,
+    source: file.name || "bulk data",
+    importedAt: Date.now(),
+    store: stage,
+  };
+  await backend.publish(info);
+  namesCache = null;
+  liveCache = null;
+  // The old rows are unreachable now; failing to drop them only wastes space
+  try {
+    await backend.clear(live);
+  } catch {}
+  opt?.onProgress?.(bytesRead, file.size, count);
+  return info;
+}
+
+export async function getCatalogInfo(): Promise<CatalogInfo | null> {
+  try {
+    const db = await openDB();
+    return await new Promise((resolve, reject) => {
+      const tx = db.transaction(META_STORE, "readonly");
+      const r = tx.objectStore(META_STORE).get("info");
+      r.onsuccess = () => resolve(r.result || null);
+      r.onerror = () => reject(r.error!);
+    });
+  } catch {
+    return null;
+  }
+}
+
+export async function clearCatalog(): Promise<void> {
+  namesCache = null;
+  liveCache = null;
+  try {
+    const db = await openDB();
+    await new Promise<void>((resolve, reject) => {
+      const tx = db.transaction([...STORES, META_STORE], "readwrite");
+      STORES.forEach((name) => tx.objectStore(name).clear());
+      tx.objectStore(META_STORE).clear();
+      tx.oncomplete = () => resolve();
+      tx.onabort = tx.onerror = () => reject(tx.error!);
+    });
+  } catch {
+    /* ignore */
+  }
+}
+
+// The store the published catalog lives in
+function liveStore(): Promise<CatalogStoreName> {
+  liveCache ??= getCatalogInfo().then((info) => info?.store ?? "cards");
+  return liveCache;
+}
+
+// Distinct card names in the catalog (for "did you mean" suggestions). Built with one
+// scan on first use and kept until the catalog changes.
+export function catalogNames(): Promise<string[]> {
+  if (namesCache) return namesCache;
+  namesCache = (async () => {
+    const names = new Set<string>();
+    try {
+      const db = await openDB();
+      const store = await liveStore();
+      await new Promise<void>((resolve, reject) => {
+        const tx = db.transaction(store, "readonly");
+        const req = tx.objectStore(store).openCursor();
+        req.onsuccess = () => {
+          const cur = req.result;
+          if (!cur) return;
+          const name = (cur.value as CatalogRow).card?.name;
+          if (name) names.add(name);
+          cur.continue();
+        };
+        req.onerror = () => reject(req.error!);
+        tx.oncomplete = () => resolve();
+        tx.onabort = () => reject(tx.error!);
+      });
+    } catch {
+      /* ignore */
+    }
+    return [...names];
+  })();
+  return namesCache;
+}
+
+// Batch lookup through one index; result keys are the (lower-cased) lookup keys.
+async function lookupByIndex(
+  index: "names" | "printing",
+  keys: string[],
+): Promise<Map<string, Card>> {
+  const out = new Map<string, Card>();
+  if (!keys.length) return out;
+  try {
+    const db = await openDB();
+    const store = await liveStore();
+    await new Promise<void>((resolve, reject) => {
+      const tx = db.transaction(store, "readonly");
+      const idx = tx.objectStore(store).index(index);
+      for (const k of keys) {
+        const r = idx.get(k);
+        r.onsuccess = () => {
+          const row = r.result as CatalogRow | undefined;
+          if (row && !out.has(k)) out.set(k, row.card);
+        };
+      }
+      tx.oncomplete = () => resolve();
+      tx.onabort = tx.onerror = () => reject(tx.error!);
+    });
+  } catch {
+    /* empty or unavailable catalog: resolve nothing */
+  }
+  return out;
+}
+
+// Resolve card names offline. Keys of the result are lower-cased names (full or front face).
+export async function catalogLookupNames(
+  names: string[],
+): Promise<Map<string, Card>> {
+  const keys = [
+    ...new Set(names.map((n) => (n || "").trim().toLowerCase())),
+  ].filter(Boolean);
+  return lookupByIndex("names", keys);
+}
+
+// Resolve exact printings offline. Keys of the result match printingKey(set, cn).
+export async function catalogLookupPrintings(
+  ids: { set: string; collector_number: string }[],
+): Promise<Map<string, Card>> {
+  const keys = [
+    ...new Set(ids.map((p) => printingKey(p.set, p.collector_number))),
+  ];
+  return lookupByIndex("printing", keys);
+}
+
+// Walk the catalog with a cursor, collecting cards that match (stops at limit).
+// onProgress receives the number of rows scanned so far (every 2000 rows).
+export async function searchCatalog(
+  predicate: (card: Card) => boolean,
+  opt?: {
+    limit?: number;
+    signal?: AbortSignal;
+    onProgress?: (scanned: number) => void;
+  },
+): Promise<Card[]> {
+  const limit = opt?.limit ?? Infinity;
+  const out: Card[] = [];
+  let scanned = 0;
+  try {
+    const db = await openDB();
+    const store = await liveStore();
+    await new Promise<void>((resolve, reject) => {
+      const tx = db.transaction(store, "readonly");
+      const req = tx.objectStore(store).openCursor();
+      req.onsuccess = () => {
+        const cur = req.result;
+        if (!cur || out.length >= limit || opt?.signal?.aborted) return;
+        const card = (cur.value as CatalogRow).card;
+        if (predicate(card)) out.push(card);
+        if (++scanned % 2000 === 0) opt?.onProgress?.(scanned);
+        cur.continue();
+      };
+      req.onerror = () => reject(req.error!);
+      tx.oncomplete = () => resolve();
+      tx.onabort = () => reject(tx.error!);
+    });
+  } catch {
+    /* ignore */
+  }
+  return out;
+}
+
+// Evaluate a Scryfall query locally. Mirrors searchScryfall's options (unique, order, dir,
+// include_extras, include_multilingual, maxCards) and progress callbacks.
+export async function searchCatalogQuery(
+  query: string,
+  opts: SearchOptions = {},
+): Promise<Card[]> {
+  const info = await getCatalogInfo();
+  if (!info) throw new Error("No offline card database loaded");
+  const filter = createLocalSearchFilter(query, opts);
+  let matched = 0;
+  const matches = await searchCatalog(
+    (c) => {
+      if (!filter(c)) return false;
+      matched++;
+      return true;
+    },
+    {
+      signal: opts.signal,
+      // Scan progress (matches so far); total is known only once the scan ends
+      onProgress: () => opts.onProgress?.(matched),
+    },
+  );
+  if (opts.signal?.aborted) throw new DOMException("Aborted", "AbortError");
+  const out = finishLocalSearch(matches, query, opts);
+  opts.onCards?.(out);
+  opts.onProgress?.(out.length, out.length);
+  return out;
+}
@@ -0,0 +1,395 @@
+// Card data sources behind one interface: the live Scryfall API, the offline bulk-data
+// catalog, and an in-memory fixture source for tests. Callers only see CardSource.
+import type { ScryfallCard, SearchOptions } from "./scryfall";
+import {
+  searchScryfall,
+  fetchScryfallByNames,
+  fetchScryfallByPrintings,
+  fetchScryfallAutocomplete,
+  fetchScryfallFuzzyName,
+  printingKey,
+} from "./scryfall";
+import {
+  catalogLookupNames,
+  catalogLookupPrintings,
+  catalogNames,
+  getCatalogInfo,
+  searchCatalogQuery,
+} from "./cardCatalog";
+import { normalizeCardName, suggestNames } from "../search/fuzzyName";
+import {
+  createLocalSearchFilter,
+  finishLocalSearch,
+} from "../search/localSearch";
+import type { DeckEntry } from "./decklist";
+
+export type PrintingId = { set: string; collector_number: string };
+export type LookupOptions = {
+  signal?: AbortSignal;
+  onProgress?: (done: number, total?: number) => void;
+};
+
+export interface CardSource {
+  readonly kind: "scryfall" | "catalog" | "fixture" | "fallback";
+  // Scryfall query syntax; honors the same options as searchScryfall
+  search(query: string, opts?: SearchOptions): Promise<ScryfallCard[]>;
+  // Map keys are lower-cased names; unknown holds names that did not resolve
+  byNames(
+    names: string[],
+    opts?: LookupOptions,
+  ): Promise<{ byName: Map<string, ScryfallCard>; unknown: string[] }>;
+  // Map keys are printingKey(set, collector_number)
+  byPrintings(
+    ids: PrintingId[],
+    opts?: LookupOptions,
+  ): Promise<{ byPrinting: Map<string, ScryfallCard>; unknown: string[] }>;
+  // Year of the card's first printing (null when unknown)
+  firstPrintYear(card: ScryfallCard): Promise<number | null>;
+  // SVG icon for a set code (null when unknown)
+  setIconUri(code: string): Promise<string | null>;
+  // "Did you mean" candidates for a name that did not resolve (best first)
+  suggest(name: string, opts?: { signal?: AbortSignal }): Promise<string[]>;
+}
+
+// Unknown names with their suggestions; the answer maps original -> chosen name
+export type UnknownReview = (
+  items: { name: string; suggestions: string[] }[],
+) => Promise<Map<string, string>>;
+
+// Thrown by a source that has nothing to answer with (no offline catalog loaded).
+// withFallback hands the request on only for this; other errors reach the caller.
+export class SourceUnavailableError extends Error {
+  name = "SourceUnavailableError";
+}
+
+const SUGGEST_LIMIT = 3;
+// Bound suggestion lookups (one autocomplete request each on the live API)
+const MAX_SUGGESTED_NAMES = 40;
+
+const yearOf = (date: unknown) =>
+  date ? new Date(String(date)).getUTCFullYear() : null;
+
+export const scryfallSource: CardSource = {
+  kind: "scryfall",
+  search: (query, opts) => searchScryfall(query, opts),
+  byNames: (names, opts) => fetchScryfallByNames(names, opts),
+  byPrintings: (ids, opts) => fetchScryfallByPrintings(ids, opts),
+  async firstPrintYear(card) {
+    const oracleId = String(card?.oracle_id || "");
+    let url = String(card?.prints_search_uri || "");
+    if (url) {
+      try {
+        const u = new URL(url);
+        u.searchParams.set("order", "released");
+        u.searchParams.set("dir", "asc");
+        u.searchParams.set("unique", "prints");
+        url = u.toString();
+      } catch {}
+    } else if (oracleId) {
+      url = `https://api.scryfall.com/cards/search?q=oracleid:${encodeURIComponent(oracleId)}&order=released&dir=asc&unique=prints`;
+    } else if (card?.name) {
+      url = `https://api.scryfall.com/cards/search?q=%21${encodeURIComponent(String(card.name))}&order=released&dir=asc&unique=prints`;
+    } else {
+      return null;
+    }
+    try {
+      const res = await fetch(url, {
+        headers: { Accept: "application/json" },
+      });
+      if (!res.ok) return null;
+      const json: any = await res.json();
+      const data: any[] = Array.isArray(json?.data) ? json.data : [];
+      const first = data.length ? data[0] : null;
+      return yearOf(first?.released_at) ?? yearOf(card?.released_at);
+    } catch {
+      return null;
+    }
+  },
+  async setIconUri(code) {
+    const res = await fetch(
+      `https://api.scryfall.com/sets/${encodeURIComponent(code)}`,
+    );
+    if (!res.ok) return null;
+    const json: any = await res.json();
+    return json && json.icon_svg_uri ? String(json.icon_svg_uri) : null;
+  },
+  async suggest(name, opts) {
+    // Autocomplete works on the front face; rank its completions locally
+    const front = (name || "").split("//")[0].trim();
+    const rank = (list: string[]) =>
+      suggestNames(name, list, { limit: SUGGEST_LIMIT });
+    const list = await fetchScryfallAutocomplete(front, opts);
+    const ranked = rank(list);
+    if (ranked.length) return ranked;
+    // Autocomplete only completes prefixes, so a typo or a missing accent finds
+    // nothing. Widen the candidates (accent-free name, its first word, Scryfall's
+    // fuzzy match) and rank them the same way.
+    const plain = normalizeCardName(front);
+    const more = [...list];
+    const queries = new Set([plain, plain.split(" ")[0]]);
+    queries.delete(front.toLowerCase());
+    for (const q of queries)
+      more.push(...(await fetchScryfallAutocomplete(q, opts)));
+    const fuzzy = await fetchScryfallFuzzyName(front, opts);
+    if (fuzzy) more.push(fuzzy);
+    const wider = rank(more);
+    if (wider.length) return wider;
+    return fuzzy ? [fuzzy] : list.slice(0, SUGGEST_LIMIT);
+  },
+};
+
+function unknownNames(names: string[], found: Map<string, ScryfallCard>) {
+  return [
+    ...new Set(names.map((n) => (n || "").trim()).filter(Boolean)),
+  ].filter((n) => !found.has(n.toLowerCase()));
+}
+
+// Offline catalog (see cardCatalog.ts). search() throws SourceUnavailableError when no
+// catalog is loaded, and the query's own parse error otherwise.
+export const catalogSource: CardSource = {
+  kind: "catalog",
+  async search(query, opts) {
+    if (!(await getCatalogInfo()))
+      throw new SourceUnavailableError("No offline card database loaded");
+    return searchCatalogQuery(query, opts);
+  },
+  async byNames(names) {
+    const byName = await catalogLookupNames(names);
+    return { byName, unknown: unknownNames(names, byName) };
+  },
+  async byPrintings(ids) {
+    const byPrinting = await catalogLookupPrintings(ids);
+    const unknown = [
+      ...new Set(ids.map((p) => printingKey(p.set, p.collector_number))),
+    ].filter((k) => !byPrinting.has(k));
+    return { byPrinting, unknown };
+  },
+  // Bulk files don't say which printing came first; defer to the next source
+  firstPrintYear: async () => null,
+  setIconUri: async () => null,
+  suggest: async (name) =>
+    suggestNames(name, await catalogNames(), { limit: SUGGEST_LIMIT }),
+};
+
+// In-memory source over a fixed card list (tests, demos). Sets map code -> icon URI.
+export function createFixtureSource(
+  cards: ScryfallCard[],
+  opts?: { sets?: Record<string, string> },
+): CardSource {
+  const byName = new Map<string, ScryfallCard>();
+  const byPrinting = new Map<string, ScryfallCard>();
+  for (const c of cards) {
+    const full = String(c.name || "").toLowerCase();
+    if (!full) continue;
+    if (!byName.has(full)) byName.set(full, c);
+    const i = full.indexOf("//");
+    if (i >= 0 && !byName.has(full.slice(0, i).trim()))
+      byName.set(full.slice(0, i).trim(), c);
+    if (c.set && c.collector_number) {
+      const k = printingKey(c.set, c.collector_number);
+      if (!byPrinting.has(k)) byPrinting.set(k, c);
+    }
+  }
+  return {
+    kind: "fixture",
+    async search(query, so = {}) {
+      const filter = createLocalSearchFilter(query, so);
+      const out = finishLocalSearch(cards.filter(filter), query, so);
+      so.onCards?.(out);
+      so.onProgress?.(out.length, out.length);
+      return out;
+    },
+    async byNames(names, lo) {
+      const found = new Map<string, ScryfallCard>();
+      for (const n of names) {
+        const k = (n || "").trim().toLowerCase();
+        const c = byName.get(k);
+        if (c) found.set(k, c);
+      }
+      lo?.onProgress?.(names.length, names.length);
+      return { byName: found, unknown: unknownNames(names, found) };
+    },
+    async byPrintings(ids, lo) {
+      const found = new Map<string, ScryfallCard>();
+      const unknown: string[] = [];
+      for (const p of ids) {
+        const k = printingKey(p.set, p.collector_number);
+        const c = byPrinting.get(k);
+        if (c) found.set(k, c);
+        else if (!unknown.includes(k)) unknown.push(k);
+      }
+      lo?.onProgress?.(ids.length, ids.length);
+      return { byPrinting: found, unknown };
+    },
+    async firstPrintYear(card) {
+      const id = card?.oracle_id;
+      const nm = String(card?.name || "").toLowerCase();
+      let min: number | null = null;
+      for (const c of cards) {
+        const same = id
+          ? c.oracle_id === id
+          : String(c.name || "").toLowerCase() === nm;
+        const y = same ? yearOf(c.released_at) : null;
+        if (y != null && (min == null || y < min)) min = y;
+      }
+      return min;
+    },
+    setIconUri: async (code) =>
+      opts?.sets?.[String(code || "").toLowerCase()] ?? null,
+    suggest: async (name) =>
+      suggestNames(
+        name,
+        cards.map((c) => String(c.name || "")),
+        { limit: SUGGEST_LIMIT },
+      ),
+  };
+}
+
+// Try primary first and ask fallback only for what primary could not answer. Searches
+// go to fallback only when primary is unavailable; a query primary rejects (bad syntax)
+// is reported rather than quietly sent to the live API.
+export function withFallback(
+  primary: CardSource,
+  fallback: CardSource,
+): CardSource {
+  return {
+    kind: "fallback",
+    async search(query, opts) {
+      try {
+        return await primary.search(query, opts);
+      } catch (e) {
+        if (!(e instanceof SourceUnavailableError)) throw e;
+        return fallback.search(query, opts);
+      }
+    },
+    async byNames(names, opts) {
+      const a = await primary.byNames(names, opts);
+      if (!a.unknown.length) return a;
+      const b = await fallback.byNames(a.unknown, opts);
+      b.byName.forEach((c, k) => a.byName.set(k, c));
+      return { byName: a.byName, unknown: b.unknown };
+    },
+    async byPrintings(ids, opts) {
+      const a = await primary.byPrintings(ids, opts);
+      if (!a.unknown.length) return a;
+      const missing = ids.filter((p) =>
+        a.unknown.includes(printingKey(p.set, p.collector_number)),
+      );
+      const b = await fallback.byPrintings(missing, opts);
+      b.byPrinting.forEach((c, k) => a.byPrinting.set(k, c));
+      return { byPrinting: a.byPrinting, unknown: b.unknown };
+    },
+    firstPrintYear: async (card) =>
+      (await primary.firstPrintYear(card)) ?? fallback.firstPrintYear(card),
+    setIconUri: async (code) =>
+      (await primary.setIconUri(code)) ?? fallback.setIconUri(code),
+    async suggest(name, opts) {
+      const a = await primary.suggest(name, opts);
+      return a.length ? a : fallback.suggest(name, opts);
+    },
+  };
+}
+
+// Resolve decklist entries: exact printing (set + collector number) first, then by name.
+// `known` seeds the lookups (e.g. cards already on the canvas) to avoid refetching.
+export async function resolveDeckEntries(
+  source: CardSource,
+  entries: DeckEntry[],
+  opts?: LookupOptions & {
+    known?: ScryfallCard[];
+    // Called with suggestions for misses before anything is placed
+    reviewUnknown?: UnknownReview;
+  },
+): Promise<{
+  resolve: (e: DeckEntry) => ScryfallCard | undefined;
+  unknown: string[];
+}> {
+  const byPrinting = new Map<string, ScryfallCard>();
+  const byName = new Map<string, ScryfallCard>();
+  for (const c of opts?.known || []) {
+    if (!c) continue;
+    if (c.set && c.collector_number) {
+      const k = printingKey(c.set, c.collector_number);
+      if (!byPrinting.has(k)) byPrinting.set(k, c);
+    }
+    const nm = (c.name || "").toLowerCase();
+    if (nm && !byName.has(nm)) byName.set(nm, c);
+  }
+  const lookup = { signal: opts?.signal, onProgress: opts?.onProgress };
+  const byExact = (e: DeckEntry) =>
+    e.set && e.collector_number
+      ? byPrinting.get(printingKey(e.set, e.collector_number))
+      : undefined;
+  const resolve = (e: DeckEntry) =>
+    byExact(e) || byName.get((e.name || "").toLowerCase());
+  // 1) Exact printings
+  const wantPrintings = entries.filter(
+    (e) => e.set && e.collector_number && !byExact(e),
+  );
+  if (wantPrintings.length) {
+    try {
+      const { byPrinting: fetched } = await source.byPrintings(
+        wantPrintings.map((e) => ({
+          set: e.set!,
+          collector_number: e.collector_number!,
+        })),
+        lookup,
+      );
+      fetched.forEach((card, key) => {
+        if (!byPrinting.has(key)) byPrinting.set(key, card);
+      });
+    } catch (e) {
+      console.warn("[import] printing lookup failed", e);
+    }
+  }
+  // 2) Names for entries without (or with an unknown) printing
+  const missing = entries.filter((e) => !resolve(e)).map((e) => e.name);
+  if (missing.length) {
+    try {
+      const { byName: fetched } = await source.byNames(missing, lookup);
+      fetched.forEach((card, key) => {
+        if (!byName.has(key)) byName.set(key, card);
+      });
+    } catch (e) {
+      console.warn("[import] name lookup failed", e);
+    }
+  }
+  const unresolved = () =>
+    [...new Set(entries.filter((e) => !resolve(e)).map((e) => e.name))].filter(
+      (n) => typeof n === "string" && n.trim().length,
+    );
+  // 3) "Did you mean": let the caller map misses to suggested names
+  const misses = unresolved();
+  if (misses.length && opts?.reviewUnknown) {
+    const items: { name: string; suggestions: string[] }[] = [];
+    for (const name of misses) {
+      let suggestions: string[] = [];
+      if (items.length < MAX_SUGGESTED_NAMES) {
+        try {
+          suggestions = await source.suggest(name, { signal: opts.signal });
+        } catch (e: any) {
+          if (e?.name === "AbortError") throw e;
+        }
+      }
+      items.push({ name, suggestions });
+    }
+    if (items.some((it) => it.suggestions.length)) {
+      const fixes = await opts.reviewUnknown(items);
+      if (fixes.size) {
+        try {
+          const { byName: fetched } = await source.byNames(
+            [...fixes.values()],
+            lookup,
+          );
+          fixes.forEach((to, from) => {
+            const card = fetched.get(to.toLowerCase());
+            if (card) byName.set(from.toLowerCase(), card);
+          });
+        } catch (e) {
+          console.warn("[import] correction lookup failed", e);
+        }
+      }
+    }
+  }
+  return { resolve, unknown: unresolved() };
+}
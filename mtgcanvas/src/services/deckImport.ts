@@ -0,0 +1,83 @@
+// Plain decklist import: resolve the entries through a CardSource, cap the copies at the
+// canvas capacity and lay them out with planImportPositions. The canvas side (sprites,
+// undo, persistence, camera) comes in through DeckImportTarget so the flow runs in tests.
+import type { ScryfallCard } from "./scryfall";
+import type { DecklistItem } from "./decklist";
+import {
+  resolveDeckEntries,
+  type CardSource,
+  type LookupOptions,
+  type UnknownReview,
+} from "./cardSource";
+import { planImportPositions, type PlacementContext } from "../placement";
+import type { Rect } from "../types/geometry";
+
+const MAX_COPIES = 999;
+
+export interface PlannedCard {
+  card: ScryfallCard;
+  foil?: boolean;
+  x: number;
+  y: number;
+}
+
+export interface DeckImportTarget {
+  known(): ScryfallCard[]; // cards already on the canvas (skip refetching them)
+  capacity(): number; // cards that may still be added
+  placement(): PlacementContext;
+  // Create the planned cards; resolves to how many were created
+  place(cards: PlannedCard[], block: Rect): Promise<number> | number;
+}
+
+export async function importDeckEntries(
+  source: CardSource,
+  items: DecklistItem[],
+  target: DeckImportTarget,
+  opt?: LookupOptions & {
+    reviewUnknown?: UnknownReview;
+    stackDuplicates?: boolean; // pile copies of a card on one spot
+  },
+): Promise<{ imported: number; unknown: string[]; limited: number }> {
+  const { resolve, unknown } = await resolveDeckEntries(source, items, {
+    ...opt,
+    known: target.known(),
+  });
+  const toPlace: { card: ScryfallCard; count: number; foil?: boolean }[] = [];
+  for (const it of items) {
+    const card = resolve(it);
+    if (!card) continue;
+    toPlace.push({
+      card,
+      count: Math.max(1, Math.min(MAX_COPIES, it.count | 0)),
+      foil: it.foil,
+    });
+  }
+  let total = toPlace.reduce((sum, p) => sum + p.count, 0);
+  let limited = 0;
+  const cap = target.capacity();
+  if (total > cap) {
+    limited = total - cap;
+    total = cap;
+  }
+  // Stacked imports pile each entry's copies on one spot
+  const stack = !!opt?.stackDuplicates;
+  const { positions, block } = planImportPositions(
+    stack ? Math.min(toPlace.length, total) : total,
+    target.placement(),
+  );
+  const planned: PlannedCard[] = [];
+  let slot = 0;
+  let left = total;
+  for (const pl of toPlace) {
+    if (left <= 0) break;
+    const n = Math.min(pl.count, left);
+    for (let i = 0; i < n; i++) {
+      const pos = positions[stack ? slot : slot + i];
+      planned.push({ card: pl.card, foil: pl.foil, x: pos.x, y: pos.y });
+    }
+    slot += stack ? 1 : n;
+    left -= n;
+  }
+  const imported = planned.length ? await target.place(planned, block) : 0;
+  return { imported, unknown, limited };
+}
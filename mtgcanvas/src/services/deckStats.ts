@@ -0,0 +1,122 @@
+// Deck statistics for a set of cards (group info panel): mana curve, colored pips,
+// type breakdown, average mana value, land count and color identity.
+// Buckets keep the items they were built from so the panel can select them.
+import type { Card } from "../types/card";
+import { COLOR_NAMES, planStacks, primaryType } from "../scene/stackLayout";
+
+export interface StatBucket<T> {
+  key: string;
+  label: string;
+  // Cards in the bucket; for pips the number of symbols instead
+  count: number;
+  items: T[];
+}
+
+export interface DeckStats<T> {
+  total: number;
+  // Non-land cards by mana value: 0..6 and "7+", gaps filled with empty buckets
+  curve: StatBucket<T>[];
+  // W U B R G and C (colorless) symbols in mana costs, only colors that appear
+  pips: StatBucket<T>[];
+  types: StatBucket<T>[];
+  // Average over non-land cards; null when there are none
+  avgManaValue: number | null;
+  lands: T[];
+  // Union of color identities in WUBRG order
+  identity: string[];
+}
+
+const CURVE_CAP = 7;
+const PIP_COLORS = [...COLOR_NAMES.map(([c]) => c), "C"];
+
+// Colored symbols in a mana cost. Hybrid symbols count for each of their colors,
+// Phyrexian ones for their color; generic and X costs are ignored.
+export function countPips(manaCost: string | null | undefined) {
+  const out: Record<string, number> = {};
+  if (!manaCost) return out;
+  for (const m of manaCost.matchAll(/\{([^}]+)\}/g)) {
+    const parts = new Set(m[1].toUpperCase().split("/"));
+    for (const p of parts)
+      if (PIP_COLORS.includes(p)) out[p] = (out[p] || 0) + 1;
+  }
+  return out;
+}
+
+// Mana cost of all faces (split and modal cards carry costs per face)
+function fullManaCost(card: Card): string {
+  if (card.mana_cost) return String(card.mana_cost);
+  const faces = card.card_faces ?? [];
+  let cost = "";
+  for (let i = 0; i < faces.length; i++) cost += faces[i].mana_cost || "";
+  return cost;
+}
+
+function identityOf(card: Card): string[] {
+  const ci = card.color_identity ?? card.colors ?? [];
+  return typeof ci === "string" ? ci.toUpperCase().split("") : ci;
+}
+
+export function computeDeckStats<T>(
+  items: T[],
+  cardOf: (item: T) => Card | null | undefined,
+): DeckStats<T> {
+  const curve = new Map<number, T[]>();
+  const pips = new Map<string, { count: number; items: T[] }>();
+  const lands: T[] = [];
+  const identity = new Set<string>();
+  let mvSum = 0;
+  let mvCount = 0;
+  for (const it of items) {
+    const card = cardOf(it);
+    if (!card) continue;
+    for (const c of identityOf(card)) identity.add(c);
+    for (const [c, n] of Object.entries(countPips(fullManaCost(card)))) {
+      let p = pips.get(c);
+      if (!p) pips.set(c, (p = { count: 0, items: [] }));
+      p.count += n;
+      p.items.push(it);
+    }
+    if (primaryType(card.type_line) === "Land") {
+      lands.push(it);
+      continue;
+    }
+    const cmc = typeof card.cmc === "number" ? card.cmc : 0;
+    mvSum += cmc;
+    mvCount++;
+    const bucket = Math.min(CURVE_CAP, Math.floor(cmc));
+    const list = curve.get(bucket);
+    if (list) list.push(it);
+    else curve.set(bucket, [it]);
+  }
+  const maxBucket = curve.size ? Math.max(...curve.keys()) : -1;
+  const curveBuckets: StatBucket<T>[] = [];
+  for (let b = 0; b <= maxBucket; b++) {
+    const list = curve.get(b) ?? [];
+    const label = b === CURVE_CAP ? `${CURVE_CAP}+` : String(b);
+    curveBuckets.push({ key: label, label, count: list.length, items: list });
+  }
+  return {
+    total: items.filter((it) => cardOf(it)).length,
+    curve: curveBuckets,
+    pips: PIP_COLORS.filter((c) => pips.has(c)).map((c) => {
+      const p = pips.get(c)!;
+      return { key: c, label: c, count: p.count, items: p.items };
+    }),
+    types: planStacks(
+      items.filter((it) => cardOf(it)),
+      cardOf,
+      "type",
+    ).map((col) => ({ ...col, count: col.items.length })),
+    avgManaValue: mvCount ? mvSum / mvCount : null,
+    lands,
+    identity: COLOR_NAMES.map(([c]) => c).filter((c) => identity.has(c)),
+  };
+}
+
+// "White, Blue, Green" / "Colorless"
+export function describeIdentity(identity: string[]): string {
+  if (!identity.length) return "Colorless";
+  return identity
+    .map((c) => COLOR_NAMES.find(([k]) => k === c)?.[1] ?? c)
+    .join(", ");
+}
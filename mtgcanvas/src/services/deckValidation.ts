@@ -0,0 +1,240 @@
+// Deck validation for groups marked as a deck for a format (persisted in
+// GroupRow.transform_json). Checks deck and sideboard size, copy limits, legality
+// and, for commander formats, the commander choice and color identity.
+// Violations keep the offending items so the UI can select them.
+import type { Card } from "../types/card";
+import { formatPredicate } from "../search/scryfallQuery";
+
+export interface DeckFormatRules {
+  label: string;
+  deckSize: number;
+  exact?: boolean; // deck must have exactly deckSize cards
+  copies: number; // max copies of a card by name (basic lands exempt)
+  sideboard: number; // max sideboard cards
+  commander?: boolean; // commander/partners chosen from the deck
+}
+
+// Keys are Scryfall legality keys
+export const DECK_FORMATS: Record<string, DeckFormatRules> = {
+  standard: { label: "Standard", deckSize: 60, copies: 4, sideboard: 15 },
+  pioneer: { label: "Pioneer", deckSize: 60, copies: 4, sideboard: 15 },
+  modern: { label: "Modern", deckSize: 60, copies: 4, sideboard: 15 },
+  legacy: { label: "Legacy", deckSize: 60, copies: 4, sideboard: 15 },
+  vintage: { label: "Vintage", deckSize: 60, copies: 4, sideboard: 15 },
+  pauper: { label: "Pauper", deckSize: 60, copies: 4, sideboard: 15 },
+  commander: {
+    label: "Commander",
+    deckSize: 100,
+    exact: true,
+    copies: 1,
+    sideboard: 0,
+    commander: true,
+  },
+  brawl: {
+    label: "Brawl",
+    deckSize: 100,
+    exact: true,
+    copies: 1,
+    sideboard: 0,
+    commander: true,
+  },
+  standardbrawl: {
+    label: "Standard Brawl",
+    deckSize: 60,
+    exact: true,
+    copies: 1,
+    sideboard: 0,
+    commander: true,
+  },
+};
+
+// Deck settings of a group; commanders are card instance ids
+export interface GroupDeck {
+  format: string;
+  commanders: number[];
+}
+
+export function parseGroupDeck(v: unknown): GroupDeck | null {
+  if (!v || typeof v !== "object") return null;
+  const { format, commanders } = v as Record<string, unknown>;
+  if (typeof format !== "string" || !DECK_FORMATS[format]) return null;
+  return {
+    format,
+    commanders: Array.isArray(commanders)
+      ? commanders.filter((id): id is number => typeof id === "number")
+      : [],
+  };
+}
+
+// A nested group with this name holds the sideboard
+export function isSideboardName(name: string): boolean {
+  return /^\s*side\s*board\s*$/i.test(name);
+}
+
+export type DeckViolationKind =
+  | "count"
+  | "sideboard"
+  | "copies"
+  | "legality"
+  | "commander"
+  | "identity";
+
+export interface DeckViolation<T> {
+  kind: DeckViolationKind;
+  message: string;
+  items: T[];
+}
+
+export interface DeckParts<T> {
+  main: T[]; // includes the commanders
+  sideboard: T[];
+  commanders: T[];
+}
+
+const WUBRG = ["W", "U", "B", "R", "G"];
+
+function identityOf(card: Card): string[] {
+  const ci = card.color_identity ?? [];
+  return typeof ci === "string" ? ci.toUpperCase().split("") : ci;
+}
+
+// Basics and cards like Relentless Rats ignore the copy limit
+function unlimitedCopies(card: Card): boolean {
+  if (/\bBasic\b/.test(card.type_line || "")) return true;
+  return /A deck can have any number of cards named/i.test(
+    card.oracle_text || "",
+  );
+}
+
+function canBeCommander(card: Card): boolean {
+  const tl = card.type_line || "";
+  if (/\bLegendary\b/.test(tl) && /\bCreature\b/.test(tl)) return true;
+  return /can be your commander/i.test(card.oracle_text || "");
+}
+
+function hasKeyword(card: Card, kw: RegExp): boolean {
+  return (card.keywords ?? []).some((k) => kw.test(k));
+}
+
+// `bg` is a Background its partner `x` may bring along ("Choose a Background")
+function backgroundFor(x: Card, bg: Card): boolean {
+  return (
+    /choose a background/i.test(x.oracle_text || "") &&
+    /\bBackground\b/.test(bg.type_line || "")
+  );
+}
+
+// Two commanders are allowed for partners or a commander with its Background
+function validPair(a: Card, b: Card): boolean {
+  const partner = /^(partner|friends forever|doctor's companion)/i;
+  if (hasKeyword(a, partner) && hasKeyword(b, partner)) return true;
+  return backgroundFor(a, b) || backgroundFor(b, a);
+}
+
+const plural = (n: number, word: string) => `${n} ${word}${n === 1 ? "" : "s"}`;
+
+export function validateDeck<T>(
+  format: string,
+  parts: DeckParts<T>,
+  cardOf: (item: T) => Card | null | undefined,
+): DeckViolation<T>[] {
+  const rules = DECK_FORMATS[format];
+  if (!rules) return [];
+  const out: DeckViolation<T>[] = [];
+  const { main, sideboard, commanders } = parts;
+
+  // Sizes
+  const size = main.length;
+  if (rules.exact ? size !== rules.deckSize : size < rules.deckSize) {
+    const need = rules.exact ? "needs exactly" : "needs at least";
+    out.push({
+      kind: "count",
+      message: `Deck has ${plural(size, "card")} (${need} ${rules.deckSize})`,
+      items: [],
+    });
+  }
+  if (sideboard.length > rules.sideboard)
+    out.push({
+      kind: "sideboard",
+      message: rules.sideboard
+        ? `Sideboard has ${plural(sideboard.length, "card")} (max ${rules.sideboard})`
+        : `${rules.label} decks have no sideboard`,
+      items: sideboard,
+    });
+
+  // Copy limits count the deck and sideboard together
+  const legal = formatPredicate(format, "legal", false);
+  const banned = formatPredicate(format, "banned", false);
+  const restricted = formatPredicate(format, "restricted", false);
+  const byName = new Map<string, { card: Card; items: T[] }>();
+  for (const it of [...main, ...sideboard]) {
+    const card = cardOf(it);
+    if (!card?.name) continue;
+    const entry = byName.get(card.name);
+    if (entry) entry.items.push(it);
+    else byName.set(card.name, { card, items: [it] });
+  }
+  byName.forEach(({ card, items }, name) => {
+    const limit = restricted(card) ? 1 : rules.copies;
+    if (items.length > limit && !unlimitedCopies(card))
+      out.push({
+        kind: "copies",
+        message: `${items.length} copies of ${name} (max ${limit})`,
+        items,
+      });
+    // Cards without legality data can't be judged
+    if (!card.legalities) return;
+    if (banned(card))
+      out.push({ kind: "legality", message: `${name} is banned`, items });
+    else if (!legal(card) && !restricted(card))
+      out.push({
+        kind: "legality",
+        message: `${name} is not legal in ${rules.label}`,
+        items,
+      });
+  });
+
+  if (!rules.commander) return out;
+  const cmdCards = commanders
+    .map((it) => [it, cardOf(it)] as const)
+    .filter((p): p is readonly [T, Card] => !!p[1]);
+  if (!cmdCards.length) {
+    out.push({ kind: "commander", message: "No commander chosen", items: [] });
+    return out;
+  }
+  for (const [it, card] of cmdCards) {
+    // A Background is no creature; it commands beside a "Choose a Background" one
+    const background = cmdCards.some(
+      ([other, c]) => other !== it && backgroundFor(c, card),
+    );
+    if (!canBeCommander(card) && !background)
+      out.push({
+        kind: "commander",
+        message: `${card.name} can't be your commander`,
+        items: [it],
+      });
+  }
+  if (
+    cmdCards.length > 2 ||
+    (cmdCards.length === 2 && !validPair(cmdCards[0][1], cmdCards[1][1]))
+  )
+    out.push({
+      kind: "commander",
+      message: `${cmdCards.map(([, c]) => c.name).join(", ")} can't share command`,
+      items: cmdCards.map(([it]) => it),
+    });
+  const identity = new Set(cmdCards.flatMap(([, c]) => identityOf(c)));
+  const outside = main.filter((it) => {
+    const card = cardOf(it);
+    return !!card && identityOf(card).some((c) => !identity.has(c));
+  });
+  if (outside.length) {
+    const colors = WUBRG.filter((c) => identity.has(c)).join("") || "colorless";
+    out.push({
+      kind: "identity",
+      message: `${plural(outside.length, "card")} outside the commander's color identity (${colors})`,
+      items: outside,
+    });
+  }
+  return out;
+}
@@ -35,8 +35,40 @@ export function extractBaseCardName(raw: string): string {
   return out.trim();
 }
 
-export function parseDecklist(text: string): { name: string; count: number }[] {
-  const out: { name: string; count: number }[] = [];
+// A single card reference with the printing metadata extractBaseCardName strips.
+export interface DeckEntry {
+  name: string;
+  set?: string; // upper-case set code
+  collector_number?: string;
+  foil?: boolean;
+}
+
+// Parse "Name (SET) 123 *F*" / "Name [SET] 123" into a structured entry.
+// Fields that are not present are omitted.
+export function parseCardEntry(raw: string): DeckEntry {
+  const s = (raw || "").trim();
+  const name = extractBaseCardName(s);
+  const out: DeckEntry = { name };
+  const rest = s.slice(name.length);
+  const pm = rest.match(/^\s*[([]([A-Za-z0-9]{2,6})[)\]](?:\s+([^\s*]+))?/);
+  if (pm) {
+    out.set = pm[1].toUpperCase();
+    if (pm[2]) out.collector_number = pm[2];
+  }
+  if (/\*(?:f|foil)\*/i.test(rest)) out.foil = true;
+  return out;
+}
+
+// Decklist line item: entry + count (printing fields only when present in the text)
+export type DecklistItem = DeckEntry & { count: number };
+
+function entryKey(e: DeckEntry): string {
+  const foil = e.foil ? "f" : "";
+  return `${e.name}|${e.set || ""}|${e.collector_number || ""}|${foil}`;
+}
+
+export function parseDecklist(text: string): DecklistItem[] {
+  const out: DecklistItem[] = [];
   const lines = text.split(/\r?\n/);
   for (const rawLine of lines) {
     let line = rawLine.trim();
@@ -49,40 +81,81 @@ export function parseDecklist(text: string): { name: string; count: number }[] {
     // Also accept leading count with 'x': "3x Lightning Bolt"
     let m = line.match(/^(\d+)\s*[xX]\s+(.+)$/);
     if (m) {
-      const base = extractBaseCardName(m[2]);
-      out.push({ count: Math.max(1, parseInt(m[1], 10)), name: base });
+      const count = Math.max(1, parseInt(m[1], 10));
+      out.push({ ...parseCardEntry(m[2]), count });
       continue;
     }
     m = line.match(/^(\d+)\s+(.+)$/);
     if (m) {
-      const base = extractBaseCardName(m[2]);
-      out.push({ count: Math.max(1, parseInt(m[1], 10)), name: base });
+      const count = Math.max(1, parseInt(m[1], 10));
+      out.push({ ...parseCardEntry(m[2]), count });
       continue;
     }
     m = line.match(/^(.+?)\s*[xX]\s*(\d+)$/);
     if (m) {
-      const base = extractBaseCardName(m[1]);
-      out.push({ name: base, count: Math.max(1, parseInt(m[2], 10)) });
+      const count = Math.max(1, parseInt(m[2], 10));
+      out.push({ ...parseCardEntry(m[1]), count });
       continue;
     }
-    out.push({ name: extractBaseCardName(line), count: 1 });
+    out.push({ ...parseCardEntry(line), count: 1 });
+  }
+  // Combine duplicates (same name and printing)
+  const grouped = new Map<string, DecklistItem>();
+  for (const it of out) {
+    const key = entryKey(it);
+    const prev = grouped.get(key);
+    if (prev) prev.count += it.count;
+    else grouped.set(key, it);
   }
-  // Combine duplicates
-  const grouped = new Map<string, number>();
-  for (const it of out)
-    grouped.set(it.name, (grouped.get(it.name) || 0) + it.count);
-  return [...grouped.entries()].map(([name, count]) => ({ name, count }));
+  return [...grouped.values()];
 }
 
-export function parseGroupsText(
-  text: string,
-): { groups: { name: string; cards: string[] }[]; ungrouped: string[] } | null {
+// Grouped text: `cards`/`ungrouped` hold base names; `entries`/`ungroupedEntries` are the
+// parallel structured entries (same order) carrying any printing metadata.
+// `depth` is the heading level ("#" = 1, "##" = 2, ...); deeper headings nest in the previous group.
+// `combos` are the connector lines listed under the "#combos" sentinel heading.
+export interface GroupsText {
+  groups: {
+    name: string;
+    depth: number;
+    cards: string[];
+    entries: DeckEntry[];
+  }[];
+  ungrouped: string[];
+  ungroupedEntries: DeckEntry[];
+  combos: ComboLine[];
+}
+
+// Connector between two cards or groups (by name): "From -> To | label"
+export interface ComboLine {
+  from: string;
+  to: string;
+  label: string;
+}
+
+export function formatComboLine(c: ComboLine): string {
+  return `${c.from} -> ${c.to}${c.label ? ` | ${c.label}` : ""}`;
+}
+
+export function parseComboLine(line: string): ComboLine | null {
+  const m = /^(.+?)\s*->\s*(.+?)(?:\s*\|\s*(.*))?$/.exec(line.trim());
+  if (!m) return null;
+  const from = m[1].trim();
+  const to = m[2].trim();
+  if (!from || !to) return null;
+  return { from, to, label: (m[3] ?? "").trim() };
+}
+
+export function parseGroupsText(text: string): GroupsText | null {
   const lines = text.split(/\r?\n/);
   let hasHeading = false;
-  const groups: { name: string; cards: string[] }[] = [];
+  const groups: GroupsText["groups"] = [];
   const ungrouped: string[] = [];
-  let current: { name: string; cards: string[] } | null = null;
+  const ungroupedEntries: DeckEntry[] = [];
+  let current: GroupsText["groups"][number] | null = null;
   let inUngrouped = false;
+  const combos: ComboLine[] = [];
+  let inCombos = false;
   for (const raw of lines) {
     const line = raw.trim();
     if (!line) continue;
@@ -93,20 +166,35 @@ export function parseGroupsText(
       if (/^#ungrouped$/i.test(line)) {
         current = null;
         inUngrouped = true;
+        inCombos = false;
+        continue;
+      }
+      // Connector sentinel: exactly "#combos"; its lines are not cards
+      if (/^#combos$/i.test(line)) {
+        current = null;
+        inUngrouped = false;
+        inCombos = true;
         continue;
       }
-      const name = line.replace(/^#+\s*/, "").trim();
+      inCombos = false;
+      const depth = line.match(/^#+/)![0].length;
+      const name = line.slice(depth).trim();
       if (!name) {
         current = null;
         inUngrouped = false;
         continue;
       }
       inUngrouped = false;
-      current = { name, cards: [] };
+      current = { name, depth, cards: [], entries: [] };
       groups.push(current);
       continue;
     }
     if (/^\((empty|none)\)$/i.test(line)) continue; // ignore placeholders
+    if (inCombos) {
+      const combo = parseComboLine(line.replace(/^[-*]\s*/, ""));
+      if (combo) combos.push(combo);
+      continue;
+    }
     // List items: plain lines or those starting with '-'/'*'. Optional leading count.
     let item = line;
     const m = line.match(/^[-*]\s*(.+)$/);
@@ -125,14 +213,124 @@ export function parseGroupsText(
       }
     }
     if (!item) continue;
-    const pushMany = (arr: string[]) => {
-      const base = extractBaseCardName(item);
-      for (let i = 0; i < count; i++) arr.push(base);
+    const pushMany = (arr: string[], entries: DeckEntry[]) => {
+      const entry = parseCardEntry(item);
+      for (let i = 0; i < count; i++) {
+        arr.push(entry.name);
+        entries.push(entry);
+      }
     };
-    if (inUngrouped) pushMany(ungrouped);
-    else if (current) pushMany(current.cards);
-    else pushMany(ungrouped);
+    if (current && !inUngrouped) pushMany(current.cards, current.entries);
+    else pushMany(ungrouped, ungroupedEntries);
   }
   if (!hasHeading && !(ungrouped.length && groups.length === 0)) return null;
-  return { groups, ungrouped };
+  return { groups, ungrouped, ungroupedEntries, combos };
+}
+
+// --- MTG Arena deck format ---
+// Arena exports look like:
+//   Commander
+//   1 Omo, Queen of Vesuva (M3C) 2
+//
+//   Deck
+//   1 Arcane Signet (M3C) 283
+//   30 Forest (MH3) 318
+// An optional "About" / "Name <deck>" preamble is ignored.
+export interface ArenaCard extends DeckEntry {
+  count: number;
+}
+
+export interface ArenaSection {
+  name: string;
+  cards: ArenaCard[];
+}
+
+// Canonical section order used by Arena when exporting
+export const ARENA_SECTIONS = [
+  "Commander",
+  "Companion",
+  "Deck",
+  "Sideboard",
+] as const;
+
+export function arenaSectionName(line: string): string | null {
+  const low = line.trim().toLowerCase();
+  for (const s of ARENA_SECTIONS) if (s.toLowerCase() === low) return s;
+  return null;
+}
+
+// "4 Lightning Bolt (M10) 146" -> { count: 4, name, set: "M10", collector_number: "146" }
+export function parseArenaLine(line: string): ArenaCard | null {
+  const m = line.trim().match(/^(\d+)\s*[xX]?\s+(.+)$/);
+  if (!m) return null;
+  const entry = parseCardEntry(m[2]);
+  if (!entry.name) return null;
+  return { ...entry, count: Math.max(1, parseInt(m[1], 10)) };
+}
+
+// Parse Arena text into sections. Returns null unless the text has a section header
+// (Commander/Companion/Deck/Sideboard/About) so plain lists, even ones carrying
+// "(SET) 123" printings, keep the decklist path.
+export function parseArenaDeck(text: string): ArenaSection[] | null {
+  const lines = text.split(/\r?\n/);
+  const sections: ArenaSection[] = [];
+  let current: ArenaSection | null = null;
+  let sawHeader = false;
+  let inAbout = false;
+  for (const raw of lines) {
+    const line = raw.trim();
+    if (!line) continue;
+    if (/^about$/i.test(line)) {
+      inAbout = true;
+      sawHeader = true;
+      continue;
+    }
+    const header = arenaSectionName(line);
+    if (header) {
+      inAbout = false;
+      sawHeader = true;
+      current = sections.find((s) => s.name === header) || null;
+      if (!current) {
+        current = { name: header, cards: [] };
+        sections.push(current);
+      }
+      continue;
+    }
+    if (inAbout) continue; // "Name My Deck" etc.
+    // Group-text headings mean this is not an Arena list
+    if (line.startsWith("#")) return null;
+    const card = parseArenaLine(line);
+    if (!card) return null;
+    if (!current) {
+      current = { name: "Deck", cards: [] };
+      sections.push(current);
+    }
+    current.cards.push(card);
+  }
+  if (!sawHeader) return null;
+  const out = sections.filter((s) => s.cards.length);
+  return out.length ? out : null;
+}
+
+// Format sections as Arena text (canonical section order; unknown sections last).
+export function formatArenaDeck(sections: ArenaSection[]): string {
+  const rank = (n: string) => {
+    const i = ARENA_SECTIONS.findIndex((s) => s === n);
+    return i >= 0 ? i : ARENA_SECTIONS.length;
+  };
+  return sections
+    .filter((s) => s.cards.length)
+    .sort((a, b) => rank(a.name) - rank(b.name))
+    .map((s) => {
+      const lines = [s.name];
+      for (const c of s.cards) {
+        const printing =
+          c.set && c.collector_number
+            ? ` (${c.set.toUpperCase()}) ${c.collector_number}`
+            : "";
+        lines.push(`${c.count} ${c.name}${printing}`);
+      }
+      return lines.join("\n");
+    })
+    .join("\n\n");
 }
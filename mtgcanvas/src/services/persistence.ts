@@ -1,5 +1,13 @@
 import type { CardSprite } from "../scene/cardNode";
+import {
+  cardBounds,
+  cardSize,
+  normalizeRotation,
+  normalizeScale,
+} from "../scene/cardTransform";
 import type { GroupVisual } from "../scene/groupNode";
+import type { NoteRecord } from "../scene/notes";
+import type { ConnectorRecord } from "../scene/connectors";
 import type { SpatialIndex } from "../scene/SpatialIndex";
 import { InstancesRepo } from "../data/repositories";
 
@@ -14,6 +22,10 @@ export type PositionsPayload = {
     z: number;
     group_id: number | null;
     scryfall_id?: string | null;
+    foil?: boolean;
+    rotation?: number; // degrees clockwise; absent means 0
+    scale?: number; // absent means 1
+    tags?: string; // CardInstance.tags; absent means none
   }>;
   byIndex?: Array<{ x: number; y: number }>;
 };
@@ -27,6 +39,11 @@ function buildPositionsPayload(sprites: CardSprite[]): PositionsPayload {
       z: (s.zIndex as number) || (s as any).__baseZ || 0,
       group_id: (s as any).__groupId ?? null,
       scryfall_id: (s as any).__scryfallId || ((s as any).__card?.id ?? null),
+      // Only written when set to keep payloads small
+      foil: s.__foil || undefined,
+      rotation: s.__rotation || undefined,
+      scale: s.__scale != null && s.__scale !== 1 ? s.__scale : undefined,
+      tags: InstancesRepo.get(s.__id)?.tags || undefined,
     })),
     byIndex: sprites.map((s) => ({ x: s.x, y: s.y })),
   };
@@ -38,9 +55,14 @@ function savePositionsLS(
 ) {
   try {
     const data = buildPositionsPayload(sprites);
-    // Mirror z into repository for in-memory consistency
+    // Mirror z and transform into repository for in-memory consistency
     InstancesRepo.updateMany(
-      data.instances.map((r) => ({ id: r.id, z: r.z })) as any,
+      data.instances.map((r) => ({
+        id: r.id,
+        z: r.z,
+        rotation: r.rotation ?? 0,
+        scale: r.scale ?? 1,
+      })),
     );
     localStorage.setItem(key, JSON.stringify(data));
   } catch {
@@ -57,10 +79,16 @@ function savePositionsLS(
   }
 }
 
+// Sticky notes and connectors ride along in the groups payload (`notes`, `connectors`),
+// so project copies carry them
+type SceneExtras = { notes?: NoteRecord[]; connectors?: ConnectorRecord[] };
 function saveGroupsLS(
   groups: Map<number, GroupVisual>,
   key: string = LS_GROUPS_KEY,
+  extras: SceneExtras = {},
 ) {
+  const notes = extras.notes?.length ? extras.notes : undefined;
+  const connectors = extras.connectors?.length ? extras.connectors : undefined;
   try {
     const data = {
       groups: [...groups.values()].map((gv) => ({
@@ -71,8 +99,18 @@ function saveGroupsLS(
         h: gv.h,
         z: (gv.gfx.zIndex as number) || 0,
         name: gv.name,
+        parent_id: gv.parentId ?? null,
+        collapsed: gv.collapsed || undefined,
+        expanded: gv.collapsed ? gv.expandedSize : undefined,
+        layout: gv.layoutMode !== "grid" ? gv.layoutMode : undefined,
+        sort: gv.sortSpec.length ? gv.sortSpec : undefined,
+        stack: gv.stackDuplicates || undefined,
+        deck: gv.deck ?? undefined,
+        smart: gv.smart ?? undefined,
         membersById: gv.order.map((s: CardSprite) => s.__id),
       })),
+      notes,
+      connectors,
     };
     localStorage.setItem(key, JSON.stringify(data));
   } catch {
@@ -85,7 +123,17 @@ function saveGroupsLS(
         h: gv.h,
         z: (gv.gfx.zIndex as number) || 0,
         name: gv.name,
+        parent_id: gv.parentId ?? null,
+        collapsed: gv.collapsed || undefined,
+        expanded: gv.collapsed ? gv.expandedSize : undefined,
+        layout: gv.layoutMode !== "grid" ? gv.layoutMode : undefined,
+        sort: gv.sortSpec.length ? gv.sortSpec : undefined,
+        stack: gv.stackDuplicates || undefined,
+        deck: gv.deck ?? undefined,
+        smart: gv.smart ?? undefined,
       })),
+      notes,
+      connectors,
     };
     localStorage.setItem(key, JSON.stringify(framesOnly));
   }
@@ -130,6 +178,9 @@ export function applyStoredPositions(
         group_id?: number | null;
         scryfall_id?: string | null;
         z?: number;
+        foil?: boolean;
+        rotation?: number;
+        scale?: number;
       }
     >();
     for (const r of obj.instances) {
@@ -139,15 +190,21 @@ export function applyStoredPositions(
     sprites.forEach((s) => {
       const p = map.get(s.__id);
       if (p) {
+        // Callers re-apply the sprite transform (applyCardTransform) afterwards
+        s.__rotation = normalizeRotation(p.rotation) || undefined;
+        const k = normalizeScale(p.scale);
+        s.__scale = k !== 1 ? k : undefined;
         const b = getCanvasBounds();
-        s.x = Math.min(b.x + b.w - cardW, Math.max(b.x, p.x));
-        s.y = Math.min(b.y + b.h - cardH, Math.max(b.y, p.y));
+        const size = cardSize(s);
+        s.x = Math.min(b.x + b.w - size.w, Math.max(b.x, p.x));
+        s.y = Math.min(b.y + b.h - size.h, Math.max(b.y, p.y));
         if (typeof p.z === "number") {
           s.zIndex = p.z as number;
           (s as any).__baseZ = p.z as number;
         }
         if (p.group_id != null) (s as any).__groupId = p.group_id;
         if (p.scryfall_id) (s as any).__scryfallId = p.scryfall_id;
+        if (p.foil) s.__foil = true;
         matched++;
       }
     });
@@ -161,21 +218,15 @@ export function applyStoredPositions(
         }
       });
     }
-    sprites.forEach((s) =>
-      spatial.update({
-        sprite: s,
-        minX: s.x,
-        minY: s.y,
-        maxX: s.x + cardW,
-        maxY: s.y + cardH,
-      }),
-    );
+    sprites.forEach((s) => spatial.update({ sprite: s, ...cardBounds(s) }));
   }
 }
 
 export function createLocalPersistence(deps: {
   getSprites: () => CardSprite[];
   getGroups: () => Map<number, GroupVisual>;
+  getNotes?: () => NoteRecord[];
+  getConnectors?: () => ConnectorRecord[];
   spatial: SpatialIndex;
   getCanvasBounds: () => { x: number; y: number; w: number; h: number };
   cardW: number;
@@ -220,16 +271,22 @@ export function createLocalPersistence(deps: {
     groupTimer = setTimeout(() => {
       groupTimer = null;
       if (suppressed) return;
-      saveGroupsLS(deps.getGroups(), GRP_KEY);
+      saveGroupsLS(deps.getGroups(), GRP_KEY, extras());
     }, 400);
   }
+  function extras(): SceneExtras {
+    return {
+      notes: deps.getNotes?.(),
+      connectors: deps.getConnectors?.(),
+    };
+  }
   function flushGroups() {
     if (suppressed) {
       groupTimer = null;
       return;
     }
     groupTimer = null;
-    saveGroupsLS(deps.getGroups(), GRP_KEY);
+    saveGroupsLS(deps.getGroups(), GRP_KEY, extras());
   }
   function setSuppressed(v: boolean) {
     suppressed = v;
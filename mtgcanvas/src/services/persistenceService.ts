@@ -1,6 +1,10 @@
 import type { CardSprite } from "../scene/cardNode";
 import { InstancesRepo, GroupsRepo } from "../data/repositories";
 import type { CardInstance, GroupRow } from "../types/repositories";
+import type { GroupLayoutMode } from "../scene/stackLayout";
+import type { GroupSortSpec } from "../scene/groupSort";
+import type { GroupDeck } from "./deckValidation";
+import type { SmartGroupRule } from "./smartGroups";
 
 export interface LoadedData {
   instances: CardInstance[];
@@ -36,6 +40,31 @@ export function persistGroupTransform(
 ) {
   GroupsRepo.updateTransform(id, t);
 }
+export function persistGroupCollapsed(
+  id: number,
+  collapsed: boolean,
+  expanded: { w: number; h: number } | null,
+) {
+  GroupsRepo.setCollapsed(id, collapsed, expanded);
+}
+export function persistGroupLayout(id: number, layout: GroupLayoutMode) {
+  GroupsRepo.setLayout(id, layout);
+}
+export function persistGroupSort(id: number, sort: GroupSortSpec) {
+  GroupsRepo.setSort(id, sort);
+}
+export function persistGroupStacking(id: number, stack: boolean) {
+  GroupsRepo.setStacking(id, stack);
+}
+export function persistGroupDeck(id: number, deck: GroupDeck | null) {
+  GroupsRepo.setDeck(id, deck);
+}
+export function persistGroupSmart(id: number, smart: SmartGroupRule | null) {
+  GroupsRepo.setSmart(id, smart);
+}
 export function persistGroupRename(id: number, name: string) {
   GroupsRepo.rename(id, name);
 }
+export function persistGroupParent(id: number, parentId: number | null) {
+  GroupsRepo.setParent(id, parentId);
+}
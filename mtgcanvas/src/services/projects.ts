@@ -1,12 +1,26 @@
 // Minimal project management backed by localStorage.
 // A project encapsulates the positions and groups data (what we already persist)
-// under namespaced keys, plus a small meta record (id, name, timestamps).
+// under namespaced keys, plus a small meta record (id, name, timestamps, view bookmarks).
+
+// A saved camera view: the world rectangle that was on screen, recalled by fitting it
+// back into the viewport (so it survives window resizes). Slots map to number keys.
+export type CameraBookmark = {
+  slot: number; // 1..MAX_BOOKMARKS
+  name: string;
+  x: number;
+  y: number;
+  w: number;
+  h: number;
+};
+
+export const MAX_BOOKMARKS = 9;
 
 export type ProjectMeta = {
   id: string;
   name: string;
   createdAt: number;
   updatedAt: number;
+  bookmarks?: CameraBookmark[];
 };
 
 const LS_PROJECTS_INDEX = "mtgcanvas_projects_index_v1" as const;
@@ -50,6 +64,25 @@ function writeIndex(ids: string[]) {
   }
 }
 
+// Drop malformed entries and duplicate slots; result is sorted by slot
+export function normalizeBookmarks(raw: unknown): CameraBookmark[] {
+  if (!Array.isArray(raw)) return [];
+  const bySlot = new Map<number, CameraBookmark>();
+  for (const b of raw) {
+    if (!b || typeof b !== "object") continue;
+    const slot = Number(b.slot);
+    if (!Number.isInteger(slot) || slot < 1 || slot > MAX_BOOKMARKS) continue;
+    const [x, y, w, h] = [b.x, b.y, b.w, b.h].map(Number);
+    if (![x, y, w, h].every(Number.isFinite) || w <= 0 || h <= 0) continue;
+    const name =
+      typeof b.name === "string" && b.name.trim()
+        ? b.name.trim()
+        : `View ${slot}`;
+    bySlot.set(slot, { slot, name, x, y, w, h });
+  }
+  return [...bySlot.values()].sort((a, b) => a.slot - b.slot);
+}
+
 function readMeta(id: string): ProjectMeta | null {
   try {
     const raw = localStorage.getItem(projectMetaKey(id));
@@ -61,6 +94,7 @@ function readMeta(id: string): ProjectMeta | null {
       name: typeof m.name === "string" && m.name ? m.name : "Untitled Project",
       createdAt: Number(m.createdAt) || Date.now(),
       updatedAt: Number(m.updatedAt) || Date.now(),
+      bookmarks: normalizeBookmarks(m.bookmarks),
     };
   } catch {
     return null;
@@ -69,12 +103,13 @@ function readMeta(id: string): ProjectMeta | null {
 
 function writeMeta(meta: ProjectMeta) {
   try {
-    const save = {
+    const save: Record<string, unknown> = {
       id: meta.id,
       name: meta.name,
       createdAt: meta.createdAt,
       updatedAt: meta.updatedAt,
     };
+    if (meta.bookmarks?.length) save.bookmarks = meta.bookmarks;
     localStorage.setItem(projectMetaKey(meta.id), JSON.stringify(save));
   } catch {
     /* ignore */
@@ -190,6 +225,18 @@ export function touchProjectUpdated(id: string) {
   writeIndex(ids);
 }
 
+export function getProjectBookmarks(id: string): CameraBookmark[] {
+  return readMeta(id)?.bookmarks ?? [];
+}
+
+// Replace the project's bookmarks. Views aren't content, so updatedAt is left alone.
+export function setProjectBookmarks(id: string, bookmarks: CameraBookmark[]) {
+  const meta = readMeta(id);
+  if (!meta) return;
+  meta.bookmarks = normalizeBookmarks(bookmarks);
+  writeMeta(meta);
+}
+
 export function getProjectKeysFor(id: string): {
   positionsKey: string;
   groupsKey: string;
@@ -221,7 +268,7 @@ export function createProject(name?: string): ProjectMeta {
   return meta;
 }
 
-// Duplicate an existing project: copies positions and groups payloads and creates a new meta.
+// Duplicate an existing project: copies positions and groups payloads and bookmarks into a new meta.
 export function duplicateProject(
   sourceId: string,
   newName?: string,
@@ -241,6 +288,10 @@ export function duplicateProject(
     const dstGrpKey = projectGroupsKey(meta.id);
     if (pos != null) localStorage.setItem(dstPosKey, pos);
     if (grp != null) localStorage.setItem(dstGrpKey, grp);
+    if (srcMeta.bookmarks?.length) {
+      meta.bookmarks = srcMeta.bookmarks.map((b) => ({ ...b }));
+      writeMeta(meta);
+    }
     touchProjectUpdated(meta.id);
     return meta;
   } catch {
@@ -248,6 +299,64 @@ export function duplicateProject(
   }
 }
 
+// Serialize a project (name, bookmarks and its stored payloads) as a JSON backup.
+// Payloads are embedded as parsed JSON; card images stay in the shared IDB cache.
+export function exportProject(id: string): string | null {
+  const meta = readMeta(id);
+  if (!meta) return null;
+  const parse = (key: string) => {
+    try {
+      const raw = localStorage.getItem(key);
+      return raw ? JSON.parse(raw) : null;
+    } catch {
+      return null;
+    }
+  };
+  return JSON.stringify({
+    version: 1,
+    name: meta.name,
+    createdAt: meta.createdAt,
+    updatedAt: meta.updatedAt,
+    bookmarks: meta.bookmarks ?? [],
+    positions: parse(projectPositionsKey(id)),
+    groups: parse(projectGroupsKey(id)),
+  });
+}
+
+// Restore a backup written by exportProject as a new, current project (the project it
+// came from is left alone). Returns null when the text is not a project backup.
+export function importProject(json: string): ProjectMeta | null {
+  let data: any;
+  try {
+    data = JSON.parse(json);
+  } catch {
+    return null;
+  }
+  if (!data || typeof data !== "object" || data.version !== 1) return null;
+  try {
+    const meta = createProject(
+      typeof data.name === "string" ? data.name : undefined,
+    );
+    if (data.positions != null)
+      localStorage.setItem(
+        projectPositionsKey(meta.id),
+        JSON.stringify(data.positions),
+      );
+    if (data.groups != null)
+      localStorage.setItem(
+        projectGroupsKey(meta.id),
+        JSON.stringify(data.groups),
+      );
+    const createdAt = Number(data.createdAt);
+    if (Number.isFinite(createdAt) && createdAt > 0) meta.createdAt = createdAt;
+    meta.bookmarks = normalizeBookmarks(data.bookmarks);
+    writeMeta(meta);
+    return meta;
+  } catch {
+    return null;
+  }
+}
+
 // Delete a project by id. Removes from index and clears stored payloads/meta.
 // If deleting the current project, switches to the most-recent remaining project or creates a new one.
 export function deleteProject(id: string): {
@@ -448,6 +448,140 @@ export async function fetchScryfallByNames(
   return { byName, unknown: [...notFound] };
 }
 
+// Stable lookup key for a printing: "set/collector_number" (lowercase)
+export function printingKey(set: string, collectorNumber: string): string {
+  return `${(set || "").toLowerCase()}/${(collectorNumber || "").toLowerCase()}`;
+}
+
+/**
+ * Fetch exact printings by set code + collector number via /cards/collection.
+ * Returns a map keyed by printingKey(set, collector_number) and the list of
+ * identifiers Scryfall could not find (as "set/cn" keys).
+ */
+export async function fetchScryfallByPrintings(
+  ids: { set: string; collector_number: string }[],
+  opts: {
+    signal?: AbortSignal;
+    onProgress?: (done: number, total?: number) => void;
+  } = {},
+): Promise<{ byPrinting: Map<string, ScryfallCard>; unknown: string[] }> {
+  const unique: { set: string; collector_number: string }[] = [];
+  const seen = new Set<string>();
+  for (const id of ids) {
+    if (!id?.set || !id?.collector_number) continue;
+    const key = printingKey(id.set, id.collector_number);
+    if (seen.has(key)) continue;
+    seen.add(key);
+    unique.push({
+      set: id.set.toLowerCase(),
+      collector_number: String(id.collector_number),
+    });
+  }
+  const byPrinting = new Map<string, ScryfallCard>();
+  const total = unique.length;
+  let done = 0;
+  const signal = opts.signal;
+  if (signal?.aborted) throw new DOMException("Aborted", "AbortError");
+  opts.onProgress?.(done, total);
+  const CHUNK = 75;
+  for (let i = 0; i < unique.length; i += CHUNK) {
+    const batch = unique.slice(i, i + CHUNK);
+    let attempt = 0;
+    for (;;) {
+      // Space requests globally
+      await __scheduleScryfall();
+      const res = await fetch(
+        "https://api.scryfall.com/cards/collection",
+        __withAcceptHeader({
+          method: "POST",
+          headers: { "Content-Type": "application/json" },
+          body: JSON.stringify({ identifiers: batch }),
+          signal,
+        }),
+      );
+      if (res.ok) {
+        const json = (await res.json()) as any;
+        const data = Array.isArray(json?.data)
+          ? (json.data as ScryfallCard[])
+          : [];
+        for (const c of data) {
+          if (c?.set && c?.collector_number)
+            byPrinting.set(printingKey(c.set, c.collector_number), c);
+        }
+        break;
+      }
+      // Retry on 429/5xx with backoff and Retry-After support
+      if (
+        (res.status === 429 || (res.status >= 500 && res.status < 600)) &&
+        attempt < 4
+      ) {
+        const ra = res.headers.get("Retry-After");
+        let waitMs = ra
+          ? Math.max(0, Math.floor(parseFloat(ra) * 1000))
+          : 250 * Math.pow(2, attempt);
+        waitMs += Math.floor(Math.random() * 100);
+        await __delay(waitMs);
+        attempt++;
+        continue;
+      }
+      const text = await safeText(res);
+      throw new Error(
+        `Scryfall collection error ${res.status}: ${text || res.statusText}`,
+      );
+    }
+    done += batch.length;
+    opts.onProgress?.(done, total);
+  }
+  const unknown = [...seen].filter((k) => !byPrinting.has(k));
+  return { byPrinting, unknown };
+}
+
+/**
+ * Name completions for a partial or misspelled name via /cards/autocomplete
+ * (up to 20 names). Returns an empty list on errors or 404s.
+ */
+export async function fetchScryfallAutocomplete(
+  q: string,
+  opts: { signal?: AbortSignal } = {},
+): Promise<string[]> {
+  const query = (q || "").trim();
+  if (query.length < 2) return [];
+  const url = `https://api.scryfall.com/cards/autocomplete?q=${encodeURIComponent(query)}`;
+  try {
+    await __scheduleScryfall();
+    const res = await fetch(url, __withAcceptHeader({ signal: opts.signal }));
+    if (!res.ok) return [];
+    const json = (await res.json()) as any;
+    return Array.isArray(json?.data) ? (json.data as string[]) : [];
+  } catch (e: any) {
+    if (e?.name === "AbortError") throw e;
+    return [];
+  }
+}
+
+/**
+ * Scryfall's best guess for a misspelled name via /cards/named?fuzzy= (one card).
+ * Returns null when nothing matches, the match is ambiguous, or on errors.
+ */
+export async function fetchScryfallFuzzyName(
+  q: string,
+  opts: { signal?: AbortSignal } = {},
+): Promise<string | null> {
+  const query = (q || "").trim();
+  if (query.length < 2) return null;
+  const url = `https://api.scryfall.com/cards/named?fuzzy=${encodeURIComponent(query)}`;
+  try {
+    await __scheduleScryfall();
+    const res = await fetch(url, __withAcceptHeader({ signal: opts.signal }));
+    if (!res.ok) return null;
+    const json = (await res.json()) as any;
+    return typeof json?.name === "string" ? json.name : null;
+  } catch (e: any) {
+    if (e?.name === "AbortError") throw e;
+    return null;
+  }
+}
+
 async function safeText(res: Response): Promise<string | null> {
   try {
     return await res.text();
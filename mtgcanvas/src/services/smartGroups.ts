@@ -0,0 +1,124 @@
+// Smart groups: membership is a saved search query (parseScryfallQuery syntax) over a scope
+// of the canvas instead of a hand-picked list, e.g. `t:land -t:basic` pulled from the
+// ungrouped cards, or "o:add t:artifact" from the Omo deck. The rule is persisted in
+// GroupRow.transform_json; this module plans who joins and leaves, main moves the cards.
+
+import { compileScryfallQuery, segmentQuery } from "../search/scryfallQuery";
+
+export type SmartGroupScope =
+  | { kind: "ungrouped" } // cards outside any group
+  | { kind: "canvas" } // every card not held by another smart group
+  | { kind: "group"; id: number }; // a group's cards (nested groups included)
+
+export interface SmartGroupRule {
+  query: string;
+  scope: SmartGroupScope;
+}
+
+export const SMART_QUERY_MAX = 200;
+
+export function parseSmartGroupRule(v: unknown): SmartGroupRule | null {
+  if (!v || typeof v !== "object") return null;
+  const { query, scope } = v as Record<string, unknown>;
+  if (typeof query !== "string" || !query.trim()) return null;
+  const s = (scope ?? {}) as Record<string, unknown>;
+  let parsed: SmartGroupScope = { kind: "ungrouped" };
+  if (s.kind === "canvas") parsed = { kind: "canvas" };
+  else if (s.kind === "group" && Number.isInteger(s.id) && Number(s.id) > 0)
+    parsed = { kind: "group", id: Number(s.id) };
+  return { query: query.trim().slice(0, SMART_QUERY_MAX), scope: parsed };
+}
+
+// Why a query can't drive a smart group, or null. group: and in:ungrouped / in:grouped
+// test the membership the group itself changes: a pulled card would stop matching and be
+// released, then match again on the next refresh. The scope picks where cards come from.
+export function smartQueryError(query: string): string | null {
+  const { error } = compileScryfallQuery(query);
+  if (error) return error;
+  const segs = segmentQuery(query);
+  const text = (i: number) =>
+    segs[i] ? query.slice(segs[i].start, segs[i].end).toLowerCase() : "";
+  for (let i = 0; i < segs.length; i++) {
+    if (segs[i].kind !== "field") continue;
+    const field = text(i);
+    // field, operator, then the value when there is one
+    const value = segs[i + 2]?.start === segs[i + 1]?.end ? text(i + 2) : "";
+    const membership =
+      field === "group" ||
+      (field === "in" && /^"?(un)?grouped"?$/.test(value));
+    if (membership)
+      return `${field}: can't drive a smart group; pick a scope instead`;
+  }
+  return null;
+}
+
+export function describeSmartScope(
+  scope: SmartGroupScope,
+  groupName: (id: number) => string | undefined,
+): string {
+  if (scope.kind === "ungrouped") return "ungrouped cards";
+  if (scope.kind === "canvas") return "the whole canvas";
+  const name = groupName(scope.id);
+  return name ? `"${name}"` : "a deleted group";
+}
+
+// What the planner needs to know about each group
+export type SmartGroupInfo = { parentId: number | null; smart: boolean };
+
+export interface SmartGroupPlan {
+  pull: number[]; // card ids joining the smart group
+  release: { id: number; to: number | null }[]; // members leaving, and where to (null = ungrouped)
+}
+
+function isWithin(
+  gid: number,
+  ancestorId: number,
+  groups: Map<number, SmartGroupInfo>,
+) {
+  const seen = new Set<number>();
+  let cur: number | null | undefined = gid;
+  while (cur != null && !seen.has(cur)) {
+    if (cur === ancestorId) return true;
+    seen.add(cur);
+    cur = groups.get(cur)?.parentId;
+  }
+  return false;
+}
+
+// Plan one smart group against the current membership. Cards already held by a smart
+// group (this one's nested groups included) are never taken, so two overlapping rules
+// settle instead of trading cards back and forth. Members that stop matching go back to
+// the scope group, or out of any group.
+export function planSmartGroup(
+  groupId: number,
+  rule: SmartGroupRule,
+  cards: { id: number; groupId: number | null }[],
+  groups: Map<number, SmartGroupInfo>,
+  test: (cardId: number) => boolean,
+): SmartGroupPlan {
+  const { scope } = rule;
+  // A scope group inside the smart group itself would have it feed on its own cards
+  const scopeGroup =
+    scope.kind === "group" &&
+    groups.has(scope.id) &&
+    !isWithin(scope.id, groupId, groups)
+      ? scope.id
+      : null;
+  const inScope = (gid: number | null) => {
+    if (gid == null) return scope.kind !== "group";
+    if (scope.kind === "ungrouped") return false;
+    if (isWithin(gid, groupId, groups)) return false;
+    if (groups.get(gid)?.smart) return false;
+    if (scope.kind === "canvas") return true;
+    return scopeGroup != null && isWithin(gid, scopeGroup, groups);
+  };
+  const plan: SmartGroupPlan = { pull: [], release: [] };
+  for (const c of cards) {
+    if (c.groupId === groupId) {
+      if (!test(c.id)) plan.release.push({ id: c.id, to: scopeGroup });
+    } else if (inScope(c.groupId) && test(c.id)) {
+      plan.pull.push(c.id);
+    }
+  }
+  return plan;
+}
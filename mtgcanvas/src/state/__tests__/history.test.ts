@@ -0,0 +1,90 @@
+import { describe, it, expect } from "vitest";
+import { createHistory } from "../history";
+
+describe("History", () => {
+  it("undoes and redoes in order", () => {
+    const h = createHistory();
+    let v = 0;
+    const set = (from: number, to: number) => {
+      v = to;
+      h.push({
+        label: `set ${to}`,
+        undo: () => (v = from),
+        redo: () => (v = to),
+      });
+    };
+    set(0, 1);
+    set(1, 2);
+    expect(h.undo()).toBe(true);
+    expect(v).toBe(1);
+    expect(h.undo()).toBe(true);
+    expect(v).toBe(0);
+    expect(h.undo()).toBe(false);
+    expect(h.redo()).toBe(true);
+    expect(v).toBe(1);
+    expect(h.peekRedoLabel()).toBe("set 2");
+  });
+
+  it("drops the redo branch on a new push", () => {
+    const h = createHistory();
+    h.push({ label: "a", undo: () => {}, redo: () => {} });
+    h.undo();
+    expect(h.canRedo()).toBe(true);
+    h.push({ label: "b", undo: () => {}, redo: () => {} });
+    expect(h.canRedo()).toBe(false);
+    expect(h.peekUndoLabel()).toBe("b");
+  });
+
+  it("merges entries with the same key inside the window", () => {
+    let t = 0;
+    const h = createHistory({ mergeWindowMs: 100, now: () => t });
+    const log: string[] = [];
+    h.push({
+      label: "move",
+      mergeKey: "k",
+      undo: () => log.push("undo1"),
+      redo: () => log.push("redo1"),
+    });
+    t = 50;
+    h.push({
+      label: "move",
+      mergeKey: "k",
+      undo: () => log.push("undo2"),
+      redo: () => log.push("redo2"),
+    });
+    t = 500;
+    h.push({
+      label: "move",
+      mergeKey: "k",
+      undo: () => log.push("undo3"),
+      redo: () => log.push("redo3"),
+    });
+    h.undo();
+    h.undo();
+    expect(log).toEqual(["undo3", "undo1"]);
+    expect(h.canUndo()).toBe(false);
+    h.redo();
+    expect(log[log.length - 1]).toBe("redo2");
+  });
+
+  it("ignores pushes made while applying an entry", () => {
+    const h = createHistory();
+    h.push({
+      label: "outer",
+      undo: () => h.push({ label: "inner", undo: () => {}, redo: () => {} }),
+      redo: () => {},
+    });
+    h.undo();
+    expect(h.canUndo()).toBe(false);
+    expect(h.peekRedoLabel()).toBe("outer");
+  });
+
+  it("caps the stack at the limit", () => {
+    const h = createHistory({ limit: 2 });
+    for (const l of ["a", "b", "c"])
+      h.push({ label: l, undo: () => {}, redo: () => {} });
+    h.undo();
+    h.undo();
+    expect(h.undo()).toBe(false);
+  });
+});
@@ -31,5 +31,31 @@ describe("SelectionStore", () => {
     s.selectOnlyGroup(20);
     expect(new Set(s.getCards())).toEqual(new Set());
     expect(new Set(s.getGroups())).toEqual(new Set([20]));
+    s.toggleNote(3);
+    expect(s.getNotes()).toEqual([3]);
+    s.selectOnlyNote(4);
+    expect(s.getGroups()).toEqual([]);
+    expect(s.getNotes()).toEqual([4]);
+    s.clear();
+    expect(s.isEmpty).toBe(true);
+  });
+
+  it("expands new selections before listeners see them", () => {
+    const s = createSelectionStore();
+    const rep = fakeSprite(1);
+    const copy = fakeSprite(2);
+    s.setCardExpander((cards) => {
+      if (cards.has(rep)) cards.add(copy);
+    });
+    const seen: number[] = [];
+    let calls = 0;
+    s.on(() => {
+      calls++;
+      seen.push(s.state.cards.size);
+    });
+    s.selectOnlyCard(rep);
+    s.toggleCard(fakeSprite(3));
+    expect(seen).toEqual([2, 3]);
+    expect(calls).toBe(2);
   });
 });
@@ -0,0 +1,131 @@
+// Undo/redo command stack. Each entry carries its own inverse so callers can
+// capture whatever state they touch (repos, sprites, group visuals).
+export interface HistoryEntry {
+  label: string;
+  undo: () => void;
+  redo: () => void;
+  // Consecutive entries with the same key inside the merge window collapse into one
+  mergeKey?: string;
+}
+
+export interface IHistory {
+  push(entry: HistoryEntry): void;
+  undo(): boolean;
+  redo(): boolean;
+  canUndo(): boolean;
+  canRedo(): boolean;
+  peekUndoLabel(): string | null;
+  peekRedoLabel(): string | null;
+  clear(): void;
+  // True while an entry's undo/redo runs; pushes are ignored during that time
+  readonly isApplying: boolean;
+  on(cb: () => void): () => void;
+}
+
+interface StoredEntry extends HistoryEntry {
+  at: number;
+}
+
+class HistoryImpl implements IHistory {
+  undoStack: StoredEntry[] = [];
+  redoStack: StoredEntry[] = [];
+  listeners: Set<() => void> = new Set();
+  applying = false;
+  constructor(
+    private limit: number,
+    private mergeWindowMs: number,
+    private now: () => number,
+  ) {}
+
+  get isApplying() {
+    return this.applying;
+  }
+  push(entry: HistoryEntry) {
+    if (this.applying) return;
+    const at = this.now();
+    const top = this.undoStack[this.undoStack.length - 1];
+    this.redoStack.length = 0;
+    if (
+      top &&
+      entry.mergeKey &&
+      top.mergeKey === entry.mergeKey &&
+      at - top.at <= this.mergeWindowMs
+    ) {
+      // Keep the oldest inverse, take the newest forward op
+      this.undoStack[this.undoStack.length - 1] = {
+        label: top.label,
+        mergeKey: top.mergeKey,
+        undo: top.undo,
+        redo: entry.redo,
+        at,
+      };
+    } else {
+      this.undoStack.push({ ...entry, at });
+      while (this.undoStack.length > this.limit) this.undoStack.shift();
+    }
+    this.emit();
+  }
+  undo() {
+    const e = this.undoStack.pop();
+    if (!e) return false;
+    this.run(e.undo);
+    this.redoStack.push(e);
+    this.emit();
+    return true;
+  }
+  redo() {
+    const e = this.redoStack.pop();
+    if (!e) return false;
+    this.run(e.redo);
+    // Redone entries never merge with whatever gets pushed next
+    this.undoStack.push({ ...e, at: -Infinity });
+    this.emit();
+    return true;
+  }
+  canUndo() {
+    return this.undoStack.length > 0;
+  }
+  canRedo() {
+    return this.redoStack.length > 0;
+  }
+  peekUndoLabel() {
+    return this.undoStack[this.undoStack.length - 1]?.label ?? null;
+  }
+  peekRedoLabel() {
+    return this.redoStack[this.redoStack.length - 1]?.label ?? null;
+  }
+  clear() {
+    this.undoStack.length = 0;
+    this.redoStack.length = 0;
+    this.emit();
+  }
+  on(cb: () => void) {
+    this.listeners.add(cb);
+    return () => this.listeners.delete(cb);
+  }
+  run(fn: () => void) {
+    this.applying = true;
+    try {
+      fn();
+    } finally {
+      this.applying = false;
+    }
+  }
+  emit() {
+    for (const l of this.listeners) l();
+  }
+}
+
+export function createHistory(opts?: {
+  limit?: number;
+  mergeWindowMs?: number;
+  now?: () => number;
+}): IHistory {
+  return new HistoryImpl(
+    opts?.limit ?? 100,
+    opts?.mergeWindowMs ?? 800,
+    opts?.now ?? (() => performance.now()),
+  );
+}
+
+export const History: IHistory = createHistory();
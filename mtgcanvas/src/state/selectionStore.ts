@@ -3,6 +3,7 @@ import type { CardSprite } from "../scene/cardNode";
 export interface SelectionState {
   cards: Set<CardSprite>;
   groupIds: Set<number>;
+  noteIds: Set<number>;
 }
 
 export interface ISelectionStore {
@@ -11,17 +12,28 @@ export interface ISelectionStore {
   clear(): void;
   toggleCard(card: CardSprite): void;
   toggleGroup(id: number): void;
+  toggleNote(id: number): void;
   selectOnlyCard(card: CardSprite): void;
   selectOnlyGroup(id: number): void;
+  selectOnlyNote(id: number): void;
   readonly isEmpty: boolean;
   getCards(): CardSprite[];
   getGroups(): number[];
+  getNotes(): number[];
   on(cb: () => void): () => void;
+  // Adjusts the card set of every new selection before listeners run (e.g. adds the
+  // copies hidden under a stack). Must be idempotent.
+  setCardExpander(fn: ((cards: Set<CardSprite>) => void) | null): void;
 }
 
 class SelectionStoreImpl implements ISelectionStore {
-  state: SelectionState = { cards: new Set(), groupIds: new Set() };
+  state: SelectionState = {
+    cards: new Set(),
+    groupIds: new Set(),
+    noteIds: new Set(),
+  };
   listeners: Set<() => void> = new Set();
+  expander: ((cards: Set<CardSprite>) => void) | null = null;
 
   replace(next: SelectionState) {
     this.state = next;
@@ -30,6 +42,7 @@ class SelectionStoreImpl implements ISelectionStore {
   clear() {
     this.state.cards.clear();
     this.state.groupIds.clear();
+    this.state.noteIds.clear();
     this.emit();
   }
   toggleCard(card: CardSprite) {
@@ -42,20 +55,38 @@ class SelectionStoreImpl implements ISelectionStore {
     else this.state.groupIds.add(id);
     this.emit();
   }
+  toggleNote(id: number) {
+    if (this.state.noteIds.has(id)) this.state.noteIds.delete(id);
+    else this.state.noteIds.add(id);
+    this.emit();
+  }
   selectOnlyCard(card: CardSprite) {
     this.state.cards.clear();
     this.state.groupIds.clear();
+    this.state.noteIds.clear();
     this.state.cards.add(card);
     this.emit();
   }
   selectOnlyGroup(id: number) {
     this.state.cards.clear();
     this.state.groupIds.clear();
+    this.state.noteIds.clear();
     this.state.groupIds.add(id);
     this.emit();
   }
+  selectOnlyNote(id: number) {
+    this.state.cards.clear();
+    this.state.groupIds.clear();
+    this.state.noteIds.clear();
+    this.state.noteIds.add(id);
+    this.emit();
+  }
   get isEmpty() {
-    return this.state.cards.size === 0 && this.state.groupIds.size === 0;
+    return (
+      this.state.cards.size === 0 &&
+      this.state.groupIds.size === 0 &&
+      this.state.noteIds.size === 0
+    );
   }
   getCards() {
     return [...this.state.cards];
@@ -63,11 +94,18 @@ class SelectionStoreImpl implements ISelectionStore {
   getGroups() {
     return [...this.state.groupIds];
   }
+  getNotes() {
+    return [...this.state.noteIds];
+  }
   on(cb: () => void) {
     this.listeners.add(cb);
     return () => this.listeners.delete(cb);
   }
+  setCardExpander(fn: ((cards: Set<CardSprite>) => void) | null) {
+    this.expander = fn;
+  }
   emit() {
+    this.expander?.(this.state.cards);
     for (const l of this.listeners) l();
   }
 }
@@ -79,6 +117,7 @@ export function createSelectionStore(
   if (initial) {
     s.state.cards = (initial as any).cards ?? s.state.cards;
     s.state.groupIds = initial.groupIds ?? s.state.groupIds;
+    s.state.noteIds = initial.noteIds ?? s.state.noteIds;
   }
   return s;
 }
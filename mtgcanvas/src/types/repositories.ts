@@ -1,4 +1,8 @@
 import type { Rect } from "./geometry";
+import type { GroupLayoutMode } from "../scene/stackLayout";
+import type { GroupSortSpec } from "../scene/groupSort";
+import type { GroupDeck } from "../services/deckValidation";
+import type { SmartGroupRule } from "../services/smartGroups";
 
 export interface CardInstance {
   id: number;
@@ -10,14 +14,26 @@ export interface CardInstance {
   rotation: number;
   scale: number;
   tags: string | null;
+  foil?: boolean;
 }
 
 export interface GroupRow {
   id: number;
   parent_id: number | null;
   name: string | null;
-  // collapsed removed
-  transform_json: string | null;
+  transform_json: string | null; // JSON GroupTransform
+}
+
+// Stored in GroupRow.transform_json. w/h are the frame as drawn; while collapsed,
+// `expanded` keeps the size to restore.
+export interface GroupTransform extends Rect {
+  collapsed?: boolean;
+  expanded?: { w: number; h: number } | null;
+  layout?: GroupLayoutMode; // absent means "grid"
+  sort?: GroupSortSpec; // absent means manual order
+  stack?: boolean; // duplicates share a slot; absent means off
+  deck?: GroupDeck; // absent means not a deck
+  smart?: SmartGroupRule; // membership query; absent means a manual group
 }
 
 export interface InstancesRepository {
@@ -32,6 +48,7 @@ export interface InstancesRepository {
     scale?: number;
     tags?: string | null;
     group_id?: number | null;
+    foil?: boolean;
   }): number;
   list(): CardInstance[];
   deleteMany(ids: number[]): void;
@@ -43,6 +60,9 @@ export interface InstancesRepository {
       y?: number;
       z?: number;
       group_id?: number | null;
+      foil?: boolean;
+      rotation?: number;
+      scale?: number;
     }[],
   ): void;
   updateManyDebounced: (
@@ -69,6 +89,17 @@ export interface GroupsRepository {
   list(): GroupRow[];
   deleteMany(ids: number[]): void;
   updateTransform(id: number, t: Rect): void;
+  setCollapsed(
+    id: number,
+    collapsed: boolean,
+    expanded: { w: number; h: number } | null,
+  ): void;
+  setLayout(id: number, layout: GroupLayoutMode): void;
+  setSort(id: number, sort: GroupSortSpec): void;
+  setStacking(id: number, stack: boolean): void;
+  setDeck(id: number, deck: GroupDeck | null): void;
+  setSmart(id: number, smart: SmartGroupRule | null): void;
   rename(id: number, name: string): void;
+  setParent(id: number, parent_id: number | null): void;
   ensureNextId(min: number): void;
 }
@@ -16,6 +16,9 @@ const HELP_SECTIONS = [
       ["Fit All", "F"],
       ["Fit Selection", "Shift+F or Z"],
       ["Reset Zoom", "Ctrl+0"],
+      ["Minimap", "M"],
+      ["Go to Bookmark", "1 … 9"],
+      ["Save Bookmark", "Shift+1 … 9"],
     ],
   },
   {
@@ -26,22 +29,46 @@ const HELP_SECTIONS = [
       ["Marquee", "Drag empty space (Shift = additive)"],
       ["Select All / Clear", "Ctrl+A / Esc"],
       ["Delete", "Delete key"],
+      ["Undo / Redo", "Ctrl+Z / Ctrl+Shift+Z (or Ctrl+Y)"],
     ],
   },
   {
     title: "Cards",
-    items: [["Move", "Drag"]],
+    items: [
+      ["Move", "Drag"],
+      ["Take One Off a Stack", "Ctrl/Cmd+Drag"],
+      ["Smart Guides", "Drag near cards / groups (hold Alt to move freely)"],
+      ["Tap / Untap", "T"],
+      ["Enlarge (2x)", "Right-click → Enlarge"],
+      ["Combo Arrow", "Right-click → Connect to…, then click the target"],
+      ["Tag Cards", "Right-click → Tags… (search them with tag:)"],
+      ["Label / Reverse Arrow", "Double-click or right-click the arrow"],
+    ],
   },
   {
     title: "Groups",
     items: [
       ["Create", "G (around selection) or empty at center"],
+      ["Smart Group", "Search palette → Smart Group, or right-click a group"],
       ["Delete", "Delete key"],
     ],
   },
+  {
+    title: "Notes",
+    items: [
+      ["Add Note", "N (at the cursor)"],
+      ["Edit", "Double-click (Ctrl+Enter saves, Esc cancels)"],
+      ["Color / Delete", "Right-click"],
+      ["Resize", "Drag the bottom-right corner"],
+    ],
+  },
   {
     title: "Search",
-    items: [["Open Search", "Ctrl+F or /"]],
+    items: [
+      ["Open Search", "Ctrl+F or /"],
+      ["Spotlight Matches", "Alt+S in the palette (Esc on the canvas ends it)"],
+      ["Complete Field / Value", "Tab (↑ ↓ to pick)"],
+    ],
   },
   {
     title: "Help & Misc",
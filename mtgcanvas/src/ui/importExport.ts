@@ -4,22 +4,55 @@ import {
   extractBaseCardName,
   parseDecklist,
   parseGroupsText,
+  parseArenaDeck,
+  formatArenaDeck,
+  formatComboLine,
+  arenaSectionName,
 } from "../services/decklist";
+import type {
+  ArenaCard,
+  ArenaSection,
+  ComboLine,
+  DeckEntry,
+  DecklistItem,
+} from "../services/decklist";
+import {
+  importBulkFile,
+  getCatalogInfo,
+  clearCatalog,
+} from "../services/cardCatalog";
+import type { CatalogInfo } from "../services/cardCatalog";
+import type { UnknownReview } from "../services/cardSource";
 import type { CardSprite } from "../scene/cardNode";
 import type { GroupVisual } from "../scene/groupNode";
+import { markDuplicateStacksDirty } from "../scene/duplicateStacks";
 export { extractBaseCardName, parseDecklist };
 
+// Connector endpoint as seen by the export: a card or a group frame
+export type ExportConnectorEnd =
+  | { sprite: CardSprite }
+  | { group: GroupVisual };
+export interface ExportConnector {
+  from: ExportConnectorEnd;
+  to: ExportConnectorEnd;
+  label: string;
+}
+
 export interface ImportExportOptions {
   // Core data accessors
   getSprites: () => CardSprite[]; // list of CardSprite objects
   getGroups: () => Map<number, GroupVisual>; // Map of groupId -> GroupVisual
+  // Optional: arrows between cards/groups, exported as a "#combos" section
+  getConnectors?: () => ExportConnector[];
   getAllNames: () => string[]; // all sprite names (one per sprite)
   getSelectedNames: () => string[]; // names for selected sprites
   importByNames: (
-    items: { name: string; count: number }[],
+    items: DecklistItem[],
     opt?: {
       onProgress?: (done: number, total?: number) => void;
       signal?: AbortSignal;
+      reviewUnknown?: UnknownReview; // "did you mean" step before placing
+      stackDuplicates?: boolean; // pile copies of a card on one spot
     },
   ) => Promise<{ imported: number; unknown: string[]; limited?: number }>; // performs import, returns stats
   // Optional: provide a preformatted text export of groups and ungrouped cards
@@ -31,12 +64,32 @@ export interface ImportExportOptions {
   // Optional: import the simple groups text format (headings + list items)
   importGroups?: (
     data: {
-      groups: { name: string; cards: string[] }[];
+      groups: {
+        name: string;
+        depth?: number; // heading level; deeper groups nest in the previous one
+        cards: string[];
+        entries?: DeckEntry[];
+      }[];
       ungrouped: string[];
+      // Parallel to cards/ungrouped; carries set, collector number and foil
+      ungroupedEntries?: DeckEntry[];
+      combos?: ComboLine[]; // connectors to recreate between the imported cards/groups
     },
     opt?: {
       onProgress?: (done: number, total?: number) => void;
       signal?: AbortSignal;
+      reviewUnknown?: UnknownReview; // "did you mean" step before placing
+      stackDuplicates?: boolean; // pile copies of a card on one spot
+    },
+  ) => Promise<{ imported: number; unknown: string[]; limited?: number }>;
+  // Optional: import MTG Arena text (sections become groups, printings are resolved by set/number)
+  importArena?: (
+    sections: ArenaSection[],
+    opt?: {
+      onProgress?: (done: number, total?: number) => void;
+      signal?: AbortSignal;
+      reviewUnknown?: UnknownReview; // "did you mean" step before placing
+      stackDuplicates?: boolean; // pile copies of a card on one spot
     },
   ) => Promise<{ imported: number; unknown: string[]; limited?: number }>;
   // Optional: Scryfall search integration – when provided, panel shows a Search tab
@@ -53,6 +106,50 @@ export interface ImportExportOptions {
   clearPersistedData?: () => Promise<void>;
 }
 
+const STACK_PREF_KEY = "importStackDuplicates";
+// Canvas-wide stacking: imports pile copies, and loose copies sharing a spot stack.
+// Groups opt in on their own (GroupVisual.stackDuplicates).
+export function stackDuplicatesPref(): boolean {
+  return localStorage.getItem(STACK_PREF_KEY) === "1";
+}
+
+type ImportRunOptions = Parameters<ImportExportOptions["importByNames"]>[1];
+type ImportRun = () => ReturnType<ImportExportOptions["importByNames"]>;
+
+// Pick the import for pasted text: Arena sections, then grouped text, then a plain
+// decklist. Null when there is nothing to import.
+export function chooseTextImport(
+  text: string,
+  opts: Pick<
+    ImportExportOptions,
+    "importByNames" | "importGroups" | "importArena"
+  >,
+  runOpts: ImportRunOptions,
+): { label: string; run: ImportRun } | null {
+  const asArena = opts.importArena ? parseArenaDeck(text) : null;
+  if (asArena && opts.importArena) {
+    const importArena = opts.importArena;
+    return {
+      label: "Importing Arena deck…",
+      run: () => importArena(asArena, runOpts),
+    };
+  }
+  const asGroups = parseGroupsText(text);
+  if (asGroups && opts.importGroups) {
+    const importGroups = opts.importGroups;
+    return {
+      label: "Importing groups…",
+      run: () => importGroups(asGroups, runOpts),
+    };
+  }
+  const items = parseDecklist(text);
+  if (!items.length) return null;
+  return {
+    label: "Importing…",
+    run: () => opts.importByNames(items, runOpts),
+  };
+}
+
 export interface ImportExportAPI {
   show(): void;
   hide(): void;
@@ -62,6 +159,34 @@ export function installImportExport(
   opts: ImportExportOptions,
 ): ImportExportAPI {
   ensureThemeStyles();
+  // "Name (SET) 123 *F*": front face name plus the exact printing and foil flag
+  function frontName(s: CardSprite): string {
+    const raw = (s.__card?.name || "").trim();
+    const i = raw.indexOf("//");
+    return i >= 0 ? raw.slice(0, i).trim() : raw;
+  }
+  // The sprite's printing as upper-case set code and collector number
+  function printingOf(s: CardSprite): {
+    set?: string;
+    collector_number?: string;
+  } {
+    const c: any = s.__card;
+    return {
+      set: c?.set ? String(c.set).toUpperCase() : undefined,
+      collector_number: c?.collector_number
+        ? String(c.collector_number)
+        : undefined,
+    };
+  }
+  function exportLabel(s: CardSprite): string {
+    const name = frontName(s);
+    if (!name) return "";
+    const { set, collector_number } = printingOf(s);
+    let out = name;
+    if (set && collector_number) out += ` (${set}) ${collector_number}`;
+    if (s.__foil) out += " *F*";
+    return out;
+  }
   // Default export builders if none provided
   function buildGroupsExport(scope: "all" | "selection"): string {
     try {
@@ -80,21 +205,31 @@ export function installImportExport(
       const isSel = (s: CardSprite) =>
         considerAll || (selected?.has(s) ?? true);
       const lines: string[] = [];
-      const grouped = Array.from(groups.values()).sort(
+      // Nested groups follow their parent with one more heading level ("## Ramp")
+      const all = Array.from(groups.values()).sort(
         (a: GroupVisual, b: GroupVisual) => a.id - b.id,
       );
+      const childrenOf = (gv: GroupVisual) =>
+        all.filter((g) => g !== gv && g.parentId === gv.id);
+      // `seen` guards against parent_id cycles from bad saved or imported data
+      const hasCards = (gv: GroupVisual, seen = new Set<number>()): boolean => {
+        if (seen.has(gv.id)) return false;
+        seen.add(gv.id);
+        return (
+          gv.order.some((s) => exportLabel(s)) ||
+          childrenOf(gv).some((c) => hasCards(c, seen))
+        );
+      };
+      const printed = new Set<number>();
       let anyGroupPrinted = false;
-      for (const gv of grouped) {
+      const printGroup = (gv: GroupVisual, depth: number) => {
+        if (printed.has(gv.id) || !hasCards(gv)) return;
+        printed.add(gv.id);
         const names: string[] = gv.order
-          .map((s: CardSprite) => {
-            const raw = (s.__card?.name || "").trim();
-            const i = raw.indexOf("//");
-            return i >= 0 ? raw.slice(0, i).trim() : raw;
-          })
+          .map(exportLabel)
           .filter((n: string) => n);
-        if (!names.length) continue;
         const title = gv.name || `Group ${gv.id}`;
-        lines.push(`# ${title}`);
+        lines.push(`${"#".repeat(depth)} ${title}`);
         const order: string[] = [];
         const counts = new Map<string, number>();
         for (const n of names) {
@@ -104,15 +239,19 @@ export function installImportExport(
         for (const n of order) lines.push(`${counts.get(n) || 1} ${n}`);
         lines.push("");
         anyGroupPrinted = true;
-      }
+        childrenOf(gv).forEach((c) => printGroup(c, depth + 1));
+      };
+      all
+        .filter((gv) => gv.parentId == null || !groups.has(gv.parentId))
+        .forEach((gv) => printGroup(gv, 1));
+      // Groups in a parent cycle have no root above them; print them top level
+      all.forEach((gv) => printGroup(gv, 1));
       // Ungrouped
       const ungroupedNames: string[] = [];
       for (const s of sprites) {
         if (s.__groupId) continue;
         if (!isSel(s)) continue;
-        const raw = (s.__card?.name || "").trim();
-        const i = raw.indexOf("//");
-        const n = i >= 0 ? raw.slice(0, i).trim() : raw;
+        const n = exportLabel(s);
         if (n) ungroupedNames.push(n);
       }
       const orderU: string[] = [];
@@ -129,15 +268,98 @@ export function installImportExport(
       } else if (ungroupedLines.length) {
         lines.push(...ungroupedLines);
       }
+      // Combo lines by card / group name. A selection export keeps only those whose both
+      // ends are in it: selected cards, and groups that are selected or hold a selected card.
+      const selectedGroups = considerAll
+        ? null
+        : new Set(SelectionStore.getGroups());
+      const groupInScope = (gv: GroupVisual, seen = new Set<number>()) => {
+        if (considerAll || selectedGroups?.has(gv.id)) return true;
+        if (seen.has(gv.id)) return false;
+        seen.add(gv.id);
+        return (
+          gv.order.some(isSel) ||
+          childrenOf(gv).some((c) => groupInScope(c, seen))
+        );
+      };
+      const endName = (e: ExportConnectorEnd) =>
+        "sprite" in e
+          ? isSel(e.sprite)
+            ? frontName(e.sprite)
+            : ""
+          : printed.has(e.group.id) && groupInScope(e.group)
+            ? e.group.name || `Group ${e.group.id}`
+            : "";
+      const combos: string[] = [];
+      for (const c of opts.getConnectors?.() || []) {
+        const from = endName(c.from);
+        const to = endName(c.to);
+        if (from && to)
+          combos.push(formatComboLine({ from, to, label: c.label }));
+      }
+      if (combos.length) {
+        if (lines.length && lines[lines.length - 1] !== "") lines.push("");
+        lines.push("#combos", ...combos);
+      }
       return lines.join("\n");
     } catch {
       return "";
     }
   }
+  // Arena export: groups named like an Arena section (Commander, Sideboard, ...) map to that
+  // section; every other group and ungrouped cards land in "Deck". Printings are kept.
+  function buildArenaExport(scope: "all" | "selection"): string {
+    try {
+      const sprites: CardSprite[] = opts.getSprites?.() || [];
+      const groups: Map<number, GroupVisual> = opts.getGroups?.() || new Map();
+      let selected: Set<CardSprite> | null = null;
+      if (scope !== "all") {
+        try {
+          selected = new Set(SelectionStore.getCards());
+        } catch {
+          selected = new Set();
+        }
+      }
+      const isSel = (s: CardSprite) => !selected || selected.has(s);
+      const sections = new Map<string, Map<string, ArenaCard>>();
+      const add = (section: string, s: CardSprite) => {
+        const name = frontName(s);
+        if (!name) return;
+        const { set, collector_number } = printingOf(s);
+        const key = `${name}|${set || ""}|${collector_number || ""}`;
+        let bySection = sections.get(section);
+        if (!bySection) {
+          bySection = new Map();
+          sections.set(section, bySection);
+        }
+        const prev = bySection.get(key);
+        if (prev) prev.count += 1;
+        else bySection.set(key, { count: 1, name, set, collector_number });
+      };
+      const grouped = Array.from(groups.values()).sort((a, b) => a.id - b.id);
+      for (const gv of grouped) {
+        const section = arenaSectionName(gv.name || "") || "Deck";
+        for (const s of gv.order) if (isSel(s)) add(section, s);
+      }
+      for (const s of sprites) {
+        if (s.__groupId || !isSel(s)) continue;
+        add("Deck", s);
+      }
+      return formatArenaDeck(
+        [...sections].map(([name, cards]) => ({
+          name,
+          cards: [...cards.values()],
+        })),
+      );
+    } catch {
+      return "";
+    }
+  }
   let panel: HTMLDivElement | null = null;
   let exportArea: HTMLTextAreaElement | null = null;
   let importArea: HTMLTextAreaElement | null = null;
   let scopeAll = true; // true=all, false=selection
+  let exportFormat: "text" | "arena" = "text";
   let statusEl: HTMLDivElement | null = null;
   // Busy state to prevent concurrent operations
   let textInFlight = false;
@@ -194,6 +416,16 @@ export function installImportExport(
       scryStatusEl.textContent = otherBusyMsg;
   };
 
+  function currentExportText(): string {
+    const scope: "all" | "selection" = scopeAll ? "all" : "selection";
+    if (exportFormat === "arena") return buildArenaExport(scope);
+    return opts.getGroupsExportScoped
+      ? opts.getGroupsExportScoped(scope)
+      : opts.getGroupsExport
+        ? opts.getGroupsExport()
+        : buildGroupsExport(scope);
+  }
+
   function summarizeUnknown(unknown: string[], maxShow = 20): string {
     const n = unknown.length | 0;
     if (n <= 0) return "All resolved.";
@@ -202,6 +434,84 @@ export function installImportExport(
     return `Unknown: ${n} names (showing first ${maxShow}): ${first.join(", ")}`;
   }
 
+  // "Did you mean": list misses with suggestions and wait for the user's picks.
+  // Resolves with original -> chosen name (empty when skipped or canceled).
+  function showUnknownReview(
+    items: { name: string; suggestions: string[] }[],
+    signal?: AbortSignal,
+  ): Promise<Map<string, string>> {
+    const box = panel?.querySelector("#ie-review") as HTMLDivElement | null;
+    if (!box) return Promise.resolve(new Map());
+    box.replaceChildren();
+    const head = document.createElement("div");
+    head.textContent = "Did you mean…";
+    head.style.fontWeight = "600";
+    head.style.marginBottom = "calc(6px * var(--ui-scale))";
+    box.appendChild(head);
+    const picks: { name: string; select: HTMLSelectElement }[] = [];
+    for (const it of items) {
+      if (!it.suggestions.length) continue;
+      const row = document.createElement("div");
+      row.style.cssText =
+        "display:flex;gap:calc(8px * var(--ui-scale));align-items:center;margin:calc(4px * var(--ui-scale)) 0;";
+      const label = document.createElement("span");
+      label.textContent = it.name;
+      label.style.cssText = "flex:1;opacity:.85;text-decoration:line-through;";
+      const select = document.createElement("select");
+      select.className = "ui-input";
+      for (const sug of it.suggestions) select.add(new Option(sug, sug));
+      select.add(new Option("(skip)", ""));
+      row.append(label, select);
+      box.appendChild(row);
+      picks.push({ name: it.name, select });
+    }
+    const misses = items.filter((it) => !it.suggestions.length).length;
+    if (misses) {
+      const note = document.createElement("div");
+      note.textContent = `${misses} more without suggestions.`;
+      note.style.opacity = ".7";
+      box.appendChild(note);
+    }
+    const bar = document.createElement("div");
+    bar.style.cssText =
+      "display:flex;gap:calc(10px * var(--ui-scale));margin-top:calc(8px * var(--ui-scale));";
+    const mkBtn = (text: string) => {
+      const b = document.createElement("button");
+      b.type = "button";
+      b.className = "ui-btn";
+      b.textContent = text;
+      bar.appendChild(b);
+      return b;
+    };
+    const acceptAll = mkBtn("Accept all");
+    const apply = mkBtn("Apply selected");
+    const skip = mkBtn("Skip");
+    box.appendChild(bar);
+    box.style.display = "block";
+    if (statusEl)
+      statusEl.textContent = `${picks.length} unknown name(s) have suggestions.`;
+    return new Promise((resolve) => {
+      const finish = (useFirst: boolean | null) => {
+        const out = new Map<string, string>();
+        if (useFirst !== null) {
+          for (const p of picks) {
+            const v = useFirst ? p.select.options[0].value : p.select.value;
+            if (v) out.set(p.name, v);
+          }
+        }
+        signal?.removeEventListener("abort", onAbort);
+        box.replaceChildren();
+        box.style.display = "none";
+        resolve(out);
+      };
+      const onAbort = () => finish(null);
+      signal?.addEventListener("abort", onAbort, { once: true });
+      acceptAll.onclick = () => finish(true);
+      apply.onclick = () => finish(false);
+      skip.onclick = () => finish(null);
+    });
+  }
+
   function ensure() {
     if (panel) return panel;
     const el = createPanel({
@@ -233,15 +543,21 @@ export function installImportExport(
               <label class="ui-pill" style="display:inline-flex;gap:calc(8px * var(--ui-scale));align-items:center;padding:calc(6px * var(--ui-scale)) calc(10px * var(--ui-scale));cursor:pointer;"><input id="ie-scope-all" type="radio" name="ie-scope" checked style="margin:0 calc(6px * var(--ui-scale)) 0 0"/> All</label>
               <label class="ui-pill" style="display:inline-flex;gap:calc(8px * var(--ui-scale));align-items:center;padding:calc(6px * var(--ui-scale)) calc(10px * var(--ui-scale));cursor:pointer;"><input id="ie-scope-sel" type="radio" name="ie-scope" style="margin:0 calc(6px * var(--ui-scale)) 0 0"/> Selection</label>
             </div>
+            <div style="display:flex;gap:calc(12px * var(--ui-scale));align-items:center;">
+              <label class="ui-pill" style="display:inline-flex;gap:calc(8px * var(--ui-scale));align-items:center;padding:calc(6px * var(--ui-scale)) calc(10px * var(--ui-scale));cursor:pointer;"><input id="ie-format-text" type="radio" name="ie-format" checked style="margin:0 calc(6px * var(--ui-scale)) 0 0"/> Text</label>
+              <label class="ui-pill" style="display:inline-flex;gap:calc(8px * var(--ui-scale));align-items:center;padding:calc(6px * var(--ui-scale)) calc(10px * var(--ui-scale));cursor:pointer;" title="MTG Arena format: Deck / Sideboard / Commander / Companion sections with (SET) numbers"><input id="ie-format-arena" type="radio" name="ie-format" style="margin:0 calc(6px * var(--ui-scale)) 0 0"/> Arena</label>
+            </div>
           </div>
         </div>
         <div>
           <h2 style="margin-top:0">Import</h2>
-          <textarea id="ie-import" class="ui-input" style="width:100%;min-height:calc(300px * var(--ui-scale));white-space:pre;resize:none;" placeholder="Paste decklist: e.g.\n4 Lightning Bolt\n2 Counterspell\nIsland x8"></textarea>
+          <textarea id="ie-import" class="ui-input" style="width:100%;min-height:calc(300px * var(--ui-scale));white-space:pre;resize:none;" placeholder="Paste decklist: e.g.\n4 Lightning Bolt\n2 Counterspell\nIsland x8\n\nArena exports (Deck / Sideboard sections) are recognized too."></textarea>
           <div style="display:flex;gap:calc(10px * var(--ui-scale));margin-top:calc(10px * var(--ui-scale));align-items:center;">
             <button id="ie-import-btn" type="button" class="ui-btn">Import</button>
+            <label class="ui-pill" style="display:inline-flex;gap:calc(8px * var(--ui-scale));align-items:center;padding:calc(6px * var(--ui-scale)) calc(10px * var(--ui-scale));cursor:pointer;white-space:nowrap;" title="Pile copies of the same card on one spot with a count badge (imports and loose cards on the canvas)"><input id="ie-stack" type="checkbox" style="margin:0 calc(6px * var(--ui-scale)) 0 0"/> Stack duplicates</label>
             <div id="ie-status" style="opacity:.88;font-size:calc(16px * var(--ui-scale));"></div>
           </div>
+          <div id="ie-review" style="display:none;margin-top:calc(10px * var(--ui-scale));"></div>
         </div>
         ${
           opts.scryfallSearchAndPlace
@@ -256,6 +572,15 @@ export function installImportExport(
         </div>`
             : ""
         }
+        <div id="ie-catalog-pane" style="grid-column:1 / span 2;">
+          <h2>Offline card database</h2>
+          <div style="display:flex;gap:calc(10px * var(--ui-scale));align-items:center;margin:calc(8px * var(--ui-scale)) 0 calc(10px * var(--ui-scale));">
+            <input id="ie-catalog-file" type="file" accept=".json,application/json" style="display:none"/>
+            <button id="ie-catalog-load" class="ui-btn" type="button" title="Load a Scryfall bulk data file (oracle-cards or default-cards JSON)">Load bulk JSON…</button>
+            <button id="ie-catalog-clear" class="ui-btn" type="button">Clear</button>
+            <div id="ie-catalog-status" style="opacity:.88;font-size:calc(16px * var(--ui-scale));"></div>
+          </div>
+        </div>
         </div>
       </div>
     `;
@@ -284,19 +609,27 @@ export function installImportExport(
 
     const scopeAllEl = el.querySelector("#ie-scope-all") as HTMLInputElement;
     const scopeSelEl = el.querySelector("#ie-scope-sel") as HTMLInputElement;
+    const formatTextEl = el.querySelector(
+      "#ie-format-text",
+    ) as HTMLInputElement;
+    const formatArenaEl = el.querySelector(
+      "#ie-format-arena",
+    ) as HTMLInputElement;
     const importBtn = el.querySelector("#ie-import-btn") as HTMLButtonElement;
+    // Remembered across sessions; imports pile copies (and new groups stack them)
+    const stackEl = el.querySelector("#ie-stack") as HTMLInputElement;
+    stackEl.checked = stackDuplicatesPref();
+    stackEl.onchange = () => {
+      localStorage.setItem(STACK_PREF_KEY, stackEl.checked ? "1" : "0");
+      // Loose copies on the canvas stack (or unstack) with the setting
+      markDuplicateStacksDirty();
+    };
     // Initialize disabled state after wiring up controls
     updateBusyUI();
     const scryPane = el.querySelector("#ie-scry-pane") as HTMLDivElement | null;
 
     const refreshExport = () => {
-      const scope: "all" | "selection" = scopeAll ? "all" : "selection";
-      const txt = opts.getGroupsExportScoped
-        ? opts.getGroupsExportScoped(scope)
-        : opts.getGroupsExport
-          ? opts.getGroupsExport()
-          : buildGroupsExport(scope);
-      if (exportArea) exportArea.value = txt;
+      if (exportArea) exportArea.value = currentExportText();
     };
 
     scopeAllEl.onchange = () => {
@@ -307,8 +640,16 @@ export function installImportExport(
       scopeAll = false;
       refreshExport();
     };
+    formatTextEl.onchange = () => {
+      exportFormat = "text";
+      refreshExport();
+    };
+    formatArenaEl.onchange = () => {
+      exportFormat = "arena";
+      refreshExport();
+    };
 
-    // no format selector; always output counts + groups
+    // Format selector: grouped text (counts + # headings) or Arena sections
     copyBtn.onclick = async () => {
       if (!exportArea) return;
       await navigator.clipboard.writeText(exportArea.value);
@@ -356,59 +697,32 @@ export function installImportExport(
       textAbort = new AbortController();
       textInFlight = true;
       updateBusyUI();
-      // Try groups format first
-      const asGroups = parseGroupsText(inputText);
-      if (asGroups && opts.importGroups) {
-        if (statusEl) statusEl.textContent = "Importing groups…";
-        try {
-          const res = await opts.importGroups(asGroups, {
-            onProgress: (done, total) => {
-              if (!statusEl) return;
-              if (typeof total === "number" && total > 0)
-                statusEl.textContent = `Resolving ${done}/${total}…`;
-              else statusEl.textContent = `Resolving ${done}…`;
-            },
-            signal: textAbort.signal,
-          });
-          if (statusEl)
-            statusEl.textContent = `Imported ${res.imported}${res.limited ? ` (limited by cap)` : ""}. ${summarizeUnknown(res.unknown)}`;
-        } catch (e: any) {
-          if (
-            e &&
-            (e.name === "AbortError" || /aborted/i.test(e.message || ""))
-          ) {
-            if (statusEl) statusEl.textContent = "Canceled.";
-          } else {
-            if (statusEl)
-              statusEl.textContent =
-                "Import failed: " + (e?.message || String(e));
-          }
-        } finally {
-          textInFlight = false;
-          textAbort = null;
-          updateBusyUI();
-        }
-        return;
-      }
-      // Fallback to decklist format
-      const items = parseDecklist(inputText);
-      if (!items.length) {
+      const onProgress = (done: number, total?: number) => {
+        if (!statusEl) return;
+        if (typeof total === "number" && total > 0)
+          statusEl.textContent = `Resolving ${done}/${total}…`;
+        else statusEl.textContent = `Resolving ${done}…`;
+      };
+      const signal = textAbort.signal;
+      const reviewUnknown: UnknownReview = (items) =>
+        showUnknownReview(items, signal);
+      const runOpts = {
+        onProgress,
+        signal,
+        reviewUnknown,
+        stackDuplicates: stackEl.checked,
+      };
+      const picked = chooseTextImport(inputText, opts, runOpts);
+      if (!picked) {
         if (statusEl) statusEl.textContent = "Nothing to import.";
         textInFlight = false;
+        textAbort = null;
         updateBusyUI();
         return;
       }
-      if (statusEl) statusEl.textContent = "Importing…";
+      if (statusEl) statusEl.textContent = picked.label;
       try {
-        const res = await opts.importByNames(items, {
-          onProgress: (done, total) => {
-            if (!statusEl) return;
-            if (typeof total === "number" && total > 0)
-              statusEl.textContent = `Resolving ${done}/${total}…`;
-            else statusEl.textContent = `Resolving ${done}…`;
-          },
-          signal: textAbort.signal,
-        });
+        const res = await picked.run();
         if (statusEl)
           statusEl.textContent = `Imported ${res.imported}${res.limited ? ` (limited by cap)` : ""}. ${summarizeUnknown(res.unknown)}`;
       } catch (e: any) {
@@ -512,6 +826,66 @@ export function installImportExport(
       updateBusyUI();
     }
 
+    // Offline catalog: stream a Scryfall bulk file into IndexedDB
+    {
+      const fileEl = el.querySelector("#ie-catalog-file") as HTMLInputElement;
+      const loadBtn = el.querySelector(
+        "#ie-catalog-load",
+      ) as HTMLButtonElement;
+      const clearBtn = el.querySelector(
+        "#ie-catalog-clear",
+      ) as HTMLButtonElement;
+      const catStatus = el.querySelector(
+        "#ie-catalog-status",
+      ) as HTMLDivElement;
+      let catAbort: AbortController | null = null;
+      const describe = (info: CatalogInfo | null) =>
+        info
+          ? `${info.count.toLocaleString()} cards from ${info.source} (${new Date(info.importedAt).toLocaleDateString()}). Names and Scryfall searches run offline.`
+          : "No offline database. Download oracle-cards or default-cards from scryfall.com/docs/api/bulk-data.";
+      getCatalogInfo().then((info) => (catStatus.textContent = describe(info)));
+      loadBtn.onclick = () => {
+        if (catAbort) {
+          catAbort.abort();
+          return;
+        }
+        fileEl.value = "";
+        fileEl.click();
+      };
+      fileEl.onchange = async () => {
+        const file = fileEl.files && fileEl.files[0];
+        if (!file) return;
+        catAbort = new AbortController();
+        loadBtn.textContent = "Cancel";
+        clearBtn.disabled = true;
+        try {
+          const info = await importBulkFile(file, {
+            signal: catAbort.signal,
+            onProgress: (read, total, cards) => {
+              const pct = total > 0 ? Math.floor((read / total) * 100) : 0;
+              catStatus.textContent = `Reading ${pct}% (${cards.toLocaleString()} cards)…`;
+            },
+          });
+          catStatus.textContent = describe(info);
+        } catch (e: any) {
+          // The previous catalog (if any) is still in place
+          const why =
+            e && e.name === "AbortError"
+              ? "Canceled."
+              : "Load failed: " + (e?.message || String(e));
+          catStatus.textContent = `${why} ${describe(await getCatalogInfo())}`;
+        } finally {
+          catAbort = null;
+          loadBtn.textContent = "Load bulk JSON…";
+          clearBtn.disabled = false;
+        }
+      };
+      clearBtn.onclick = async () => {
+        await clearCatalog();
+        catStatus.textContent = describe(null);
+      };
+    }
+
     document.body.appendChild(el);
     panel = el;
     return el;
@@ -538,14 +912,8 @@ export function installImportExport(
         elp.style.transform = "translateX(-50%)";
       }
     }
-    // Use current scope selection when showing
-    const scope: "all" | "selection" = scopeAll ? "all" : "selection";
-    const txt = opts.getGroupsExportScoped
-      ? opts.getGroupsExportScoped(scope)
-      : opts.getGroupsExport
-        ? opts.getGroupsExport()
-        : buildGroupsExport(scope);
-    if (exportArea) exportArea.value = txt;
+    // Use current scope/format selection when showing
+    if (exportArea) exportArea.value = currentExportText();
     // focus import area for quick paste
     setTimeout(() => importArea?.focus(), 0);
   }
@@ -0,0 +1,239 @@
+/**
+ * Minimap overview
+ * - Corner widget showing group frames and card clusters for the whole scene
+ * - The scene layer is cached and only redrawn when invalidated (throttled), so the
+ *   per-frame cost is the viewport rectangle alone, independent of card count
+ * - Drag the viewport rectangle to pan; click elsewhere to glide the camera there
+ */
+import { Colors, ensureThemeStyles, registerThemeListener } from "./theme";
+import type { Rect } from "../types/geometry";
+
+type Box = { minX: number; minY: number; maxX: number; maxY: number };
+
+export interface MinimapDeps {
+  getSceneBounds: () => Rect | null;
+  getGroupRects: () => Rect[];
+  // At most `limit` boxes summarizing where cards are (see SpatialIndex.clusters)
+  getClusters: (limit: number) => Box[];
+  // World-space rectangle currently on screen
+  getViewport: () => Rect;
+  centerOn: (x: number, y: number, animate: boolean) => void;
+}
+
+export interface MinimapAPI {
+  toggle(): void;
+  isVisible(): boolean;
+  // Scene content changed; redraw the cached layer soon
+  invalidate(): void;
+  // Per-frame: redraw the viewport rectangle when the camera moved
+  update(): void;
+}
+
+const VISIBLE_KEY = "minimapVisible";
+const MAP_W = 240; // CSS px before --ui-scale
+const MAP_H = 160;
+const CLUSTER_LIMIT = 600;
+const REDRAW_MS = 250; // minimum gap between scene layer redraws
+
+const css = (n: number) => `#${n.toString(16).padStart(6, "0")}`;
+
+export function installMinimap(deps: MinimapDeps): MinimapAPI {
+  ensureThemeStyles();
+  const host = document.createElement("div");
+  host.id = "minimap";
+  host.className = "ui-panel";
+  host.style.cssText =
+    "position:fixed;left:calc(14px * var(--ui-scale));bottom:calc(24px * var(--ui-scale) + var(--fab-size));padding:0;overflow:hidden;z-index:9998;display:none;cursor:pointer;";
+  const canvas = document.createElement("canvas");
+  canvas.style.cssText =
+    "display:block;width:calc(240px * var(--ui-scale));height:calc(160px * var(--ui-scale));";
+  host.appendChild(canvas);
+  document.body.appendChild(host);
+  // Scene layer (groups + clusters), blitted under the viewport rectangle
+  const layer = document.createElement("canvas");
+
+  let visible = localStorage.getItem(VISIBLE_KEY) === "1";
+  let dirty = true;
+  let lastDraw = 0;
+  let lastView = "";
+  // World -> map transform of the last scene layer
+  let map = { x: 0, y: 0, s: 1 };
+  let drag: { dx: number; dy: number } | null = null;
+
+  function resize(): boolean {
+    const r = canvas.getBoundingClientRect();
+    const dpr = window.devicePixelRatio || 1;
+    const w = Math.max(1, Math.round((r.width || MAP_W) * dpr));
+    const h = Math.max(1, Math.round((r.height || MAP_H) * dpr));
+    if (canvas.width === w && canvas.height === h) return false;
+    canvas.width = layer.width = w;
+    canvas.height = layer.height = h;
+    return true;
+  }
+
+  function drawScene() {
+    const ctx = layer.getContext("2d");
+    if (!ctx) return;
+    const w = layer.width;
+    const h = layer.height;
+    ctx.clearRect(0, 0, w, h);
+    // Frame the scene plus the viewport, so the rectangle never leaves the map
+    const view = deps.getViewport();
+    const scene = deps.getSceneBounds();
+    const b = scene ? union(scene, view) : view;
+    const pad = 6;
+    const s = Math.min((w - pad * 2) / b.w, (h - pad * 2) / b.h);
+    map = {
+      s,
+      x: pad + (w - pad * 2 - b.w * s) / 2 - b.x * s,
+      y: pad + (h - pad * 2 - b.h * s) / 2 - b.y * s,
+    };
+    ctx.fillStyle = css(Colors.panelFgDim());
+    ctx.globalAlpha = 0.55;
+    // Clusters at least a pixel wide so lone cards stay visible when zoomed far out
+    for (const c of deps.getClusters(CLUSTER_LIMIT)) {
+      const cw = Math.max(1, (c.maxX - c.minX) * s);
+      const ch = Math.max(1, (c.maxY - c.minY) * s);
+      ctx.fillRect(c.minX * s + map.x, c.minY * s + map.y, cw, ch);
+    }
+    ctx.globalAlpha = 0.9;
+    ctx.strokeStyle = css(Colors.panelBorder());
+    ctx.lineWidth = 1;
+    for (const g of deps.getGroupRects())
+      ctx.strokeRect(g.x * s + map.x, g.y * s + map.y, g.w * s, g.h * s);
+    ctx.globalAlpha = 1;
+  }
+
+  function draw() {
+    const ctx = canvas.getContext("2d");
+    if (!ctx) return;
+    ctx.clearRect(0, 0, canvas.width, canvas.height);
+    ctx.drawImage(layer, 0, 0);
+    const v = deps.getViewport();
+    const dpr = window.devicePixelRatio || 1;
+    ctx.strokeStyle = css(Colors.accent());
+    ctx.lineWidth = 2 * dpr;
+    ctx.fillStyle = css(Colors.accent());
+    ctx.globalAlpha = 0.12;
+    const r = toMap(v);
+    ctx.fillRect(r.x, r.y, r.w, r.h);
+    ctx.globalAlpha = 1;
+    ctx.strokeRect(r.x, r.y, r.w, r.h);
+  }
+
+  function toMap(r: Rect): Rect {
+    return {
+      x: r.x * map.s + map.x,
+      y: r.y * map.s + map.y,
+      w: r.w * map.s,
+      h: r.h * map.s,
+    };
+  }
+
+  // Pointer position in world units
+  function toWorld(e: PointerEvent) {
+    const rect = canvas.getBoundingClientRect();
+    const px = ((e.clientX - rect.left) / rect.width) * canvas.width;
+    const py = ((e.clientY - rect.top) / rect.height) * canvas.height;
+    return { x: (px - map.x) / map.s, y: (py - map.y) / map.s };
+  }
+
+  canvas.addEventListener("pointerdown", (e) => {
+    if (e.button !== 0) return;
+    e.preventDefault();
+    e.stopPropagation();
+    const p = toWorld(e);
+    const v = deps.getViewport();
+    const inside =
+      p.x >= v.x && p.x <= v.x + v.w && p.y >= v.y && p.y <= v.y + v.h;
+    if (!inside) {
+      deps.centerOn(p.x, p.y, true);
+      return;
+    }
+    // Keep the grab offset so the rectangle doesn't jump under the pointer
+    drag = { dx: v.x + v.w / 2 - p.x, dy: v.y + v.h / 2 - p.y };
+    canvas.setPointerCapture(e.pointerId);
+  });
+  canvas.addEventListener("pointermove", (e) => {
+    if (!drag) return;
+    const p = toWorld(e);
+    deps.centerOn(p.x + drag.dx, p.y + drag.dy, false);
+  });
+  const endDrag = (e: PointerEvent) => {
+    if (!drag) return;
+    drag = null;
+    canvas.releasePointerCapture(e.pointerId);
+    // Re-frame now that the viewport may have left the previous map bounds
+    dirty = true;
+  };
+  canvas.addEventListener("pointerup", endDrag);
+  canvas.addEventListener("pointercancel", endDrag);
+  // Keep wheel and context menu off the canvas underneath
+  host.addEventListener("wheel", (e) => e.stopPropagation(), {
+    passive: true,
+  });
+  host.addEventListener("contextmenu", (e) => e.preventDefault());
+
+  registerThemeListener(() => {
+    dirty = true;
+  });
+
+  function setVisible(v: boolean) {
+    visible = v;
+    host.style.display = v ? "block" : "none";
+    try {
+      localStorage.setItem(VISIBLE_KEY, v ? "1" : "0");
+    } catch {}
+    dirty = true;
+    lastView = "";
+  }
+  setVisible(visible);
+
+  function update() {
+    if (!visible) return;
+    const now = performance.now();
+    if (resize()) dirty = true;
+    const v = deps.getViewport();
+    const key = `${v.x},${v.y},${v.w},${v.h}`;
+    // While dragging the rectangle the map frame stays put, so the pointer math holds
+    if (dirty && !drag && now - lastDraw >= REDRAW_MS) {
+      dirty = false;
+      lastDraw = now;
+      drawScene();
+      lastView = "";
+    } else if (!drag) {
+      // Re-frame when the viewport leaves the mapped area
+      const r = toMap(v);
+      if (
+        r.x < 0 ||
+        r.y < 0 ||
+        r.x + r.w > canvas.width ||
+        r.y + r.h > canvas.height
+      )
+        dirty = true;
+    }
+    if (key === lastView) return;
+    lastView = key;
+    draw();
+  }
+
+  return {
+    toggle: () => setVisible(!visible),
+    isVisible: () => visible,
+    invalidate() {
+      dirty = true;
+    },
+    update,
+  };
+}
+
+function union(a: Rect, b: Rect): Rect {
+  const x = Math.min(a.x, b.x);
+  const y = Math.min(a.y, b.y);
+  return {
+    x,
+    y,
+    w: Math.max(a.x + a.w, b.x + b.w) - x,
+    h: Math.max(a.y + a.h, b.y + b.h) - y,
+  };
+}
@@ -1,13 +1,53 @@
 // Lightweight search palette: Ctrl+F opens, Enter creates group with matches.
 // Searches across in-memory loaded card sprites (name + oracle_text) with OR semantics between tokens.
+// Spotlight (Alt+S) dims everything but the matches live while typing and stays on after the
+// palette closes, with a small chip to edit or dismiss it (Esc on the canvas also dismisses).
+// "Smart Group" saves the query as a group that keeps collecting matches (services/smartGroups.ts).
+// The All / Ungrouped / Grouped pills just edit the query's in:ungrouped / in:grouped term.
+// The input colors fields, operators and values as you type (a highlight layer over the
+// transparent input text), underlines the span a parse error points at, and Tab completes
+// field names and values (search/queryComplete.ts).
 
 import type { CardSprite } from "../scene/cardNode";
-import { parseScryfallQuery } from "../search/scryfallQuery";
+import {
+  compileScryfallQuery,
+  segmentQuery,
+  type CanvasContext,
+} from "../search/scryfallQuery";
+import {
+  completeQuery,
+  type CompletionSources,
+  type QueryCompletion,
+} from "../search/queryComplete";
+import {
+  smartQueryError,
+  type SmartGroupScope,
+} from "../services/smartGroups";
+
+// Tests one card; `ctxOf` is a fresh canvas lookup when the caller re-tests after edits
+export type SpotlightTest = (
+  s: CardSprite,
+  ctxOf?: (s: CardSprite) => CanvasContext,
+) => boolean;
+
+// What the spotlight shows: `test` is the whole query (outlines and dimming), `count` the
+// query without the All / Ungrouped / Grouped term (group header counts)
+export interface SpotlightHit {
+  matches: CardSprite[];
+  test: SpotlightTest;
+  count: SpotlightTest;
+}
 
 export interface SearchPaletteOptions {
   getSprites: () => CardSprite[];
   createGroupForSprites: (cards: CardSprite[], name: string) => void;
   focusSprite: (s: CardSprite) => void; // centers / fits viewport around a sprite
+  // Spotlight on the current matches; the tests re-classify cards added or regrouped
+  // later. Null turns it off.
+  spotlight?: (hit: SpotlightHit | null) => void;
+  createSmartGroup?: (query: string, scope: SmartGroupScope) => void;
+  // Lookup for the canvas fields (group:, tag:, count …), taken fresh for each search
+  canvasContext?: () => (s: CardSprite) => CanvasContext;
 }
 
 interface LastQueryResult {
@@ -17,9 +57,21 @@ interface LastQueryResult {
 }
 
 export function installSearchPalette(opts: SearchPaletteOptions) {
-  const { getSprites, createGroupForSprites, focusSprite } = opts;
+  const {
+    getSprites,
+    createGroupForSprites,
+    focusSprite,
+    spotlight,
+    createSmartGroup,
+    canvasContext,
+  } = opts;
   let palette: HTMLDivElement | null = null;
   let inputEl: HTMLInputElement | null = null;
+  let highlightEl: HTMLDivElement | null = null; // colored copy of the query over the input
+  let suggestEl: HTMLDivElement | null = null;
+  let suggestion: QueryCompletion | null = null;
+  let suggestIndex = 0;
+  let sources: CompletionSources | null = null; // sets / formats on the canvas, per show
   let infoEl: HTMLDivElement | null = null;
   let filtersEl: HTMLDivElement | null = null;
   let last: LastQueryResult | null = null;
@@ -30,8 +82,15 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
   let prevBtn: HTMLButtonElement | null = null;
   let nextBtn: HTMLButtonElement | null = null;
   let groupBtn: HTMLButtonElement | null = null;
+  let smartBtn: HTMLButtonElement | null = null;
   type FilterMode = "all" | "ungrouped" | "grouped";
-  let filterMode: FilterMode = "ungrouped";
+  const FILTER_TERM = /(^|\s)in:(un)?grouped(?=\s|$)/gi;
+  let filterPills: HTMLButtonElement[] = [];
+  let lastFilter: FilterMode = "ungrouped"; // pre-filled when the palette opens empty
+  let spotlightOn = false;
+  let spotlightQuery = ""; // query shown while spotlighting (kept when the palette closes)
+  let spotlightPill: HTMLButtonElement | null = null;
+  let chipEl: HTMLDivElement | null = null;
 
   function ensure() {
     if (palette) return palette;
@@ -56,7 +115,32 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
     inputEl.setAttribute("autocorrect", "off");
     inputEl.setAttribute("data-gramm", "false");
     inputEl.setAttribute("data-gramm_editor", "false");
-    wrap.appendChild(inputEl);
+    inputEl.classList.add("qt-input");
+    // Same classes as the input so font, padding and border line up with its text
+    highlightEl = document.createElement("div");
+    highlightEl.className = "ui-input ui-input-lg qt-layer";
+    highlightEl.setAttribute("aria-hidden", "true");
+    suggestEl = document.createElement("div");
+    suggestEl.className = "ui-menu qt-suggest";
+    suggestEl.style.display = "none";
+    const field = document.createElement("div");
+    field.className = "qt-wrap";
+    field.append(inputEl, highlightEl, suggestEl);
+    wrap.appendChild(field);
+    const syncScroll = () => {
+      if (highlightEl && inputEl) highlightEl.scrollLeft = inputEl.scrollLeft;
+    };
+    inputEl.addEventListener("scroll", syncScroll);
+    inputEl.addEventListener("keyup", (ev) => {
+      syncScroll();
+      // Moving the caret moves the word being completed
+      if (/^(ArrowLeft|ArrowRight|Home|End)$/.test(ev.key)) updateSuggestions();
+    });
+    inputEl.addEventListener("click", () => {
+      syncScroll();
+      updateSuggestions();
+    });
+    inputEl.addEventListener("blur", () => closeSuggestions());
     // Filter pills
     filtersEl = document.createElement("div");
     filtersEl.style.cssText =
@@ -70,26 +154,34 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
       b.style.padding =
         "calc(8px * var(--ui-scale)) calc(14px * var(--ui-scale))";
       const update = () => {
-        b.style.opacity = filterMode === mode ? "1" : "0.55";
-        b.style.outline =
-          filterMode === mode ? "1px solid var(--pill-active-outline)" : "none";
-      };
-      b.onclick = () => {
-        filterMode = mode;
-        updateAllPills();
-        runSearch(false);
+        const active = filterModeOf(inputEl?.value || "") === mode;
+        b.style.opacity = active ? "1" : "0.55";
+        b.style.outline = active
+          ? "1px solid var(--pill-active-outline)"
+          : "none";
       };
+      b.onclick = () => setFilterMode(mode);
       (b as any).__update = update;
       return b;
     }
     const pillAll = makePill("All", "all");
     const pillUng = makePill("Ungrouped", "ungrouped");
     const pillGrp = makePill("Grouped", "grouped");
-    function updateAllPills() {
-      [pillAll, pillUng, pillGrp].forEach((p) => (p as any).__update());
-    }
-    updateAllPills();
+    filterPills = [pillAll, pillUng, pillGrp];
+    updateFilterPills();
+    spotlightPill = document.createElement("button");
+    spotlightPill.type = "button";
+    spotlightPill.textContent = "Spotlight";
+    spotlightPill.title = "Dim everything but the matches (Alt+S)";
+    spotlightPill.className = "ui-pill";
+    spotlightPill.style.fontSize = "calc(14px * var(--ui-scale))";
+    spotlightPill.style.padding =
+      "calc(8px * var(--ui-scale)) calc(14px * var(--ui-scale))";
+    spotlightPill.style.marginLeft = "auto";
+    spotlightPill.onclick = () => toggleSpotlight();
+    updateSpotlightPill();
     filtersEl.append(pillAll, pillUng, pillGrp);
+    if (spotlight) filtersEl.appendChild(spotlightPill);
     wrap.appendChild(filtersEl);
     infoEl = document.createElement("div");
     infoEl.style.cssText =
@@ -164,7 +256,37 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
         hide();
       }
     };
+    smartBtn = document.createElement("button");
+    smartBtn.type = "button";
+    smartBtn.textContent = "Smart Group";
+    smartBtn.title =
+      "Create a group that keeps collecting matches (ungrouped cards, or the whole canvas with other filters)";
+    smartBtn.className = "ui-btn";
+    smartBtn.style.fontSize = "calc(14px * var(--ui-scale))";
+    smartBtn.style.padding =
+      "calc(6px * var(--ui-scale)) calc(14px * var(--ui-scale))";
+    smartBtn.onclick = () => {
+      const q = inputEl?.value.trim() || "";
+      if (!q || !createSmartGroup) return;
+      // in:ungrouped becomes the group's scope; anything else draws from the whole canvas
+      const ungrouped = filterModeOf(q) === "ungrouped";
+      const query = stripFilterTerm(q);
+      const error = query ? smartQueryError(query) : "Type a query first";
+      if (error) {
+        if (infoEl) {
+          infoEl.textContent = `⚠ ${error}`;
+          infoEl.style.color = "#e5a03a";
+        }
+        return;
+      }
+      createSmartGroup(
+        query,
+        ungrouped ? { kind: "ungrouped" } : { kind: "canvas" },
+      );
+      hide();
+    };
     navEl.append(prevBtn, counterEl, nextBtn, groupBtn);
+    if (createSmartGroup) navEl.appendChild(smartBtn);
     wrap.appendChild(navEl);
     const hint = document.createElement("div");
     hint.style.cssText =
@@ -178,7 +300,7 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
   <li>Free text matches name + type + oracle. Use quotes for phrases: <code>"draw a card"</code></li>
     <li>Negate with <code>-</code>. Combine with <code>OR</code>. Parentheses <code>( )</code> supported.</li>
     <li>Exact name: prefix with <code>!</code> e.g. <code>!"Lightning Bolt"</code></li>
-    <li>Regex only with text fields: <code>o:/^\\{T\\}:/</code>. Wildcard <code>*</code> works in text fields.</li>
+    <li>Regex in <code>name</code>, <code>o</code>, <code>t</code>, <code>ft</code> and <code>a</code>: <code>o:/^{T}: add/</code>, <code>name:/^a.*z$/</code>; <code>~</code> stands for the card's name (<code>o:/~ deals/</code>). Wildcard <code>*</code> works in text fields.</li>
   </ul>
   <div><b>Fields</b></div>
   <div style="margin:6px 0 8px 0;">
@@ -217,21 +339,61 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
     <li><code>is:</code> <em>spell</em>, <em>permanent</em>, <em>dfc</em>, <em>modal</em>, <em>vanilla</em>, <em>frenchvanilla</em>, <em>bear</em>, <em>hybrid</em>, <em>phyrexian</em>, <em>foil</em>, <em>etched</em>, <em>hires</em>, <em>promo</em>, <em>spotlight</em>, <em>digital</em>, <em>reserved</em>, <em>commander</em>…</li>
     <li><code>has:</code> <em>indicator</em>, <em>watermark</em>, <em>flavor</em>, <em>security_stamp</em></li>
   </ul>
+  <div><b>Canvas</b></div>
+  <ul style="margin:6px 0 8px 18px;">
+    <li><code>group:"Removal"</code> (nested groups count for their parents), <code>in:ungrouped</code>, <code>in:grouped</code></li>
+    <li><code>tag:combo</code>, <code>is:selected</code>, <code>face:back</code> (flipped double-faced cards)</li>
+    <li>Copies: <code>owned&gt;=4</code> (same name), <code>count&gt;1</code> (same printing), <code>dup:print</code>, <code>dup:name</code>, <code>owned:foil</code></li>
+  </ul>
   <div style="opacity:.8">Tip: plain text without fields matches names and oracle. Use <code>name:</code> to target name only, or <code>o:</code> for oracle‑only text.</div>
 </details>`;
     wrap.appendChild(hint);
     document.body.appendChild(wrap);
     // Global escape handler so palette closes even if focus moved to buttons
     const escListener = (ev: KeyboardEvent) => {
-      if (ev.key === "Escape" && palette && palette.style.display !== "none") {
+      if (ev.key !== "Escape") return;
+      // An open suggestion list closes first
+      if (suggestion) {
+        ev.stopPropagation();
+        ev.preventDefault();
+        closeSuggestions();
+        return;
+      }
+      if (palette && palette.style.display !== "none") {
         ev.stopPropagation();
         hide();
+      } else if (spotlightOn) {
+        // Closed palette: Esc on the canvas also ends the spotlight (the selection still
+        // clears); Esc inside an editor only cancels the edit
+        const t = ev.target as HTMLElement | null;
+        const inText = !!(
+          t &&
+          (t.tagName === "INPUT" ||
+            t.tagName === "TEXTAREA" ||
+            t.isContentEditable)
+        );
+        if (!inText) dismissSpotlight();
       }
     };
     // Attach once
     window.addEventListener("keydown", escListener, { capture: true });
 
     inputEl.addEventListener("keydown", (ev) => {
+      if (suggestion && !ev.altKey) {
+        const n = suggestion.items.length;
+        if (ev.key === "ArrowDown" || ev.key === "ArrowUp") {
+          const step = ev.key === "ArrowDown" ? 1 : n - 1;
+          suggestIndex = (suggestIndex + step) % n;
+          renderSuggestions();
+          ev.preventDefault();
+          return;
+        }
+        if (ev.key === "Tab") {
+          acceptSuggestion(suggestIndex);
+          ev.preventDefault();
+          return;
+        }
+      }
       if (ev.key === "Escape") {
         hide();
       }
@@ -258,35 +420,160 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
       }
       if (ev.altKey) {
         if (ev.key === "u" || ev.key === "U") {
-          filterMode = "ungrouped";
-          updateAllPills();
-          runSearch(false);
+          setFilterMode("ungrouped");
           ev.preventDefault();
         }
         if (ev.key === "g" || ev.key === "G") {
-          filterMode = "grouped";
-          updateAllPills();
-          runSearch(false);
+          setFilterMode("grouped");
           ev.preventDefault();
         }
         if (ev.key === "a" || ev.key === "A") {
-          filterMode = "all";
-          updateAllPills();
-          runSearch(false);
+          setFilterMode("all");
+          ev.preventDefault();
+        }
+        if ((ev.key === "s" || ev.key === "S") && spotlight) {
+          toggleSpotlight();
           ev.preventDefault();
         }
       }
     });
-    inputEl.addEventListener("input", () => runSearch(false));
+    inputEl.addEventListener("input", () => {
+      runSearch(false);
+      updateSuggestions();
+    });
     return wrap;
   }
 
+  // Query highlighting: one span per segment piece, the error span underlined on top
+  function renderHighlight(errorAt: { start: number; end: number } | null) {
+    if (!highlightEl || !inputEl) return;
+    const q = inputEl.value;
+    const segments = segmentQuery(q);
+    const cuts = new Set([0, q.length]);
+    for (const s of segments) cuts.add(s.start).add(s.end);
+    if (errorAt) cuts.add(errorAt.start).add(errorAt.end);
+    const points = [...cuts].filter((c) => c <= q.length).sort((a, b) => a - b);
+    const frag = document.createDocumentFragment();
+    for (let i = 0; i < points.length - 1; i++) {
+      const [from, to] = [points[i], points[i + 1]];
+      const seg = segments.find((s) => s.start <= from && to <= s.end);
+      const err = !!errorAt && errorAt.start <= from && to <= errorAt.end;
+      const text = q.slice(from, to);
+      if (!seg && !err) {
+        frag.appendChild(document.createTextNode(text));
+        continue;
+      }
+      const span = document.createElement("span");
+      span.className = [seg ? `qt-${seg.kind}` : "", err ? "qt-error" : ""]
+        .filter(Boolean)
+        .join(" ");
+      span.textContent = text;
+      frag.appendChild(span);
+    }
+    highlightEl.replaceChildren(frag);
+    highlightEl.scrollLeft = inputEl.scrollLeft;
+  }
+
+  // Set codes and formats of the loaded cards, gathered once per opening
+  function completionSources(): CompletionSources {
+    if (sources) return sources;
+    const sets = new Set<string>();
+    const formats = new Set<string>();
+    for (const s of getSprites()) {
+      const c = s.__card;
+      if (!c) continue;
+      if (c.set) sets.add(String(c.set).toLowerCase());
+      for (const f of Object.keys(c.legalities || {})) formats.add(f);
+    }
+    sources = { sets: [...sets], formats: [...formats] };
+    return sources;
+  }
+
+  function updateSuggestions() {
+    if (!inputEl) return;
+    const caret = inputEl.selectionStart ?? inputEl.value.length;
+    // Only for a plain caret; a selection is being replaced wholesale
+    suggestion =
+      caret === inputEl.selectionEnd
+        ? completeQuery(inputEl.value, caret, completionSources())
+        : null;
+    suggestIndex = 0;
+    renderSuggestions();
+  }
+  function closeSuggestions() {
+    suggestion = null;
+    renderSuggestions();
+  }
+  function renderSuggestions() {
+    if (!suggestEl) return;
+    if (!suggestion) {
+      suggestEl.style.display = "none";
+      return;
+    }
+    suggestEl.replaceChildren(
+      ...suggestion.items.map((item, i) => {
+        const row = document.createElement("div");
+        row.className = "ui-menu-item" + (i === suggestIndex ? " active" : "");
+        row.textContent = item.text;
+        if (item.hint) {
+          const hint = document.createElement("span");
+          hint.className = "qt-hint";
+          hint.textContent = item.hint;
+          row.appendChild(hint);
+        }
+        // mousedown keeps focus in the input
+        row.onmousedown = (ev) => {
+          ev.preventDefault();
+          acceptSuggestion(i);
+        };
+        return row;
+      }),
+    );
+    suggestEl.style.display = "block";
+  }
+  function acceptSuggestion(i: number) {
+    const item = suggestion?.items[i];
+    if (!inputEl || !suggestion || !item) return;
+    const { start, end } = suggestion;
+    const q = inputEl.value;
+    inputEl.value = q.slice(0, start) + item.text + q.slice(end);
+    const caret = start + item.text.length;
+    inputEl.setSelectionRange(caret, caret);
+    runSearch(false);
+    // A completed field name goes straight on to its values
+    updateSuggestions();
+  }
+
   // Deprecation notes for old local syntax
 
+  function filterModeOf(q: string): FilterMode {
+    const m = q.match(/(?:^|\s)in:(un)?grouped(?=\s|$)/i);
+    if (!m) return "all";
+    return m[1] ? "ungrouped" : "grouped";
+  }
+  function stripFilterTerm(q: string): string {
+    return q.replace(FILTER_TERM, "$1").trim();
+  }
+  // Pills and Alt+U/G/A swap the in: term at the front of the query
+  function setFilterMode(mode: FilterMode) {
+    if (!inputEl) return;
+    const rest = stripFilterTerm(inputEl.value);
+    const term = mode === "all" ? "" : `in:${mode}`;
+    inputEl.value = [term, rest].filter(Boolean).join(" ");
+    runSearch(false);
+  }
+  function updateFilterPills() {
+    filterPills.forEach((p) => (p as any).__update());
+  }
+
   function runSearch(commit: boolean) {
     if (!inputEl || !infoEl) return;
+    updateFilterPills();
     const q = inputEl.value.trim();
-    if (!q) {
+    lastFilter = filterModeOf(q);
+    // The filter term alone is not a search yet
+    if (!stripFilterTerm(q)) {
+      renderHighlight(null);
       // Clear prior state so reopening palette starts fresh (no stale nav / matches)
       infoEl.textContent = "";
       last = null;
@@ -296,31 +583,53 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
       if (prevBtn) prevBtn.disabled = true;
       if (nextBtn) nextBtn.disabled = true;
       if (navEl) navEl.style.display = "none";
+      spotlightQuery = "";
+      if (spotlightOn) spotlight?.(null);
       if (commit) hide();
       return;
     }
-    // Parse query (Scryfall-compatible). No local fallback tokenizer.
-    const adv = parseScryfallQuery(q);
+    // Parse query (Scryfall-compatible). No local fallback tokenizer; syntax errors
+    // (unknown fields, bad /regex/ operands) are shown instead of matching nothing quietly.
+    // (compiled untrimmed so errorAt lines up with the input)
+    const compiled = compileScryfallQuery(inputEl.value);
+    renderHighlight(compiled.errorAt);
     const sprites = getSprites();
+    const ctxOf = canvasContext?.();
+    const testFor =
+      (adv: typeof compiled.predicate): SpotlightTest =>
+      (s, lookup = ctxOf) => {
+        const c = s.__card;
+        if (!c) return false;
+        return adv ? !!adv(c, lookup?.(s)) : false;
+      };
+    const test = testFor(compiled.predicate);
     const matched: CardSprite[] = [];
-    for (const s of sprites) {
-      const c = s.__card;
-      if (!c) continue;
-      if (filterMode === "ungrouped" && s.__groupId) continue;
-      if (filterMode === "grouped" && !s.__groupId) continue;
-      let ok = false;
-      if (adv) ok = adv(c);
-      if (ok) matched.push(s);
+    for (const s of sprites) if (test(s)) matched.push(s);
+    if (spotlightOn) {
+      spotlightQuery = q;
+      // Group headers count the query itself: with in:ungrouped every group would show 0
+      const raw = stripFilterTerm(q);
+      const count =
+        raw === q ? test : testFor(compileScryfallQuery(raw).predicate);
+      spotlight?.({ matches: matched, test, count });
     }
     currentMatches = matched;
     cursor = 0;
     last = { query: q, total: matched.length, limited: matched.length };
-    infoEl.textContent = `Matches: ${matched.length}  |  Filter: ${filterMode}`;
+    infoEl.style.color = "";
+    if (compiled.error) {
+      infoEl.textContent = `⚠ ${compiled.error}`;
+      infoEl.style.color = "#e5a03a";
+    } else if (compiled.timedOut()) {
+      infoEl.textContent = `Matches: ${matched.length}\n⚠ A regular expression took too long; the search stopped early`;
+      infoEl.style.color = "#e5a03a";
+    } else infoEl.textContent = `Matches: ${matched.length}`;
     if (navEl) {
       (navEl as any).style ||= {};
     }
-    // Update nav and center on first match when not committing (preview mode)
-    if (!commit && matched.length) {
+    // Update nav and center on first match when not committing (preview mode). The
+    // spotlight is an overview, so it leaves the camera alone.
+    if (!commit && !spotlightOn && matched.length) {
       focusSprite(matched[0]);
     }
     // Refresh nav controls
@@ -365,8 +674,14 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
   function show(initial = "") {
     ensure();
     if (palette) palette.style.display = "flex";
+    updateChip();
+    sources = null;
+    closeSuggestions();
     if (inputEl) {
-      inputEl.value = initial;
+      // Reopening during a spotlight continues editing its query; otherwise start
+      // from the last filter term
+      const prefill = lastFilter === "all" ? "" : `in:${lastFilter} `;
+      inputEl.value = initial || (spotlightOn ? spotlightQuery : prefill);
       // Reset state preemptively (runSearch will also clear if empty)
       currentMatches = [];
       cursor = 0;
@@ -375,14 +690,84 @@ export function installSearchPalette(opts: SearchPaletteOptions) {
       if (nextBtn) nextBtn.disabled = true;
       if (navEl) navEl.style.display = "none";
       inputEl.focus();
-      inputEl.select();
+      if (inputEl.value === prefill) {
+        const end = inputEl.value.length;
+        inputEl.setSelectionRange(end, end);
+      } else inputEl.select();
       runSearch(false);
     }
   }
   function hide() {
     if (palette) palette.style.display = "none";
+    closeSuggestions();
+    // A spotlight without a query has nothing to show
+    if (spotlightOn && !spotlightQuery) dismissSpotlight();
+    updateChip();
+  }
+
+  function updateSpotlightPill() {
+    if (!spotlightPill) return;
+    spotlightPill.style.opacity = spotlightOn ? "1" : "0.55";
+    spotlightPill.style.outline = spotlightOn
+      ? "1px solid var(--pill-active-outline)"
+      : "none";
+  }
+
+  function toggleSpotlight() {
+    if (spotlightOn) {
+      dismissSpotlight();
+      return;
+    }
+    spotlightOn = true;
+    updateSpotlightPill();
+    runSearch(false);
+  }
+
+  function dismissSpotlight() {
+    if (!spotlightOn) return;
+    spotlightOn = false;
+    spotlightQuery = "";
+    spotlight?.(null);
+    updateSpotlightPill();
+    updateChip();
+  }
+
+  // Chip shown while the palette is closed: "Spotlight: t:creature · 12" [Edit] [✕]
+  function updateChip() {
+    const visible =
+      spotlightOn && (!palette || palette.style.display === "none");
+    if (!visible) {
+      if (chipEl) chipEl.style.display = "none";
+      return;
+    }
+    if (!chipEl) {
+      chipEl = document.createElement("div");
+      chipEl.id = "spotlight-chip";
+      chipEl.className = "ui-panel";
+      chipEl.style.cssText =
+        "position:fixed;top:calc(16px * var(--ui-scale));left:50%;transform:translateX(-50%);z-index:10040;display:flex;align-items:center;gap:calc(10px * var(--ui-scale));padding:calc(8px * var(--ui-scale)) calc(12px * var(--ui-scale));font-size:calc(15px * var(--ui-scale));";
+      const label = document.createElement("span");
+      label.style.cssText =
+        "max-width:calc(420px * var(--ui-scale));overflow:hidden;text-overflow:ellipsis;white-space:nowrap;";
+      const edit = document.createElement("button");
+      edit.type = "button";
+      edit.className = "ui-btn";
+      edit.textContent = "Edit";
+      edit.onclick = () => show(spotlightQuery);
+      const close = document.createElement("button");
+      close.type = "button";
+      close.className = "ui-btn";
+      close.textContent = "✕";
+      close.title = "End spotlight (Esc)";
+      close.onclick = () => dismissSpotlight();
+      chipEl.append(label, edit, close);
+      document.body.appendChild(chipEl);
+    }
+    chipEl.style.display = "flex";
+    const text = chipEl.firstChild as HTMLSpanElement;
+    text.textContent = `Spotlight: ${spotlightQuery} · ${currentMatches.length}`;
   }
 
   // Public API (if needed later)
-  return { show, hide };
+  return { show, hide, dismissSpotlight };
 }
@@ -346,6 +346,22 @@ export function ensureThemeStyles() {
   .ui-menu-item{ padding:calc(10px * var(--ui-scale)) calc(14px * var(--ui-scale)); cursor:pointer; border-radius:calc(8px * var(--ui-scale)); }
   .ui-menu-item:hover{ background:var(--menu-hover-bg); }
   .ui-menu-item.disabled{ opacity:.5; cursor:default; }
+  /* Search palette query highlighting: the input's text is transparent and a copy with the same
+     metrics is drawn over it, so only colors may differ (no weight / style changes) */
+  .qt-wrap{ position:relative; }
+  .qt-wrap .ui-input{ width:100%; line-height:1.3; }
+  .qt-input{ color:transparent; caret-color:var(--input-fg); }
+  .qt-input::placeholder{ color:var(--panel-fg-dim); }
+  .qt-input::selection{ color:transparent; background:color-mix(in srgb, var(--panel-accent) 35%, transparent); }
+  .qt-layer{ position:absolute; inset:0; pointer-events:none; white-space:pre; overflow:hidden; background:transparent; border-color:transparent; box-shadow:none; }
+  .qt-field, .qt-keyword{ color:var(--panel-accent); }
+  .qt-operator, .qt-paren{ color:var(--panel-fg-dim); }
+  .qt-value, .qt-text{ color:var(--input-fg); }
+  .qt-regex{ color:color-mix(in srgb, var(--panel-accent) 50%, var(--input-fg)); }
+  .qt-error{ text-decoration:underline wavy #e5a03a; text-decoration-skip-ink:none; text-underline-offset:3px; }
+  .qt-suggest{ position:absolute; top:calc(100% + 4px * var(--ui-scale)); left:0; min-width:40%; z-index:1; }
+  .qt-suggest .ui-menu-item.active{ background:var(--menu-hover-bg); }
+  .qt-hint{ float:right; margin-left:calc(24px * var(--ui-scale)); color:var(--panel-fg-dim); }
   /* Perf overlay monospace */
   .perf-grid{ font-family:monospace; font-size:calc(15px * var(--ui-scale)); line-height:1.5; white-space:pre; }
   .theme-toggle-btn{ position:fixed; bottom:calc(14px * var(--ui-scale)); left:calc(14px * var(--ui-scale)); width:var(--fab-size); height:var(--fab-size); border-radius:50%; background:var(--fab-bg); color:var(--fab-fg); border:1px solid var(--fab-border); font-family:var(--panel-font); font-size:calc(26px * var(--ui-scale)); line-height:var(--fab-size); text-align:center; cursor:pointer; user-select:none; z-index:9999; box-shadow:var(--panel-shadow); transition:filter .2s, box-shadow .2s, background .2s; }
@@ -0,0 +1,52 @@
+import { describe, expect, it } from "vitest";
+import { computeAlignment, type GuideBox } from "../alignGuides";
+
+const box = (x: number, y: number, w = 100, h = 100): GuideBox => ({
+  minX: x,
+  minY: y,
+  maxX: x + w,
+  maxY: y + h,
+});
+
+describe("alignment guides", () => {
+  it("snaps edges and centers within the threshold", () => {
+    const a = computeAlignment(box(100, 300), [box(103, 0)], 6);
+    expect(a.dx).toBe(3);
+    expect(a.snapX).toBe(true);
+    expect(a.dy).toBe(0);
+    expect(a.snapY).toBe(false);
+    // Left edge, center and right edge all line up
+    expect(a.guides).toEqual([
+      { kind: "line", axis: "x", pos: 103, from: 0, to: 400 },
+      { kind: "line", axis: "x", pos: 153, from: 0, to: 400 },
+      { kind: "line", axis: "x", pos: 203, from: 0, to: 400 },
+    ]);
+  });
+
+  it("ignores boxes farther than the threshold", () => {
+    const a = computeAlignment(box(100, 300), [box(120, 0)], 6);
+    expect(a).toEqual({ dx: 0, dy: 0, snapX: false, snapY: false, guides: [] });
+  });
+
+  it("repeats the gap of the row", () => {
+    const a = computeAlignment(box(302, 0), [box(0, 0), box(150, 0)], 6);
+    expect(a.dx).toBe(-2);
+    expect(a.guides.filter((g) => g.kind === "gap")).toEqual([
+      { kind: "gap", axis: "x", from: 100, to: 150, at: 50 },
+      { kind: "gap", axis: "x", from: 250, to: 300, at: 50 },
+    ]);
+  });
+
+  it("centers between two neighbours", () => {
+    const a = computeAlignment(
+      box(148, 0, 100, 40),
+      [box(0, 0), box(300, 0)],
+      6,
+    );
+    expect(a.dx).toBe(2);
+    expect(a.guides.filter((g) => g.kind === "gap")).toEqual([
+      { kind: "gap", axis: "x", from: 100, to: 150, at: 20 },
+      { kind: "gap", axis: "x", from: 250, to: 300, at: 20 },
+    ]);
+  });
+});
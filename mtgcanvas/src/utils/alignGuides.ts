@@ -0,0 +1,155 @@
+// Smart guides for hand-arranging: while dragging, pull the moving box onto the edges and
+// centers of nearby cards / groups, or onto equal spacing with its row / column neighbours.
+// Pure geometry; main collects the nearby boxes from the SpatialIndex and draws the guides.
+
+export type GuideBox = {
+  minX: number;
+  minY: number;
+  maxX: number;
+  maxY: number;
+};
+
+// "line": an alignment line at `pos` on `axis` ("x" = vertical line), spanning from..to
+// on the other axis. "gap": one of the equal gaps, from..to along `axis`, drawn at `at`.
+export type Guide =
+  | { kind: "line"; axis: "x" | "y"; pos: number; from: number; to: number }
+  | { kind: "gap"; axis: "x" | "y"; from: number; to: number; at: number };
+
+export interface Alignment {
+  dx: number; // correction to add to the proposed position
+  dy: number;
+  snapX: boolean; // snapped to a guide on this axis (skip the grid on drop)
+  snapY: boolean;
+  guides: Guide[];
+}
+
+type Axis = "x" | "y";
+type Span = { min: number; max: number };
+
+function span(b: GuideBox, axis: Axis): Span {
+  return axis === "x"
+    ? { min: b.minX, max: b.maxX }
+    : { min: b.minY, max: b.maxY };
+}
+function lines(s: Span) {
+  return [s.min, (s.min + s.max) / 2, s.max];
+}
+
+type Candidate = { d: number; gaps: [number, number][] };
+
+// Best correction along one axis: edge/center alignment first, equal spacing on a tie
+function solveAxis(
+  moving: GuideBox,
+  targets: GuideBox[],
+  axis: Axis,
+  threshold: number,
+): Candidate | null {
+  const cross: Axis = axis === "x" ? "y" : "x";
+  const m = span(moving, axis);
+  const mc = span(moving, cross);
+  let best: Candidate | null = null;
+  const consider = (d: number, gaps: [number, number][] = []) => {
+    if (Math.abs(d) > threshold) return;
+    if (!best || Math.abs(d) < Math.abs(best.d) - 1e-6) best = { d, gaps };
+  };
+  for (const t of targets) {
+    const tl = lines(span(t, axis));
+    for (const ml of lines(m)) for (const v of tl) consider(v - ml);
+  }
+  // Equal spacing only among boxes sharing the moving box's row (or column)
+  const band = targets
+    .filter((t) => {
+      const c = span(t, cross);
+      return c.max > mc.min && c.min < mc.max;
+    })
+    .map((t) => span(t, axis))
+    .sort((a, b) => a.min - b.min);
+  const size = m.max - m.min;
+  let left: Span | null = null;
+  let right: Span | null = null;
+  for (const s of band) {
+    if (s.max <= m.min + threshold && (!left || s.max > left.max)) left = s;
+    if (s.min >= m.max - threshold && (!right || s.min < right.min)) right = s;
+  }
+  // Centered between the two neighbours
+  if (left && right && right.min - left.max > size) {
+    const min = (left.max + right.min - size) / 2;
+    consider(min - m.min, [
+      [left.max, min],
+      [min + size, right.min],
+    ]);
+  }
+  // Repeat an existing gap of the row next to a neighbour (the gap the moving box
+  // sits in doesn't count)
+  for (let i = 1; i < band.length; i++) {
+    const a = band[i - 1];
+    const b = band[i];
+    const gap = b.min - a.max;
+    if (gap <= 0) continue;
+    if (left && a !== left)
+      consider(left.max + gap - m.min, [
+        [a.max, b.min],
+        [left.max, left.max + gap],
+      ]);
+    if (right && b !== right)
+      consider(right.min - gap - m.max, [
+        [a.max, b.min],
+        [right.min - gap, right.min],
+      ]);
+  }
+  return best;
+}
+
+export function computeAlignment(
+  moving: GuideBox,
+  targets: GuideBox[],
+  threshold: number,
+): Alignment {
+  const cx = solveAxis(moving, targets, "x", threshold);
+  const cy = solveAxis(moving, targets, "y", threshold);
+  const dx = cx?.d ?? 0;
+  const dy = cy?.d ?? 0;
+  const box: GuideBox = {
+    minX: moving.minX + dx,
+    minY: moving.minY + dy,
+    maxX: moving.maxX + dx,
+    maxY: moving.maxY + dy,
+  };
+  const guides: Guide[] = [];
+  const addLines = (axis: Axis) => {
+    const cross: Axis = axis === "x" ? "y" : "x";
+    const mc = span(box, cross);
+    const byPos = new Map<number, Span>();
+    for (const t of targets) {
+      const tc = span(t, cross);
+      for (const v of lines(span(t, axis))) {
+        if (!lines(span(box, axis)).some((ml) => Math.abs(ml - v) < 0.5))
+          continue;
+        const key = Math.round(v * 2) / 2;
+        const prev = byPos.get(key) ?? mc;
+        byPos.set(key, {
+          min: Math.min(prev.min, tc.min),
+          max: Math.max(prev.max, tc.max),
+        });
+      }
+    }
+    byPos.forEach((s, pos) =>
+      guides.push({ kind: "line", axis, pos, from: s.min, to: s.max }),
+    );
+  };
+  const addGaps = (axis: Axis, c: Candidate | null) => {
+    if (!c) return;
+    const mc = span(box, axis === "x" ? "y" : "x");
+    for (const [from, to] of c.gaps)
+      guides.push({ kind: "gap", axis, from, to, at: (mc.min + mc.max) / 2 });
+  };
+  if (cx) {
+    addLines("x");
+    addGaps("x", cx);
+  }
+  if (cy) {
+    addLines("y");
+    addGaps("y", cy);
+  }
+  return { dx, dy, snapX: !!cx, snapY: !!cy, guides };
+}